@@ -7,7 +7,10 @@ use base64::Engine as _;
 use chrono::Utc;
 use uuid::Uuid;
 
-use super::{get_agent_dir, get_data_dir_path};
+use super::{
+    denial_message, get_agent_dir, get_data_dir_path, normalize_path, path_within,
+    FilesystemSandbox,
+};
 use crate::types::{
     compute_checksum, format_file_size, FileAttachment, FileAttachmentMetadata, FileCategory,
     FileSource, FilesystemMetadata, GenerationMetadata, PathValidationResult, UploadMetadata,
@@ -413,45 +416,32 @@ pub fn delete_agent_file(file_id: String) -> Result<(), String> {
     Ok(())
 }
 
-/// Validate a filesystem path for security
+/// Validate a filesystem path against the filesystem policy, reporting the
+/// rule that decided access
 #[tauri::command(rename_all = "snake_case")]
 pub fn validate_filesystem_path(path: String) -> Result<PathValidationResult, String> {
-    let path_obj = Path::new(&path);
-
-    // Check if path exists
-    if !path_obj.exists() {
-        return Ok(PathValidationResult {
-            valid: false,
-            sanitized_path: None,
-            error: Some("Path does not exist".to_string()),
-            within_sandbox: false,
-        });
-    }
-
-    // Check if it's a file
-    if !path_obj.is_file() {
-        return Ok(PathValidationResult {
-            valid: false,
-            sanitized_path: None,
-            error: Some("Path is not a file".to_string()),
-            within_sandbox: false,
-        });
-    }
-
-    // Get canonical path
-    let canonical = path_obj
-        .canonicalize()
-        .map_err(|e| format!("Failed to canonicalize path: {}", e))?;
+    let (resolved, rule) = FilesystemSandbox::load()?.evaluate(&path)?;
+
+    let error = if !rule.allowed {
+        Some(denial_message(&resolved, &rule))
+    } else if !resolved.exists() {
+        Some("Path does not exist".to_string())
+    } else if !resolved.is_file() {
+        Some("Path is not a file".to_string())
+    } else {
+        None
+    };
 
     // Check if within data directory (sandbox)
     let data_dir = get_data_dir_path();
-    let within_sandbox = canonical.starts_with(&data_dir);
+    let within_sandbox = path_within(&resolved, &normalize_path(&data_dir).unwrap_or(data_dir));
 
     Ok(PathValidationResult {
-        valid: true,
-        sanitized_path: Some(canonical.to_string_lossy().to_string()),
-        error: None,
+        valid: error.is_none(),
+        sanitized_path: Some(resolved.to_string_lossy().to_string()),
+        error,
         within_sandbox,
+        rule: Some(rule),
     })
 }
 
@@ -1,15 +1,19 @@
 //! Command execution & approval
 
-use std::process::Command;
+use std::time::Duration;
 
 use chrono::Utc;
 use regex::Regex;
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension};
+use tauri::{AppHandle, Emitter};
 use uuid::Uuid;
 
 use super::{
-    get_db_connection, get_settings, PENDING_COMMANDS,
-    AgentSettings, ApprovalMode, CommandExecutionResult, CommandStatus, PendingCommand,
+    classify_command, execute_shell_command, get_db_connection, get_settings, run_in_session,
+    ExecutionLimits,
+    COMMAND_DECIDED, PENDING_COMMANDS, AgentSettings, ApprovalDecision, ApprovalMode,
+    CommandExecutionResult, CommandOutputEvent, CommandRisk, CommandRiskClass, CommandStatus,
+    PendingCommand,
 };
 
 /// Check if a command matches the whitelist patterns
@@ -24,60 +28,100 @@ fn is_command_whitelisted(command: &str, patterns: &[String]) -> bool {
     false
 }
 
-/// Execute a shell command
-fn execute_shell_command(command: &str) -> Result<CommandExecutionResult, String> {
-    #[cfg(windows)]
-    {
-        let output = Command::new("powershell")
-            .args(["-NoProfile", "-Command", command])
-            .output()
-            .map_err(|e| format!("Failed to execute command: {}", e))?;
-
-        Ok(CommandExecutionResult {
-            exit_code: output.status.code().unwrap_or(-1),
-            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-        })
+/// Run a shell command within the configured limits, emitting its output
+/// as `command-output` events under `output_id` while it runs. With
+/// persistent shells enabled, a command from a conversation runs in that
+/// conversation's shell session.
+fn run_shell_command(
+    app: Option<&AppHandle>,
+    command: &str,
+    output_id: &str,
+    conversation_id: Option<&str>,
+) -> Result<CommandExecutionResult, String> {
+    let settings = get_settings()?;
+    let limits = ExecutionLimits::from_settings(&settings.agent);
+    let on_output = |stream, text| {
+        if let Some(app) = app {
+            let event = CommandOutputEvent {
+                id: output_id.to_string(),
+                stream,
+                text,
+            };
+            let _ = app.emit("command-output", &event);
+        }
+    };
+    match conversation_id {
+        Some(conversation_id) if settings.agent.persistent_shell => {
+            run_in_session(conversation_id, command, limits, on_output)
+        }
+        _ => execute_shell_command(command, limits, on_output),
     }
+}
 
-    #[cfg(not(windows))]
-    {
-        let output = Command::new("sh")
-            .args(["-c", command])
-            .output()
-            .map_err(|e| format!("Failed to execute command: {}", e))?;
-
-        Ok(CommandExecutionResult {
-            exit_code: output.status.code().unwrap_or(-1),
-            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-        })
+/// Record how a command ran
+fn apply_execution_result(cmd: &mut PendingCommand, result: CommandExecutionResult) {
+    cmd.status = if result.exit_code == 0 && !result.timed_out {
+        CommandStatus::Executed
+    } else {
+        CommandStatus::Failed
+    };
+    let mut error = result.stderr;
+    if result.timed_out {
+        if !error.is_empty() {
+            error.push_str("\n\n");
+        }
+        error.push_str(&format!(
+            "Command timed out after {}s and was stopped",
+            result.elapsed_ms / 1000
+        ));
     }
+    cmd.output = Some(result.stdout);
+    cmd.error = if error.is_empty() { None } else { Some(error) };
+    cmd.elapsed_ms = Some(result.elapsed_ms);
+    cmd.timed_out = result.timed_out;
+    cmd.output_truncated = result.truncated;
 }
 
-/// Execute a command directly (bypasses approval mode check)
-/// Used by the frontend HITL flow after user has already approved
-#[tauri::command]
-pub fn execute_agent_command(command: String, reason: String) -> Result<PendingCommand, String> {
-    let result = execute_shell_command(&command)?;
+/// Where a command runs and reports its output
+#[derive(Default)]
+struct RunContext<'a> {
+    app: Option<&'a AppHandle>,
+    /// ID for the `command-output` events, instead of the command's own
+    output_id: Option<String>,
+    /// Conversation whose shell session to run in
+    conversation_id: Option<String>,
+}
 
-    let pending = PendingCommand {
-        id: Uuid::new_v4().to_string(),
+/// Run a command now and record it in the history
+fn run_and_record(
+    context: RunContext,
+    command: String,
+    reason: String,
+    source: Option<String>,
+    risk: CommandRisk,
+) -> Result<PendingCommand, String> {
+    let id = Uuid::new_v4().to_string();
+    let result = run_shell_command(
+        context.app,
+        &command,
+        context.output_id.as_deref().unwrap_or(&id),
+        context.conversation_id.as_deref(),
+    )?;
+    let mut pending = PendingCommand {
+        id,
         command,
         reason,
         created_at: Utc::now().to_rfc3339(),
-        status: if result.exit_code == 0 {
-            CommandStatus::Executed
-        } else {
-            CommandStatus::Failed
-        },
-        output: Some(result.stdout),
-        error: if result.stderr.is_empty() {
-            None
-        } else {
-            Some(result.stderr)
-        },
+        status: CommandStatus::Approved,
+        output: None,
+        error: None,
+        source,
+        risk,
+        elapsed_ms: None,
+        timed_out: false,
+        output_truncated: false,
     };
+    apply_execution_result(&mut pending, result);
 
     // Log to history
     log_command_to_history(&pending)?;
@@ -85,77 +129,109 @@ pub fn execute_agent_command(command: String, reason: String) -> Result<PendingC
     Ok(pending)
 }
 
-/// Queue a command for approval
+/// Execute a command directly (bypasses approval mode check)
+/// Used by the frontend HITL flow after user has already approved.
+/// `output_id` names the `command-output` events for the caller to follow;
+/// `conversation_id` picks the shell session when persistent shells are on.
 #[tauri::command]
-pub fn queue_agent_command(command: String, reason: String) -> Result<PendingCommand, String> {
-    let settings = get_settings()?;
-    let agent_settings = &settings.agent;
-
-    // Check approval mode
-    match agent_settings.approval_mode {
-        ApprovalMode::Yolo => {
-            // Execute immediately
-            let result = execute_shell_command(&command)?;
-            let pending = PendingCommand {
-                id: Uuid::new_v4().to_string(),
-                command,
-                reason,
-                created_at: Utc::now().to_rfc3339(),
-                status: if result.exit_code == 0 {
-                    CommandStatus::Executed
-                } else {
-                    CommandStatus::Failed
-                },
-                output: Some(result.stdout),
-                error: if result.stderr.is_empty() {
-                    None
-                } else {
-                    Some(result.stderr)
-                },
-            };
-
-            // Log to history
-            log_command_to_history(&pending)?;
+pub async fn execute_agent_command(
+    app: AppHandle,
+    command: String,
+    reason: String,
+    output_id: Option<String>,
+    conversation_id: Option<String>,
+) -> Result<PendingCommand, String> {
+    tokio::task::spawn_blocking(move || {
+        let risk = classify_command(&command);
+        let context = RunContext {
+            app: Some(&app),
+            output_id,
+            conversation_id,
+        };
+        run_and_record(context, command, reason, None, risk)
+    })
+    .await
+    .map_err(|e| format!("Command task failed: {}", e))?
+}
 
-            Ok(pending)
-        }
+/// Decide whether the technician must approve a command. In whitelist mode
+/// a whitelisted command still needs approval when it is destructive or
+/// touches credentials.
+pub(crate) fn approval_decision(command: &str, settings: &AgentSettings) -> ApprovalDecision {
+    let risk = classify_command(command);
+    let (needs_approval, explanation) = match settings.approval_mode {
+        ApprovalMode::Yolo => (false, "YOLO mode runs every command".to_string()),
+        ApprovalMode::Always => (true, "Every command needs approval".to_string()),
         ApprovalMode::Whitelist => {
-            if is_command_whitelisted(&command, &agent_settings.whitelisted_commands) {
-                // Execute immediately
-                let result = execute_shell_command(&command)?;
-                let pending = PendingCommand {
-                    id: Uuid::new_v4().to_string(),
-                    command,
-                    reason,
-                    created_at: Utc::now().to_rfc3339(),
-                    status: if result.exit_code == 0 {
-                        CommandStatus::Executed
-                    } else {
-                        CommandStatus::Failed
-                    },
-                    output: Some(result.stdout),
-                    error: if result.stderr.is_empty() {
-                        None
-                    } else {
-                        Some(result.stderr)
-                    },
-                };
-
-                log_command_to_history(&pending)?;
-                Ok(pending)
+            if !is_command_whitelisted(command, &settings.whitelisted_commands) {
+                (true, "Command is not whitelisted".to_string())
+            } else if risk.class >= CommandRiskClass::CredentialAccess {
+                (
+                    true,
+                    format!(
+                        "Command is whitelisted, but {} commands always need approval",
+                        risk.class.label()
+                    ),
+                )
             } else {
-                // Queue for approval
-                queue_for_approval(command, reason)
+                (false, "Command is whitelisted".to_string())
             }
         }
-        ApprovalMode::Always => {
-            // Always queue for approval
-            queue_for_approval(command, reason)
-        }
+    };
+    ApprovalDecision {
+        needs_approval,
+        explanation,
+        risk,
     }
 }
 
-fn queue_for_approval(command: String, reason: String) -> Result<PendingCommand, String> {
+/// Assess a command without running it: its risk and whether the current
+/// approval mode would ask for approval
+#[tauri::command]
+pub fn assess_agent_command(command: String) -> Result<ApprovalDecision, String> {
+    let settings = get_settings()?;
+    Ok(approval_decision(&command, &settings.agent))
+}
+
+/// Run a command if the approval mode allows it, otherwise queue it for
+/// approval. `source` names remote callers such as MCP clients.
+pub(crate) fn submit_command(
+    app: Option<&AppHandle>,
+    command: String,
+    reason: String,
+    source: Option<String>,
+) -> Result<PendingCommand, String> {
+    let settings = get_settings()?;
+    let decision = approval_decision(&command, &settings.agent);
+    if decision.needs_approval {
+        queue_for_approval(command, reason, source, decision.risk)
+    } else {
+        let context = RunContext {
+            app,
+            ..Default::default()
+        };
+        run_and_record(context, command, reason, source, decision.risk)
+    }
+}
+
+/// Queue a command for approval
+#[tauri::command]
+pub async fn queue_agent_command(
+    app: AppHandle,
+    command: String,
+    reason: String,
+) -> Result<PendingCommand, String> {
+    tokio::task::spawn_blocking(move || submit_command(Some(&app), command, reason, None))
+        .await
+        .map_err(|e| format!("Command task failed: {}", e))?
+}
+
+fn queue_for_approval(
+    command: String,
+    reason: String,
+    source: Option<String>,
+    risk: CommandRisk,
+) -> Result<PendingCommand, String> {
     let pending = PendingCommand {
         id: Uuid::new_v4().to_string(),
         command,
@@ -164,6 +240,11 @@ fn queue_for_approval(command: String, reason: String) -> Result<PendingCommand,
         status: CommandStatus::Pending,
         output: None,
         error: None,
+        source,
+        risk,
+        elapsed_ms: None,
+        timed_out: false,
+        output_truncated: false,
     };
 
     let mut commands = PENDING_COMMANDS
@@ -174,6 +255,28 @@ fn queue_for_approval(command: String, reason: String) -> Result<PendingCommand,
     Ok(pending)
 }
 
+/// Block until a queued command is approved (and has run) or rejected, or
+/// until `timeout` passes. Returns `None` if it is still pending.
+pub(crate) fn wait_for_command_decision(
+    command_id: &str,
+    timeout: Duration,
+) -> Result<Option<PendingCommand>, String> {
+    let commands = PENDING_COMMANDS
+        .lock()
+        .map_err(|e| format!("Failed to lock pending commands: {}", e))?;
+    let (commands, _) = COMMAND_DECIDED
+        .wait_timeout_while(commands, timeout, |commands| {
+            commands.iter().any(|c| c.id == command_id)
+        })
+        .map_err(|e| format!("Failed to wait for approval: {}", e))?;
+    if commands.iter().any(|c| c.id == command_id) {
+        return Ok(None);
+    }
+    drop(commands);
+
+    get_command_status(command_id.to_string()).map(Some)
+}
+
 fn log_command_to_history(cmd: &PendingCommand) -> Result<(), String> {
     let conn = get_db_connection()?;
 
@@ -185,9 +288,13 @@ fn log_command_to_history(cmd: &PendingCommand) -> Result<(), String> {
         CommandStatus::Failed => "failed",
     };
 
+    let risk_json = serde_json::to_string(&cmd.risk)
+        .map_err(|e| format!("Failed to serialize command risk: {}", e))?;
+
     conn.execute(
-        "INSERT INTO command_history (id, command, reason, status, output, error, created_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        "INSERT INTO command_history (id, command, reason, status, output, error, created_at, risk,
+                                      elapsed_ms, timed_out, output_truncated)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
         params![
             cmd.id,
             cmd.command,
@@ -195,7 +302,11 @@ fn log_command_to_history(cmd: &PendingCommand) -> Result<(), String> {
             status_str,
             cmd.output,
             cmd.error,
-            cmd.created_at
+            cmd.created_at,
+            risk_json,
+            cmd.elapsed_ms.map(|ms| ms as i64),
+            cmd.timed_out,
+            cmd.output_truncated
         ],
     )
     .map_err(|e| format!("Failed to log command: {}", e))?;
@@ -212,47 +323,68 @@ pub fn get_pending_commands() -> Result<Vec<PendingCommand>, String> {
     Ok(commands.clone())
 }
 
-/// Clear all pending commands
+/// Clear all pending commands. Approved commands that are still running
+/// stay until they finish.
 #[tauri::command]
 pub fn clear_pending_commands() -> Result<(), String> {
     let mut commands = PENDING_COMMANDS
         .lock()
         .map_err(|e| format!("Failed to lock pending commands: {}", e))?;
-    commands.clear();
+    commands.retain(|c| c.status == CommandStatus::Approved);
+    COMMAND_DECIDED.notify_all();
     Ok(())
 }
 
-/// Approve a pending command and execute it
-#[tauri::command(rename_all = "snake_case")]
-pub fn approve_command(command_id: String) -> Result<PendingCommand, String> {
-    let mut commands = PENDING_COMMANDS
-        .lock()
-        .map_err(|e| format!("Failed to lock pending commands: {}", e))?;
-
+/// Position of a command still waiting for a decision
+fn pending_position(commands: &[PendingCommand], command_id: &str) -> Result<usize, String> {
     let idx = commands
         .iter()
         .position(|c| c.id == command_id)
         .ok_or_else(|| "Command not found".to_string())?;
+    if commands[idx].status != CommandStatus::Pending {
+        return Err("Command has already been approved and is running".to_string());
+    }
+    Ok(idx)
+}
 
-    let mut cmd = commands.remove(idx);
-
-    // Execute the command
-    let result = execute_shell_command(&cmd.command)?;
+/// Approve a pending command and execute it
+#[tauri::command(rename_all = "snake_case")]
+pub async fn approve_command(app: AppHandle, command_id: String) -> Result<PendingCommand, String> {
+    tokio::task::spawn_blocking(move || run_approved_command(&app, &command_id))
+        .await
+        .map_err(|e| format!("Command task failed: {}", e))?
+}
 
-    cmd.status = if result.exit_code == 0 {
-        CommandStatus::Executed
-    } else {
-        CommandStatus::Failed
-    };
-    cmd.output = Some(result.stdout);
-    cmd.error = if result.stderr.is_empty() {
-        None
-    } else {
-        Some(result.stderr)
+/// Run an approved command. It stays in the pending list, marked approved,
+/// while it runs, so the lock isn't held for the whole run and anyone
+/// waiting on the decision keeps waiting until there is an outcome.
+fn run_approved_command(app: &AppHandle, command_id: &str) -> Result<PendingCommand, String> {
+    let mut cmd = {
+        let mut commands = PENDING_COMMANDS
+            .lock()
+            .map_err(|e| format!("Failed to lock pending commands: {}", e))?;
+        let idx = pending_position(&commands, command_id)?;
+        commands[idx].status = CommandStatus::Approved;
+        commands[idx].clone()
     };
 
-    // Log to history
-    log_command_to_history(&cmd)?;
+    match run_shell_command(Some(app), &cmd.command, &cmd.id, None) {
+        Ok(result) => apply_execution_result(&mut cmd, result),
+        Err(e) => {
+            cmd.status = CommandStatus::Failed;
+            cmd.error = Some(e);
+        }
+    }
+
+    let mut commands = PENDING_COMMANDS
+        .lock()
+        .map_err(|e| format!("Failed to lock pending commands: {}", e))?;
+    commands.retain(|c| c.id != cmd.id);
+
+    // Log to history, then wake anyone waiting on the decision
+    let logged = log_command_to_history(&cmd);
+    COMMAND_DECIDED.notify_all();
+    logged?;
 
     Ok(cmd)
 }
@@ -264,16 +396,14 @@ pub fn reject_command(command_id: String) -> Result<PendingCommand, String> {
         .lock()
         .map_err(|e| format!("Failed to lock pending commands: {}", e))?;
 
-    let idx = commands
-        .iter()
-        .position(|c| c.id == command_id)
-        .ok_or_else(|| "Command not found".to_string())?;
-
+    let idx = pending_position(&commands, &command_id)?;
     let mut cmd = commands.remove(idx);
     cmd.status = CommandStatus::Rejected;
 
-    // Log to history
-    log_command_to_history(&cmd)?;
+    // Log to history, then wake anyone waiting on the decision
+    let logged = log_command_to_history(&cmd);
+    COMMAND_DECIDED.notify_all();
+    logged?;
 
     Ok(cmd)
 }
@@ -285,6 +415,42 @@ pub fn get_agent_settings() -> Result<AgentSettings, String> {
     Ok(settings.agent)
 }
 
+/// Map a `command_history` row (id, command, reason, status, output, error,
+/// created_at, risk, elapsed_ms, timed_out, output_truncated) to a command. Rows logged before risks were recorded
+/// are assessed now.
+fn command_from_row(row: &rusqlite::Row) -> rusqlite::Result<PendingCommand> {
+    let status_str: String = row.get(3)?;
+    let status = match status_str.as_str() {
+        "pending" => CommandStatus::Pending,
+        "approved" => CommandStatus::Approved,
+        "rejected" => CommandStatus::Rejected,
+        "executed" => CommandStatus::Executed,
+        "failed" => CommandStatus::Failed,
+        _ => CommandStatus::Pending,
+    };
+
+    let command: String = row.get(1)?;
+    let risk = row
+        .get::<_, Option<String>>(7)?
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_else(|| classify_command(&command));
+
+    Ok(PendingCommand {
+        id: row.get(0)?,
+        command,
+        reason: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+        created_at: row.get(6)?,
+        status,
+        output: row.get(4)?,
+        error: row.get(5)?,
+        source: None,
+        risk,
+        elapsed_ms: row.get::<_, Option<i64>>(8)?.map(|ms| ms as u64),
+        timed_out: row.get(9)?,
+        output_truncated: row.get(10)?,
+    })
+}
+
 /// Get command history
 #[tauri::command]
 pub fn get_command_history(limit: Option<usize>) -> Result<Vec<PendingCommand>, String> {
@@ -293,7 +459,8 @@ pub fn get_command_history(limit: Option<usize>) -> Result<Vec<PendingCommand>,
 
     let mut stmt = conn
         .prepare(
-            "SELECT id, command, reason, status, output, error, created_at
+            "SELECT id, command, reason, status, output, error, created_at, risk,
+                    elapsed_ms, timed_out, output_truncated
              FROM command_history
              ORDER BY created_at DESC
              LIMIT ?1",
@@ -301,43 +468,38 @@ pub fn get_command_history(limit: Option<usize>) -> Result<Vec<PendingCommand>,
         .map_err(|e| format!("Failed to prepare query: {}", e))?;
 
     let rows = stmt
-        .query_map(params![limit as i64], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, Option<String>>(2)?,
-                row.get::<_, String>(3)?,
-                row.get::<_, Option<String>>(4)?,
-                row.get::<_, Option<String>>(5)?,
-                row.get::<_, String>(6)?,
-            ))
-        })
+        .query_map(params![limit as i64], command_from_row)
         .map_err(|e| format!("Failed to execute query: {}", e))?;
 
     let mut history = Vec::new();
     for row in rows {
-        let (id, command, reason, status_str, output, error, created_at) =
-            row.map_err(|e| format!("Failed to read row: {}", e))?;
-
-        let status = match status_str.as_str() {
-            "pending" => CommandStatus::Pending,
-            "approved" => CommandStatus::Approved,
-            "rejected" => CommandStatus::Rejected,
-            "executed" => CommandStatus::Executed,
-            "failed" => CommandStatus::Failed,
-            _ => CommandStatus::Pending,
-        };
-
-        history.push(PendingCommand {
-            id,
-            command,
-            reason: reason.unwrap_or_default(),
-            created_at,
-            status,
-            output,
-            error,
-        });
+        history.push(row.map_err(|e| format!("Failed to read row: {}", e))?);
     }
 
     Ok(history)
 }
+
+/// Get a command by ID: still pending, or its outcome from the history
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_command_status(command_id: String) -> Result<PendingCommand, String> {
+    {
+        let commands = PENDING_COMMANDS
+            .lock()
+            .map_err(|e| format!("Failed to lock pending commands: {}", e))?;
+        if let Some(cmd) = commands.iter().find(|c| c.id == command_id) {
+            return Ok(cmd.clone());
+        }
+    }
+
+    let conn = get_db_connection()?;
+    conn.query_row(
+        "SELECT id, command, reason, status, output, error, created_at, risk,
+                elapsed_ms, timed_out, output_truncated
+         FROM command_history WHERE id = ?1",
+        params![command_id],
+        command_from_row,
+    )
+    .optional()
+    .map_err(|e| format!("Failed to read command history: {}", e))?
+    .ok_or_else(|| format!("Command not found: {}", command_id))
+}
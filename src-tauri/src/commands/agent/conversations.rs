@@ -5,7 +5,7 @@ use rusqlite::params;
 use uuid::Uuid;
 
 use super::{
-    get_db_connection, Conversation, ConversationMessage, ConversationWithMessages,
+    close_agent_shell_session, delete_file_journal, get_db_connection, Conversation, ConversationMessage, ConversationWithMessages,
 };
 
 /// Create a new conversation
@@ -196,5 +196,9 @@ pub fn delete_conversation(conversation_id: String) -> Result<(), String> {
     )
     .map_err(|e| format!("Failed to delete conversation: {}", e))?;
 
+    // The conversation's shell session and file journal go with it
+    delete_file_journal(&conversation_id)?;
+    close_agent_shell_session(conversation_id)?;
+
     Ok(())
 }
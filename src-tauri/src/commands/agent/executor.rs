@@ -0,0 +1,442 @@
+//! Shell command executor
+//!
+//! Runs agent and MCP shell commands with a time limit and bounded output.
+//! Stdout and stderr are read as they arrive: the text is passed on to the
+//! caller in batches (the app forwards it to the frontend as `command-output`
+//! events) and kept within the output limit, keeping the start and the end
+//! of long output and counting what was dropped in between. A command that
+//! runs past its timeout is killed together with every process it started.
+
+use std::collections::VecDeque;
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::time::{Duration, Instant};
+
+use crate::services::kill_process_tree;
+use crate::types::{AgentSettings, CommandExecutionResult, CommandOutputStream};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// How often output is passed on while a command runs
+pub(super) const FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Most text passed on in one batch; a larger backlog keeps only its end
+const MAX_BATCH_BYTES: usize = 64 * 1024;
+
+/// How long to wait for output pipes to close once the command has exited or
+/// been killed (a detached grandchild can hold them open)
+pub(super) const PIPE_GRACE: Duration = Duration::from_secs(2);
+
+/// How often a command whose pipes have closed is checked for exit
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// =============================================================================
+// Limits
+// =============================================================================
+
+/// Time and output limits for one command
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ExecutionLimits {
+    pub timeout: Option<Duration>,
+    /// Bytes kept from each stream
+    pub max_output_bytes: Option<usize>,
+}
+
+impl ExecutionLimits {
+    pub fn from_settings(settings: &AgentSettings) -> Self {
+        Self {
+            timeout: (settings.command_timeout_secs > 0)
+                .then(|| Duration::from_secs(settings.command_timeout_secs)),
+            max_output_bytes: (settings.command_max_output_bytes > 0)
+                .then_some(settings.command_max_output_bytes as usize),
+        }
+    }
+}
+
+// =============================================================================
+// Output Capture
+// =============================================================================
+
+/// One stream's output within a byte budget: the first half of the budget
+/// holds the start of the output, the second half the most recent bytes
+pub(super) struct CappedOutput {
+    limit: Option<usize>,
+    head: Vec<u8>,
+    tail: VecDeque<u8>,
+    /// Bytes pushed, including any dropped
+    pub(super) total: u64,
+}
+
+impl CappedOutput {
+    pub(super) fn new(limit: Option<usize>) -> Self {
+        Self {
+            limit,
+            head: Vec::new(),
+            tail: VecDeque::new(),
+            total: 0,
+        }
+    }
+
+    pub(super) fn push(&mut self, bytes: &[u8]) {
+        self.total += bytes.len() as u64;
+        let Some(limit) = self.limit else {
+            self.head.extend_from_slice(bytes);
+            return;
+        };
+
+        let head_room = (limit / 2).saturating_sub(self.head.len());
+        let (head, rest) = bytes.split_at(head_room.min(bytes.len()));
+        self.head.extend_from_slice(head);
+        self.tail.extend(rest);
+        let tail_limit = limit - limit / 2;
+        if self.tail.len() > tail_limit {
+            self.tail.drain(..self.tail.len() - tail_limit);
+        }
+    }
+
+    pub(super) fn dropped(&self) -> u64 {
+        self.total - (self.head.len() + self.tail.len()) as u64
+    }
+
+    /// The kept output, with a marker where bytes were dropped
+    pub(super) fn into_text(mut self) -> String {
+        let dropped = self.dropped();
+        let head = String::from_utf8_lossy(&self.head);
+        let tail = String::from_utf8_lossy(self.tail.make_contiguous());
+        if dropped == 0 {
+            format!("{}{}", head, tail)
+        } else {
+            format!(
+                "{}\n\n[... {} bytes omitted ...]\n\n{}",
+                head, dropped, tail
+            )
+        }
+    }
+}
+
+/// Text not yet passed on for one stream
+#[derive(Default)]
+pub(super) struct PendingText {
+    bytes: Vec<u8>,
+    skipped: u64,
+}
+
+impl PendingText {
+    pub(super) fn push(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+        if self.bytes.len() > MAX_BATCH_BYTES {
+            let excess = self.bytes.len() - MAX_BATCH_BYTES;
+            self.bytes.drain(..excess);
+            self.skipped += excess as u64;
+        }
+    }
+
+    /// Take the complete UTF-8 text, leaving a character split across reads
+    /// for the next batch
+    pub(super) fn take(&mut self, all: bool) -> Option<String> {
+        let split = match std::str::from_utf8(&self.bytes) {
+            Ok(_) => self.bytes.len(),
+            Err(e) if e.error_len().is_none() && !all => e.valid_up_to(),
+            Err(_) => self.bytes.len(),
+        };
+        if split == 0 && self.skipped == 0 {
+            return None;
+        }
+        let rest = self.bytes.split_off(split);
+        let mut text =
+            String::from_utf8_lossy(&std::mem::replace(&mut self.bytes, rest)).into_owned();
+        if self.skipped > 0 {
+            text = format!("[... {} bytes skipped ...]\n{}", self.skipped, text);
+            self.skipped = 0;
+        }
+        Some(text)
+    }
+}
+
+// =============================================================================
+// Execution
+// =============================================================================
+
+/// The shell used for agent commands
+fn shell_command(command: &str) -> Command {
+    #[cfg(windows)]
+    {
+        let mut cmd = Command::new("powershell");
+        cmd.args(["-NoProfile", "-Command", command]);
+        cmd
+    }
+
+    #[cfg(not(windows))]
+    {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+/// Read a pipe to its end on a thread, sending each chunk
+fn spawn_reader(
+    pipe: Option<impl Read + Send + 'static>,
+    stream: CommandOutputStream,
+    tx: Sender<(CommandOutputStream, Vec<u8>)>,
+) {
+    let Some(mut pipe) = pipe else {
+        return;
+    };
+    std::thread::spawn(move || {
+        let mut buf = [0u8; 8192];
+        loop {
+            match pipe.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if tx.send((stream, buf[..n].to_vec())).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Wait for a child to exit, killing it at the deadline. Returns its exit
+/// code and whether it was killed.
+fn wait_for_exit(child: &mut Child, deadline: Option<Instant>) -> (i32, bool) {
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return (status.code().unwrap_or(-1), false),
+            Ok(None) => {}
+            Err(_) => return (-1, false),
+        }
+        if deadline.is_some_and(|d| Instant::now() >= d) {
+            kill_process_tree(child.id());
+            let _ = child.wait();
+            return (-1, true);
+        }
+        std::thread::sleep(EXIT_POLL_INTERVAL);
+    }
+}
+
+/// Run a shell command within `limits`. While it runs, `on_output` receives
+/// its output in batches.
+pub(crate) fn execute_shell_command(
+    command: &str,
+    limits: ExecutionLimits,
+    mut on_output: impl FnMut(CommandOutputStream, String),
+) -> Result<CommandExecutionResult, String> {
+    let started = Instant::now();
+    let deadline = limits.timeout.map(|t| started + t);
+
+    let mut child = shell_command(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to execute command: {}", e))?;
+
+    let (tx, rx) = mpsc::channel();
+    spawn_reader(child.stdout.take(), CommandOutputStream::Stdout, tx.clone());
+    spawn_reader(child.stderr.take(), CommandOutputStream::Stderr, tx);
+
+    let mut stdout = CappedOutput::new(limits.max_output_bytes);
+    let mut stderr = CappedOutput::new(limits.max_output_bytes);
+    let mut pending_out = PendingText::default();
+    let mut pending_err = PendingText::default();
+    let mut last_flush = Instant::now();
+    let mut killed = false;
+    let mut pipes_deadline: Option<Instant> = None;
+
+    let mut flush = |pending_out: &mut PendingText, pending_err: &mut PendingText, all: bool| {
+        if let Some(text) = pending_out.take(all) {
+            on_output(CommandOutputStream::Stdout, text);
+        }
+        if let Some(text) = pending_err.take(all) {
+            on_output(CommandOutputStream::Stderr, text);
+        }
+    };
+
+    loop {
+        match rx.recv_timeout(FLUSH_INTERVAL) {
+            Ok((CommandOutputStream::Stdout, bytes)) => {
+                stdout.push(&bytes);
+                pending_out.push(&bytes);
+            }
+            Ok((CommandOutputStream::Stderr, bytes)) => {
+                stderr.push(&bytes);
+                pending_err.push(&bytes);
+            }
+            // Both pipes closed
+            Err(RecvTimeoutError::Disconnected) => break,
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+
+        if last_flush.elapsed() >= FLUSH_INTERVAL {
+            flush(&mut pending_out, &mut pending_err, false);
+            last_flush = Instant::now();
+        }
+
+        if pipes_deadline.is_some_and(|d| Instant::now() >= d) {
+            break;
+        }
+        if pipes_deadline.is_none() {
+            if !killed && deadline.is_some_and(|d| Instant::now() >= d) {
+                kill_process_tree(child.id());
+                killed = true;
+            }
+            // The command is done but something still holds its pipes
+            if killed || matches!(child.try_wait(), Ok(Some(_))) {
+                pipes_deadline = Some(Instant::now() + PIPE_GRACE);
+            }
+        }
+    }
+    flush(&mut pending_out, &mut pending_err, true);
+
+    let (exit_code, timed_out) = if killed {
+        let _ = child.wait();
+        (-1, true)
+    } else {
+        wait_for_exit(&mut child, deadline)
+    };
+
+    let truncated = stdout.dropped() > 0 || stderr.dropped() > 0;
+    let stdout_bytes = stdout.total;
+    let stderr_bytes = stderr.total;
+    Ok(CommandExecutionResult {
+        exit_code,
+        stdout: stdout.into_text(),
+        stderr: stderr.into_text(),
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        timed_out,
+        stdout_bytes,
+        stderr_bytes,
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capped_output_keeps_head_and_tail() {
+        let mut output = CappedOutput::new(Some(10));
+        output.push(b"0123456789");
+        output.push(b"abcdefghij");
+        assert_eq!(output.total, 20);
+        assert_eq!(output.dropped(), 10);
+        assert_eq!(
+            output.into_text(),
+            "01234\n\n[... 10 bytes omitted ...]\n\nfghij"
+        );
+    }
+
+    #[test]
+    fn capped_output_within_limit_is_whole() {
+        let mut output = CappedOutput::new(Some(10));
+        for byte in b"abcdefghij" {
+            output.push(&[*byte]);
+        }
+        assert_eq!(output.dropped(), 0);
+        assert_eq!(output.into_text(), "abcdefghij");
+
+        let mut unlimited = CappedOutput::new(None);
+        unlimited.push(&[b'x'; 100_000]);
+        assert_eq!(unlimited.total, 100_000);
+        assert_eq!(unlimited.dropped(), 0);
+    }
+
+    #[test]
+    fn pending_text_holds_a_split_character() {
+        let mut pending = PendingText::default();
+        assert_eq!(pending.take(false), None);
+
+        pending.push(b"caf\xC3");
+        assert_eq!(pending.take(false).as_deref(), Some("caf"));
+        assert_eq!(pending.take(false), None);
+        pending.push(b"\xA9!");
+        assert_eq!(pending.take(false).as_deref(), Some("é!"));
+
+        // At the end a dangling byte is passed on rather than lost
+        pending.push(b"\xC3");
+        assert_eq!(pending.take(true).as_deref(), Some("\u{FFFD}"));
+    }
+
+    #[test]
+    fn pending_text_skips_an_oversized_backlog() {
+        let mut pending = PendingText::default();
+        pending.push(&vec![b'a'; MAX_BATCH_BYTES + 5]);
+        let text = pending.take(false).unwrap();
+        assert!(text.starts_with("[... 5 bytes skipped ...]\n"));
+        assert_eq!(
+            text.len() - "[... 5 bytes skipped ...]\n".len(),
+            MAX_BATCH_BYTES
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shell_output_is_capped_and_counted() {
+        let limits = ExecutionLimits {
+            timeout: Some(Duration::from_secs(30)),
+            max_output_bytes: Some(4),
+        };
+        let result =
+            execute_shell_command("printf abcdefghij; printf err >&2", limits, |_, _| {}).unwrap();
+        assert_eq!(result.exit_code, 0);
+        assert!(!result.timed_out);
+        assert_eq!(result.stdout, "ab\n\n[... 6 bytes omitted ...]\n\nij");
+        assert_eq!(result.stdout_bytes, 10);
+        assert_eq!(result.stderr, "err");
+        assert_eq!(result.stderr_bytes, 3);
+        assert!(result.truncated);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shell_output_keeps_characters_split_across_reads() {
+        let limits = ExecutionLimits {
+            timeout: Some(Duration::from_secs(30)),
+            max_output_bytes: None,
+        };
+        let mut batches = Vec::new();
+        let result = execute_shell_command(
+            r"printf 'caf\303'; sleep 0.5; printf '\251\n'",
+            limits,
+            |stream, text| {
+                if stream == CommandOutputStream::Stdout {
+                    batches.push(text);
+                }
+            },
+        )
+        .unwrap();
+        assert_eq!(result.stdout, "café\n");
+        assert!(batches.len() > 1, "{:?}", batches);
+        assert!(
+            batches.iter().all(|b| !b.contains('\u{FFFD}')),
+            "{:?}",
+            batches
+        );
+        assert_eq!(batches.concat(), "café\n");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn timeout_kills_the_command_and_its_children() {
+        let limits = ExecutionLimits {
+            timeout: Some(Duration::from_secs(1)),
+            max_output_bytes: None,
+        };
+        let result = execute_shell_command("sleep 30 & echo $!; wait", limits, |_, _| {}).unwrap();
+        assert!(result.timed_out);
+        assert_eq!(result.exit_code, -1);
+        assert!(result.elapsed_ms < 10_000, "{}", result.elapsed_ms);
+
+        // The grandchild is gone, or at most a zombie waiting to be reaped
+        let pid: u32 = result.stdout.trim().parse().unwrap();
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).unwrap_or_default();
+        let state = stat.rsplit(") ").next().and_then(|s| s.chars().next());
+        assert!(matches!(state, None | Some('Z') | Some('X')), "{}", stat);
+    }
+}
@@ -2,76 +2,13 @@
 
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
 
 use regex::Regex;
 
-use super::{get_data_dir_path, FileEntry, Instrument};
-
-/// Canonicalizes a path and blocks access to critical Windows system directories.
-///
-/// For new files (not yet on disk), canonicalizes the parent and re-appends the
-/// filename so that `../` tricks are resolved before the path is used.
-fn validate_agent_path(path: &std::path::Path) -> Result<PathBuf, String> {
-    let canonical = path.canonicalize().or_else(|_| {
-        if let Some(parent) = path.parent() {
-            let canonical_parent = parent
-                .canonicalize()
-                .map_err(|e| format!("Invalid path: {}", e))?;
-            Ok(canonical_parent
-                .join(path.file_name().ok_or("Invalid filename")?))
-        } else {
-            Err(format!("Invalid path: {}", path.display()))
-        }
-    })?;
-
-    // canonicalize() on Windows resolves 8.3 short names (e.g. PROGRA~1 -> Program Files)
-    let path_str = canonical.to_string_lossy().to_lowercase();
-
-    // Secondary defense: blocklist of critical system directories
-    let blocked = [
-        "\\windows\\system32",
-        "\\windows\\syswow64",
-        "\\windows\\winsxs",
-        "\\program files\\windowsapps",
-    ];
-
-    for blocked_path in &blocked {
-        if path_str.contains(blocked_path) {
-            return Err(format!(
-                "Access denied: cannot modify files in {}",
-                blocked_path
-            ));
-        }
-    }
-
-    // Primary defense: allowlist — path must be under an allowed directory
-    let data_dir = get_data_dir_path()
-        .canonicalize()
-        .unwrap_or_else(|_| get_data_dir_path());
-    let cwd = std::env::current_dir()
-        .ok()
-        .and_then(|p| p.canonicalize().ok());
-    let home_dir = dirs::home_dir().and_then(|p| p.canonicalize().ok());
-
-    let allowed_dirs: Vec<PathBuf> = [Some(data_dir), cwd, home_dir]
-        .into_iter()
-        .flatten()
-        .collect();
-
-    let is_allowed = allowed_dirs
-        .iter()
-        .any(|allowed| canonical.starts_with(allowed));
-
-    if !is_allowed {
-        return Err(format!(
-            "Access denied: path '{}' is outside allowed directories",
-            canonical.display()
-        ));
-    }
-
-    Ok(canonical)
-}
+use super::{
+    check_path_access, get_data_dir_path, journaled, FileEntry, FileOperationKind,
+    FilesystemSandbox, Instrument,
+};
 
 /// Read file with optional line numbers and pagination
 #[tauri::command(rename_all = "snake_case")]
@@ -81,7 +18,7 @@ pub fn agent_read_file(
     limit: Option<usize>,
     line_numbers: Option<bool>,
 ) -> Result<serde_json::Value, String> {
-    let safe_path = validate_agent_path(std::path::Path::new(&path))?;
+    let safe_path = check_path_access(&path)?;
     let content =
         fs::read_to_string(&safe_path).map_err(|e| format!("Failed to read file: {}", e))?;
 
@@ -118,28 +55,40 @@ pub fn agent_read_file(
 }
 
 /// Write to a file (requires approval in non-YOLO mode)
-/// Creates parent directories if they don't exist
+/// Creates parent directories if they don't exist. Journaled for undo;
+/// `force` allows replacing a binary or oversized file.
 #[tauri::command]
-pub fn agent_write_file(path: String, content: String) -> Result<(), String> {
-    let path_buf = PathBuf::from(&path);
-
+pub fn agent_write_file(
+    path: String,
+    content: String,
+    conversation_id: Option<String>,
+    force: Option<bool>,
+) -> Result<(), String> {
     // Validate path BEFORE creating any directories
-    let safe_path = validate_agent_path(&path_buf)?;
-
-    // Create parent directories if they don't exist
-    if let Some(parent) = safe_path.parent() {
-        if !parent.exists() {
-            fs::create_dir_all(parent)
-                .map_err(|e| format!("Failed to create parent directories: {}", e))?;
-        }
-    }
-
-    fs::write(&safe_path, content).map_err(|e| format!("Failed to write file: {}", e))
+    let safe_path = check_path_access(&path)?;
+
+    journaled(
+        conversation_id.as_deref(),
+        FileOperationKind::Write,
+        &safe_path,
+        None,
+        force.unwrap_or(false),
+        || {
+            // Create parent directories only once the write is journaled
+            if let Some(parent) = safe_path.parent() {
+                if !parent.exists() {
+                    fs::create_dir_all(parent)
+                        .map_err(|e| format!("Failed to create parent directories: {}", e))?;
+                }
+            }
+            fs::write(&safe_path, content).map_err(|e| format!("Failed to write file: {}", e))
+        },
+    )
 }
 
 #[tauri::command]
 pub fn agent_list_dir(path: String) -> Result<Vec<FileEntry>, String> {
-    let safe_path = validate_agent_path(std::path::Path::new(&path))?;
+    let safe_path = check_path_access(&path)?;
     let mut entries = Vec::new();
     for entry in fs::read_dir(&safe_path).map_err(|e| format!("Failed to read dir: {}", e))? {
         let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
@@ -162,20 +111,52 @@ pub fn agent_list_dir(path: String) -> Result<Vec<FileEntry>, String> {
     Ok(entries)
 }
 
+/// Move a file, journaled for undo. `force` allows replacing an oversized
+/// file at the destination.
 #[tauri::command]
-pub fn agent_move_file(src: String, dest: String) -> Result<(), String> {
-    let safe_src = validate_agent_path(std::path::Path::new(&src))?;
-    let safe_dest = validate_agent_path(std::path::Path::new(&dest))?;
-    fs::rename(&safe_src, &safe_dest).map_err(|e| format!("Failed to move file: {}", e))
+pub fn agent_move_file(
+    src: String,
+    dest: String,
+    conversation_id: Option<String>,
+    force: Option<bool>,
+) -> Result<(), String> {
+    let sandbox = FilesystemSandbox::load()?;
+    let safe_src = sandbox.check(&src)?;
+    let safe_dest = sandbox.check(&dest)?;
+    journaled(
+        conversation_id.as_deref(),
+        FileOperationKind::Move,
+        &safe_dest,
+        Some(&safe_src),
+        force.unwrap_or(false),
+        || fs::rename(&safe_src, &safe_dest).map_err(|e| format!("Failed to move file: {}", e)),
+    )
 }
 
+/// Copy a file, journaled for undo. `force` allows replacing an oversized
+/// file at the destination.
 #[tauri::command]
-pub fn agent_copy_file(src: String, dest: String) -> Result<(), String> {
-    let safe_src = validate_agent_path(std::path::Path::new(&src))?;
-    let safe_dest = validate_agent_path(std::path::Path::new(&dest))?;
-    fs::copy(&safe_src, &safe_dest)
-        .map(|_| ())
-        .map_err(|e| format!("Failed to copy file: {}", e))
+pub fn agent_copy_file(
+    src: String,
+    dest: String,
+    conversation_id: Option<String>,
+    force: Option<bool>,
+) -> Result<(), String> {
+    let sandbox = FilesystemSandbox::load()?;
+    let safe_src = sandbox.check(&src)?;
+    let safe_dest = sandbox.check(&dest)?;
+    journaled(
+        conversation_id.as_deref(),
+        FileOperationKind::Copy,
+        &safe_dest,
+        Some(&safe_src),
+        force.unwrap_or(false),
+        || {
+            fs::copy(&safe_src, &safe_dest)
+                .map(|_| ())
+                .map_err(|e| format!("Failed to copy file: {}", e))
+        },
+    )
 }
 
 /// List instruments (custom scripts)
@@ -318,15 +299,20 @@ pub fn agent_find_exe(query: String, search_path: Option<bool>) -> Result<Vec<St
     Ok(matches)
 }
 
-/// Edit file by replacing old_string with new_string
+/// Edit file by replacing old_string with new_string. Journaled for undo;
+/// `force` allows editing an oversized file.
 #[tauri::command(rename_all = "snake_case")]
 pub fn agent_edit_file(
     path: String,
     old_string: String,
     new_string: String,
     all: Option<bool>,
+    conversation_id: Option<String>,
+    force: Option<bool>,
 ) -> Result<serde_json::Value, String> {
-    let text = fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let safe_path = check_path_access(&path)?;
+    let text =
+        fs::read_to_string(&safe_path).map_err(|e| format!("Failed to read file: {}", e))?;
 
     let replace_all = all.unwrap_or(false);
 
@@ -353,7 +339,14 @@ pub fn agent_edit_file(
         text.replacen(&old_string, &new_string, 1)
     };
 
-    fs::write(&path, replacement).map_err(|e| format!("Failed to write file: {}", e))?;
+    journaled(
+        conversation_id.as_deref(),
+        FileOperationKind::Edit,
+        &safe_path,
+        None,
+        force.unwrap_or(false),
+        || fs::write(&safe_path, replacement).map_err(|e| format!("Failed to write file: {}", e)),
+    )?;
 
     let replacements = if replace_all { count } else { 1 };
 
@@ -375,8 +368,8 @@ pub fn agent_grep(
     let regex = Regex::new(&pattern).map_err(|e| format!("Invalid regex pattern: {}", e))?;
 
     let base_path = path.unwrap_or_else(|| ".".to_string());
-    let base_path_buf = PathBuf::from(&base_path);
-    validate_agent_path(&base_path_buf)?;
+    let sandbox = FilesystemSandbox::load()?;
+    sandbox.check(&base_path)?;
 
     let max = max_results.unwrap_or(50);
     let glob_pat = file_pattern.unwrap_or_else(|| "*".to_string());
@@ -390,7 +383,8 @@ pub fn agent_grep(
         .map_err(|e| format!("Invalid glob pattern: {}", e))?
         .flatten()
     {
-        if !entry.is_file() {
+        // Skip files the policy protects, e.g. `.ssh` under the home folder
+        if !entry.is_file() || !sandbox.permits(&entry) {
             continue;
         }
 
@@ -423,8 +417,8 @@ pub fn agent_glob(
     limit: Option<usize>,
 ) -> Result<Vec<serde_json::Value>, String> {
     let base_path = path.unwrap_or_else(|| ".".to_string());
-    let base_path_buf = PathBuf::from(&base_path);
-    validate_agent_path(&base_path_buf)?;
+    let sandbox = FilesystemSandbox::load()?;
+    sandbox.check(&base_path)?;
 
     let max = limit.unwrap_or(100);
 
@@ -435,6 +429,7 @@ pub fn agent_glob(
     for entry in glob::glob(&full_pattern)
         .map_err(|e| format!("Invalid glob pattern: {}", e))?
         .flatten()
+        .filter(|entry| sandbox.permits(entry))
     {
         if let Ok(metadata) = fs::metadata(&entry) {
             let mtime = metadata
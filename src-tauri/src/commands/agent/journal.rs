@@ -0,0 +1,428 @@
+//! File operation journal
+//!
+//! Before the agent writes, edits, moves or copies a file, whatever the
+//! operation would replace is snapshotted into the data dir and the operation
+//! is recorded in the `file_journal` table against its conversation. An
+//! operation can then be undone on its own, or together with everything the
+//! conversation did after it.
+//!
+//! Undo puts back the replaced file, removes a file the operation created,
+//! and moves a moved file back. It refuses to touch a file that has changed
+//! since the operation unless forced.
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::{get_agent_dir, get_db_connection, FileJournalEntry, FileOperationKind};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Largest file any operation may replace without `force`, which bounds
+/// the snapshots kept in the data dir
+const MAX_REPLACED_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Bytes read from the start of a file to decide whether it is binary
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+// =============================================================================
+// Snapshots
+// =============================================================================
+
+pub(super) fn snapshot_dir() -> PathBuf {
+    get_agent_dir().join("journal")
+}
+
+fn snapshot_path(entry_id: &str) -> PathBuf {
+    snapshot_dir().join(entry_id)
+}
+
+/// SHA-256 of a file, or `None` if it isn't a readable file
+fn file_checksum(path: &Path) -> Option<String> {
+    if !path.is_file() {
+        return None;
+    }
+    let mut file = fs::File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).ok()?;
+    Some(hex::encode(hasher.finalize()))
+}
+
+/// Whether a file looks binary (has a NUL byte near the start)
+fn is_binary(path: &Path) -> bool {
+    let Ok(file) = fs::File::open(path) else {
+        return false;
+    };
+    let mut head = Vec::with_capacity(BINARY_SNIFF_BYTES);
+    if file
+        .take(BINARY_SNIFF_BYTES as u64)
+        .read_to_end(&mut head)
+        .is_err()
+    {
+        return false;
+    }
+    head.contains(&0)
+}
+
+/// Refuse to replace (and so snapshot) an oversized file
+fn check_replaced_size(path: &Path, size: u64) -> Result<(), String> {
+    if size > MAX_REPLACED_BYTES {
+        return Err(format!(
+            "Refusing to overwrite {}: it is {} bytes, over the {} byte limit for journaled writes. \
+             Pass force to overwrite it anyway.",
+            path.display(),
+            size,
+            MAX_REPLACED_BYTES
+        ));
+    }
+    Ok(())
+}
+
+/// Refuse to let a text write replace a binary file
+fn check_text_target(path: &Path) -> Result<(), String> {
+    if is_binary(path) {
+        return Err(format!(
+            "Refusing to overwrite {}: it looks like a binary file. Pass force to overwrite it anyway.",
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Recording
+// =============================================================================
+
+/// An operation about to run, with the snapshot of what it will replace
+struct PendingEntry {
+    entry: FileJournalEntry,
+    snapshot: Option<PathBuf>,
+}
+
+impl PendingEntry {
+    fn begin(
+        conversation_id: Option<&str>,
+        operation: FileOperationKind,
+        path: &Path,
+        source: Option<&Path>,
+        force: bool,
+    ) -> Result<Self, String> {
+        let id = Uuid::new_v4().to_string();
+        let replaced = fs::metadata(path).ok().filter(|m| m.is_file());
+
+        let snapshot = match &replaced {
+            Some(metadata) => {
+                if !force {
+                    check_replaced_size(path, metadata.len())?;
+                    if matches!(
+                        operation,
+                        FileOperationKind::Write | FileOperationKind::Edit
+                    ) {
+                        check_text_target(path)?;
+                    }
+                }
+                let snapshot = snapshot_path(&id);
+                fs::create_dir_all(snapshot_dir())
+                    .map_err(|e| format!("Failed to create journal directory: {}", e))?;
+                fs::copy(path, &snapshot).map_err(|e| {
+                    format!(
+                        "Failed to snapshot {} before changing it: {}",
+                        path.display(),
+                        e
+                    )
+                })?;
+                Some(snapshot)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            entry: FileJournalEntry {
+                id,
+                conversation_id: conversation_id.map(str::to_string),
+                operation,
+                path: path.to_string_lossy().to_string(),
+                source: source.map(|s| s.to_string_lossy().to_string()),
+                replaced_bytes: replaced.map(|m| m.len()),
+                result_checksum: None,
+                created_at: Utc::now().to_rfc3339(),
+                undone_at: None,
+            },
+            snapshot,
+        })
+    }
+
+    /// The operation succeeded: record it
+    fn record(mut self) -> Result<(), String> {
+        self.entry.result_checksum = file_checksum(Path::new(&self.entry.path));
+        let entry = &self.entry;
+        let conn = get_db_connection()?;
+        conn.execute(
+            "INSERT INTO file_journal (id, conversation_id, operation, path, source, replaced_bytes,
+                                       result_checksum, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                entry.id,
+                entry.conversation_id,
+                operation_str(entry.operation),
+                entry.path,
+                entry.source,
+                entry.replaced_bytes.map(|b| b as i64),
+                entry.result_checksum,
+                entry.created_at
+            ],
+        )
+        .map_err(|e| format!("Failed to record file operation: {}", e))?;
+        Ok(())
+    }
+
+    /// The operation failed: nothing to undo
+    fn discard(self) {
+        if let Some(snapshot) = self.snapshot {
+            let _ = fs::remove_file(snapshot);
+        }
+    }
+}
+
+/// Run a file operation on `path`, journaling it for `conversation_id`.
+/// No operation replaces a file over `MAX_REPLACED_BYTES`, and writes and
+/// edits don't replace binary files, unless `force` is set.
+pub(super) fn journaled<T>(
+    conversation_id: Option<&str>,
+    operation: FileOperationKind,
+    path: &Path,
+    source: Option<&Path>,
+    force: bool,
+    run: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+    let pending = PendingEntry::begin(conversation_id, operation, path, source, force)?;
+    match run() {
+        Ok(value) => {
+            pending.record()?;
+            Ok(value)
+        }
+        Err(e) => {
+            pending.discard();
+            Err(e)
+        }
+    }
+}
+
+// =============================================================================
+// Undo
+// =============================================================================
+
+fn operation_str(operation: FileOperationKind) -> &'static str {
+    match operation {
+        FileOperationKind::Write => "write",
+        FileOperationKind::Edit => "edit",
+        FileOperationKind::Move => "move",
+        FileOperationKind::Copy => "copy",
+    }
+}
+
+/// Map a `file_journal` row (id, conversation_id, operation, path, source,
+/// replaced_bytes, result_checksum, created_at, undone_at) to an entry
+fn entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<FileJournalEntry> {
+    let operation: String = row.get(2)?;
+    let operation = match operation.as_str() {
+        "edit" => FileOperationKind::Edit,
+        "move" => FileOperationKind::Move,
+        "copy" => FileOperationKind::Copy,
+        _ => FileOperationKind::Write,
+    };
+    Ok(FileJournalEntry {
+        id: row.get(0)?,
+        conversation_id: row.get(1)?,
+        operation,
+        path: row.get(3)?,
+        source: row.get(4)?,
+        replaced_bytes: row.get::<_, Option<i64>>(5)?.map(|b| b as u64),
+        result_checksum: row.get(6)?,
+        created_at: row.get(7)?,
+        undone_at: row.get(8)?,
+    })
+}
+
+const ENTRY_COLUMNS: &str = "id, conversation_id, operation, path, source, replaced_bytes, \
+                             result_checksum, created_at, undone_at";
+
+/// Reverse one operation on disk
+fn undo_entry(entry: &FileJournalEntry, force: bool) -> Result<(), String> {
+    let path = Path::new(&entry.path);
+    if !force && entry.result_checksum.is_some() && file_checksum(path) != entry.result_checksum {
+        return Err(format!(
+            "{} has changed since the agent's {} of it. Undo with force to discard the changes.",
+            entry.path,
+            operation_str(entry.operation)
+        ));
+    }
+
+    if entry.operation == FileOperationKind::Move {
+        let source = entry
+            .source
+            .as_deref()
+            .ok_or_else(|| "Journal entry for a move has no source".to_string())?;
+        if !force && Path::new(source).exists() {
+            return Err(format!(
+                "Can't move {} back: {} exists again. Undo with force to replace it.",
+                entry.path, source
+            ));
+        }
+        fs::rename(path, source)
+            .map_err(|e| format!("Failed to move {} back to {}: {}", entry.path, source, e))?;
+    }
+
+    // Put back what the operation replaced, or remove what it created
+    if entry.replaced_bytes.is_some() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to recreate {}: {}", parent.display(), e))?;
+        }
+        fs::copy(snapshot_path(&entry.id), path)
+            .map_err(|e| format!("Failed to restore {}: {}", entry.path, e))?;
+    } else if entry.operation != FileOperationKind::Move && path.exists() {
+        fs::remove_file(path).map_err(|e| format!("Failed to remove {}: {}", entry.path, e))?;
+    }
+    Ok(())
+}
+
+/// Undo an entry and mark it undone
+fn undo_and_mark(
+    conn: &rusqlite::Connection,
+    mut entry: FileJournalEntry,
+    force: bool,
+) -> Result<FileJournalEntry, String> {
+    if entry.undone_at.is_some() {
+        return Err(format!("Operation {} has already been undone", entry.id));
+    }
+    undo_entry(&entry, force)?;
+
+    let undone_at = Utc::now().to_rfc3339();
+    conn.execute(
+        "UPDATE file_journal SET undone_at = ?1 WHERE id = ?2",
+        params![undone_at, entry.id],
+    )
+    .map_err(|e| format!("Failed to update file journal: {}", e))?;
+    let _ = fs::remove_file(snapshot_path(&entry.id));
+
+    entry.undone_at = Some(undone_at);
+    Ok(entry)
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// List a conversation's file operations, newest first
+#[tauri::command]
+pub fn list_file_journal(conversation_id: Option<String>) -> Result<Vec<FileJournalEntry>, String> {
+    let conn = get_db_connection()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM file_journal WHERE conversation_id IS ?1 ORDER BY rowid DESC",
+            ENTRY_COLUMNS
+        ))
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+    let rows = stmt
+        .query_map(params![conversation_id], entry_from_row)
+        .map_err(|e| format!("Failed to execute query: {}", e))?;
+
+    let mut entries = Vec::new();
+    for row in rows {
+        entries.push(row.map_err(|e| format!("Failed to read row: {}", e))?);
+    }
+    Ok(entries)
+}
+
+/// Undo one file operation. `force` undoes it even if the file has changed
+/// since.
+#[tauri::command]
+pub fn undo_file_operation(
+    entry_id: String,
+    force: Option<bool>,
+) -> Result<FileJournalEntry, String> {
+    let conn = get_db_connection()?;
+    let entry = conn
+        .query_row(
+            &format!("SELECT {} FROM file_journal WHERE id = ?1", ENTRY_COLUMNS),
+            params![entry_id],
+            entry_from_row,
+        )
+        .optional()
+        .map_err(|e| format!("Failed to read file journal: {}", e))?
+        .ok_or_else(|| format!("Journal entry not found: {}", entry_id))?;
+
+    undo_and_mark(&conn, entry, force.unwrap_or(false))
+}
+
+/// Undo a file operation and every later one in its conversation, newest
+/// first. Stops at the first operation that can't be undone. Returns the
+/// entries undone.
+#[tauri::command]
+pub fn rollback_file_journal(
+    entry_id: String,
+    force: Option<bool>,
+) -> Result<Vec<FileJournalEntry>, String> {
+    let conn = get_db_connection()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM file_journal
+             WHERE conversation_id IS (SELECT conversation_id FROM file_journal WHERE id = ?1)
+               AND rowid >= (SELECT rowid FROM file_journal WHERE id = ?1)
+               AND undone_at IS NULL
+             ORDER BY rowid DESC",
+            ENTRY_COLUMNS
+        ))
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+    let entries = stmt
+        .query_map(params![entry_id], entry_from_row)
+        .map_err(|e| format!("Failed to execute query: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read row: {}", e))?;
+    if entries.is_empty() {
+        return Err(format!("Nothing to undo from journal entry {}", entry_id));
+    }
+
+    let force = force.unwrap_or(false);
+    let mut undone = Vec::new();
+    for entry in entries {
+        match undo_and_mark(&conn, entry, force) {
+            Ok(entry) => undone.push(entry),
+            Err(e) => {
+                return Err(format!(
+                    "Undid {} operation{}, then stopped: {}",
+                    undone.len(),
+                    if undone.len() == 1 { "" } else { "s" },
+                    e
+                ));
+            }
+        }
+    }
+    Ok(undone)
+}
+
+/// Drop a conversation's journal and snapshots (when it is deleted)
+pub(super) fn delete_file_journal(conversation_id: &str) -> Result<(), String> {
+    let entries = list_file_journal(Some(conversation_id.to_string()))?;
+    for entry in &entries {
+        let _ = fs::remove_file(snapshot_path(&entry.id));
+    }
+
+    let conn = get_db_connection()?;
+    conn.execute(
+        "DELETE FROM file_journal WHERE conversation_id = ?1",
+        params![conversation_id],
+    )
+    .map_err(|e| format!("Failed to delete file journal: {}", e))?;
+    Ok(())
+}
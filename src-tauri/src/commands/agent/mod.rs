@@ -6,26 +6,38 @@
 pub mod attachments;
 pub mod commands;
 pub mod conversations;
+pub mod executor;
 pub mod files;
+pub mod journal;
+pub mod risk;
+pub mod sandbox;
 pub mod search;
+pub mod session;
 
 pub use attachments::*;
 pub use commands::*;
 pub use conversations::*;
+pub use executor::*;
 pub use files::*;
+pub use journal::*;
+pub use risk::*;
+pub use sandbox::*;
 pub use search::*;
+pub use session::*;
 
 use std::fs;
 use std::path::PathBuf;
-use std::sync::Mutex;
+use std::sync::{Condvar, Mutex};
 
 use rusqlite::Connection;
 
 pub(super) use super::data_dir::get_data_dir_path;
 pub(super) use super::settings::get_settings;
 use crate::types::{
-    AgentSettings, ApprovalMode, CommandExecutionResult, CommandStatus, Conversation,
-    ConversationMessage, ConversationWithMessages, PendingCommand,
+    AgentSettings, ApprovalDecision, ApprovalMode, CommandExecutionResult, CommandOutputEvent, CommandRisk,
+    CommandRiskClass, CommandStatus, Conversation,
+    ConversationMessage, ConversationWithMessages, FileJournalEntry, FileOperationKind,
+    FilesystemPolicy, PathPolicyRule, PathRuleKind, PendingCommand,
     SearchResult,
 };
 
@@ -52,6 +64,10 @@ pub struct Instrument {
 /// Pending commands awaiting approval
 pub(super) static PENDING_COMMANDS: Mutex<Vec<PendingCommand>> = Mutex::new(Vec::new());
 
+/// Signalled (with `PENDING_COMMANDS`) when a pending command is approved,
+/// rejected or cleared
+pub(super) static COMMAND_DECIDED: Condvar = Condvar::new();
+
 // =============================================================================
 // Machine Identification
 // =============================================================================
@@ -95,6 +111,30 @@ pub(super) fn ensure_agent_dir() -> Result<(), String> {
     Ok(())
 }
 
+/// Add a column to an existing table unless an earlier run already did
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<(), String> {
+    let exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info(?1) WHERE name = ?2",
+            [table, column],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to inspect {} table: {}", table, e))?;
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )
+        .map_err(|e| format!("Failed to add {} column to {}: {}", column, table, e))?;
+    }
+    Ok(())
+}
+
 pub(super) fn get_db_connection() -> Result<Connection, String> {
     ensure_agent_dir()?;
     let path = get_memory_db_path();
@@ -116,6 +156,40 @@ pub(super) fn get_db_connection() -> Result<Connection, String> {
     )
     .map_err(|e| format!("Failed to create command_history table: {}", e))?;
 
+    // Columns added after the table was first released
+    add_column_if_missing(&conn, "command_history", "risk", "TEXT")?;
+    add_column_if_missing(&conn, "command_history", "elapsed_ms", "INTEGER")?;
+    add_column_if_missing(&conn, "command_history", "timed_out", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(
+        &conn,
+        "command_history",
+        "output_truncated",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+
+    // Agent file operations, for undo
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS file_journal (
+            id TEXT PRIMARY KEY,
+            conversation_id TEXT,
+            operation TEXT NOT NULL,
+            path TEXT NOT NULL,
+            source TEXT,
+            replaced_bytes INTEGER,
+            result_checksum TEXT,
+            created_at TEXT NOT NULL,
+            undone_at TEXT
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create file_journal table: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_file_journal_conv_id ON file_journal(conversation_id)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create file journal index: {}", e))?;
+
     // Conversations table for chat persistence
     conn.execute(
         "CREATE TABLE IF NOT EXISTS conversations (
@@ -0,0 +1,1896 @@
+//! Command risk analysis
+//!
+//! Sorts a PowerShell or cmd command line into risk classes before it runs,
+//! so approval decisions can look past the whitelist. The command is split
+//! into statements (pipelines, `;`, `&&`, script blocks, subexpressions),
+//! each statement's command name is resolved through the common aliases, and
+//! known cmdlets and programs are judged on their parameters. Nested
+//! `powershell -Command`, `-EncodedCommand` and `cmd /c` invocations are
+//! analysed as well, as are the commands run by wrappers such as `env`,
+//! `xargs` and `find -exec`. Method calls count as changes unless they only
+//! read. Commands that aren't recognised count as modifying the system.
+
+use std::sync::LazyLock;
+
+use base64::Engine;
+use regex::Regex;
+
+use crate::types::{CommandRisk, CommandRiskClass, CommandRiskReason};
+
+use CommandRiskClass::{CredentialAccess, Destructive, ModifiesSystem, NetworkEgress, ReadOnly};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// How deeply nested shells are followed
+const MAX_NESTING: usize = 4;
+
+/// PowerShell verbs that only read or report
+const READ_VERBS: &[&str] = &[
+    "get",
+    "test",
+    "select",
+    "measure",
+    "format",
+    "where",
+    "foreach",
+    "sort",
+    "group",
+    "resolve",
+    "find",
+    "search",
+    "show",
+    "read",
+    "compare",
+    "convertto",
+    "convertfrom",
+    "join",
+    "split",
+    "write",
+    "wait",
+    "watch",
+    "trace",
+    "use",
+    "confirm",
+    "debug",
+    "ping",
+];
+
+/// Cmdlets whose verb suggests a change but which only affect the session
+const SESSION_CMDLETS: &[&str] = &[
+    "set-location",
+    "push-location",
+    "pop-location",
+    "set-variable",
+    "new-variable",
+    "remove-variable",
+    "clear-variable",
+    "clear-host",
+    "start-sleep",
+    "import-module",
+    "remove-module",
+    "new-object",
+    "new-timespan",
+    "new-guid",
+    "set-strictmode",
+    "add-member",
+    "out-string",
+    "out-null",
+    "out-host",
+    "out-default",
+    "out-gridview",
+    "tee-object",
+    "new-psdrive",
+    "remove-psdrive",
+    "new-pssessionoption",
+    "new-ciminstance",
+];
+
+/// Programs that only read or report
+const READ_ONLY_PROGRAMS: &[&str] = &[
+    "ipconfig",
+    "systeminfo",
+    "tasklist",
+    "hostname",
+    "whoami",
+    "ver",
+    "timeout",
+    "findstr",
+    "where",
+    "ping",
+    "tracert",
+    "pathping",
+    "nslookup",
+    "netstat",
+    "getmac",
+    "arp",
+    "driverquery",
+    "qwinsta",
+    "quser",
+    "query",
+    "nbtstat",
+    "gpresult",
+    "winver",
+    "tree",
+    "more",
+    "fc",
+    "comp",
+    "echo",
+    "uname",
+    "df",
+    "du",
+    "free",
+    "uptime",
+    "ps",
+    "top",
+    "lsblk",
+    "lscpu",
+    "lsusb",
+    "lspci",
+    "id",
+    "printenv",
+    "which",
+    "file",
+    "stat",
+    "head",
+    "tail",
+    "wc",
+    "grep",
+    "less",
+    "dmesg",
+    "journalctl",
+    "date",
+    "true",
+    "false",
+    "test",
+    "pwd",
+    "cd",
+];
+
+/// .NET methods that only read or convert values. Any other method call
+/// counts as a change, since objects such as files, processes and WMI
+/// instances act on the system through their methods.
+const READ_ONLY_METHODS: &[&str] = &[
+    "tostring",
+    "trim",
+    "trimstart",
+    "trimend",
+    "split",
+    "substring",
+    "replace",
+    "tolower",
+    "toupper",
+    "tolowerinvariant",
+    "toupperinvariant",
+    "contains",
+    "startswith",
+    "endswith",
+    "indexof",
+    "lastindexof",
+    "padleft",
+    "padright",
+    "equals",
+    "compareto",
+    "gettype",
+    "gethashcode",
+    "getenumerator",
+    "toarray",
+    "where",
+    "foreach",
+    "getvalue",
+    "getvaluenames",
+    "getsubkeynames",
+];
+
+/// .NET and WMI methods that delete, end or wipe what they are called on
+const DESTRUCTIVE_METHODS: &[&str] = &[
+    "delete",
+    "deletesubkey",
+    "deletesubkeytree",
+    "deletevalue",
+    "kill",
+    "terminate",
+    "format",
+    "uninstall",
+];
+
+/// Control-flow keywords. The statement after `else`, `do` and the like is
+/// analysed; declarations are skipped.
+const KEYWORDS: &[&str] = &[
+    "if", "elseif", "else", "for", "foreach", "while", "do", "until", "switch", "try", "catch",
+    "finally", "trap", "return", "break", "continue", "exit", "param", "begin", "process", "end",
+    "function", "filter", "throw", "rem", "setlocal", "endlocal", "goto", "call",
+];
+
+/// Hives that hold password hashes and LSA secrets
+static SECRET_HIVE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\bhk(?:lm|ey_local_machine)\\(?:sam|security|system)\b")
+        .expect("valid secret hive regex")
+});
+
+/// Constructs found anywhere in the text, whatever command they appear in
+static TEXT_PATTERNS: LazyLock<Vec<(Regex, CommandRiskClass, &'static str, &'static str)>> =
+    LazyLock::new(|| {
+        [
+            (
+                r"(?i)\blsass\b",
+                CredentialAccess,
+                "lsass",
+                "Targets the LSASS process, which holds logon credentials",
+            ),
+            (
+                r"(?i)\\config\\(?:sam|security)\b|\bhk(?:lm|ey_local_machine)\\(?:sam|security)\b",
+                CredentialAccess,
+                "SAM",
+                "Touches the SAM or SECURITY hive, which holds password hashes",
+            ),
+            (
+                r"(?i)\\Microsoft\\(?:Credentials|Protect|Vault)\\|\\Login Data\b|\blogins\.json\b|\bkey[34]\.db\b|\bntds\.dit\b",
+                CredentialAccess,
+                "credential store",
+                "Reads files where Windows or browsers keep saved passwords",
+            ),
+            (
+                r"(?i)[\\/]\.ssh[\\/]id_|\.aws[\\/]credentials\b|\.git-credentials\b",
+                CredentialAccess,
+                "private keys",
+                "Reads private keys or stored credentials",
+            ),
+            (
+                r"(?i)SecureStringToBSTR|\.GetNetworkCredential\(\)|ProtectedData\]::Unprotect",
+                CredentialAccess,
+                "secure string",
+                "Decrypts a secure string or protected data",
+            ),
+            (
+                r"(?i)\bMiniDump\b",
+                CredentialAccess,
+                "MiniDump",
+                "Dumps process memory, a common way to steal credentials",
+            ),
+            (
+                r"(?i)\bNet\.(?:WebClient|Http\.HttpClient|Sockets\.TcpClient)\b|\b(?:Download|Upload)(?:String|File|Data)\s*\(",
+                NetworkEgress,
+                ".NET networking",
+                "Connects to the network through .NET",
+            ),
+            (
+                r"(?i)\[(?:System\.)?IO\.(?:File|Directory)\]::Delete\b",
+                Destructive,
+                "[IO.File]::Delete",
+                "Deletes files through .NET",
+            ),
+            (
+                r"(?i)\[(?:System\.)?IO\.File\]::(?:WriteAll\w*|AppendAll\w*|Move|Copy|Replace|Create)\b",
+                ModifiesSystem,
+                "[IO.File]",
+                "Writes files through .NET",
+            ),
+        ]
+        .into_iter()
+        .map(|(pattern, class, command, detail)| {
+            (
+                Regex::new(pattern).expect("valid risk pattern"),
+                class,
+                command,
+                detail,
+            )
+        })
+        .collect()
+    });
+
+// =============================================================================
+// Tokenizer
+// =============================================================================
+
+#[derive(Debug, Clone)]
+struct Token {
+    text: String,
+    /// Whether any part of the token was quoted
+    quoted: bool,
+}
+
+impl Token {
+    fn bare(text: &str) -> Self {
+        Token {
+            text: text.to_string(),
+            quoted: false,
+        }
+    }
+
+    fn is(&self, text: &str) -> bool {
+        !self.quoted && self.text.eq_ignore_ascii_case(text)
+    }
+}
+
+#[derive(Default)]
+struct Tokenizer {
+    statements: Vec<Vec<Token>>,
+    current: Vec<Token>,
+    word: String,
+    quoted: bool,
+    /// Double-quoted strings with `$(...)` subexpressions, analysed separately
+    embedded: Vec<String>,
+    /// Names of methods called with `.Name(`
+    methods: Vec<String>,
+}
+
+impl Tokenizer {
+    fn end_word(&mut self) {
+        if !self.word.is_empty() || self.quoted {
+            self.current.push(Token {
+                text: std::mem::take(&mut self.word),
+                quoted: self.quoted,
+            });
+            self.quoted = false;
+        }
+    }
+
+    fn end_statement(&mut self) {
+        self.end_word();
+        if !self.current.is_empty() {
+            self.statements.push(std::mem::take(&mut self.current));
+        }
+    }
+
+    fn at_statement_start(&self) -> bool {
+        self.current.is_empty() && self.word.is_empty() && !self.quoted
+    }
+
+    /// Before a `(`: note a method call if the word ends in `.Name`
+    fn note_method_call(&mut self) {
+        if self.quoted {
+            return;
+        }
+        let Some((_, name)) = self.word.rsplit_once('.') else {
+            return;
+        };
+        if name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            self.methods.push(name.to_string());
+        }
+    }
+}
+
+/// A command line split into statements, with what needs analysing apart
+/// from them
+struct Tokenized {
+    statements: Vec<Vec<Token>>,
+    embedded: Vec<String>,
+    methods: Vec<String>,
+}
+
+/// Split a command line into statements of tokens. Statements end at `;`,
+/// `&&`, `||`, `|`, `&`, line breaks, braces and parentheses, so script
+/// blocks and subexpressions become statements of their own. A leading `&`
+/// (PowerShell's call operator) is kept as a token. Method calls are
+/// collected as they are found, since `(` splits them from their object.
+fn tokenize(text: &str) -> Tokenized {
+    let mut t = Tokenizer::default();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                t.quoted = true;
+                while let Some(c) = chars.next() {
+                    if c != '\'' {
+                        t.word.push(c);
+                    } else if chars.peek() == Some(&'\'') {
+                        chars.next();
+                        t.word.push('\'');
+                    } else {
+                        break;
+                    }
+                }
+            }
+            '"' => {
+                t.quoted = true;
+                let start = t.word.len();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '`' => t.word.extend(chars.next()),
+                        _ => t.word.push(c),
+                    }
+                }
+                if t.word[start..].contains("$(") {
+                    t.embedded.push(t.word[start..].to_string());
+                }
+            }
+            // PowerShell escape and line continuation, cmd escape
+            '`' | '^' => match chars.next() {
+                Some('\r') | Some('\n') | None => {}
+                Some(next) => t.word.push(next),
+            },
+            '#' if t.word.is_empty() && !t.quoted => {
+                // Comment to the end of the line
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                t.end_statement();
+            }
+            '(' => {
+                t.note_method_call();
+                t.end_statement();
+            }
+            '\n' | ';' | '{' | '}' | ')' => t.end_statement(),
+            '|' => {
+                if chars.peek() == Some(&'|') {
+                    chars.next();
+                }
+                t.end_statement();
+            }
+            '&' => {
+                if chars.peek() == Some(&'&') {
+                    chars.next();
+                    t.end_statement();
+                } else if t.at_statement_start() {
+                    t.current.push(Token::bare("&"));
+                } else {
+                    t.end_statement();
+                }
+            }
+            '>' => {
+                t.end_word();
+                let mut op = String::from(">");
+                if chars.peek() == Some(&'>') {
+                    chars.next();
+                    op.push('>');
+                }
+                if chars.peek() == Some(&'&') {
+                    // Stream merge such as 2>&1
+                    chars.next();
+                    op.push('&');
+                    while let Some(d) = chars.next_if(char::is_ascii_digit) {
+                        op.push(d);
+                    }
+                }
+                t.current.push(Token::bare(&op));
+            }
+            c if c.is_whitespace() => t.end_word(),
+            _ => t.word.push(c),
+        }
+    }
+    t.end_statement();
+    Tokenized {
+        statements: t.statements,
+        embedded: t.embedded,
+        methods: t.methods,
+    }
+}
+
+// =============================================================================
+// Invocation Helpers
+// =============================================================================
+
+/// Lower-case command name without its directory or `.exe`
+fn command_name(token: &str) -> String {
+    let name = token
+        .rsplit(['\\', '/'])
+        .next()
+        .unwrap_or(token)
+        .to_ascii_lowercase();
+    match name
+        .strip_suffix(".exe")
+        .or_else(|| name.strip_suffix(".com"))
+    {
+        Some(stem) => stem.to_string(),
+        None => name,
+    }
+}
+
+/// Resolve PowerShell aliases and cmd built-ins to the cmdlet they run
+fn resolve_alias(name: &str) -> &str {
+    match name {
+        "rm" | "del" | "erase" | "rd" | "rmdir" | "ri" => "remove-item",
+        "ls" | "dir" | "gci" => "get-childitem",
+        "cat" | "type" | "gc" => "get-content",
+        "cp" | "copy" | "cpi" => "copy-item",
+        "mv" | "move" | "mi" => "move-item",
+        "ren" | "rni" => "rename-item",
+        "ni" | "md" | "mkdir" => "new-item",
+        "ac" => "add-content",
+        "clc" => "clear-content",
+        "cli" => "clear-item",
+        "si" => "set-item",
+        "gp" => "get-itemproperty",
+        "sp" => "set-itemproperty",
+        "rp" => "remove-itemproperty",
+        "iwr" | "wget" | "curl" => "invoke-webrequest",
+        "irm" => "invoke-restmethod",
+        "iex" => "invoke-expression",
+        "icm" => "invoke-command",
+        "ii" => "invoke-item",
+        "start" | "saps" => "start-process",
+        "kill" | "spps" => "stop-process",
+        "gps" => "get-process",
+        "gsv" => "get-service",
+        "sasv" => "start-service",
+        "spsv" => "stop-service",
+        "gwmi" => "get-wmiobject",
+        "gcim" => "get-ciminstance",
+        "iwmi" => "invoke-wmimethod",
+        "rwmi" => "remove-wmiobject",
+        "echo" | "write" => "write-output",
+        "select" => "select-object",
+        "?" => "where-object",
+        "foreach" | "%" => "foreach-object",
+        "sort" => "sort-object",
+        "measure" => "measure-object",
+        "sls" => "select-string",
+        "ft" => "format-table",
+        "fl" => "format-list",
+        "cls" | "clear" => "clear-host",
+        "sl" | "chdir" | "pushd" | "popd" => "set-location",
+        "gl" => "get-location",
+        "set" | "sv" => "set-variable",
+        "sleep" => "start-sleep",
+        "ipmo" => "import-module",
+        "gcm" => "get-command",
+        "gm" => "get-member",
+        "h" | "history" => "get-history",
+        "man" | "help" => "get-help",
+        "ogv" => "out-gridview",
+        "tee" => "tee-object",
+        _ => name,
+    }
+}
+
+/// A `-Name` or `-Name:value` parameter, lower-cased
+struct Param {
+    name: String,
+    /// Inline value, or the token after it when that isn't another parameter
+    value: Option<String>,
+}
+
+/// PowerShell-style parameters of an invocation
+fn params(args: &[Token]) -> Vec<Param> {
+    let is_param = |t: &Token| {
+        !t.quoted && t.text.len() > 1 && t.text.starts_with('-') && {
+            let c = t.text.as_bytes()[1];
+            c.is_ascii_alphabetic()
+        }
+    };
+    args.iter()
+        .enumerate()
+        .filter(|(_, t)| is_param(t))
+        .map(|(i, t)| {
+            let body = t.text[1..].to_ascii_lowercase();
+            match body.split_once(':') {
+                Some((name, value)) => Param {
+                    name: name.to_string(),
+                    value: Some(value.to_string()),
+                },
+                None => Param {
+                    name: body,
+                    value: args
+                        .get(i + 1)
+                        .filter(|next| !is_param(next))
+                        .map(|next| next.text.to_ascii_lowercase()),
+                },
+            }
+        })
+        .collect()
+}
+
+/// Whether a parameter abbreviating `full` (at least `min` characters, as
+/// PowerShell allows) was given and not switched off with `:$false`
+fn has_param(params: &[Param], full: &str, min: usize) -> bool {
+    params.iter().any(|p| {
+        p.name.len() >= min
+            && full.starts_with(p.name.as_str())
+            && p.value.as_deref() != Some("$false")
+    })
+}
+
+/// cmd-style `/x` switches, lower-cased
+fn switches(args: &[Token]) -> Vec<String> {
+    args.iter()
+        .filter(|t| !t.quoted && t.text.len() > 1 && t.text.starts_with('/'))
+        .map(|t| t.text.to_ascii_lowercase())
+        .collect()
+}
+
+fn has_switch(switches: &[String], names: &[&str]) -> bool {
+    switches.iter().any(|s| {
+        names
+            .iter()
+            .any(|n| s == n || s.starts_with(&format!("{}:", n)))
+    })
+}
+
+/// Arguments that aren't parameters or switches, lower-cased
+fn words(args: &[Token]) -> Vec<String> {
+    args.iter()
+        .filter(|t| t.quoted || !(t.text.starts_with('-') || t.text.starts_with('/')))
+        .map(|t| t.text.to_ascii_lowercase())
+        .collect()
+}
+
+/// The command a wrapper such as `env` or `xargs` runs: what follows its
+/// options and the values of the options in `valued`
+fn wrapped_command<'a>(args: &'a [Token], valued: &[&str]) -> &'a [Token] {
+    let mut i = 0;
+    while let Some(arg) = args.get(i) {
+        if arg.quoted || !arg.text.starts_with('-') {
+            break;
+        }
+        if arg.text == "--" {
+            return &args[i + 1..];
+        }
+        i += if valued.contains(&arg.text.as_str()) {
+            2
+        } else {
+            1
+        };
+    }
+    &args[i.min(args.len())..]
+}
+
+fn join_tokens(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map(|t| t.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Host part of the first URL among the words
+fn url_host(words: &[String]) -> Option<String> {
+    words.iter().find_map(|w| {
+        let (_, rest) = w.split_once("://")?;
+        Some(
+            rest.split(['/', ':', '?'])
+                .next()
+                .unwrap_or(rest)
+                .to_string(),
+        )
+    })
+}
+
+/// Whether a path is a drive root, a wildcard or a system location
+fn is_sweeping_path(path: &str) -> bool {
+    let p = path.trim_end_matches(['\\', '/']);
+    p.contains('*')
+        || p.is_empty()
+        || p == "~"
+        || (p.len() == 2 && p.ends_with(':'))
+        || [
+            "windows",
+            "system32",
+            "program files",
+            "programdata",
+            "users",
+            "$env:systemroot",
+            "$env:windir",
+            "%windir%",
+            "%systemroot%",
+            "$env:userprofile",
+            "%userprofile%",
+            "/etc",
+            "/usr",
+            "/home",
+            "/var",
+        ]
+        .iter()
+        .any(|s| p.ends_with(s) && p.len() <= s.len() + 3)
+}
+
+/// Contents of the `$(...)` subexpressions in an expandable string
+fn subexpressions(string: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    for (start, _) in string.match_indices("$(") {
+        let body = start + 2;
+        let mut depth = 1;
+        for (i, c) in string[body..].char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                found.push(&string[body..body + i]);
+                break;
+            }
+        }
+    }
+    found
+}
+
+/// Decode a PowerShell `-EncodedCommand` argument (base64 of UTF-16LE)
+fn decode_encoded_command(encoded: &str) -> Option<String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .ok()?;
+    if bytes.len() % 2 != 0 {
+        return None;
+    }
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16(&units).ok()
+}
+
+// =============================================================================
+// Analysis
+// =============================================================================
+
+#[derive(Default)]
+struct Analysis {
+    reasons: Vec<CommandRiskReason>,
+}
+
+impl Analysis {
+    fn add(&mut self, class: CommandRiskClass, command: &str, detail: impl Into<String>) {
+        let reason = CommandRiskReason {
+            class,
+            command: command.to_string(),
+            detail: detail.into(),
+        };
+        if !self.reasons.contains(&reason) {
+            self.reasons.push(reason);
+        }
+    }
+
+    fn finish(mut self) -> CommandRisk {
+        // Most severe first
+        self.reasons.sort_by_key(|r| std::cmp::Reverse(r.class));
+        CommandRisk {
+            class: self.reasons.first().map(|r| r.class).unwrap_or(ReadOnly),
+            reasons: self.reasons,
+        }
+    }
+
+    fn text(&mut self, text: &str, depth: usize) {
+        if depth > MAX_NESTING {
+            self.add(
+                ModifiesSystem,
+                "nested shell",
+                "Shells are nested too deeply to analyse",
+            );
+            return;
+        }
+        for (re, class, command, detail) in TEXT_PATTERNS.iter() {
+            if re.is_match(text) {
+                self.add(*class, command, *detail);
+            }
+        }
+        let tokenized = tokenize(text);
+        for statement in tokenized.statements {
+            self.statement(&statement, depth);
+        }
+        for method in tokenized.methods {
+            self.method_call(&method);
+        }
+        for string in tokenized.embedded {
+            for sub in subexpressions(&string) {
+                self.text(sub, depth + 1);
+            }
+        }
+    }
+
+    fn statement(&mut self, tokens: &[Token], depth: usize) {
+        let mut tokens = tokens.to_vec();
+
+        // Redirection to a file
+        while let Some(i) = tokens
+            .iter()
+            .position(|t| !t.quoted && t.text.starts_with('>'))
+        {
+            let op = tokens.remove(i);
+            if !op.text.contains('&') && i < tokens.len() {
+                let target = tokens.remove(i);
+                let lower = target.text.to_ascii_lowercase();
+                if !matches!(lower.as_str(), "$null" | "nul" | "null" | "/dev/null") {
+                    self.add(
+                        ModifiesSystem,
+                        &op.text,
+                        format!("Writes output to {}", target.text),
+                    );
+                }
+            }
+            // The stream number before the operator (2>) isn't an argument
+            if i > 0
+                && !tokens[i - 1].quoted
+                && tokens[i - 1].text.chars().all(|c| c.is_ascii_digit())
+            {
+                tokens.remove(i - 1);
+            }
+        }
+
+        // Assignment: analyse the right-hand side
+        if let Some(first) = tokens.first() {
+            if !first.quoted && first.text.starts_with('$') {
+                if let Some((_, rest)) = first.text.split_once('=') {
+                    let rest = rest.to_string();
+                    if rest.is_empty() {
+                        tokens.remove(0);
+                    } else {
+                        tokens[0] = Token::bare(&rest);
+                    }
+                } else if tokens
+                    .get(1)
+                    .is_some_and(|t| !t.quoted && t.text.len() <= 3 && t.text.ends_with('='))
+                {
+                    tokens.drain(..2);
+                }
+            }
+        }
+
+        // Call and dot-source operators
+        if tokens.first().is_some_and(|t| t.is("&") || t.is(".")) {
+            tokens.remove(0);
+            if tokens
+                .first()
+                .is_some_and(|t| !t.quoted && t.text.starts_with('$'))
+            {
+                self.add(
+                    ModifiesSystem,
+                    &tokens[0].text,
+                    "Runs a command held in a variable, which can't be analysed",
+                );
+                return;
+            }
+        }
+
+        let Some(first) = tokens.first() else {
+            return;
+        };
+        if first.quoted {
+            // A quoted program path runs it; any other string is a value
+            let is_path = first.text.contains(['\\', '/']) && !first.text.contains("://");
+            if !is_path && !first.text.to_ascii_lowercase().ends_with(".exe") {
+                return;
+            }
+        } else if first
+            .text
+            .starts_with(['$', '[', '@', '-', '!', '%', ',', '+', '=', '.'])
+            && !first.text.starts_with(".\\")
+            && !first.text.starts_with("./")
+            && first.text != "%"
+            || first.text.starts_with(|c: char| c.is_ascii_digit())
+        {
+            // An expression, not a command
+            return;
+        }
+
+        let name = command_name(&first.text);
+        if KEYWORDS.contains(&name.as_str()) {
+            self.keyword(&name, &tokens[1..], depth);
+            return;
+        }
+        self.invocation(&first.text, &name, &tokens[1..], depth);
+    }
+
+    /// Analyse what follows a control-flow keyword
+    fn keyword(&mut self, keyword: &str, rest: &[Token], depth: usize) {
+        let rest: &[Token] = match keyword {
+            // cmd: if [/i] [not] exist X | defined X | errorlevel N | A==B | A op B
+            "if" => {
+                let mut rest = rest;
+                while rest.first().is_some_and(|t| t.is("/i") || t.is("not")) {
+                    rest = &rest[1..];
+                }
+                match rest.first() {
+                    Some(t) if t.is("exist") || t.is("defined") || t.is("errorlevel") => {
+                        rest.get(2..).unwrap_or_default()
+                    }
+                    Some(t) if t.text.contains("==") => &rest[1..],
+                    Some(_)
+                        if rest.get(1).is_some_and(|op| {
+                            ["equ", "neq", "lss", "leq", "gtr", "geq"]
+                                .iter()
+                                .any(|o| op.is(o))
+                        }) =>
+                    {
+                        rest.get(3..).unwrap_or_default()
+                    }
+                    _ => &[],
+                }
+            }
+            "else" | "do" | "try" | "finally" | "return" | "call" => rest,
+            _ => &[],
+        };
+        if !rest.is_empty() {
+            self.statement(rest, depth);
+        }
+    }
+
+    fn invocation(&mut self, raw: &str, name: &str, args: &[Token], depth: usize) {
+        let cmdlet = resolve_alias(name);
+        let params = params(args);
+        let switches = switches(args);
+        let words = words(args);
+        let sub = words.first().map(String::as_str).unwrap_or("");
+
+        // Cmdlets make no changes under -WhatIf
+        if cmdlet.contains('-') && has_param(&params, "whatif", 2) {
+            return;
+        }
+
+        match cmdlet {
+            // ---- Nested shells and interpreters ----
+            "powershell" | "pwsh" => self.powershell(raw, args, depth),
+            "cmd" => {
+                if let Some(i) = args.iter().position(|t| t.is("/c") || t.is("/k")) {
+                    self.text(&join_tokens(&args[i + 1..]), depth + 1);
+                }
+            }
+            "sh" | "bash" | "zsh" if args.first().is_some_and(|t| t.is("-c")) => {
+                if let Some(script) = args.get(1) {
+                    self.text(&script.text, depth + 1);
+                }
+            }
+            "sudo" | "doas" => self.statement(args, depth + 1),
+
+            // ---- Wrappers: analyse the command they run ----
+            "env" => {
+                // -S splits a string into the command and its arguments
+                if let Some(i) = args
+                    .iter()
+                    .position(|t| t.text == "-S" || t.text.starts_with("--split-string"))
+                {
+                    self.text(&join_tokens(&args[i + 1..]), depth + 1);
+                    return;
+                }
+                let rest = wrapped_command(args, &["-u", "-C", "--unset", "--chdir"]);
+                let assignments = rest
+                    .iter()
+                    .take_while(|t| !t.quoted && t.text.contains('='))
+                    .count();
+                self.statement(&rest[assignments..], depth + 1);
+            }
+            "xargs" => {
+                let valued = ["-a", "-d", "-E", "-I", "-L", "-n", "-P", "-s"];
+                self.statement(wrapped_command(args, &valued), depth + 1);
+            }
+            // cmd's `timeout /t 5` only waits
+            "timeout" if !args.first().is_some_and(|t| t.text.starts_with('/')) => {
+                let rest = wrapped_command(args, &["-s", "-k", "--signal", "--kill-after"]);
+                self.statement(rest.get(1..).unwrap_or_default(), depth + 1);
+            }
+            "nohup" => self.statement(args, depth + 1),
+            "nice" => self.statement(wrapped_command(args, &["-n"]), depth + 1),
+            "find" => self.find(raw, args, depth),
+            "runas" => self.add(ModifiesSystem, raw, "Runs a program as another user"),
+            "python" | "python3" | "py" | "node" | "perl" | "ruby" | "bash" | "sh" | "wsl" => {
+                if !args.is_empty() {
+                    self.add(
+                        ModifiesSystem,
+                        raw,
+                        "Runs code in another interpreter, which isn't analysed",
+                    );
+                }
+            }
+            "mshta" | "wscript" | "cscript" | "rundll32" | "regsvr32" | "installutil"
+            | "msbuild" => {
+                self.add(
+                    ModifiesSystem,
+                    raw,
+                    "Runs a script or library that can execute arbitrary code",
+                );
+                if words.iter().any(|w| w.contains("http")) {
+                    self.add(NetworkEgress, raw, "Loads code from the network");
+                }
+            }
+            "invoke-expression" => {
+                if !args.is_empty()
+                    && args.iter().all(|t| t.quoted)
+                    && !args.iter().any(|t| t.text.contains('$'))
+                {
+                    self.text(&join_tokens(args), depth + 1);
+                } else {
+                    self.add(
+                        ModifiesSystem,
+                        raw,
+                        "Runs a string as code, which can't be analysed",
+                    );
+                }
+            }
+
+            // ---- Deletion and wiping ----
+            "remove-item" => self.remove_item(raw, args, &params, &switches),
+            "format-volume" | "format" => self.add(
+                Destructive,
+                raw,
+                "Formats a volume, erasing everything on it",
+            ),
+            "clear-disk" => self.add(Destructive, raw, "Wipes a disk's partitions and data"),
+            "remove-partition" => self.add(Destructive, raw, "Deletes a disk partition"),
+            "diskpart" => self.add(
+                Destructive,
+                raw,
+                "Runs DiskPart, which can wipe disks and partitions",
+            ),
+            "dd" | "shred" | "wipefs" => {
+                self.add(Destructive, raw, "Overwrites disk or file contents")
+            }
+            n if n.starts_with("mkfs") => self.add(
+                Destructive,
+                raw,
+                "Creates a file system, erasing everything on the device",
+            ),
+            "clear-content" => self.add(Destructive, raw, "Empties files"),
+            "clear-recyclebin" => self.add(Destructive, raw, "Permanently empties the Recycle Bin"),
+            "clear-eventlog" | "remove-eventlog" => self.add(Destructive, raw, "Clears event logs"),
+            "wevtutil" => match sub {
+                "cl" | "clear-log" => self.add(Destructive, raw, "Clears an event log"),
+                "qe" | "query-events" | "gl" | "get-log" | "el" | "enum-logs" | "gli"
+                | "get-log-info" | "ep" | "enum-publishers" | "gp" | "get-publisher" => {}
+                _ => self.add(ModifiesSystem, raw, "Changes event log configuration"),
+            },
+            "vssadmin" => match sub {
+                "delete" | "resize" => self.add(
+                    Destructive,
+                    raw,
+                    "Deletes Volume Shadow Copies (restore points and backups)",
+                ),
+                "list" => {}
+                _ => self.add(ModifiesSystem, raw, "Changes Volume Shadow Copies"),
+            },
+            "wbadmin" => match sub {
+                "delete" => self.add(Destructive, raw, "Deletes backups"),
+                "get" => {}
+                _ => self.add(ModifiesSystem, raw, "Starts or changes backups"),
+            },
+            "wmic" => {
+                if words.iter().any(|w| w == "delete") {
+                    if words.iter().any(|w| w == "shadowcopy") {
+                        self.add(Destructive, raw, "Deletes Volume Shadow Copies");
+                    } else {
+                        self.add(Destructive, raw, "Deletes WMI objects");
+                    }
+                } else if words
+                    .iter()
+                    .any(|w| w == "call" || w == "set" || w == "create")
+                {
+                    self.add(
+                        ModifiesSystem,
+                        raw,
+                        "Calls WMI methods or changes WMI objects",
+                    );
+                }
+            }
+            "cipher" => {
+                if has_switch(&switches, &["/w"]) {
+                    self.add(
+                        Destructive,
+                        raw,
+                        "Overwrites free space so deleted files can't be recovered",
+                    );
+                } else if has_switch(&switches, &["/e", "/d", "/k", "/r", "/u", "/x"]) {
+                    self.add(ModifiesSystem, raw, "Changes file encryption");
+                }
+            }
+            "bcdedit" => {
+                if has_switch(&switches, &["/delete"]) {
+                    self.add(Destructive, raw, "Deletes a boot entry");
+                } else if has_switch(&switches, &["/deletevalue"]) {
+                    self.add(Destructive, raw, "Deletes a boot configuration setting");
+                } else if !switches.is_empty() && !has_switch(&switches, &["/enum", "/v"]) {
+                    self.add(ModifiesSystem, raw, "Changes boot configuration");
+                }
+            }
+
+            // ---- Registry ----
+            "reg" => match sub {
+                "query" | "compare" => {}
+                "delete" => self.add(Destructive, raw, "Deletes registry keys or values"),
+                "save" | "export" if words.iter().any(|w| SECRET_HIVE_RE.is_match(w)) => self.add(
+                    CredentialAccess,
+                    raw,
+                    "Saves a registry hive that holds password hashes or LSA secrets",
+                ),
+                "save" | "export" => {
+                    self.add(ModifiesSystem, raw, "Writes registry contents to a file")
+                }
+                _ => self.add(ModifiesSystem, raw, "Changes the registry"),
+            },
+            "set-itemproperty" | "new-itemproperty" => self.add(
+                ModifiesSystem,
+                raw,
+                "Changes registry values or item properties",
+            ),
+            "remove-itemproperty" => self.add(
+                ModifiesSystem,
+                raw,
+                "Deletes registry values or item properties",
+            ),
+
+            // ---- Security settings ----
+            "set-mppreference" => {
+                let weakened: Vec<&str> = params
+                    .iter()
+                    .filter(|p| {
+                        (p.name.starts_with("disable")
+                            && !matches!(p.value.as_deref(), Some("$false") | Some("0")))
+                            || p.name.starts_with("exclusion")
+                    })
+                    .map(|p| p.name.as_str())
+                    .collect();
+                if weakened.is_empty() {
+                    self.add(ModifiesSystem, raw, "Changes Microsoft Defender settings");
+                } else {
+                    self.add(
+                        Destructive,
+                        raw,
+                        format!("Weakens Microsoft Defender ({})", weakened.join(", ")),
+                    );
+                }
+            }
+            "add-mppreference" => {
+                if params.iter().any(|p| p.name.starts_with("exclusion")) {
+                    self.add(Destructive, raw, "Adds a Microsoft Defender exclusion");
+                } else {
+                    self.add(ModifiesSystem, raw, "Changes Microsoft Defender settings");
+                }
+            }
+            "set-executionpolicy" => self.add(
+                ModifiesSystem,
+                raw,
+                "Changes the PowerShell execution policy",
+            ),
+            "disable-computerrestore" => self.add(ModifiesSystem, raw, "Turns off System Restore"),
+            "netsh" => {
+                const CHANGES: &[&str] = &[
+                    "add",
+                    "set",
+                    "delete",
+                    "del",
+                    "reset",
+                    "import",
+                    "install",
+                    "uninstall",
+                    "init",
+                    "connect",
+                    "disconnect",
+                    "start",
+                    "stop",
+                    "trace",
+                    "flush",
+                ];
+                if words.iter().any(|w| w == "key=clear") {
+                    self.add(
+                        CredentialAccess,
+                        raw,
+                        "Shows saved Wi-Fi passwords in clear text",
+                    );
+                } else if words.iter().any(|w| w == "firewall" || w == "advfirewall")
+                    && words.iter().any(|w| w == "off" || w == "disable")
+                {
+                    self.add(ModifiesSystem, raw, "Turns off the Windows firewall");
+                } else if words.iter().any(|w| CHANGES.contains(&w.as_str()))
+                    || !words.iter().any(|w| w == "show" || w == "dump")
+                {
+                    self.add(ModifiesSystem, raw, "Changes network configuration");
+                }
+            }
+
+            // ---- Credentials ----
+            "cmdkey" => {
+                if has_switch(&switches, &["/add", "/generic", "/delete"]) {
+                    self.add(ModifiesSystem, raw, "Saves or deletes Windows credentials");
+                } else {
+                    self.add(CredentialAccess, raw, "Lists saved Windows credentials");
+                }
+            }
+            "vaultcmd" => self.add(
+                CredentialAccess,
+                raw,
+                "Reads the Windows Credential Manager vault",
+            ),
+            "mimikatz" | "pypykatz" | "lazagne" | "secretsdump" | "procdump" | "ntdsutil" => {
+                self.add(CredentialAccess, raw, "Known credential dumping tool")
+            }
+            "get-credential" => {
+                self.add(CredentialAccess, raw, "Prompts for and holds a credential")
+            }
+            "get-storedcredential" | "get-secret" | "unlock-secretstore" => {
+                self.add(CredentialAccess, raw, "Reads stored secrets")
+            }
+            "export-pfxcertificate" => self.add(
+                CredentialAccess,
+                raw,
+                "Exports a certificate with its private key",
+            ),
+            "convertfrom-securestring" => self.add(
+                CredentialAccess,
+                raw,
+                "Converts a secure string to text that can leave the machine",
+            ),
+            "manage-bde" => {
+                if has_param(&params, "protectors", 2) && has_param(&params, "get", 1) {
+                    self.add(CredentialAccess, raw, "Shows BitLocker recovery keys");
+                } else if !has_param(&params, "status", 2) {
+                    self.add(ModifiesSystem, raw, "Changes BitLocker protection");
+                }
+            }
+            "certutil" => {
+                if has_param(&params, "urlcache", 2) || has_param(&params, "verifyctl", 2) {
+                    self.add(NetworkEgress, raw, "Downloads a file with certutil");
+                } else if has_param(&params, "exportpfx", 7) {
+                    self.add(
+                        CredentialAccess,
+                        raw,
+                        "Exports a certificate with its private key",
+                    );
+                } else if params.iter().any(|p| {
+                    [
+                        "decode",
+                        "encode",
+                        "decodehex",
+                        "encodehex",
+                        "addstore",
+                        "delstore",
+                        "importpfx",
+                    ]
+                    .contains(&p.name.as_str())
+                }) {
+                    self.add(
+                        ModifiesSystem,
+                        raw,
+                        "Writes files or changes certificate stores",
+                    );
+                }
+            }
+
+            // ---- Network ----
+            "invoke-webrequest" | "invoke-restmethod" => {
+                let host = url_host(&words).unwrap_or_else(|| "a remote server".to_string());
+                let sends = params.iter().any(|p| {
+                    ["infile", "body", "form", "data", "d", "upload-file", "t"]
+                        .contains(&p.name.as_str())
+                        || (p.name.starts_with("me")
+                            && "method".starts_with(p.name.as_str())
+                            && matches!(p.value.as_deref(), Some("post" | "put" | "patch")))
+                });
+                if sends {
+                    self.add(NetworkEgress, raw, format!("Sends data to {}", host));
+                } else {
+                    self.add(NetworkEgress, raw, format!("Downloads from {}", host));
+                }
+                if has_param(&params, "outfile", 1) || has_param(&params, "output", 1) {
+                    self.add(ModifiesSystem, raw, "Saves the download to a file");
+                }
+            }
+            "start-bitstransfer" => self.add(NetworkEgress, raw, "Transfers files with BITS"),
+            "bitsadmin" => {
+                if has_switch(&switches, &["/transfer", "/addfile", "/create", "/resume"]) {
+                    self.add(NetworkEgress, raw, "Transfers files with BITS");
+                }
+            }
+            "send-mailmessage" => self.add(NetworkEgress, raw, "Sends an email"),
+            "ssh" | "scp" | "sftp" | "ftp" | "tftp" | "telnet" | "nc" | "ncat" | "rsync" => {
+                self.add(NetworkEgress, raw, "Opens a connection to another machine")
+            }
+            "invoke-command" | "enter-pssession" | "new-pssession" => {
+                if has_param(&params, "computername", 2)
+                    || has_param(&params, "session", 2)
+                    || has_param(&params, "hostname", 2)
+                    || cmdlet != "invoke-command"
+                {
+                    self.add(NetworkEgress, raw, "Runs commands on a remote computer");
+                }
+            }
+            "git" => match sub {
+                "clone" | "fetch" | "pull" | "push" => self.add(
+                    NetworkEgress,
+                    raw,
+                    "Transfers a repository over the network",
+                ),
+                "status" | "log" | "diff" | "show" | "branch" | "remote" | "rev-parse" => {}
+                _ => self.add(ModifiesSystem, raw, "Changes a repository"),
+            },
+            "winget" | "choco" | "scoop" | "pip" | "pip3" | "npm" => match sub {
+                "install" | "upgrade" | "update" | "add" => {
+                    self.add(ModifiesSystem, raw, "Installs or updates software");
+                    self.add(NetworkEgress, raw, "Downloads packages");
+                }
+                "list" | "search" | "show" | "info" | "outdated" | "--version" => {}
+                _ => self.add(ModifiesSystem, raw, "Changes installed software"),
+            },
+            "msiexec" => {
+                if has_switch(&switches, &["/x", "/uninstall"]) {
+                    self.add(ModifiesSystem, raw, "Uninstalls a package");
+                } else {
+                    self.add(ModifiesSystem, raw, "Installs or changes a package");
+                }
+            }
+
+            // ---- Processes, services and power ----
+            "start-process" => {
+                let target = params
+                    .iter()
+                    .find(|p| p.name.len() >= 2 && "filepath".starts_with(p.name.as_str()))
+                    .and_then(|p| p.value.clone())
+                    .or_else(|| words.first().cloned())
+                    .unwrap_or_else(|| "a program".to_string());
+                if params.iter().any(|p| p.value.as_deref() == Some("runas")) {
+                    self.add(ModifiesSystem, raw, format!("Starts {} elevated", target));
+                } else {
+                    self.add(ModifiesSystem, raw, format!("Starts {}", target));
+                }
+            }
+            "stop-process" | "taskkill" | "pkill" | "killall" => {
+                self.add(ModifiesSystem, raw, "Ends processes")
+            }
+            "stop-computer" | "restart-computer" | "shutdown" | "reboot" | "poweroff" => {
+                self.add(ModifiesSystem, raw, "Shuts down or restarts the computer")
+            }
+            "sc" => match sub {
+                "query" | "queryex" | "qc" | "qdescription" | "qfailure" | "qtriggerinfo"
+                | "qprivs" | "qsidtype" | "getdisplayname" | "getkeyname" | "enumdepend"
+                | "sdshow" => {}
+                "delete" => self.add(ModifiesSystem, raw, "Deletes a service"),
+                _ => self.add(ModifiesSystem, raw, "Changes a service"),
+            },
+            "net" => match sub {
+                "user" | "localgroup" | "group" | "accounts" | "share" | "use"
+                    if words.len() > 2 || switches.iter().any(|s| s != "/domain") =>
+                {
+                    self.add(ModifiesSystem, raw, "Changes accounts, groups or shares")
+                }
+                "start" | "stop" | "pause" | "continue" if words.len() > 1 => {
+                    self.add(ModifiesSystem, raw, "Starts or stops a service")
+                }
+                _ => {}
+            },
+            "schtasks" => {
+                if has_switch(&switches, &["/delete"]) {
+                    self.add(ModifiesSystem, raw, "Deletes a scheduled task");
+                } else if !switches.is_empty() && !has_switch(&switches, &["/query"]) {
+                    self.add(
+                        ModifiesSystem,
+                        raw,
+                        "Creates, runs or changes scheduled tasks",
+                    );
+                }
+            }
+            "systemctl" | "service" => {
+                if !words.iter().any(|w| {
+                    [
+                        "status",
+                        "list-units",
+                        "list-unit-files",
+                        "is-active",
+                        "is-enabled",
+                        "show",
+                    ]
+                    .contains(&w.as_str())
+                }) {
+                    self.add(ModifiesSystem, raw, "Changes a service");
+                }
+            }
+
+            // ---- File system and repair tools ----
+            "chkdsk" => {
+                if has_switch(&switches, &["/f", "/r", "/x", "/b"]) {
+                    self.add(ModifiesSystem, raw, "Repairs the file system");
+                }
+            }
+            "sfc" => {
+                if !has_switch(&switches, &["/verifyonly", "/verifyfile"]) {
+                    self.add(ModifiesSystem, raw, "Repairs system files");
+                }
+            }
+            "dism" => {
+                if !switches.iter().all(|s| {
+                    s.starts_with("/get-")
+                        || s.starts_with("/english")
+                        || ["/online", "/cleanup-image", "/checkhealth", "/scanhealth"]
+                            .contains(&s.as_str())
+                }) {
+                    self.add(ModifiesSystem, raw, "Services the Windows image");
+                }
+            }
+            "powercfg" => {
+                if switches.iter().any(|s| {
+                    ![
+                        "/list",
+                        "/l",
+                        "/query",
+                        "/q",
+                        "/a",
+                        "/availablesleepstates",
+                        "/getactivescheme",
+                        "/batteryreport",
+                        "/energy",
+                        "/sleepstudy",
+                        "/requests",
+                        "/lastwake",
+                        "/devicequery",
+                    ]
+                    .contains(&s.as_str())
+                }) {
+                    self.add(ModifiesSystem, raw, "Changes power settings");
+                }
+            }
+            "route" => {
+                if sub != "print" && !words.is_empty() {
+                    self.add(ModifiesSystem, raw, "Changes the routing table");
+                }
+            }
+            "robocopy" => {
+                if has_switch(&switches, &["/mir", "/purge", "/move"]) {
+                    self.add(
+                        Destructive,
+                        raw,
+                        "Deletes destination files that aren't in the source",
+                    );
+                } else if !has_switch(&switches, &["/l"]) {
+                    self.add(ModifiesSystem, raw, "Copies files");
+                }
+            }
+            "xcopy" => self.add(ModifiesSystem, raw, "Copies files"),
+            "takeown" | "icacls" | "chmod" | "chown" | "attrib" => {
+                let changes = cmdlet == "takeown"
+                    || cmdlet == "chmod"
+                    || cmdlet == "chown"
+                    || has_switch(
+                        &switches,
+                        &[
+                            "/grant",
+                            "/deny",
+                            "/remove",
+                            "/reset",
+                            "/setowner",
+                            "/restore",
+                            "/setintegritylevel",
+                        ],
+                    )
+                    || args
+                        .iter()
+                        .any(|t| t.text.starts_with(['+', '-']) && t.text.len() == 2);
+                if changes {
+                    self.add(
+                        ModifiesSystem,
+                        raw,
+                        "Changes file ownership, permissions or attributes",
+                    );
+                }
+            }
+            "sed" => {
+                if has_param(&params, "i", 1) || has_param(&params, "in-place", 2) {
+                    self.add(ModifiesSystem, raw, "Edits files in place");
+                }
+            }
+            "out-file" | "set-content" | "add-content" => {
+                self.add(ModifiesSystem, raw, "Writes to a file")
+            }
+            "add-type" => self.add(ModifiesSystem, raw, "Compiles and loads .NET code"),
+
+            // ---- Method calls ----
+            "foreach-object" => {
+                let member = params
+                    .iter()
+                    .find(|p| "membername".starts_with(p.name.as_str()))
+                    .and_then(|p| p.value.clone())
+                    .or_else(|| {
+                        args.first()
+                            .filter(|t| !t.quoted && !t.text.starts_with('-'))
+                            .map(|t| t.text.to_ascii_lowercase())
+                    });
+                // `% Name` may read a property instead; only arguments make
+                // it certainly a method call
+                match member {
+                    Some(member) if has_param(&params, "argumentlist", 1) => {
+                        self.method_call(&member)
+                    }
+                    Some(member) if DESTRUCTIVE_METHODS.contains(&member.as_str()) => {
+                        self.method_call(&member)
+                    }
+                    _ => {}
+                }
+            }
+            "invoke-wmimethod" | "invoke-cimmethod" => {
+                match params
+                    .iter()
+                    .find(|p| p.name == "name" || p.name == "methodname")
+                    .and_then(|p| p.value.as_deref())
+                {
+                    Some(method) if DESTRUCTIVE_METHODS.contains(&method) => {
+                        self.add(Destructive, raw, format!("Calls the WMI method {}", method))
+                    }
+                    _ => self.add(ModifiesSystem, raw, "Calls a WMI method"),
+                }
+            }
+
+            _ => self.fallback(raw, cmdlet),
+        }
+    }
+
+    /// Delete commands: trees, wildcards and system locations are destructive
+    fn remove_item(&mut self, raw: &str, args: &[Token], params: &[Param], switches: &[String]) {
+        // Unix-style flags such as -rf
+        let unix_recursive = params.iter().any(|p| {
+            p.name.len() <= 4 && p.name.contains('r') && p.name.chars().all(|c| "rfvid".contains(c))
+        });
+        let recursive =
+            has_param(params, "recurse", 1) || unix_recursive || has_switch(switches, &["/s"]);
+
+        let targets: Vec<&str> = args
+            .iter()
+            .filter(|t| t.quoted || !(t.text.starts_with('-') || t.text.starts_with('/')))
+            .map(|t| t.text.as_str())
+            .collect();
+        let sweeping: Vec<&str> = targets
+            .iter()
+            .copied()
+            .filter(|t| is_sweeping_path(&t.to_ascii_lowercase()))
+            .collect();
+
+        if recursive {
+            self.add(Destructive, raw, "Deletes a directory tree");
+        }
+        if !sweeping.is_empty() {
+            self.add(
+                Destructive,
+                raw,
+                format!(
+                    "Deletes with wildcards or in system locations ({})",
+                    sweeping.join(", ")
+                ),
+            );
+        }
+        if !recursive && sweeping.is_empty() {
+            let what = if targets.is_empty() {
+                "items".to_string()
+            } else {
+                targets.join(", ")
+            };
+            self.add(ModifiesSystem, raw, format!("Deletes {}", what));
+        }
+    }
+
+    /// `.Name(...)`: only methods that read or convert values stay read-only
+    fn method_call(&mut self, method: &str) {
+        let command = format!(".{}()", method);
+        let lower = method.to_ascii_lowercase();
+        if READ_ONLY_METHODS.contains(&lower.as_str()) {
+            return;
+        }
+        if DESTRUCTIVE_METHODS.contains(&lower.as_str()) {
+            self.add(
+                Destructive,
+                &command,
+                "Calls a method that deletes, ends or wipes the object",
+            );
+        } else {
+            self.add(
+                ModifiesSystem,
+                &command,
+                "Calls a method whose effect can't be determined",
+            );
+        }
+    }
+
+    /// Unix `find` deletes with `-delete` and runs commands with `-exec`;
+    /// cmd's `find` only searches text
+    fn find(&mut self, raw: &str, args: &[Token], depth: usize) {
+        let mut i = 0;
+        while i < args.len() {
+            let arg = &args[i];
+            i += 1;
+            if arg.quoted {
+                continue;
+            }
+            match arg.text.as_str() {
+                "-delete" => self.add(Destructive, raw, "Deletes every file it finds"),
+                "-exec" | "-execdir" | "-ok" | "-okdir" => {
+                    // The command runs up to `;` or `+` (`{}` and `;` already
+                    // end the statement)
+                    let end = args[i..]
+                        .iter()
+                        .position(|t| matches!(t.text.as_str(), "\\" | "\\;" | ";" | "+"))
+                        .map_or(args.len(), |n| i + n);
+                    self.add(ModifiesSystem, raw, "Runs a command on every file it finds");
+                    self.statement(&args[i..end], depth + 1);
+                    i = end;
+                }
+                "-fprint" | "-fprint0" | "-fprintf" | "-fls" => {
+                    self.add(ModifiesSystem, raw, "Writes its results to a file")
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// PowerShell's own arguments: find the command it is given and analyse it
+    fn powershell(&mut self, raw: &str, args: &[Token], depth: usize) {
+        /// Parameters that take a value
+        const VALUED: &[&str] = &[
+            "executionpolicy",
+            "windowstyle",
+            "outputformat",
+            "inputformat",
+            "configurationname",
+            "workingdirectory",
+            "version",
+            "psconsolefile",
+            "settingsfile",
+            "custompipename",
+        ];
+
+        let mut i = 0;
+        while i < args.len() {
+            let arg = &args[i];
+            if arg.quoted || !(arg.text.starts_with('-') || arg.text.starts_with('/')) {
+                break;
+            }
+            let flag = arg.text[1..].to_ascii_lowercase();
+            if !flag.is_empty() && "command".starts_with(flag.as_str()) {
+                self.text(&join_tokens(&args[i + 1..]), depth + 1);
+                return;
+            }
+            if flag == "e"
+                || flag == "ec"
+                || (flag.len() >= 2 && "encodedcommand".starts_with(flag.as_str()))
+            {
+                match args
+                    .get(i + 1)
+                    .and_then(|t| decode_encoded_command(&t.text))
+                {
+                    Some(decoded) => self.text(&decoded, depth + 1),
+                    None => self.add(
+                        ModifiesSystem,
+                        raw,
+                        "Runs an encoded command that couldn't be decoded",
+                    ),
+                }
+                return;
+            }
+            if !flag.is_empty() && "file".starts_with(flag.as_str()) {
+                let script = args
+                    .get(i + 1)
+                    .map(|t| t.text.as_str())
+                    .unwrap_or("a script");
+                self.add(
+                    ModifiesSystem,
+                    raw,
+                    format!("Runs {}, whose contents aren't analysed", script),
+                );
+                return;
+            }
+            let valued = flag == "ep"
+                || flag == "w"
+                || VALUED
+                    .iter()
+                    .any(|v| flag.len() >= 2 && v.starts_with(flag.as_str()));
+            i += if valued { 2 } else { 1 };
+        }
+
+        // Without -Command or -File, the rest is a command (or a script)
+        let rest = &args[i.min(args.len())..];
+        match rest.first() {
+            Some(first) if first.text.to_ascii_lowercase().ends_with(".ps1") => self.add(
+                ModifiesSystem,
+                raw,
+                format!("Runs {}, whose contents aren't analysed", first.text),
+            ),
+            Some(_) => self.text(&join_tokens(rest), depth + 1),
+            None => {}
+        }
+    }
+
+    /// Commands without a specific rule: known read-only programs, then the
+    /// PowerShell verb, then scripts; anything else is unrecognised
+    fn fallback(&mut self, raw: &str, cmdlet: &str) {
+        if READ_ONLY_PROGRAMS.contains(&cmdlet) || SESSION_CMDLETS.contains(&cmdlet) {
+            return;
+        }
+
+        if let Some((verb, noun)) = cmdlet.split_once('-') {
+            if READ_VERBS.contains(&verb) {
+                return;
+            }
+            let action = match verb {
+                "set" => "Changes",
+                "new" => "Creates",
+                "add" => "Adds",
+                "remove" => "Removes",
+                "clear" => "Clears",
+                "copy" => "Copies",
+                "move" => "Moves",
+                "rename" => "Renames",
+                "start" => "Starts",
+                "stop" => "Stops",
+                "restart" => "Restarts",
+                "suspend" => "Suspends",
+                "resume" => "Resumes",
+                "enable" => "Enables",
+                "disable" => "Disables",
+                "install" => "Installs",
+                "uninstall" => "Uninstalls",
+                "register" => "Registers",
+                "unregister" => "Unregisters",
+                "update" => "Updates",
+                "reset" => "Resets",
+                "repair" => "Repairs",
+                "import" => "Imports",
+                "export" => "Exports",
+                "invoke" => "Invokes",
+                "mount" => "Mounts",
+                "dismount" => "Dismounts",
+                "grant" => "Grants",
+                "revoke" => "Revokes",
+                "block" => "Blocks",
+                "unblock" => "Unblocks",
+                "initialize" => "Initializes",
+                "restore" => "Restores",
+                "save" => "Saves",
+                "send" => "Sends",
+                _ => "Changes",
+            };
+            // Keep the noun as written when the command was typed in full
+            let noun = raw
+                .rsplit(['\\', '/'])
+                .next()
+                .and_then(|r| r.split_once('-'))
+                .map(|(_, n)| n)
+                .unwrap_or(noun);
+            self.add(ModifiesSystem, raw, format!("{} {}", action, noun));
+            return;
+        }
+
+        if [".ps1", ".bat", ".cmd", ".vbs", ".js", ".py", ".sh"]
+            .iter()
+            .any(|ext| cmdlet.ends_with(ext))
+        {
+            self.add(
+                ModifiesSystem,
+                raw,
+                "Runs a script whose contents aren't analysed",
+            );
+            return;
+        }
+
+        self.add(
+            ModifiesSystem,
+            raw,
+            "Unrecognised command; its effect can't be determined",
+        );
+    }
+}
+
+// =============================================================================
+// Public API
+// =============================================================================
+
+/// Analyse a PowerShell or cmd command line and sort it into a risk class,
+/// with the reasons for it
+pub fn classify_command(command: &str) -> CommandRisk {
+    let mut analysis = Analysis::default();
+    analysis.text(command, 0);
+    analysis.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class(command: &str) -> CommandRiskClass {
+        classify_command(command).class
+    }
+
+    #[test]
+    fn read_only_commands() {
+        for command in [
+            "Get-Process",
+            "Get-ChildItem C:\\Users -Recurse | Select-Object Name",
+            "ipconfig /all",
+            "dir C:\\Windows",
+            "(Get-Date).ToString('yyyy-MM-dd')",
+            "Get-Service | Where-Object { $_.Name.StartsWith('W') }",
+            "gps | % Name",
+            "find \"error\" log.txt",
+            "find . -name '*.log'",
+            "env",
+            "timeout /t 5",
+        ] {
+            assert_eq!(class(command), ReadOnly, "{}", command);
+        }
+    }
+
+    #[test]
+    fn modifying_commands() {
+        for command in [
+            "Set-ItemProperty HKCU:\\Software\\Test -Name A -Value 1",
+            "New-Item C:\\temp\\a.txt",
+            "Remove-Item C:\\temp\\a.txt",
+            "Stop-Service Spooler",
+            "echo hi > C:\\temp\\out.txt",
+            "(Get-WmiObject Win32_OperatingSystem).Win32Shutdown(1)",
+        ] {
+            assert_eq!(class(command), ModifiesSystem, "{}", command);
+        }
+    }
+
+    #[test]
+    fn destructive_commands() {
+        for command in [
+            "Remove-Item C:\\data -Recurse -Force",
+            "rm -r C:\\data",
+            "del /s /q C:\\data",
+            "Format-Volume -DriveLetter D",
+            "reg delete HKLM\\Software\\Test /f",
+            "vssadmin delete shadows /all",
+        ] {
+            assert_eq!(class(command), Destructive, "{}", command);
+        }
+    }
+
+    #[test]
+    fn defender_changes_are_flagged() {
+        let risk = classify_command("Set-MpPreference -DisableRealtimeMonitoring $true");
+        assert_eq!(risk.class, Destructive);
+        assert!(!risk.reasons.is_empty());
+        assert_eq!(class("Add-MpPreference -ExclusionPath C:\\"), Destructive);
+        assert_eq!(
+            class("Set-MpPreference -DisableRealtimeMonitoring $false"),
+            ModifiesSystem
+        );
+        assert_eq!(class("bcdedit /deletevalue safeboot"), Destructive);
+    }
+
+    #[test]
+    fn network_egress() {
+        for command in [
+            "Invoke-WebRequest https://example.com/a.zip -OutFile a.zip",
+            "iwr https://example.com",
+            "curl https://example.com",
+        ] {
+            assert!(class(command) >= NetworkEgress, "{}", command);
+        }
+    }
+
+    #[test]
+    fn credential_access() {
+        for command in ["reg save HKLM\\SAM C:\\temp\\sam", "cmdkey /list"] {
+            assert_eq!(class(command), CredentialAccess, "{}", command);
+        }
+    }
+
+    #[test]
+    fn method_calls() {
+        for command in [
+            "(Get-Item C:\\Windows\\Temp).Delete($true)",
+            "Get-ChildItem C:\\data -Recurse | ForEach-Object { $_.Delete() }",
+            "Get-WmiObject Win32_Process | % { $_.Terminate() }",
+            "(Get-Process notepad).Kill()",
+            "Get-ChildItem C:\\data | % Delete",
+            "Invoke-CimMethod -InputObject $p -MethodName Terminate",
+        ] {
+            assert_eq!(class(command), Destructive, "{}", command);
+        }
+    }
+
+    #[test]
+    fn wrappers_are_analysed_by_their_command() {
+        for command in [
+            "sh -c 'env rm -rf /'",
+            "env FOO=1 rm -rf /",
+            "env -i -u PATH rm -rf /",
+            "env -S 'rm -rf /'",
+            "find / -name x | xargs rm -rf",
+            "timeout 10 rm -rf /tmp/data",
+            "nohup rm -rf /tmp/data",
+            "sudo nice -n 5 rm -rf /tmp/data",
+        ] {
+            assert_eq!(class(command), Destructive, "{}", command);
+        }
+    }
+
+    #[test]
+    fn find_actions() {
+        assert_eq!(class("find / -name x -delete"), Destructive);
+        assert_eq!(class("sh -c 'find / -name x -delete'"), Destructive);
+        assert_eq!(class(r"find / -name x -exec rm -rf {} \;"), Destructive);
+        assert_eq!(class("find . -type f -exec chmod 644 {} +"), ModifiesSystem);
+    }
+}
@@ -0,0 +1,514 @@
+//! Filesystem sandbox
+//!
+//! The policy every file tool goes through, in the app and over MCP. A path
+//! is resolved first (`.` and `..` removed, symlinks followed for the part
+//! that exists) and then checked in order against:
+//!
+//! 1. Protected locations: registry hives, credential stores, `.ssh`, and the
+//!    app's own settings, keys, audit log, agent database and file journal.
+//!    No setting opens these up.
+//! 2. The policy's denied roots.
+//! 3. The allowed roots: the data folder, the home folder and the policy's
+//!    allowed roots. The working directory is not allowed unless it is one
+//!    of these.
+//!
+//! Anything outside every allowed root is denied.
+
+use std::path::{Component, Path, PathBuf};
+
+use super::journal::snapshot_dir;
+use super::{
+    get_data_dir_path, get_memory_db_path, get_settings, FilesystemPolicy, PathPolicyRule,
+    PathRuleKind,
+};
+use crate::commands::mcp_audit::get_audit_db_path;
+
+// =============================================================================
+// Path Resolution
+// =============================================================================
+
+/// Absolute form of `path` with `.` and `..` resolved. Symlinks are resolved
+/// for the longest prefix that exists, so a link can't lead out of a root.
+pub fn normalize_path(path: impl AsRef<Path>) -> Result<PathBuf, String> {
+    let path = path.as_ref();
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map_err(|e| format!("Failed to resolve path: {}", e))?
+            .join(path)
+    };
+
+    let mut lexical = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::ParentDir => {
+                lexical.pop();
+            }
+            Component::CurDir => {}
+            other => lexical.push(other),
+        }
+    }
+
+    // Canonicalize the deepest existing ancestor and re-append the rest
+    let mut existing = lexical.as_path();
+    let mut rest = Vec::new();
+    while !existing.exists() {
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return Ok(lexical),
+        }
+    }
+    let mut resolved = canonicalize(existing)?;
+    for name in rest.into_iter().rev() {
+        resolved.push(name);
+    }
+    Ok(resolved)
+}
+
+/// `canonicalize` without the `\\?\` prefix Windows adds, so results compare
+/// against user-entered prefixes
+fn canonicalize(path: &Path) -> Result<PathBuf, String> {
+    let canonical = path
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve path {}: {}", path.display(), e))?;
+    let text = canonical.to_string_lossy();
+    Ok(match text.strip_prefix(r"\\?\") {
+        Some(stripped) if !stripped.starts_with("UNC\\") => PathBuf::from(stripped),
+        _ => canonical,
+    })
+}
+
+/// Whether `path` is `prefix` or inside it (case-insensitive on Windows)
+pub fn path_within(path: &Path, prefix: &Path) -> bool {
+    if cfg!(windows) {
+        let lower = |p: &Path| PathBuf::from(p.to_string_lossy().to_lowercase());
+        lower(path).starts_with(lower(prefix))
+    } else {
+        path.starts_with(prefix)
+    }
+}
+
+// =============================================================================
+// Protected Locations
+// =============================================================================
+
+/// Windows system folders, which the file tools never touch
+const SYSTEM_DIRECTORIES: &[&str] = &[
+    "/windows/system32",
+    "/windows/syswow64",
+    "/windows/winsxs",
+    "/program files/windowsapps",
+];
+
+/// Chromium profile files holding saved passwords, cookies, card details and
+/// the key that decrypts them (plus their `-journal` files)
+const CHROMIUM_SECRET_FILES: &[&str] = &["login data", "cookies", "web data", "local state"];
+
+/// Firefox profile files holding saved passwords, cookies and their keys
+const FIREFOX_SECRET_FILES: &[&str] = &[
+    "logins.json",
+    "logins-backup.json",
+    "key3.db",
+    "key4.db",
+    "signons.sqlite",
+    "cookies.sqlite",
+];
+
+/// Folders of the Windows credential manager and DPAPI master keys
+const WINDOWS_CREDENTIAL_DIRS: &[&str] = &[
+    "/microsoft/credentials",
+    "/microsoft/protect",
+    "/microsoft/vault",
+];
+
+/// Name of the protected location `path` falls in, judged from its lowercase
+/// text with `/` separators
+fn protected_location(text: &str) -> Option<&'static str> {
+    let name = text.rsplit('/').next().unwrap_or("");
+    let within = |dir: &str| text.contains(&format!("{}/", dir)) || text.ends_with(dir);
+
+    if (text.contains("/system32/config/") || text.contains("/windows/repair/"))
+        && ["sam", "security"]
+            .iter()
+            .any(|hive| name == *hive || name.starts_with(&format!("{}.", hive)))
+    {
+        return Some("Windows SAM and SECURITY registry hives");
+    }
+    if text.split('/').any(|part| part == ".ssh") {
+        return Some("SSH keys");
+    }
+    if text.contains("/user data/")
+        && CHROMIUM_SECRET_FILES
+            .iter()
+            .any(|secret| name.starts_with(secret))
+    {
+        return Some("browser credential store");
+    }
+    if text.contains("/firefox/")
+        && FIREFOX_SECRET_FILES
+            .iter()
+            .any(|secret| name.starts_with(secret))
+    {
+        return Some("browser credential store");
+    }
+    if WINDOWS_CREDENTIAL_DIRS.iter().any(|dir| within(dir)) {
+        return Some("Windows credential store");
+    }
+    if SYSTEM_DIRECTORIES.iter().any(|dir| within(dir)) {
+        return Some("Windows system folder");
+    }
+    None
+}
+
+/// The app's own files holding API keys and certificates, and the records
+/// a file tool must not be able to rewrite: the MCP audit log, the agent
+/// database and the file journal's snapshots
+fn app_secret_paths(tls_key_path: Option<&str>) -> Vec<PathBuf> {
+    let data_dir = get_data_dir_path();
+    let mut paths = vec![
+        data_dir.join("settings.json"),
+        data_dir.join("mcp_keys.json"),
+        data_dir.join("mcp_tls"),
+        get_audit_db_path(),
+        get_memory_db_path(),
+        snapshot_dir(),
+    ];
+    paths.extend(tls_key_path.map(PathBuf::from));
+    paths
+        .into_iter()
+        .map(|path| normalize_path(&path).unwrap_or(path))
+        .collect()
+}
+
+// =============================================================================
+// Sandbox
+// =============================================================================
+
+/// A filesystem policy with its roots resolved, ready to check paths
+pub struct FilesystemSandbox {
+    allowed_roots: Vec<PathBuf>,
+    denied_roots: Vec<PathBuf>,
+    app_secrets: Vec<PathBuf>,
+}
+
+impl FilesystemSandbox {
+    /// The sandbox for the current settings
+    pub fn load() -> Result<Self, String> {
+        let settings = get_settings()?;
+        let agent = &settings.agent;
+        Ok(Self::new(
+            &agent.filesystem_policy,
+            agent.mcp_tls.key_path.as_deref(),
+        ))
+    }
+
+    pub fn new(policy: &FilesystemPolicy, tls_key_path: Option<&str>) -> Self {
+        let defaults = [Some(get_data_dir_path()), dirs::home_dir()];
+        let allowed_roots = defaults
+            .into_iter()
+            .flatten()
+            .chain(policy.allowed_roots.iter().map(PathBuf::from))
+            .filter_map(|root| match normalize_path(&root) {
+                Ok(root) => Some(root),
+                Err(e) => {
+                    // An unresolvable root allows nothing
+                    eprintln!("Ignoring allowed folder {}: {}", root.display(), e);
+                    None
+                }
+            })
+            .collect();
+        // An unresolvable denied root is still denied as written
+        let denied_roots = policy
+            .denied_roots
+            .iter()
+            .map(|root| normalize_path(root).unwrap_or_else(|_| PathBuf::from(root)))
+            .collect();
+
+        Self {
+            allowed_roots,
+            denied_roots,
+            app_secrets: app_secret_paths(tls_key_path),
+        }
+    }
+
+    /// Resolve `path` and find the rule that decides whether it may be used
+    pub fn evaluate(&self, path: impl AsRef<Path>) -> Result<(PathBuf, PathPolicyRule), String> {
+        let resolved = normalize_path(path)?;
+        let rule = self.rule_for(&resolved);
+        Ok((resolved, rule))
+    }
+
+    fn rule_for(&self, path: &Path) -> PathPolicyRule {
+        let rule = |kind, matched: Option<String>| PathPolicyRule {
+            kind,
+            matched,
+            allowed: kind == PathRuleKind::AllowedRoot,
+        };
+        let text = path.to_string_lossy().replace('\\', "/").to_lowercase();
+
+        if let Some(location) = protected_location(&text) {
+            return rule(PathRuleKind::Protected, Some(location.to_string()));
+        }
+        if self
+            .app_secrets
+            .iter()
+            .any(|secret| path_within(path, secret))
+        {
+            return rule(
+                PathRuleKind::Protected,
+                Some("RustService settings, keys and logs".to_string()),
+            );
+        }
+        if let Some(root) = self
+            .denied_roots
+            .iter()
+            .find(|root| path_within(path, root))
+        {
+            return rule(
+                PathRuleKind::DeniedRoot,
+                Some(root.to_string_lossy().to_string()),
+            );
+        }
+        // The innermost allowed root is the most telling one to report
+        match self
+            .allowed_roots
+            .iter()
+            .filter(|root| path_within(path, root))
+            .max_by_key(|root| root.components().count())
+        {
+            Some(root) => rule(
+                PathRuleKind::AllowedRoot,
+                Some(root.to_string_lossy().to_string()),
+            ),
+            None => rule(PathRuleKind::OutsideAllowedRoots, None),
+        }
+    }
+
+    /// Resolve `path`, or explain why it may not be used
+    pub fn check(&self, path: impl AsRef<Path>) -> Result<PathBuf, String> {
+        let (resolved, rule) = self.evaluate(path)?;
+        if rule.allowed {
+            Ok(resolved)
+        } else {
+            Err(denial_message(&resolved, &rule))
+        }
+    }
+
+    /// Whether `path` may be used, for filtering search results
+    pub fn permits(&self, path: &Path) -> bool {
+        self.check(path).is_ok()
+    }
+}
+
+/// Why a path was denied, for tool errors
+pub fn denial_message(path: &Path, rule: &PathPolicyRule) -> String {
+    let matched = rule.matched.as_deref().unwrap_or_default();
+    match rule.kind {
+        PathRuleKind::Protected => format!(
+            "Access denied: {} is in a protected location ({}) that file tools can never use",
+            path.display(),
+            matched
+        ),
+        PathRuleKind::DeniedRoot => format!(
+            "Access denied: {} is inside the denied folder {}",
+            path.display(),
+            matched
+        ),
+        PathRuleKind::OutsideAllowedRoots => format!(
+            "Access denied: {} is outside the allowed folders. Add a folder to the filesystem \
+             policy in the agent settings to allow it.",
+            path.display()
+        ),
+        PathRuleKind::AllowedRoot => format!("{} is allowed", path.display()),
+    }
+}
+
+/// Resolve `path` against the current filesystem policy, or explain why it
+/// may not be used
+pub fn check_path_access(path: impl AsRef<Path>) -> Result<PathBuf, String> {
+    FilesystemSandbox::load()?.check(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// A fresh folder under the system temp folder, removed when dropped
+    struct Scratch(PathBuf);
+
+    impl Scratch {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "rustservice-sandbox-{}",
+                uuid::Uuid::new_v4().simple()
+            ));
+            fs::create_dir_all(&dir).unwrap();
+            Self(normalize_path(&dir).unwrap())
+        }
+
+        fn dir(&self, name: &str) -> PathBuf {
+            let dir = self.0.join(name);
+            fs::create_dir_all(&dir).unwrap();
+            dir
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// A sandbox with only the given roots, independent of this machine's
+    /// data and home folders
+    fn sandbox(allowed: &[&Path], denied: &[&Path]) -> FilesystemSandbox {
+        FilesystemSandbox {
+            allowed_roots: allowed.iter().map(|p| p.to_path_buf()).collect(),
+            denied_roots: denied.iter().map(|p| p.to_path_buf()).collect(),
+            app_secrets: Vec::new(),
+        }
+    }
+
+    fn kind(sandbox: &FilesystemSandbox, path: impl AsRef<Path>) -> PathRuleKind {
+        sandbox.evaluate(path).unwrap().1.kind
+    }
+
+    #[test]
+    fn parent_components_cannot_leave_a_root() {
+        let scratch = Scratch::new();
+        let root = scratch.dir("root");
+        scratch.dir("outside");
+        let sandbox = sandbox(&[&root], &[]);
+
+        assert_eq!(
+            kind(&sandbox, root.join("sub/../notes.txt")),
+            PathRuleKind::AllowedRoot
+        );
+        assert_eq!(
+            kind(&sandbox, root.join("../outside/notes.txt")),
+            PathRuleKind::OutsideAllowedRoots
+        );
+        assert_eq!(
+            kind(&sandbox, root.join("missing/../../outside")),
+            PathRuleKind::OutsideAllowedRoots
+        );
+        assert!(sandbox.check(root.join("..")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_cannot_leave_a_root() {
+        let scratch = Scratch::new();
+        let root = scratch.dir("root");
+        let outside = scratch.dir("outside");
+        fs::write(outside.join("secret.txt"), "secret").unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+        std::os::unix::fs::symlink(outside.join("secret.txt"), root.join("file-link")).unwrap();
+        let sandbox = sandbox(&[&root], &[]);
+
+        for path in [
+            root.join("link"),
+            root.join("link/secret.txt"),
+            root.join("link/new.txt"),
+            root.join("file-link"),
+        ] {
+            assert_eq!(
+                kind(&sandbox, &path),
+                PathRuleKind::OutsideAllowedRoots,
+                "{}",
+                path.display()
+            );
+        }
+    }
+
+    #[test]
+    fn denied_root_beats_allowed_root() {
+        let scratch = Scratch::new();
+        let root = scratch.dir("root");
+        let private = scratch.dir("root/private");
+        let sandbox = sandbox(&[&root], &[&private]);
+
+        assert_eq!(
+            kind(&sandbox, root.join("notes.txt")),
+            PathRuleKind::AllowedRoot
+        );
+        assert_eq!(kind(&sandbox, &private), PathRuleKind::DeniedRoot);
+        assert_eq!(
+            kind(&sandbox, private.join("a/b.txt")),
+            PathRuleKind::DeniedRoot
+        );
+        // A denied root is still denied when reached through `..`
+        assert_eq!(
+            kind(&sandbox, root.join("other/../private/b.txt")),
+            PathRuleKind::DeniedRoot
+        );
+    }
+
+    #[test]
+    fn ssh_keys_are_protected_inside_an_allowed_root() {
+        let scratch = Scratch::new();
+        let root = scratch.dir("root");
+        let sandbox = sandbox(&[&root], &[]);
+
+        for path in [
+            root.join(".ssh"),
+            root.join(".ssh/id_ed25519"),
+            root.join("sub/.ssh/authorized_keys"),
+        ] {
+            assert_eq!(
+                kind(&sandbox, &path),
+                PathRuleKind::Protected,
+                "{}",
+                path.display()
+            );
+        }
+        assert_eq!(
+            kind(&sandbox, root.join("ssh-notes.txt")),
+            PathRuleKind::AllowedRoot
+        );
+    }
+
+    #[test]
+    fn registry_hives_are_protected() {
+        for text in [
+            "c:/windows/system32/config/sam",
+            "c:/windows/system32/config/security",
+            "c:/windows/system32/config/sam.log1",
+            "c:/windows/system32/config/regback/security",
+            "c:/windows/repair/sam",
+        ] {
+            assert_eq!(
+                protected_location(text),
+                Some("Windows SAM and SECURITY registry hives"),
+                "{}",
+                text
+            );
+        }
+        assert_eq!(protected_location("c:/users/tech/documents/sam"), None);
+        assert_eq!(protected_location("d:/backup/config/security"), None);
+    }
+
+    #[test]
+    fn app_settings_are_protected() {
+        let sandbox = FilesystemSandbox::new(&FilesystemPolicy::default(), None);
+        let data_dir = get_data_dir_path();
+
+        for name in ["settings.json", "mcp_keys.json", "mcp_tls/key.pem"] {
+            assert_eq!(
+                kind(&sandbox, data_dir.join(name)),
+                PathRuleKind::Protected,
+                "{}",
+                name
+            );
+        }
+        assert_eq!(
+            kind(&sandbox, data_dir.join("reports/report.json")),
+            PathRuleKind::AllowedRoot
+        );
+    }
+}
@@ -0,0 +1,550 @@
+//! Persistent shell sessions
+//!
+//! With `persistent_shell` enabled, each conversation keeps one shell alive
+//! between agent commands, so the working directory, variables and imported
+//! modules carry over from one step to the next and commands skip the
+//! interpreter's startup. The shell is PowerShell on Windows and `sh`
+//! elsewhere.
+//!
+//! Commands are written to the shell's stdin one at a time. Each is followed
+//! by a marker unique to the session, printed to stdout with the command's
+//! exit code and to stderr on its own; everything before the markers is the
+//! command's output. A command that times out is killed with its session,
+//! and the conversation's next command starts a fresh one.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+
+use uuid::Uuid;
+
+use super::executor::{CappedOutput, ExecutionLimits, PendingText, FLUSH_INTERVAL, PIPE_GRACE};
+use crate::services::kill_process_tree;
+use crate::types::{CommandExecutionResult, CommandOutputStream};
+
+// =============================================================================
+// Reader Threads
+// =============================================================================
+
+/// What a reader thread saw on its stream
+enum StreamEvent {
+    Output(Vec<u8>),
+    /// The end-of-command marker, with the exit code on stdout
+    Done(Option<i32>),
+    /// The shell closed the stream (it exited)
+    Closed,
+}
+
+/// How many bytes at the end of `buf` could be the start of `marker`
+fn partial_marker_len(buf: &[u8], marker: &[u8]) -> usize {
+    (1..marker.len().min(buf.len() + 1))
+        .rev()
+        .find(|&n| buf.ends_with(&marker[..n]))
+        .unwrap_or(0)
+}
+
+/// Read a pipe for the life of the session, splitting output at markers
+fn spawn_marker_reader(
+    mut pipe: impl Read + Send + 'static,
+    stream: CommandOutputStream,
+    marker: Vec<u8>,
+    tx: Sender<(CommandOutputStream, StreamEvent)>,
+) {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 8192];
+        loop {
+            let n = match pipe.read(&mut chunk) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n,
+            };
+            buf.extend_from_slice(&chunk[..n]);
+
+            loop {
+                let Some(pos) = buf.windows(marker.len()).position(|w| w == marker) else {
+                    // Hold back what may be the start of a marker
+                    let send = buf.len() - partial_marker_len(&buf, &marker);
+                    if send > 0 {
+                        let output = buf.drain(..send).collect();
+                        let _ = tx.send((stream, StreamEvent::Output(output)));
+                    }
+                    break;
+                };
+                // The marker line ends with the exit code on stdout
+                let after = pos + marker.len();
+                let Some(len) = buf[after..].iter().position(|&b| b == b'\n') else {
+                    break;
+                };
+                if pos > 0 {
+                    let _ = tx.send((stream, StreamEvent::Output(buf[..pos].to_vec())));
+                }
+                let code = String::from_utf8_lossy(&buf[after..after + len])
+                    .trim()
+                    .trim_start_matches(':')
+                    .parse()
+                    .ok();
+                let _ = tx.send((stream, StreamEvent::Done(code)));
+                buf.drain(..after + len + 1);
+            }
+        }
+        if !buf.is_empty() {
+            let _ = tx.send((stream, StreamEvent::Output(buf)));
+        }
+        let _ = tx.send((stream, StreamEvent::Closed));
+    });
+}
+
+// =============================================================================
+// Shell Session
+// =============================================================================
+
+/// A shell kept running between commands
+struct ShellSession {
+    child: Child,
+    stdin: ChildStdin,
+    marker: String,
+    events: Receiver<(CommandOutputStream, StreamEvent)>,
+    /// Cleared once the shell has exited or been killed
+    alive: bool,
+}
+
+impl ShellSession {
+    fn start() -> Result<Self, String> {
+        let mut child = shell_process()
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to start shell session: {}", e))?;
+
+        let marker = format!("__RUSTSERVICE_{}__", Uuid::new_v4().simple());
+        let (tx, events) = mpsc::channel();
+        let stdin = child.stdin.take();
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let (Some(stdin), Some(stdout), Some(stderr)) = (stdin, stdout, stderr) else {
+            kill_process_tree(child.id());
+            let _ = child.wait();
+            return Err("Failed to start shell session: missing pipes".to_string());
+        };
+        spawn_marker_reader(
+            stdout,
+            CommandOutputStream::Stdout,
+            marker.clone().into(),
+            tx.clone(),
+        );
+        spawn_marker_reader(
+            stderr,
+            CommandOutputStream::Stderr,
+            marker.clone().into(),
+            tx,
+        );
+
+        let mut session = Self {
+            child,
+            stdin,
+            marker,
+            events,
+            alive: true,
+        };
+        session.write(SESSION_SETUP)?;
+        Ok(session)
+    }
+
+    fn write(&mut self, script: &str) -> Result<(), String> {
+        self.stdin
+            .write_all(script.as_bytes())
+            .and_then(|_| self.stdin.flush())
+            .map_err(|e| {
+                self.alive = false;
+                format!("Shell session has exited: {}", e)
+            })
+    }
+
+    /// Stop the shell and everything it started, if it is still running
+    fn kill(&mut self) {
+        if !matches!(self.child.try_wait(), Ok(Some(_))) {
+            kill_process_tree(self.child.id());
+        }
+        let _ = self.child.wait();
+        self.alive = false;
+    }
+
+    /// Run one command in the session within `limits`
+    fn run(
+        &mut self,
+        command: &str,
+        limits: ExecutionLimits,
+        mut on_output: impl FnMut(CommandOutputStream, String),
+    ) -> Result<CommandExecutionResult, String> {
+        let started = Instant::now();
+        let deadline = limits.timeout.map(|t| started + t);
+        let script = frame_command(command, &self.marker);
+        self.write(&script)?;
+
+        let mut stdout = CappedOutput::new(limits.max_output_bytes);
+        let mut stderr = CappedOutput::new(limits.max_output_bytes);
+        let mut pending_out = PendingText::default();
+        let mut pending_err = PendingText::default();
+        let mut last_flush = Instant::now();
+        let mut exit_code = None;
+        let (mut out_done, mut err_done) = (false, false);
+        let mut timed_out = false;
+        let mut drain_deadline: Option<Instant> = None;
+
+        let mut flush = |pending_out: &mut PendingText, pending_err: &mut PendingText, all| {
+            if let Some(text) = pending_out.take(all) {
+                on_output(CommandOutputStream::Stdout, text);
+            }
+            if let Some(text) = pending_err.take(all) {
+                on_output(CommandOutputStream::Stderr, text);
+            }
+        };
+
+        while !(out_done && err_done) {
+            match self.events.recv_timeout(FLUSH_INTERVAL) {
+                Ok((CommandOutputStream::Stdout, StreamEvent::Output(bytes))) => {
+                    stdout.push(&bytes);
+                    pending_out.push(&bytes);
+                }
+                Ok((CommandOutputStream::Stderr, StreamEvent::Output(bytes))) => {
+                    stderr.push(&bytes);
+                    pending_err.push(&bytes);
+                }
+                Ok((CommandOutputStream::Stdout, StreamEvent::Done(code))) => {
+                    exit_code = code;
+                    out_done = true;
+                }
+                Ok((CommandOutputStream::Stderr, StreamEvent::Done(_))) => err_done = true,
+                Ok((stream, StreamEvent::Closed)) => {
+                    // The command ended the shell (e.g. `exit`)
+                    self.alive = false;
+                    match stream {
+                        CommandOutputStream::Stdout => out_done = true,
+                        CommandOutputStream::Stderr => err_done = true,
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.alive = false;
+                    break;
+                }
+            }
+
+            if last_flush.elapsed() >= FLUSH_INTERVAL {
+                flush(&mut pending_out, &mut pending_err, false);
+                last_flush = Instant::now();
+            }
+
+            if drain_deadline.is_some_and(|d| Instant::now() >= d) {
+                break;
+            }
+            if !timed_out && deadline.is_some_and(|d| Instant::now() >= d) {
+                // Take the session down with the command, then collect what
+                // it wrote before dying
+                self.kill();
+                timed_out = true;
+                drain_deadline = Some(Instant::now() + PIPE_GRACE);
+            }
+        }
+        flush(&mut pending_out, &mut pending_err, true);
+
+        let exit_code = if timed_out {
+            -1
+        } else if !self.alive {
+            self.kill();
+            self.child
+                .try_wait()
+                .ok()
+                .flatten()
+                .and_then(|status| status.code())
+                .unwrap_or(-1)
+        } else {
+            exit_code.unwrap_or(-1)
+        };
+
+        let truncated = stdout.dropped() > 0 || stderr.dropped() > 0;
+        let stdout_bytes = stdout.total;
+        let stderr_bytes = stderr.total;
+        Ok(CommandExecutionResult {
+            exit_code,
+            stdout: stdout.into_text(),
+            stderr: stderr.into_text(),
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            timed_out,
+            stdout_bytes,
+            stderr_bytes,
+            truncated,
+        })
+    }
+}
+
+impl Drop for ShellSession {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+// =============================================================================
+// Shell Framing
+// =============================================================================
+
+/// The long-lived interpreter, reading commands from stdin
+fn shell_process() -> Command {
+    #[cfg(windows)]
+    {
+        let mut cmd = Command::new("powershell");
+        cmd.args(["-NoProfile", "-NoLogo", "-NonInteractive", "-Command", "-"]);
+        cmd
+    }
+
+    #[cfg(not(windows))]
+    {
+        Command::new("sh")
+    }
+}
+
+/// Run once when a session starts
+#[cfg(windows)]
+const SESSION_SETUP: &str = "[Console]::OutputEncoding = [Text.Encoding]::UTF8; \
+     $ProgressPreference = 'SilentlyContinue'\n";
+
+#[cfg(not(windows))]
+const SESSION_SETUP: &str = "";
+
+/// A command followed by the end-of-command markers. The command is passed
+/// as data (base64 or a quoted string) so it can't break the framing, and a
+/// syntax error in it fails the command rather than the shell.
+#[cfg(windows)]
+fn frame_command(command: &str, marker: &str) -> String {
+    use base64::Engine;
+
+    let encoded = base64::engine::general_purpose::STANDARD.encode(command.as_bytes());
+    // Dot-sourcing keeps variables and functions in the session's scope
+    format!(
+        "$global:LASTEXITCODE = 0; $__rsLastError = $Error[0]; \
+         try {{ . ([ScriptBlock]::Create([Text.Encoding]::UTF8.GetString(\
+         [Convert]::FromBase64String('{encoded}')))) | Out-Default; \
+         $__rsCode = if ($global:LASTEXITCODE) {{ $global:LASTEXITCODE }} \
+         elseif ($Error.Count -and -not [object]::ReferenceEquals($Error[0], $__rsLastError)) {{ 1 }} \
+         else {{ 0 }} }} \
+         catch {{ [Console]::Error.WriteLine($_.ToString()); $__rsCode = 1 }}; \
+         [Console]::Out.WriteLine('{marker}:' + $__rsCode); [Console]::Error.WriteLine('{marker}')\n"
+    )
+}
+
+#[cfg(not(windows))]
+fn frame_command(command: &str, marker: &str) -> String {
+    // `command eval` keeps a syntax error from exiting the shell
+    format!(
+        "command eval '{}' </dev/null\nprintf '%s:%s\\n' '{marker}' \"$?\"\nprintf '%s\\n' '{marker}' >&2\n",
+        command.replace('\'', "'\\''")
+    )
+}
+
+// =============================================================================
+// Session Registry
+// =============================================================================
+
+/// A conversation's session, started on its first command
+#[derive(Default)]
+struct SessionSlot {
+    /// Process ID of the running shell, zero when there is none; kept outside
+    /// the lock so a session can be killed while a command holds it
+    pid: AtomicU32,
+    session: Mutex<Option<ShellSession>>,
+}
+
+static SESSIONS: Mutex<Option<HashMap<String, Arc<SessionSlot>>>> = Mutex::new(None);
+
+fn session_slot(conversation_id: &str) -> Arc<SessionSlot> {
+    let mut sessions = SESSIONS.lock().unwrap_or_else(|e| e.into_inner());
+    let sessions = sessions.get_or_insert_with(HashMap::new);
+    Arc::clone(sessions.entry(conversation_id.to_string()).or_default())
+}
+
+/// Kill the slot's shell, ending any command running in it. Returns the
+/// slot's lock with the session cleared, so a caller can start the next one
+/// before another command gets in.
+fn kill_slot(slot: &SessionSlot) -> MutexGuard<'_, Option<ShellSession>> {
+    let pid = slot.pid.swap(0, Ordering::SeqCst);
+    if pid != 0 {
+        kill_process_tree(pid);
+    }
+    let mut session = slot.session.lock().unwrap_or_else(|e| e.into_inner());
+    session.take();
+    session
+}
+
+/// Run a command in the conversation's shell session, starting one if
+/// needed. Commands in one conversation run one at a time.
+pub(crate) fn run_in_session(
+    conversation_id: &str,
+    command: &str,
+    limits: ExecutionLimits,
+    on_output: impl FnMut(CommandOutputStream, String),
+) -> Result<CommandExecutionResult, String> {
+    let slot = session_slot(conversation_id);
+    let mut guard = slot.session.lock().unwrap_or_else(|e| e.into_inner());
+    if !guard.as_ref().is_some_and(|s| s.alive) {
+        let session = ShellSession::start()?;
+        slot.pid.store(session.child.id(), Ordering::SeqCst);
+        *guard = Some(session);
+    }
+    let Some(session) = guard.as_mut() else {
+        return Err("Shell session is not running".to_string());
+    };
+
+    let result = session.run(command, limits, on_output);
+    if !session.alive {
+        slot.pid.store(0, Ordering::SeqCst);
+        guard.take();
+    }
+    result
+}
+
+/// Replace the conversation's shell session with a fresh one, ending any
+/// command running in it
+#[tauri::command]
+pub fn reset_agent_shell_session(conversation_id: String) -> Result<(), String> {
+    let slot = session_slot(&conversation_id);
+    let mut guard = kill_slot(&slot);
+    let session = ShellSession::start()?;
+    slot.pid.store(session.child.id(), Ordering::SeqCst);
+    *guard = Some(session);
+    Ok(())
+}
+
+/// Close the conversation's shell session, ending any command running in it.
+/// Returns whether there was one.
+#[tauri::command]
+pub fn close_agent_shell_session(conversation_id: String) -> Result<bool, String> {
+    let slot = {
+        let mut sessions = SESSIONS.lock().unwrap_or_else(|e| e.into_inner());
+        sessions
+            .get_or_insert_with(HashMap::new)
+            .remove(&conversation_id)
+    };
+    let Some(slot) = slot else {
+        return Ok(false);
+    };
+    let had_session = slot.pid.load(Ordering::SeqCst) != 0;
+    drop(kill_slot(&slot));
+    Ok(had_session)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    const LIMITS: ExecutionLimits = ExecutionLimits {
+        timeout: Some(Duration::from_secs(10)),
+        max_output_bytes: None,
+    };
+
+    /// A conversation of its own for each test, closed when it ends
+    struct Conversation(String);
+
+    impl Conversation {
+        fn new() -> Self {
+            Self(Uuid::new_v4().to_string())
+        }
+
+        fn run(&self, command: &str) -> CommandExecutionResult {
+            self.run_with(command, LIMITS)
+        }
+
+        fn run_with(&self, command: &str, limits: ExecutionLimits) -> CommandExecutionResult {
+            run_in_session(&self.0, command, limits, |_, _| {}).unwrap()
+        }
+
+        fn pid(&self) -> u32 {
+            session_slot(&self.0).pid.load(Ordering::SeqCst)
+        }
+    }
+
+    impl Drop for Conversation {
+        fn drop(&mut self) {
+            let _ = close_agent_shell_session(self.0.clone());
+        }
+    }
+
+    #[test]
+    fn state_carries_over_between_commands() {
+        let conversation = Conversation::new();
+        conversation.run("cd /tmp && GREETING=hello");
+        let result = conversation.run("pwd; echo \"$GREETING\"");
+        assert_eq!(result.exit_code, 0);
+        assert_eq!(result.stdout, "/tmp\nhello\n");
+    }
+
+    #[test]
+    fn exit_code_comes_from_the_marker() {
+        let conversation = Conversation::new();
+        let result = conversation.run("echo out; echo err >&2; (exit 3)");
+        assert_eq!(result.exit_code, 3);
+        assert_eq!(result.stdout, "out\n");
+        assert_eq!(result.stderr, "err\n");
+        assert_eq!(conversation.run("true").exit_code, 0);
+    }
+
+    #[test]
+    fn syntax_error_keeps_the_session() {
+        let conversation = Conversation::new();
+        conversation.run("KEPT=yes");
+        let pid = conversation.pid();
+        let result = conversation.run("if then");
+        assert_ne!(result.exit_code, 0);
+        assert_eq!(conversation.pid(), pid);
+        assert_eq!(conversation.run("echo $KEPT").stdout, "yes\n");
+    }
+
+    #[test]
+    fn exit_starts_a_new_session() {
+        let conversation = Conversation::new();
+        conversation.run("GONE=yes");
+        let result = conversation.run("exit 7");
+        assert_eq!(result.exit_code, 7);
+        assert_eq!(conversation.pid(), 0);
+        let result = conversation.run("echo \"${GONE:-unset}\"");
+        assert_eq!(result.stdout, "unset\n");
+        assert_ne!(conversation.pid(), 0);
+    }
+
+    #[test]
+    fn timeout_kills_the_session() {
+        let conversation = Conversation::new();
+        conversation.run("LOST=yes");
+        let result = conversation.run_with(
+            "sleep 30",
+            ExecutionLimits {
+                timeout: Some(Duration::from_millis(300)),
+                max_output_bytes: None,
+            },
+        );
+        assert!(result.timed_out);
+        assert_eq!(result.exit_code, -1);
+        assert!(result.elapsed_ms < 10_000);
+        assert_eq!(conversation.pid(), 0);
+        assert_eq!(
+            conversation.run("echo \"${LOST:-unset}\"").stdout,
+            "unset\n"
+        );
+    }
+
+    #[test]
+    fn reset_replaces_the_session() {
+        let conversation = Conversation::new();
+        conversation.run("RESET=no");
+        let pid = conversation.pid();
+        reset_agent_shell_session(conversation.0.clone()).unwrap();
+        assert_ne!(conversation.pid(), pid);
+        assert_eq!(conversation.run("echo \"${RESET:-yes}\"").stdout, "yes\n");
+    }
+}
@@ -0,0 +1,200 @@
+//! Machine history commands
+//!
+//! Identifies the machine a service run is performed on and links saved
+//! reports by that identity, so repeat visits of the same PC can be listed
+//! together and their metrics (drive wear, free space, battery capacity,
+//! network speed) compared over time.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use sha2::{Digest, Sha256};
+use sysinfo::{Motherboard, Product};
+
+use super::agent::get_current_machine_id;
+use super::report_index::{list_indexed_machines, machine_report_ids, search_reports};
+use super::services::load_report;
+use crate::reports::metrics::extract_metrics;
+use crate::types::{
+    MachineIdentity, MachineSummary, MachineTrends, MetricTrend, ReportQuery, ReportQueryResult,
+    TrendPoint,
+};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Number of hex characters kept from the identity hash
+const MACHINE_KEY_LEN: usize = 32;
+
+/// Maximum number of reports returned for a single machine
+const MAX_MACHINE_REPORTS: usize = 500;
+
+/// Values OEMs leave in SMBIOS fields instead of a real identifier
+const PLACEHOLDER_IDENTIFIERS: [&str; 12] = [
+    "to be filled by o.e.m.",
+    "default string",
+    "system serial number",
+    "system product name",
+    "base board serial number",
+    "not applicable",
+    "not specified",
+    "none",
+    "n/a",
+    "0",
+    "0123456789",
+    "123456789",
+];
+
+/// Identity is read once per process; hardware doesn't change while we run
+static MACHINE_IDENTITY: OnceLock<MachineIdentity> = OnceLock::new();
+
+// =============================================================================
+// Identity Detection
+// =============================================================================
+
+/// Trim an SMBIOS value and drop empty or placeholder values
+fn clean_identifier(value: Option<String>) -> Option<String> {
+    let value = value?.trim().to_string();
+    let hex: Vec<char> = value.chars().filter(|c| c.is_ascii_hexdigit()).collect();
+    // All-zero and all-F UUIDs/serials are unset fields
+    let is_blank_id = value.len() >= 8
+        && !hex.is_empty()
+        && (hex.iter().all(|&c| c == '0') || hex.iter().all(|c| c.eq_ignore_ascii_case(&'f')));
+    let is_placeholder = value.is_empty()
+        || is_blank_id
+        || PLACEHOLDER_IDENTIFIERS.contains(&value.to_lowercase().as_str());
+    (!is_placeholder).then_some(value)
+}
+
+/// Derive a stable key from the most specific identifier available.
+/// The board UUID is preferred; the hostname is the last resort.
+fn compute_machine_key(identity: &MachineIdentity) -> String {
+    let source = if let Some(ref uuid) = identity.system_uuid {
+        format!("uuid:{}", uuid.to_lowercase())
+    } else if let Some(ref serial) = identity.system_serial {
+        format!("system-serial:{}", serial)
+    } else if let Some(ref serial) = identity.board_serial {
+        format!("board-serial:{}", serial)
+    } else {
+        format!("hostname:{}", identity.hostname.to_lowercase())
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update(source.as_bytes());
+    let mut key = hex::encode(hasher.finalize());
+    key.truncate(MACHINE_KEY_LEN);
+    key
+}
+
+fn detect_machine_identity() -> MachineIdentity {
+    let mut identity = MachineIdentity {
+        machine_key: String::new(),
+        hostname: get_current_machine_id(),
+        system_uuid: clean_identifier(Product::uuid()),
+        system_serial: clean_identifier(Product::serial_number()),
+        board_serial: clean_identifier(Motherboard::new().and_then(|mb| mb.serial_number())),
+        vendor: clean_identifier(Product::vendor_name()),
+        model: clean_identifier(Product::name()),
+    };
+    identity.machine_key = compute_machine_key(&identity);
+    identity
+}
+
+/// Identity of the machine this app is running on
+pub(crate) fn current_machine_identity() -> MachineIdentity {
+    MACHINE_IDENTITY
+        .get_or_init(detect_machine_identity)
+        .clone()
+}
+
+// =============================================================================
+// Trends
+// =============================================================================
+
+/// Build per-metric series from a machine's reports (oldest first)
+fn compute_trends(report_ids: &[String]) -> Vec<MetricTrend> {
+    let mut trends: Vec<MetricTrend> = Vec::new();
+    let mut positions: HashMap<(&'static str, Option<String>), usize> = HashMap::new();
+
+    for report_id in report_ids {
+        // Reports missing on disk are skipped; the index catches up on next save
+        let Ok(report) = load_report(report_id) else {
+            continue;
+        };
+
+        for sample in extract_metrics(&report) {
+            let key = (sample.metric, sample.subject.clone());
+            let index = *positions.entry(key).or_insert_with(|| {
+                trends.push(MetricTrend {
+                    metric: sample.metric.to_string(),
+                    label: sample.label.to_string(),
+                    unit: sample.unit.to_string(),
+                    subject: sample.subject.clone(),
+                    points: Vec::new(),
+                    change: None,
+                });
+                trends.len() - 1
+            });
+
+            let points = &mut trends[index].points;
+            // A report can contain the same metric twice (e.g. a service queued twice);
+            // keep the last value
+            if points.last().is_some_and(|p| &p.report_id == report_id) {
+                points.pop();
+            }
+            points.push(TrendPoint {
+                report_id: report.id.clone(),
+                timestamp: report.started_at.clone(),
+                value: sample.value,
+            });
+        }
+    }
+
+    for trend in &mut trends {
+        if let [first, .., last] = trend.points.as_slice() {
+            trend.change = Some(last.value - first.value);
+        }
+    }
+
+    trends
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Get the hardware identity of the current machine
+#[tauri::command]
+pub fn get_machine_identity() -> MachineIdentity {
+    current_machine_identity()
+}
+
+/// List every machine that has saved reports, most recently seen first
+#[tauri::command]
+pub fn list_machines() -> Result<Vec<MachineSummary>, String> {
+    list_indexed_machines()
+}
+
+/// List the reports recorded for a machine, newest first
+#[tauri::command]
+pub fn list_machine_reports(machine_key: String) -> Result<ReportQueryResult, String> {
+    search_reports(ReportQuery {
+        machine_key: Some(machine_key),
+        limit: Some(MAX_MACHINE_REPORTS),
+        ..Default::default()
+    })
+}
+
+/// Compute per-metric trends across all reports for a machine
+#[tauri::command]
+pub fn get_machine_trends(machine_key: String) -> Result<MachineTrends, String> {
+    let report_ids = machine_report_ids(&machine_key)?;
+    let trends = compute_trends(&report_ids);
+
+    Ok(MachineTrends {
+        machine_key,
+        report_count: report_ids.len(),
+        trends,
+    })
+}
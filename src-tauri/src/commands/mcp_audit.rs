@@ -0,0 +1,407 @@
+//! MCP audit log
+//!
+//! Every request the MCP server handles is recorded in `data/logs/mcp_audit.db`
+//! with the caller's key, what it asked for and how it went, so a technician
+//! can show afterwards exactly what a remote client did on a machine.
+//! Arguments are redacted before they reach this module (see `mcp::audit`).
+//!
+//! Entries are written by a dedicated thread so a request never waits on
+//! the database, and entries older than `AUDIT_RETENTION_DAYS` (or beyond
+//! `MAX_AUDIT_ENTRIES`) are pruned as the log grows.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::{LazyLock, Mutex};
+
+use chrono::{Duration, SecondsFormat, Utc};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection};
+
+use super::data_dir::get_data_dir_path;
+use super::report_index::{like_pattern, normalize_timestamp};
+use crate::types::{McpAuditEntry, McpAuditExportFormat, McpAuditQuery, McpAuditQueryResult};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Schema version stored in `PRAGMA user_version`
+const AUDIT_SCHEMA_VERSION: i32 = 1;
+
+/// Page size when a query doesn't specify a limit
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Upper bound on a single page
+const MAX_PAGE_SIZE: usize = 1000;
+
+/// How long entries are kept
+const AUDIT_RETENTION_DAYS: i64 = 180;
+
+/// Most entries kept, however recent; the oldest go first
+const MAX_AUDIT_ENTRIES: i64 = 200_000;
+
+/// Entries waiting for the writer thread before new ones are dropped
+const AUDIT_QUEUE_CAPACITY: usize = 1024;
+
+/// Entries written between prunes
+const PRUNE_INTERVAL: u64 = 1000;
+
+// =============================================================================
+// Database Helpers
+// =============================================================================
+
+/// Open audit database, shared by the MCP transports and the commands
+static AUDIT_DB: Mutex<Option<Connection>> = Mutex::new(None);
+
+fn get_audit_dir() -> PathBuf {
+    get_data_dir_path().join("logs")
+}
+
+pub(crate) fn get_audit_db_path() -> PathBuf {
+    get_audit_dir().join("mcp_audit.db")
+}
+
+fn open_audit_db() -> Result<Connection, String> {
+    let dir = get_audit_dir();
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create logs dir: {}", e))?;
+
+    let conn = Connection::open(get_audit_db_path())
+        .map_err(|e| format!("Failed to open MCP audit log: {}", e))?;
+
+    let version: i32 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read MCP audit log version: {}", e))?;
+
+    if version < AUDIT_SCHEMA_VERSION {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS mcp_audit (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp TEXT NOT NULL,
+                transport TEXT NOT NULL,
+                remote_addr TEXT,
+                key_id TEXT,
+                key_name TEXT,
+                method TEXT,
+                tool TEXT,
+                arguments TEXT,
+                duration_ms INTEGER NOT NULL,
+                success INTEGER NOT NULL,
+                error TEXT,
+                output_bytes INTEGER NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS idx_mcp_audit_timestamp ON mcp_audit(timestamp);
+             CREATE INDEX IF NOT EXISTS idx_mcp_audit_key ON mcp_audit(key_id);
+             CREATE INDEX IF NOT EXISTS idx_mcp_audit_tool ON mcp_audit(tool);",
+        )
+        .map_err(|e| format!("Failed to create MCP audit log: {}", e))?;
+
+        conn.execute_batch(&format!("PRAGMA user_version = {}", AUDIT_SCHEMA_VERSION))
+            .map_err(|e| format!("Failed to set MCP audit log version: {}", e))?;
+    }
+
+    prune_audit_log(&conn)?;
+    Ok(conn)
+}
+
+/// Delete entries past the retention period, then the oldest beyond the cap
+fn prune_audit_log(conn: &Connection) -> Result<(), String> {
+    let cutoff = (Utc::now() - Duration::days(AUDIT_RETENTION_DAYS))
+        .to_rfc3339_opts(SecondsFormat::Millis, true);
+    conn.execute(
+        "DELETE FROM mcp_audit WHERE timestamp < ?1",
+        params![cutoff],
+    )
+    .map_err(|e| format!("Failed to prune MCP audit log: {}", e))?;
+    conn.execute(
+        "DELETE FROM mcp_audit WHERE id <= (
+            SELECT id FROM mcp_audit ORDER BY id DESC LIMIT 1 OFFSET ?1
+         )",
+        params![MAX_AUDIT_ENTRIES],
+    )
+    .map_err(|e| format!("Failed to prune MCP audit log: {}", e))?;
+    Ok(())
+}
+
+/// Run `f` against the audit database, opening it on first use
+fn with_audit_db<T>(f: impl FnOnce(&Connection) -> Result<T, String>) -> Result<T, String> {
+    let mut guard = AUDIT_DB
+        .lock()
+        .map_err(|e| format!("Failed to lock MCP audit log: {}", e))?;
+    if guard.is_none() {
+        *guard = Some(open_audit_db()?);
+    }
+    f(guard.as_ref().expect("audit log opened above"))
+}
+
+// =============================================================================
+// Recording
+// =============================================================================
+
+/// Queue for the writer thread, started on first use
+static AUDIT_QUEUE: LazyLock<SyncSender<McpAuditEntry>> = LazyLock::new(|| {
+    let (sender, receiver) = mpsc::sync_channel::<McpAuditEntry>(AUDIT_QUEUE_CAPACITY);
+    let spawned = std::thread::Builder::new()
+        .name("mcp-audit".to_string())
+        .spawn(move || {
+            let mut written: u64 = 0;
+            for entry in receiver {
+                // Auditing must never take a request down with it
+                if let Err(e) = with_audit_db(|conn| insert_entry(conn, &entry)) {
+                    eprintln!("[MCP] {}", e);
+                }
+                written += 1;
+                if written % PRUNE_INTERVAL == 0 {
+                    if let Err(e) = with_audit_db(prune_audit_log) {
+                        eprintln!("[MCP] {}", e);
+                    }
+                }
+            }
+        });
+    // Without a writer the receiver is gone and every send reports it
+    if let Err(e) = spawned {
+        eprintln!("[MCP] Failed to start audit writer: {}", e);
+    }
+    sender
+});
+
+/// Append an entry to the audit log. `id` is ignored; the database assigns it.
+///
+/// The entry is handed to the writer thread, so this never blocks; if the
+/// writer has fallen too far behind the entry is dropped and logged.
+pub(crate) fn record_mcp_audit(entry: &McpAuditEntry) {
+    match AUDIT_QUEUE.try_send(entry.clone()) {
+        Ok(()) => {}
+        Err(TrySendError::Full(_)) => {
+            eprintln!("[MCP] Audit log writer is behind; dropped an entry");
+        }
+        Err(TrySendError::Disconnected(_)) => {
+            eprintln!("[MCP] Audit log writer is not running; dropped an entry");
+        }
+    }
+}
+
+fn insert_entry(conn: &Connection, entry: &McpAuditEntry) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO mcp_audit (timestamp, transport, remote_addr, key_id, key_name,
+            method, tool, arguments, duration_ms, success, error, output_bytes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            entry.timestamp,
+            entry.transport,
+            entry.remote_addr,
+            entry.key_id,
+            entry.key_name,
+            entry.method,
+            entry.tool,
+            entry.arguments,
+            entry.duration_ms as i64,
+            entry.success,
+            entry.error,
+            entry.output_bytes as i64,
+        ],
+    )
+    .map(|_| ())
+    .map_err(|e| format!("Failed to write MCP audit entry: {}", e))
+}
+
+// =============================================================================
+// Queries
+// =============================================================================
+
+/// WHERE clause and bound values for a query's filters
+fn build_filters(query: &McpAuditQuery) -> Result<(String, Vec<Value>), String> {
+    let mut clauses: Vec<String> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+
+    if let Some(key_id) = query.key_id.as_ref().filter(|s| !s.is_empty()) {
+        clauses.push("key_id = ?".to_string());
+        values.push(Value::Text(key_id.clone()));
+    }
+    if let Some(method) = query.method.as_ref().filter(|s| !s.is_empty()) {
+        clauses.push("method = ?".to_string());
+        values.push(Value::Text(method.clone()));
+    }
+    if let Some(tool) = query.tool.as_ref().filter(|s| !s.is_empty()) {
+        clauses.push("tool = ?".to_string());
+        values.push(Value::Text(tool.clone()));
+    }
+    if let Some(success) = query.success {
+        clauses.push("success = ?".to_string());
+        values.push(Value::Integer(success as i64));
+    }
+    if let Some(after) = &query.after {
+        let bound =
+            normalize_timestamp(after).ok_or_else(|| format!("Invalid after date: {}", after))?;
+        clauses.push("timestamp >= ?".to_string());
+        values.push(Value::Text(bound));
+    }
+    if let Some(before) = &query.before {
+        let bound = normalize_timestamp(before)
+            .ok_or_else(|| format!("Invalid before date: {}", before))?;
+        clauses.push("timestamp < ?".to_string());
+        values.push(Value::Text(bound));
+    }
+    if let Some(text) = query.text.as_ref().filter(|s| !s.trim().is_empty()) {
+        clauses.push("(arguments LIKE ? ESCAPE '\\' OR error LIKE ? ESCAPE '\\')".to_string());
+        let pattern = Value::Text(like_pattern(text.trim()));
+        values.push(pattern.clone());
+        values.push(pattern);
+    }
+
+    let where_sql = if clauses.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", clauses.join(" AND "))
+    };
+    Ok((where_sql, values))
+}
+
+/// Entries matching `where_sql`, newest first, optionally paginated
+fn select_entries(
+    conn: &Connection,
+    where_sql: &str,
+    mut values: Vec<Value>,
+    page: Option<(usize, usize)>,
+) -> Result<Vec<McpAuditEntry>, String> {
+    let mut sql = format!(
+        "SELECT id, timestamp, transport, remote_addr, key_id, key_name, method, tool,
+                arguments, duration_ms, success, error, output_bytes
+         FROM mcp_audit {}
+         ORDER BY timestamp DESC, id DESC",
+        where_sql
+    );
+    if let Some((limit, offset)) = page {
+        sql.push_str(" LIMIT ? OFFSET ?");
+        values.push(Value::Integer(limit as i64));
+        values.push(Value::Integer(offset as i64));
+    }
+
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let rows = stmt
+        .query_map(params_from_iter(values.iter()), |row| {
+            Ok(McpAuditEntry {
+                id: row.get(0)?,
+                timestamp: row.get(1)?,
+                transport: row.get(2)?,
+                remote_addr: row.get(3)?,
+                key_id: row.get(4)?,
+                key_name: row.get(5)?,
+                method: row.get(6)?,
+                tool: row.get(7)?,
+                arguments: row.get(8)?,
+                duration_ms: row.get::<_, i64>(9)? as u64,
+                success: row.get(10)?,
+                error: row.get(11)?,
+                output_bytes: row.get::<_, i64>(12)? as u64,
+            })
+        })
+        .map_err(|e| format!("Failed to query MCP audit log: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read MCP audit entry: {}", e))
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn entries_to_csv(entries: &[McpAuditEntry]) -> String {
+    let mut out = String::from(
+        "id,timestamp,transport,remoteAddr,keyId,keyName,method,tool,arguments,durationMs,success,error,outputBytes\n",
+    );
+    for entry in entries {
+        let opt = |v: &Option<String>| csv_field(v.as_deref().unwrap_or(""));
+        let fields = [
+            entry.id.to_string(),
+            csv_field(&entry.timestamp),
+            csv_field(&entry.transport),
+            opt(&entry.remote_addr),
+            opt(&entry.key_id),
+            opt(&entry.key_name),
+            opt(&entry.method),
+            opt(&entry.tool),
+            opt(&entry.arguments),
+            entry.duration_ms.to_string(),
+            entry.success.to_string(),
+            opt(&entry.error),
+            entry.output_bytes.to_string(),
+        ];
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Search the MCP audit log with optional filters and pagination.
+/// Results are ordered newest first.
+#[tauri::command]
+pub fn query_mcp_audit_log(query: McpAuditQuery) -> Result<McpAuditQueryResult, String> {
+    let (where_sql, values) = build_filters(&query)?;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let offset = query.offset.unwrap_or(0);
+
+    with_audit_db(|conn| {
+        let total: i64 = conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM mcp_audit {}", where_sql),
+                params_from_iter(values.iter()),
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Failed to count MCP audit entries: {}", e))?;
+        let entries = select_entries(conn, &where_sql, values, Some((limit, offset)))?;
+        Ok(McpAuditQueryResult {
+            total: total as usize,
+            entries,
+        })
+    })
+}
+
+/// Export every audit entry matching the filters (pagination is ignored) and
+/// return the file path. Defaults to a timestamped file in `data/logs`.
+#[tauri::command]
+pub fn export_mcp_audit_log(
+    query: McpAuditQuery,
+    format: McpAuditExportFormat,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    let (where_sql, values) = build_filters(&query)?;
+    let entries = with_audit_db(|conn| select_entries(conn, &where_sql, values, None))?;
+
+    let content = match format {
+        McpAuditExportFormat::Csv => entries_to_csv(&entries),
+        McpAuditExportFormat::Json => serde_json::to_string_pretty(&entries)
+            .map_err(|e| format!("Failed to serialize MCP audit log: {}", e))?,
+    };
+
+    let export_path = match output_path.filter(|p| !p.trim().is_empty()) {
+        Some(p) => PathBuf::from(p),
+        None => get_audit_dir().join(format!(
+            "mcp_audit_{}.{}",
+            Utc::now().format("%Y%m%d-%H%M%S"),
+            format.extension()
+        )),
+    };
+
+    if let Some(parent) = export_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create dir: {}", e))?;
+    }
+
+    fs::write(&export_path, content)
+        .map_err(|e| format!("Failed to write MCP audit export: {}", e))?;
+
+    Ok(export_path.to_string_lossy().to_string())
+}
@@ -0,0 +1,40 @@
+//! MCP client commands
+//!
+//! Status and tools of the external MCP servers configured in the agent
+//! settings, for the agent and the settings page. The connections themselves
+//! are managed by `mcp::client` and follow the saved settings.
+
+use serde_json::Value;
+
+use crate::types::{McpClientServerStatus, McpClientTool};
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Connection status of every enabled external MCP server
+#[tauri::command]
+pub fn list_mcp_client_servers() -> Vec<McpClientServerStatus> {
+    crate::mcp::mcp_client_statuses()
+}
+
+/// Tools of the connected external MCP servers, with namespaced names
+#[tauri::command]
+pub fn list_mcp_client_tools() -> Vec<McpClientTool> {
+    crate::mcp::mcp_client_tools()
+}
+
+/// Call an external tool by its namespaced name. Returns the server's
+/// `tools/call` result (`content` and `isError`).
+#[tauri::command]
+pub async fn call_mcp_client_tool(name: String, arguments: Option<Value>) -> Result<Value, String> {
+    let arguments = arguments.unwrap_or_else(|| Value::Object(Default::default()));
+    crate::mcp::call_mcp_client_tool(&name, arguments).await
+}
+
+/// Reconnect to an external MCP server now instead of waiting for the
+/// next retry
+#[tauri::command]
+pub fn reconnect_mcp_client(server_id: String) -> Result<(), String> {
+    crate::mcp::reconnect_mcp_client(&server_id)
+}
@@ -0,0 +1,299 @@
+//! MCP API key commands
+//!
+//! Named, revocable API keys for the MCP server, stored in
+//! `data/mcp_keys.json`. Only a SHA-256 hash of each secret is kept; the
+//! secret itself is returned once, when the key is created.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::data_dir::get_data_dir_path;
+use crate::types::{CreateMcpApiKeyRequest, CreatedMcpApiKey, McpApiKey, McpFsAccess};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Prefix on every generated secret, so keys are recognisable in configs
+const SECRET_PREFIX: &str = "rsk_";
+
+/// Characters of the secret kept for display
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+/// `last_used_at` is only rewritten when older than this, so busy clients
+/// don't cause a file write per request
+const LAST_USED_RESOLUTION_SECS: i64 = 60;
+
+// =============================================================================
+// Key Store
+// =============================================================================
+
+/// A key record on disk: the public metadata plus the secret's hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredMcpApiKey {
+    #[serde(flatten)]
+    key: McpApiKey,
+    key_hash: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct McpKeyStore {
+    #[serde(default)]
+    keys: Vec<StoredMcpApiKey>,
+}
+
+/// Loaded key store, shared by the commands and the MCP server
+static KEY_STORE: Mutex<Option<McpKeyStore>> = Mutex::new(None);
+
+fn get_key_store_path() -> PathBuf {
+    get_data_dir_path().join("mcp_keys.json")
+}
+
+fn load_key_store() -> Result<McpKeyStore, String> {
+    let path = get_key_store_path();
+    if !path.exists() {
+        return Ok(McpKeyStore::default());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read MCP keys: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse MCP keys: {}", e))
+}
+
+fn save_key_store(store: &McpKeyStore) -> Result<(), String> {
+    let data_dir = get_data_dir_path();
+    fs::create_dir_all(&data_dir).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    let content = serde_json::to_string_pretty(store)
+        .map_err(|e| format!("Failed to serialize MCP keys: {}", e))?;
+    fs::write(get_key_store_path(), content).map_err(|e| format!("Failed to write MCP keys: {}", e))
+}
+
+/// Run `f` against the loaded key store, loading it on first use
+fn with_key_store<T>(f: impl FnOnce(&mut McpKeyStore) -> Result<T, String>) -> Result<T, String> {
+    let mut guard = KEY_STORE
+        .lock()
+        .map_err(|e| format!("Failed to lock MCP keys: {}", e))?;
+    if guard.is_none() {
+        *guard = Some(load_key_store()?);
+    }
+    f(guard.as_mut().expect("key store loaded above"))
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn hash_secret(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+fn generate_secret() -> String {
+    // Two v4 UUIDs give 244 random bits
+    format!(
+        "{}{}{}",
+        SECRET_PREFIX,
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    )
+}
+
+fn now_rfc3339() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+fn is_expired(key: &McpApiKey, now: DateTime<Utc>) -> bool {
+    key.expires_at
+        .as_deref()
+        .and_then(parse_time)
+        .is_some_and(|expires| expires <= now)
+}
+
+fn new_key(
+    request: CreateMcpApiKeyRequest,
+    secret: &str,
+    expires_at: Option<String>,
+) -> StoredMcpApiKey {
+    StoredMcpApiKey {
+        key: McpApiKey {
+            id: Uuid::new_v4().to_string(),
+            name: request.name.trim().to_string(),
+            key_prefix: secret.chars().take(DISPLAY_PREFIX_LEN).collect(),
+            allowed_tools: request.allowed_tools,
+            fs_access: request.fs_access,
+            fs_paths: request
+                .fs_paths
+                .into_iter()
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect(),
+            created_at: now_rfc3339(),
+            expires_at,
+            last_used_at: None,
+            revoked_at: None,
+        },
+        key_hash: hash_secret(secret),
+    }
+}
+
+// =============================================================================
+// Authentication
+// =============================================================================
+
+/// Look up the key for a bearer secret, rejecting revoked and expired keys.
+/// Records the time of use.
+pub(crate) fn authenticate_mcp_api_key(secret: &str) -> Result<McpApiKey, String> {
+    let hash = hash_secret(secret);
+    with_key_store(|store| {
+        let now = Utc::now();
+        let stored = store
+            .keys
+            .iter_mut()
+            .find(|k| k.key_hash == hash)
+            .ok_or_else(|| "Unknown API key".to_string())?;
+
+        if stored.key.revoked_at.is_some() {
+            return Err(format!("API key '{}' has been revoked", stored.key.name));
+        }
+        if is_expired(&stored.key, now) {
+            return Err(format!("API key '{}' has expired", stored.key.name));
+        }
+
+        let stale = stored
+            .key
+            .last_used_at
+            .as_deref()
+            .and_then(parse_time)
+            .is_none_or(|t| now - t >= Duration::seconds(LAST_USED_RESOLUTION_SECS));
+        let key = if stale {
+            stored.key.last_used_at = Some(now_rfc3339());
+            let key = stored.key.clone();
+            if let Err(e) = save_key_store(store) {
+                eprintln!("[MCP] Failed to record key use: {}", e);
+            }
+            key
+        } else {
+            stored.key.clone()
+        };
+        Ok(key)
+    })
+}
+
+/// Move a legacy plaintext `mcp_api_key` from settings into the key store
+/// as a full-access key. Importing the same key twice is a no-op.
+pub(crate) fn import_legacy_mcp_api_key(secret: &str) -> Result<(), String> {
+    let hash = hash_secret(secret);
+    with_key_store(|store| {
+        if store.keys.iter().any(|k| k.key_hash == hash) {
+            return Ok(());
+        }
+        let request = CreateMcpApiKeyRequest {
+            name: "Default key (migrated)".to_string(),
+            allowed_tools: None,
+            fs_access: McpFsAccess::ReadWrite,
+            fs_paths: Vec::new(),
+            expires_at: None,
+        };
+        store.keys.push(new_key(request, secret, None));
+        save_key_store(store).inspect_err(|_| {
+            store.keys.pop();
+        })
+    })
+}
+
+/// Whether any key could currently authenticate
+pub(crate) fn has_active_mcp_api_keys() -> bool {
+    let now = Utc::now();
+    with_key_store(|store| {
+        Ok(store
+            .keys
+            .iter()
+            .any(|k| k.key.revoked_at.is_none() && !is_expired(&k.key, now)))
+    })
+    .unwrap_or(false)
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Create an MCP API key. The returned secret is not stored and can't be
+/// retrieved again.
+#[tauri::command]
+pub fn create_mcp_api_key(request: CreateMcpApiKeyRequest) -> Result<CreatedMcpApiKey, String> {
+    if request.name.trim().is_empty() {
+        return Err("Key name is required".to_string());
+    }
+
+    if let Some(tools) = &request.allowed_tools {
+        let known = crate::mcp::tool_names();
+        if let Some(unknown) = tools.iter().find(|t| !known.contains(t)) {
+            return Err(format!("Unknown MCP tool: {}", unknown));
+        }
+    }
+
+    let expires_at = match request.expires_at.as_deref().filter(|s| !s.is_empty()) {
+        Some(value) => {
+            let expires =
+                parse_time(value).ok_or_else(|| format!("Invalid expiry time: {}", value))?;
+            if expires <= Utc::now() {
+                return Err("Expiry time must be in the future".to_string());
+            }
+            Some(expires.to_rfc3339_opts(SecondsFormat::Secs, true))
+        }
+        None => None,
+    };
+
+    let secret = generate_secret();
+    let stored = new_key(request, &secret, expires_at);
+    let key = stored.key.clone();
+    with_key_store(|store| {
+        store.keys.push(stored);
+        save_key_store(store).inspect_err(|_| {
+            store.keys.pop();
+        })
+    })?;
+
+    Ok(CreatedMcpApiKey { key, secret })
+}
+
+/// List all MCP API keys, including revoked and expired ones
+#[tauri::command]
+pub fn list_mcp_api_keys() -> Result<Vec<McpApiKey>, String> {
+    with_key_store(|store| Ok(store.keys.iter().map(|k| k.key.clone()).collect()))
+}
+
+/// Revoke an MCP API key. The key stays listed so its history is visible.
+#[tauri::command]
+pub fn revoke_mcp_api_key(key_id: String) -> Result<McpApiKey, String> {
+    with_key_store(|store| {
+        let stored = store
+            .keys
+            .iter_mut()
+            .find(|k| k.key.id == key_id)
+            .ok_or_else(|| format!("MCP key not found: {}", key_id))?;
+        if stored.key.revoked_at.is_none() {
+            stored.key.revoked_at = Some(now_rfc3339());
+        }
+        let key = stored.key.clone();
+        save_key_store(store)?;
+        Ok(key)
+    })
+}
+
+/// Names of the tools the MCP server offers, for building key allowlists
+#[tauri::command]
+pub fn list_mcp_tool_names() -> Vec<String> {
+    crate::mcp::tool_names()
+}
@@ -0,0 +1,60 @@
+//! MCP server control commands
+//!
+//! Start, stop and restart the MCP HTTP server while the app runs, and report
+//! what it is doing. The server otherwise follows the saved settings (see
+//! `mcp::lifecycle`). Starting and stopping wait for the server, so the
+//! commands do that work off the main thread.
+
+use tauri::AppHandle;
+
+use super::settings::get_settings;
+use crate::types::McpServerStatus;
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Start the MCP server with the saved settings
+#[tauri::command]
+pub async fn start_mcp_server(app: AppHandle) -> Result<McpServerStatus, String> {
+    tokio::task::spawn_blocking(move || {
+        let settings = get_settings()?;
+        crate::mcp::start_mcp_server(app, &settings.agent)
+    })
+    .await
+    .map_err(|e| format!("Start MCP server task failed: {e}"))?
+}
+
+/// Stop the MCP server. It stays stopped until started again or re-enabled
+/// in settings.
+#[tauri::command]
+pub async fn stop_mcp_server() -> Result<McpServerStatus, String> {
+    tokio::task::spawn_blocking(|| {
+        crate::mcp::stop_mcp_server();
+        crate::mcp::mcp_server_status()
+    })
+    .await
+    .map_err(|e| format!("Stop MCP server task failed: {e}"))
+}
+
+/// Restart the MCP server with the saved settings, dropping open connections
+/// and sessions
+#[tauri::command]
+pub async fn restart_mcp_server(app: AppHandle) -> Result<McpServerStatus, String> {
+    tokio::task::spawn_blocking(move || {
+        let settings = get_settings()?;
+        crate::mcp::restart_mcp_server(app, &settings.agent)
+    })
+    .await
+    .map_err(|e| format!("Restart MCP server task failed: {e}"))?
+}
+
+/// Whether the MCP server is running, where it listens and who is connected
+///
+/// Waits for a start or stop in progress, so it runs off the main thread too.
+#[tauri::command]
+pub async fn get_mcp_server_status() -> Result<McpServerStatus, String> {
+    tokio::task::spawn_blocking(crate::mcp::mcp_server_status)
+        .await
+        .map_err(|e| format!("MCP server status task failed: {e}"))
+}
@@ -0,0 +1,162 @@
+//! MCP TLS certificate commands
+//!
+//! Certificates for serving MCP over HTTPS. Either the user supplies a PEM
+//! certificate and key, or a self-signed certificate is generated once and
+//! kept in `data/mcp_tls`. Clients of a self-signed server pin its SHA-256
+//! fingerprint, shown in settings.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{Datelike, Utc};
+use rcgen::{CertificateParams, DistinguishedName, DnType, KeyPair};
+use sha2::{Digest, Sha256};
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::{crypto, ServerConfig};
+
+use super::data_dir::get_data_dir_path;
+use super::settings::get_settings;
+use crate::types::{McpCertificateInfo, McpTlsSettings};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Years the generated certificate is valid for
+const SELF_SIGNED_VALID_YEARS: i32 = 10;
+
+// =============================================================================
+// Certificate Files
+// =============================================================================
+
+fn get_tls_dir() -> PathBuf {
+    get_data_dir_path().join("mcp_tls")
+}
+
+/// Generate the self-signed certificate if it doesn't exist yet and return
+/// its certificate and key paths
+fn ensure_self_signed_certificate() -> Result<(PathBuf, PathBuf), String> {
+    let dir = get_tls_dir();
+    let cert_path = dir.join("cert.pem");
+    let key_path = dir.join("key.pem");
+    if cert_path.exists() && key_path.exists() {
+        return Ok((cert_path, key_path));
+    }
+
+    let mut names = vec!["localhost".to_string(), "127.0.0.1".to_string()];
+    let hostname = gethostname::gethostname().to_string_lossy().to_string();
+    if !hostname.is_empty() {
+        names.push(hostname);
+    }
+
+    let mut params = CertificateParams::new(names)
+        .map_err(|e| format!("Failed to prepare certificate: {}", e))?;
+    let mut subject = DistinguishedName::new();
+    subject.push(DnType::CommonName, "RustService MCP");
+    params.distinguished_name = subject;
+    let year = Utc::now().year();
+    params.not_before = rcgen::date_time_ymd(year - 1, 1, 1);
+    params.not_after = rcgen::date_time_ymd(year + SELF_SIGNED_VALID_YEARS, 1, 1);
+
+    let key_pair = KeyPair::generate().map_err(|e| format!("Failed to generate TLS key: {}", e))?;
+    let cert = params
+        .self_signed(&key_pair)
+        .map_err(|e| format!("Failed to generate certificate: {}", e))?;
+
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create TLS directory: {}", e))?;
+    fs::write(&key_path, key_pair.serialize_pem())
+        .map_err(|e| format!("Failed to write TLS key: {}", e))?;
+    fs::write(&cert_path, cert.pem()).map_err(|e| format!("Failed to write certificate: {}", e))?;
+
+    eprintln!(
+        "[MCP] Generated self-signed certificate at {}",
+        cert_path.display()
+    );
+    Ok((cert_path, key_path))
+}
+
+/// Certificate and key paths for the settings, generating the self-signed
+/// certificate when no certificate is configured
+fn resolve_certificate(settings: &McpTlsSettings) -> Result<(PathBuf, PathBuf, bool), String> {
+    let configured = |p: &Option<String>| {
+        p.as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+    };
+    match (
+        configured(&settings.cert_path),
+        configured(&settings.key_path),
+    ) {
+        (Some(cert), Some(key)) => Ok((cert, key, false)),
+        (None, None) => {
+            let (cert, key) = ensure_self_signed_certificate()?;
+            Ok((cert, key, true))
+        }
+        _ => {
+            Err("Both a certificate and a key path are needed for a custom certificate".to_string())
+        }
+    }
+}
+
+fn load_certificates(path: &Path) -> Result<Vec<CertificateDer<'static>>, String> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .map_err(|e| format!("Failed to read certificate {}: {}", path.display(), e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to parse certificate {}: {}", path.display(), e))?;
+    if certs.is_empty() {
+        return Err(format!("No certificate found in {}", path.display()));
+    }
+    Ok(certs)
+}
+
+/// SHA-256 of a certificate as colon-separated uppercase hex
+fn fingerprint(cert: &CertificateDer<'_>) -> String {
+    Sha256::digest(cert.as_ref())
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+// =============================================================================
+// Server Configuration
+// =============================================================================
+
+/// rustls configuration for the MCP server
+pub(crate) fn load_mcp_tls_config(settings: &McpTlsSettings) -> Result<Arc<ServerConfig>, String> {
+    let (cert_path, key_path, _) = resolve_certificate(settings)?;
+    let certs = load_certificates(&cert_path)?;
+    let key = PrivateKeyDer::from_pem_file(&key_path)
+        .map_err(|e| format!("Failed to read TLS key {}: {}", key_path.display(), e))?;
+
+    let mut config =
+        ServerConfig::builder_with_provider(Arc::new(crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| format!("Failed to configure TLS: {}", e))?
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .map_err(|e| format!("Invalid certificate or key: {}", e))?;
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(Arc::new(config))
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// The certificate the MCP server uses for HTTPS, generating the
+/// self-signed one if needed, so clients can pin its fingerprint
+#[tauri::command]
+pub fn get_mcp_tls_certificate() -> Result<McpCertificateInfo, String> {
+    let settings = get_settings()?;
+    let (cert_path, _, self_signed) = resolve_certificate(&settings.agent.mcp_tls)?;
+    let certs = load_certificates(&cert_path)?;
+    Ok(McpCertificateInfo {
+        sha256_fingerprint: fingerprint(&certs[0]),
+        cert_path: cert_path.to_string_lossy().to_string(),
+        self_signed,
+    })
+}
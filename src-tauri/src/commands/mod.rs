@@ -7,11 +7,20 @@ mod bluescreen;
 mod data_dir;
 mod disk_health;
 mod event_log;
+mod machine_history;
+mod mcp_client;
+mod mcp_server;
+pub(crate) mod mcp_audit;
+pub(crate) mod mcp_keys;
+pub(crate) mod mcp_tls;
 mod network;
 mod network_diagnostics;
 mod programs;
+mod report_index;
+mod reports;
 mod required_programs;
 pub(crate) mod restore_points;
+mod run_checkpoint;
 mod scripts;
 mod services;
 mod settings;
@@ -26,11 +35,20 @@ pub use bluescreen::*;
 pub use data_dir::*;
 pub use disk_health::*;
 pub use event_log::*;
+pub use machine_history::*;
+pub use mcp_audit::*;
+pub use mcp_client::*;
+pub use mcp_server::*;
+pub use mcp_keys::*;
+pub use mcp_tls::*;
 pub use network::*;
 pub use network_diagnostics::*;
 pub use programs::*;
+pub use report_index::*;
+pub use reports::*;
 pub use required_programs::*;
 pub use restore_points::*;
+pub use run_checkpoint::*;
 pub use scripts::*;
 pub use services::*;
 pub use settings::*;
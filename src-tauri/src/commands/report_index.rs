@@ -0,0 +1,533 @@
+//! Report index
+//!
+//! SQLite index over the JSON reports in `data/reports`, so reports can be
+//! listed and searched without deserializing every file. The JSON files stay
+//! the source of truth; the index is kept in sync by `save_report` and
+//! `delete_report` and is rebuilt from disk when first created.
+
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, Transaction, TransactionBehavior};
+
+use super::services::get_reports_dir;
+use crate::types::{
+    FindingSeverity, MachineSummary, ReportIndexEntry, ReportQuery, ReportQueryResult, ReportSort,
+    ServiceReport, ServiceRunStatus,
+};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Schema version stored in `PRAGMA user_version`
+const INDEX_SCHEMA_VERSION: i32 = 2;
+
+/// Page size when a query doesn't specify a limit
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Upper bound on a single page
+const MAX_PAGE_SIZE: usize = 500;
+
+// =============================================================================
+// Database Helpers
+// =============================================================================
+
+fn get_index_db_path() -> PathBuf {
+    get_reports_dir().join("reports.db")
+}
+
+fn get_index_connection() -> Result<Connection, String> {
+    let reports_dir = get_reports_dir();
+    fs::create_dir_all(&reports_dir).map_err(|e| format!("Failed to create reports dir: {}", e))?;
+
+    let conn = Connection::open(get_index_db_path())
+        .map_err(|e| format!("Failed to open report index: {}", e))?;
+
+    let version: i32 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read report index version: {}", e))?;
+
+    if version < INDEX_SCHEMA_VERSION {
+        migrate_index(&conn)?;
+    }
+
+    Ok(conn)
+}
+
+/// Rebuild the index at the current schema from the reports on disk.
+///
+/// Runs in one immediate transaction that re-reads the version first, so a
+/// connection racing another one waits for it and then finds the work done,
+/// and a failure part way leaves the old index untouched.
+fn migrate_index(conn: &Connection) -> Result<(), String> {
+    let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)
+        .map_err(|e| format!("Failed to start report index migration: {}", e))?;
+
+    let version: i32 = tx
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read report index version: {}", e))?;
+    if version >= INDEX_SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    tx.execute_batch(
+        "DROP TABLE IF EXISTS report_findings;
+         DROP TABLE IF EXISTS report_services;
+         DROP TABLE IF EXISTS reports;
+         CREATE TABLE reports (
+            id TEXT PRIMARY KEY,
+            started_at TEXT NOT NULL,
+            started_key TEXT NOT NULL,
+            completed_at TEXT,
+            status TEXT NOT NULL,
+            technician_name TEXT,
+            customer_name TEXT,
+            total_duration_ms INTEGER,
+            health_score INTEGER,
+            failed_count INTEGER NOT NULL,
+            finding_count INTEGER NOT NULL,
+            max_severity INTEGER,
+            pinned INTEGER NOT NULL DEFAULT 0,
+            machine_key TEXT,
+            hostname TEXT,
+            machine_vendor TEXT,
+            machine_model TEXT
+         );
+         CREATE TABLE report_services (
+            report_id TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            service_id TEXT NOT NULL,
+            success INTEGER NOT NULL,
+            PRIMARY KEY (report_id, position)
+         );
+         CREATE TABLE report_findings (
+            report_id TEXT NOT NULL,
+            service_id TEXT NOT NULL,
+            severity INTEGER NOT NULL,
+            title TEXT NOT NULL
+         );
+         CREATE INDEX idx_reports_started ON reports(started_key);
+         CREATE INDEX idx_reports_customer ON reports(customer_name);
+         CREATE INDEX idx_reports_technician ON reports(technician_name);
+         CREATE INDEX idx_reports_machine ON reports(machine_key);
+         CREATE INDEX idx_report_services_service ON report_services(service_id);
+         CREATE INDEX idx_report_findings_report ON report_findings(report_id);",
+    )
+    .map_err(|e| format!("Failed to create report index: {}", e))?;
+
+    backfill_index(&tx)?;
+
+    tx.execute_batch(&format!("PRAGMA user_version = {}", INDEX_SCHEMA_VERSION))
+        .map_err(|e| format!("Failed to set report index version: {}", e))?;
+    tx.commit()
+        .map_err(|e| format!("Failed to commit report index: {}", e))
+}
+
+/// Index every report already on disk (used when the index is first created)
+fn backfill_index(tx: &Transaction) -> Result<(), String> {
+    let Ok(entries) = fs::read_dir(get_reports_dir()) else {
+        return Ok(());
+    };
+
+    for entry in entries.flatten() {
+        if entry.path().extension().is_some_and(|ext| ext == "json") {
+            if let Ok(json) = fs::read_to_string(entry.path()) {
+                if let Ok(report) = serde_json::from_str::<ServiceReport>(&json) {
+                    write_report(tx, &report)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Normalize a timestamp to fixed-width UTC so index rows compare as strings
+pub(super) fn normalize_timestamp(timestamp: &str) -> Option<String> {
+    let parsed = DateTime::parse_from_rfc3339(timestamp)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(timestamp, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|dt| dt.and_utc())
+        })?;
+    Some(parsed.to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+fn status_to_str(status: &ServiceRunStatus) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default()
+}
+
+fn status_from_str(status: &str) -> ServiceRunStatus {
+    serde_json::from_value(serde_json::Value::String(status.to_string()))
+        .unwrap_or(ServiceRunStatus::Completed)
+}
+
+/// Insert or replace a report's index rows
+fn write_report(conn: &Connection, report: &ServiceReport) -> Result<(), String> {
+    let findings = report.results.iter().flat_map(|r| &r.findings);
+    let finding_count = findings.clone().count();
+    let max_severity = findings.map(|f| f.severity.rank()).max();
+    let failed_count = report.results.iter().filter(|r| !r.success).count();
+    let started_key =
+        normalize_timestamp(&report.started_at).unwrap_or_else(|| report.started_at.clone());
+    let machine = report.machine.as_ref();
+
+    let map_err = |e: rusqlite::Error| format!("Failed to index report: {}", e);
+
+    conn.execute(
+        "DELETE FROM report_services WHERE report_id = ?1",
+        params![report.id],
+    )
+    .map_err(map_err)?;
+    conn.execute(
+        "DELETE FROM report_findings WHERE report_id = ?1",
+        params![report.id],
+    )
+    .map_err(map_err)?;
+
+    conn.execute(
+        "INSERT OR REPLACE INTO reports (
+            id, started_at, started_key, completed_at, status, technician_name,
+            customer_name, total_duration_ms, health_score, failed_count,
+            finding_count, max_severity, pinned, machine_key, hostname,
+            machine_vendor, machine_model
+         ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+        params![
+            report.id,
+            report.started_at,
+            started_key,
+            report.completed_at,
+            status_to_str(&report.status),
+            report.technician_name,
+            report.customer_name,
+            report.total_duration_ms.map(|ms| ms as i64),
+            report.health_score,
+            failed_count as i64,
+            finding_count as i64,
+            max_severity,
+            report.pinned,
+            machine.map(|m| &m.machine_key),
+            machine.map(|m| &m.hostname),
+            machine.and_then(|m| m.vendor.as_ref()),
+            machine.and_then(|m| m.model.as_ref()),
+        ],
+    )
+    .map_err(map_err)?;
+
+    for (position, result) in report.results.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO report_services (report_id, position, service_id, success)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                report.id,
+                position as i64,
+                result.service_id,
+                result.success
+            ],
+        )
+        .map_err(map_err)?;
+
+        for finding in &result.findings {
+            conn.execute(
+                "INSERT INTO report_findings (report_id, service_id, severity, title)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    report.id,
+                    result.service_id,
+                    finding.severity.rank(),
+                    finding.title
+                ],
+            )
+            .map_err(map_err)?;
+        }
+    }
+
+    Ok(())
+}
+
+// =============================================================================
+// Index Maintenance
+// =============================================================================
+
+/// Add or update a report in the index
+pub(crate) fn index_report(report: &ServiceReport) -> Result<(), String> {
+    let mut conn = get_index_connection()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    write_report(&tx, report)?;
+    tx.commit()
+        .map_err(|e| format!("Failed to commit report index: {}", e))
+}
+
+/// Remove reports from the index
+pub(crate) fn unindex_reports(report_ids: &[String]) -> Result<(), String> {
+    let mut conn = get_index_connection()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    for id in report_ids {
+        for table in ["report_findings", "report_services"] {
+            tx.execute(
+                &format!("DELETE FROM {} WHERE report_id = ?1", table),
+                params![id],
+            )
+            .map_err(|e| format!("Failed to remove report from index: {}", e))?;
+        }
+        tx.execute("DELETE FROM reports WHERE id = ?1", params![id])
+            .map_err(|e| format!("Failed to remove report from index: {}", e))?;
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to commit report index: {}", e))
+}
+
+/// Drop and rebuild the index from the report files on disk
+#[tauri::command]
+pub fn rebuild_report_index() -> Result<usize, String> {
+    let conn = get_index_connection()?;
+    conn.execute_batch("PRAGMA user_version = 0")
+        .map_err(|e| format!("Failed to reset report index: {}", e))?;
+    drop(conn);
+
+    let conn = get_index_connection()?;
+    conn.query_row("SELECT COUNT(*) FROM reports", [], |row| {
+        row.get::<_, i64>(0)
+    })
+    .map(|count| count as usize)
+    .map_err(|e| format!("Failed to count indexed reports: {}", e))
+}
+
+// =============================================================================
+// Search
+// =============================================================================
+
+/// Escape `%` and `_` for use in a LIKE pattern with `ESCAPE '\'`
+pub(super) fn like_pattern(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// `ORDER BY` terms for a sort order
+fn order_by(sort: ReportSort) -> &'static str {
+    match sort {
+        ReportSort::Newest => "r.started_key DESC",
+        ReportSort::Oldest => "r.started_key ASC",
+        ReportSort::DurationDesc => "r.total_duration_ms DESC, r.started_key DESC",
+        ReportSort::DurationAsc => "r.total_duration_ms ASC, r.started_key DESC",
+        ReportSort::Technician => {
+            "COALESCE(r.technician_name, '') COLLATE NOCASE ASC, r.started_key DESC"
+        }
+    }
+}
+
+/// Search the report index with optional filters and pagination.
+/// Results are ordered newest first unless the query asks otherwise.
+#[tauri::command]
+pub fn search_reports(query: ReportQuery) -> Result<ReportQueryResult, String> {
+    let conn = get_index_connection()?;
+
+    let mut clauses: Vec<String> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+
+    if let Some(search) = query.search.filter(|s| !s.trim().is_empty()) {
+        clauses.push(
+            "(r.id LIKE ? ESCAPE '\\' OR r.customer_name LIKE ? ESCAPE '\\' \
+             OR r.technician_name LIKE ? ESCAPE '\\')"
+                .to_string(),
+        );
+        let pattern = like_pattern(search.trim());
+        values.extend((0..3).map(|_| Value::Text(pattern.clone())));
+    }
+    if let Some(name) = query.customer_name.filter(|s| !s.trim().is_empty()) {
+        clauses.push("r.customer_name LIKE ? ESCAPE '\\'".to_string());
+        values.push(Value::Text(like_pattern(name.trim())));
+    }
+    if let Some(name) = query.technician_name.filter(|s| !s.trim().is_empty()) {
+        clauses.push("r.technician_name LIKE ? ESCAPE '\\'".to_string());
+        values.push(Value::Text(like_pattern(name.trim())));
+    }
+    if let Some(after) = query.started_after {
+        let bound = normalize_timestamp(&after)
+            .ok_or_else(|| format!("Invalid startedAfter date: {}", after))?;
+        clauses.push("r.started_key >= ?".to_string());
+        values.push(Value::Text(bound));
+    }
+    if let Some(before) = query.started_before {
+        let bound = normalize_timestamp(&before)
+            .ok_or_else(|| format!("Invalid startedBefore date: {}", before))?;
+        clauses.push("r.started_key < ?".to_string());
+        values.push(Value::Text(bound));
+    }
+    if !query.statuses.is_empty() {
+        let placeholders = vec!["?"; query.statuses.len()].join(", ");
+        clauses.push(format!("r.status IN ({})", placeholders));
+        values.extend(query.statuses.iter().map(|s| Value::Text(status_to_str(s))));
+    }
+    if !query.service_ids.is_empty() {
+        let placeholders = vec!["?"; query.service_ids.len()].join(", ");
+        clauses.push(format!(
+            "EXISTS (SELECT 1 FROM report_services s WHERE s.report_id = r.id AND s.service_id IN ({}))",
+            placeholders
+        ));
+        values.extend(query.service_ids.into_iter().map(Value::Text));
+    }
+    if let Some(severity) = query.min_severity {
+        clauses.push("r.max_severity >= ?".to_string());
+        values.push(Value::Integer(severity.rank() as i64));
+    }
+    if let Some(text) = query.text.filter(|s| !s.trim().is_empty()) {
+        clauses.push(
+            "EXISTS (SELECT 1 FROM report_findings f WHERE f.report_id = r.id AND f.title LIKE ? ESCAPE '\\')"
+                .to_string(),
+        );
+        values.push(Value::Text(like_pattern(text.trim())));
+    }
+    if let Some(key) = query.machine_key.filter(|s| !s.is_empty()) {
+        clauses.push("r.machine_key = ?".to_string());
+        values.push(Value::Text(key));
+    }
+
+    let where_sql = if clauses.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", clauses.join(" AND "))
+    };
+
+    let total: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM reports r {}", where_sql),
+            params_from_iter(values.iter()),
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to count reports: {}", e))?;
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let offset = query.offset.unwrap_or(0);
+    values.push(Value::Integer(limit as i64));
+    values.push(Value::Integer(offset as i64));
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT r.id, r.started_at, r.completed_at, r.status, r.technician_name,
+                    r.customer_name, r.total_duration_ms, r.health_score, r.failed_count,
+                    r.finding_count, r.max_severity, r.pinned, r.machine_key, r.hostname,
+                    (SELECT GROUP_CONCAT(service_id, char(31)) FROM
+                        (SELECT service_id FROM report_services s
+                         WHERE s.report_id = r.id ORDER BY s.position))
+             FROM reports r {}
+             ORDER BY {}, r.id
+             LIMIT ? OFFSET ?",
+            where_sql,
+            order_by(query.sort)
+        ))
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+    let rows = stmt
+        .query_map(params_from_iter(values.iter()), |row| {
+            let status: String = row.get(3)?;
+            let services: Option<String> = row.get(14)?;
+            Ok(ReportIndexEntry {
+                id: row.get(0)?,
+                started_at: row.get(1)?,
+                completed_at: row.get(2)?,
+                status: status_from_str(&status),
+                technician_name: row.get(4)?,
+                customer_name: row.get(5)?,
+                total_duration_ms: row.get::<_, Option<i64>>(6)?.map(|ms| ms as u64),
+                health_score: row.get(7)?,
+                failed_count: row.get::<_, i64>(8)? as usize,
+                finding_count: row.get::<_, i64>(9)? as usize,
+                max_severity: row
+                    .get::<_, Option<u8>>(10)?
+                    .and_then(FindingSeverity::from_rank),
+                pinned: row.get(11)?,
+                machine_key: row.get(12)?,
+                hostname: row.get(13)?,
+                service_ids: services
+                    .map(|s| s.split('\u{1f}').map(String::from).collect())
+                    .unwrap_or_default(),
+            })
+        })
+        .map_err(|e| format!("Failed to execute query: {}", e))?;
+
+    let mut reports = Vec::new();
+    for row in rows {
+        reports.push(row.map_err(|e| format!("Failed to read row: {}", e))?);
+    }
+
+    Ok(ReportQueryResult {
+        total: total as usize,
+        reports,
+    })
+}
+
+// =============================================================================
+// Machines
+// =============================================================================
+
+/// Every machine that appears in the index, most recently seen first
+pub(crate) fn list_indexed_machines() -> Result<Vec<MachineSummary>, String> {
+    let conn = get_index_connection()?;
+
+    // Descriptive columns come from each machine's most recent report, and
+    // both times are taken as stored from the first and last reports
+    let mut stmt = conn
+        .prepare(
+            "SELECT r.machine_key, g.report_count, f.started_at, r.started_at,
+                    r.hostname, r.machine_vendor, r.machine_model, r.customer_name
+             FROM reports r
+             JOIN (SELECT machine_key, COUNT(*) AS report_count,
+                          MIN(started_key) AS first_key, MAX(started_key) AS last_key
+                   FROM reports WHERE machine_key IS NOT NULL GROUP BY machine_key) g
+               ON r.machine_key = g.machine_key AND r.started_key = g.last_key
+             JOIN reports f ON f.machine_key = g.machine_key AND f.started_key = g.first_key
+             GROUP BY r.machine_key
+             ORDER BY g.last_key DESC",
+        )
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(MachineSummary {
+                machine_key: row.get(0)?,
+                report_count: row.get::<_, i64>(1)? as usize,
+                first_seen: row.get(2)?,
+                last_seen: row.get(3)?,
+                hostname: row.get(4)?,
+                vendor: row.get(5)?,
+                model: row.get(6)?,
+                customer_name: row.get(7)?,
+            })
+        })
+        .map_err(|e| format!("Failed to execute query: {}", e))?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read row: {}", e))
+}
+
+/// IDs of all reports for a machine, oldest first
+pub(crate) fn machine_report_ids(machine_key: &str) -> Result<Vec<String>, String> {
+    let conn = get_index_connection()?;
+    let mut stmt = conn
+        .prepare("SELECT id FROM reports WHERE machine_key = ?1 ORDER BY started_key ASC")
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let rows = stmt
+        .query_map(params![machine_key], |row| row.get(0))
+        .map_err(|e| format!("Failed to execute query: {}", e))?;
+    rows.collect::<Result<Vec<String>, _>>()
+        .map_err(|e| format!("Failed to read row: {}", e))
+}
@@ -0,0 +1,216 @@
+//! Report housekeeping commands
+//!
+//! Retention enforcement for saved service reports. Expired reports are
+//! either deleted or moved into a zip archive, depending on settings.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use zip::write::SimpleFileOptions;
+
+use super::report_index::unindex_reports;
+use super::services::{get_reports_dir, get_service_run_state};
+use super::settings::get_settings;
+use crate::types::{ReportPruneSummary, ServiceReport};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Subdirectory of the reports folder that holds retention archives
+const ARCHIVE_DIR_NAME: &str = "archive";
+
+/// Extensions of files generated from a report that are pruned with it
+const EXPORT_EXTENSIONS: [&str; 3] = ["pdf", "html", "md"];
+
+/// Names tried for an archive before giving up
+const MAX_ARCHIVE_NAME_ATTEMPTS: u32 = 1000;
+
+// =============================================================================
+// Retention
+// =============================================================================
+
+/// An expired report and the files that belong to it
+struct ExpiredReport {
+    id: String,
+    files: Vec<PathBuf>,
+}
+
+/// When a report was last relevant: completion time, else start time,
+/// else the file's modification time
+fn report_timestamp(report: &ServiceReport, path: &Path) -> Option<DateTime<Utc>> {
+    let recorded = report.completed_at.as_deref().unwrap_or(&report.started_at);
+    DateTime::parse_from_rfc3339(recorded)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+        .or_else(|| {
+            fs::metadata(path)
+                .and_then(|m| m.modified())
+                .ok()
+                .map(DateTime::<Utc>::from)
+        })
+}
+
+/// Create a new, empty archive file named after the current time. Never
+/// replaces an existing archive: a name already taken (two prunes in the same
+/// second) gets a counter suffix.
+fn create_archive_file(archive_dir: &Path) -> Result<(PathBuf, fs::File), String> {
+    let stamp = Utc::now().format("%Y%m%d-%H%M%S").to_string();
+    for attempt in 0..MAX_ARCHIVE_NAME_ATTEMPTS {
+        let name = if attempt == 0 {
+            format!("reports-{}.zip", stamp)
+        } else {
+            format!("reports-{}-{}.zip", stamp, attempt)
+        };
+        let path = archive_dir.join(name);
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(format!("Failed to create archive: {}", e)),
+        }
+    }
+    Err("Failed to create archive: too many archives with the same name".to_string())
+}
+
+/// Write the given files into a new zip archive and return its path
+fn archive_reports(reports_dir: &Path, expired: &[ExpiredReport]) -> Result<PathBuf, String> {
+    let archive_dir = reports_dir.join(ARCHIVE_DIR_NAME);
+    fs::create_dir_all(&archive_dir).map_err(|e| format!("Failed to create archive dir: {}", e))?;
+
+    let (archive_path, file) = create_archive_file(&archive_dir)?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    for path in expired.iter().flat_map(|r| &r.files) {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let data = fs::read(path).map_err(|e| format!("Failed to read {}: {}", name, e))?;
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to add {} to archive: {}", name, e))?;
+        std::io::Write::write_all(&mut zip, &data)
+            .map_err(|e| format!("Failed to write {} to archive: {}", name, e))?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finalize archive: {}", e))?;
+    Ok(archive_path)
+}
+
+/// Remove (or archive) reports older than `report_retention_days`.
+///
+/// Pinned reports and the report of an in-progress run are always kept.
+/// Runs at startup and after every service run; a retention of 0 disables it.
+pub fn apply_report_retention() -> Result<ReportPruneSummary, String> {
+    let settings = get_settings()?;
+    let mut summary = ReportPruneSummary::default();
+
+    let retention_days = settings.reports.report_retention_days;
+    let reports_dir = get_reports_dir();
+    if retention_days == 0 || !reports_dir.exists() {
+        return Ok(summary);
+    }
+
+    let cutoff = Utc::now() - Duration::days(retention_days as i64);
+    let run_state = get_service_run_state();
+    let active_id = if run_state.is_running {
+        run_state.current_report.map(|r| r.id)
+    } else {
+        None
+    };
+
+    let entries =
+        fs::read_dir(&reports_dir).map_err(|e| format!("Failed to read reports dir: {}", e))?;
+
+    let mut expired = Vec::new();
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let Some(report) = fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<ServiceReport>(&json).ok())
+        else {
+            continue;
+        };
+
+        if active_id.as_deref() == Some(report.id.as_str()) {
+            continue;
+        }
+        if report_timestamp(&report, &path).is_none_or(|ts| ts >= cutoff) {
+            continue;
+        }
+        if report.pinned {
+            summary.kept_pinned += 1;
+            continue;
+        }
+
+        let mut files = vec![path];
+        files.extend(
+            EXPORT_EXTENSIONS
+                .iter()
+                .map(|ext| reports_dir.join(format!("{}.{}", report.id, ext)))
+                .filter(|p| p.exists()),
+        );
+        expired.push(ExpiredReport {
+            id: report.id,
+            files,
+        });
+    }
+
+    if expired.is_empty() {
+        return Ok(summary);
+    }
+
+    if settings.reports.archive_expired_reports {
+        let archive_path = archive_reports(&reports_dir, &expired)?;
+        summary.archive_path = Some(archive_path.to_string_lossy().to_string());
+    }
+
+    for report in expired {
+        let size: u64 = report
+            .files
+            .iter()
+            .filter_map(|f| fs::metadata(f).ok())
+            .map(|m| m.len())
+            .sum();
+        match report.files.iter().try_for_each(fs::remove_file) {
+            Ok(()) => {
+                summary.bytes_freed += size;
+                summary.removed.push(report.id);
+            }
+            Err(e) => summary
+                .errors
+                .push(format!("Failed to remove report {}: {}", report.id, e)),
+        }
+    }
+
+    if !summary.removed.is_empty() {
+        if let Err(e) = unindex_reports(&summary.removed) {
+            eprintln!("[Reports] Failed to update report index: {}", e);
+        }
+        eprintln!(
+            "[Reports] Pruned {} report(s) older than {} days",
+            summary.removed.len(),
+            retention_days
+        );
+    }
+
+    Ok(summary)
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Apply the report retention policy now and return what was removed
+#[tauri::command]
+pub fn prune_reports() -> Result<ReportPruneSummary, String> {
+    apply_report_retention()
+}
@@ -8,7 +8,7 @@ use std::path::PathBuf;
 use std::sync::LazyLock;
 
 use super::data_dir::get_data_dir_path;
-use super::settings::{get_settings, save_settings};
+use super::settings::{get_settings, save_settings_blocking};
 use crate::types::{RequiredProgramDef, RequiredProgramStatus};
 
 // =============================================================================
@@ -247,7 +247,7 @@ pub fn set_program_path_override(program_id: String, path: Option<String>) -> Re
         }
     }
 
-    save_settings(settings)?;
+    save_settings_blocking(settings)?;
     Ok(())
 }
 
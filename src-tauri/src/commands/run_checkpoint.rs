@@ -0,0 +1,106 @@
+//! Service run checkpoints
+//!
+//! The in-memory run state is lost if the app crashes or the machine
+//! reboots mid-run (chkdsk and Windows Update do this routinely), so the
+//! runner writes a `RunCheckpoint` after every service. A checkpoint left
+//! on disk at startup means the run was interrupted; it can be resumed
+//! (see `resume_interrupted_run`) or discarded.
+
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::Utc;
+
+use super::data_dir::get_data_dir_path;
+use super::services::{get_service_run_state, save_report};
+use crate::types::{RunCheckpoint, ServiceRunStatus};
+
+// =============================================================================
+// Checkpoint Storage
+// =============================================================================
+
+fn get_checkpoint_path() -> PathBuf {
+    get_data_dir_path().join("service-run-checkpoint.json")
+}
+
+/// Write the checkpoint, replacing any previous one.
+///
+/// Written to a temporary file first so a crash mid-write never leaves a
+/// truncated checkpoint behind.
+pub(crate) fn save_run_checkpoint(checkpoint: &RunCheckpoint) -> Result<(), String> {
+    let path = get_checkpoint_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+
+    let json = serde_json::to_string(checkpoint)
+        .map_err(|e| format!("Failed to serialize checkpoint: {}", e))?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, json).map_err(|e| format!("Failed to write checkpoint: {}", e))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to write checkpoint: {}", e))
+}
+
+/// Read the checkpoint left on disk, if any
+pub(crate) fn load_run_checkpoint() -> Option<RunCheckpoint> {
+    let json = fs::read_to_string(get_checkpoint_path()).ok()?;
+    match serde_json::from_str(&json) {
+        Ok(checkpoint) => Some(checkpoint),
+        Err(e) => {
+            eprintln!("[Services] Ignoring unreadable run checkpoint: {}", e);
+            None
+        }
+    }
+}
+
+/// Remove the checkpoint once a run has finished
+pub(crate) fn clear_run_checkpoint() {
+    let path = get_checkpoint_path();
+    if path.exists() {
+        if let Err(e) = fs::remove_file(&path) {
+            eprintln!("[Services] Failed to remove run checkpoint: {}", e);
+        }
+    }
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Get the checkpoint of a run that was interrupted by a crash or reboot.
+///
+/// Returns `None` when there is nothing to resume (including while a run
+/// is active in this session, whose checkpoint is still being written).
+#[tauri::command]
+pub fn get_interrupted_run() -> Option<RunCheckpoint> {
+    if get_service_run_state().is_running {
+        return None;
+    }
+    load_run_checkpoint()
+}
+
+/// Discard an interrupted run instead of resuming it.
+///
+/// With `save_partial` (the default) the results gathered before the
+/// interruption are kept as a cancelled report; returns its ID.
+#[tauri::command]
+pub fn discard_interrupted_run(save_partial: Option<bool>) -> Result<Option<String>, String> {
+    if get_service_run_state().is_running {
+        return Err("A service run is in progress".to_string());
+    }
+    let checkpoint = load_run_checkpoint().ok_or("No interrupted service run found")?;
+
+    let mut saved_id = None;
+    if save_partial.unwrap_or(true) && !checkpoint.report.results.is_empty() {
+        let mut report = checkpoint.report;
+        report.status = ServiceRunStatus::Cancelled;
+        report.completed_at = Some(Utc::now().to_rfc3339());
+        report.total_duration_ms = Some(checkpoint.elapsed_ms);
+        report.current_service_index = None;
+        report.current_service_indices = vec![];
+        save_report(&report)?;
+        saved_id = Some(report.id);
+    }
+
+    clear_run_checkpoint();
+    Ok(saved_id)
+}
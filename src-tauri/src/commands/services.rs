@@ -7,7 +7,7 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fs;
 use std::sync::{Arc, Condvar, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use chrono::Utc;
 use serde_json::json;
@@ -15,13 +15,20 @@ use tauri::{AppHandle, Emitter};
 use uuid::Uuid;
 
 use super::data_dir::get_data_dir_path;
+use super::machine_history::current_machine_identity;
+use super::report_index::{index_report, unindex_reports};
 use super::required_programs::validate_required_programs;
-use super::settings::{get_settings, save_settings};
+use super::run_checkpoint::{
+    clear_run_checkpoint, discard_interrupted_run, load_run_checkpoint, save_run_checkpoint,
+};
+use super::settings::{get_business_logo, get_settings, save_settings_blocking};
 use sysinfo::Disks;
 
-use crate::services;
+use crate::reports;
+use crate::services::{self, CancellationToken, PauseGate, ServiceContext};
 use crate::types::{
-    FindingSeverity, FindingSeverityCounts, ReportStatistics, ServiceDefinition, ServiceFinding,
+    AppSettings, FindingSeverity, FindingSeverityCounts, ReportDetailLevel, ReportDiff,
+    ReportExportFormat, ReportStatistics, RunCheckpoint, ServiceDefinition, ServiceFinding,
     ServicePreset, ServiceQueueItem, ServiceReport, ServiceResult, ServiceRunState,
     ServiceRunStatus,
 };
@@ -42,9 +49,6 @@ const PARALLEL_POLL_INTERVAL_MS: u64 = 250;
 /// Bytes per gigabyte (for display formatting)
 const BYTES_PER_GB: f64 = 1_073_741_824.0;
 
-/// Milliseconds per second (for duration formatting)
-const MS_PER_SECOND: f64 = 1000.0;
-
 // =============================================================================
 // Global State for Persistent Service Runs
 // =============================================================================
@@ -55,15 +59,36 @@ static SERVICE_STATE: Mutex<Option<ServiceRunState>> = Mutex::new(None);
 /// Condition variable for pause/resume support
 static PAUSE_CONDVAR: Condvar = Condvar::new();
 
+/// Cancellation token and pause gate of the active run, shared with its services
+static RUN_CONTROL: Mutex<Option<RunControl>> = Mutex::new(None);
+
+/// Signals the runner passes down to each service through its `ServiceContext`
+#[derive(Clone)]
+struct RunControl {
+    cancel: CancellationToken,
+    pause: PauseGate,
+}
+
+impl RunControl {
+    fn context(&self, app: &AppHandle, service_id: &str) -> Arc<ServiceContext> {
+        Arc::new(ServiceContext::new(
+            app,
+            service_id,
+            self.cancel.clone(),
+            self.pause.clone(),
+        ))
+    }
+}
+
 // =============================================================================
 // Report Storage
 // =============================================================================
 
-fn get_reports_dir() -> std::path::PathBuf {
+pub(crate) fn get_reports_dir() -> std::path::PathBuf {
     get_data_dir_path().join("reports")
 }
 
-fn save_report(report: &ServiceReport) -> Result<(), String> {
+pub(crate) fn save_report(report: &ServiceReport) -> Result<(), String> {
     let reports_dir = get_reports_dir();
     fs::create_dir_all(&reports_dir).map_err(|e| format!("Failed to create reports dir: {}", e))?;
 
@@ -72,6 +97,11 @@ fn save_report(report: &ServiceReport) -> Result<(), String> {
         .map_err(|e| format!("Failed to serialize report: {}", e))?;
     fs::write(file_path, json).map_err(|e| format!("Failed to write report: {}", e))?;
 
+    // The JSON file is the source of truth; a stale index entry is recoverable
+    if let Err(e) = index_report(report) {
+        eprintln!("Failed to index report {}: {}", report.id, e);
+    }
+
     Ok(())
 }
 
@@ -153,7 +183,7 @@ pub fn save_service_preset(preset: ServicePreset) -> Result<(), String> {
         settings.presets.custom_presets.push(preset);
     }
 
-    save_settings(settings)?;
+    save_settings_blocking(settings)?;
     Ok(())
 }
 
@@ -173,7 +203,7 @@ pub fn delete_service_preset(preset_id: String) -> Result<(), String> {
         return Err(format!("Custom preset not found: {}", preset_id));
     }
 
-    save_settings(settings)?;
+    save_settings_blocking(settings)?;
     Ok(())
 }
 
@@ -266,6 +296,15 @@ pub async fn run_services(
         }
     }
 
+    // Starting over replaces the checkpoint, so keep what the interrupted run found
+    if load_run_checkpoint().is_some() {
+        match discard_interrupted_run(Some(true)) {
+            Ok(Some(id)) => eprintln!("[Services] Saved interrupted run as report {}", id),
+            Ok(None) => {}
+            Err(e) => eprintln!("[Services] Failed to save interrupted run: {}", e),
+        }
+    }
+
     // Filter to only enabled services and sort by order
     let mut enabled_queue: Vec<_> = queue.iter().filter(|q| q.enabled).cloned().collect();
     enabled_queue.sort_by_key(|q| q.order);
@@ -301,7 +340,7 @@ pub async fn run_services(
 
     // Create report
     let report_id = Uuid::new_v4().to_string();
-    let mut report = ServiceReport {
+    let report = ServiceReport {
         id: report_id.clone(),
         started_at: Utc::now().to_rfc3339(),
         completed_at: None,
@@ -317,8 +356,63 @@ pub async fn run_services(
         agent_initiated: false,
         agent_summary: None,
         health_score: None,
+        pinned: false,
+        machine: Some(current_machine_identity()),
     };
 
+    execute_run(&app, &enabled_queue, report, HashSet::new(), 0)
+}
+
+/// Resume a run that was interrupted by a crash or reboot, continuing with the
+/// next un-run service and appending to the same report
+#[tauri::command]
+pub async fn resume_interrupted_run(app: AppHandle) -> Result<ServiceReport, String> {
+    {
+        let state = SERVICE_STATE.lock().unwrap();
+        if state.as_ref().is_some_and(|s| s.is_running) {
+            return Err("A service run is already in progress".to_string());
+        }
+    }
+
+    let checkpoint = load_run_checkpoint().ok_or("No interrupted service run found")?;
+    let completed: HashSet<usize> = checkpoint
+        .completed_indices
+        .into_iter()
+        .filter(|&i| i < checkpoint.queue.len())
+        .collect();
+
+    let mut report = checkpoint.report;
+    report.status = ServiceRunStatus::Running;
+    report.completed_at = None;
+    report.total_duration_ms = None;
+
+    eprintln!(
+        "[Services] Resuming run {} ({} of {} services already completed)",
+        report.id,
+        completed.len(),
+        checkpoint.queue.len()
+    );
+
+    execute_run(
+        &app,
+        &checkpoint.queue,
+        report,
+        completed,
+        checkpoint.elapsed_ms,
+    )
+}
+
+/// Run an enabled queue to completion, checkpointing after every service.
+///
+/// `completed` holds indices that already finished before an interruption
+/// (empty for a fresh run); their results are already in `report`.
+fn execute_run(
+    app: &AppHandle,
+    enabled_queue: &[ServiceQueueItem],
+    mut report: ServiceReport,
+    completed: HashSet<usize>,
+    prior_elapsed_ms: u64,
+) -> Result<ServiceReport, String> {
     // Update global state
     {
         let mut state = SERVICE_STATE.lock().unwrap();
@@ -329,24 +423,38 @@ pub async fn run_services(
         });
     }
 
+    let control = RunControl {
+        cancel: CancellationToken::new(),
+        pause: PauseGate::new(),
+    };
+    *RUN_CONTROL.lock().unwrap() = Some(control.clone());
+
     // Emit initial state
     let _ = app.emit("service-state-changed", get_service_run_state());
 
-    let start_time = Instant::now();
+    let mut checkpointer = RunCheckpointer {
+        queue: enabled_queue,
+        completed,
+        started: Instant::now(),
+        prior_elapsed_ms,
+    };
+    // Checkpoint before the first service so a crash during it is resumable too
+    checkpointer.save(&report);
+    let timeouts = service_timeouts(enabled_queue);
 
-    if parallel_mode {
+    if report.parallel_mode {
         // =====================================================================
         // Parallel Execution (Experimental)
         // Resource-based concurrent scheduler: services with overlapping
         // exclusive_resources are serialized; non-conflicting services run
         // concurrently on separate threads.
         // =====================================================================
-        run_services_parallel(&app, &enabled_queue, &mut report)?;
+        run_services_parallel(app, &mut report, &mut checkpointer, &timeouts, &control)?;
     } else {
         // =====================================================================
         // Sequential Execution (Default)
         // =====================================================================
-        run_services_sequential(&app, &enabled_queue, &mut report)?;
+        run_services_sequential(app, &mut report, &mut checkpointer, &timeouts, &control)?;
     }
 
     // Complete report
@@ -363,15 +471,23 @@ pub async fn run_services(
     } else {
         ServiceRunStatus::Failed
     };
-    report.total_duration_ms = Some(start_time.elapsed().as_millis() as u64);
+    report.total_duration_ms = Some(checkpointer.elapsed_ms());
     report.current_service_index = None;
     report.current_service_indices = vec![];
 
-    // Save report
-    if let Err(e) = save_report(&report) {
-        eprintln!("Failed to save report: {}", e);
+    // Save report; the checkpoint is only dropped once the report is safely on disk
+    match save_report(&report) {
+        Ok(()) => clear_run_checkpoint(),
+        Err(e) => eprintln!("Failed to save report: {}", e),
     }
 
+    // Enforce retention now that a new report exists
+    if let Err(e) = super::reports::apply_report_retention() {
+        eprintln!("Failed to prune reports: {}", e);
+    }
+
+    *RUN_CONTROL.lock().unwrap() = None;
+
     // Update global state
     {
         let mut state = SERVICE_STATE.lock().unwrap();
@@ -396,12 +512,19 @@ pub async fn run_services(
 /// Run services one at a time (original behavior)
 fn run_services_sequential(
     app: &AppHandle,
-    enabled_queue: &[ServiceQueueItem],
     report: &mut ServiceReport,
+    checkpointer: &mut RunCheckpointer,
+    timeouts: &[Option<Duration>],
+    control: &RunControl,
 ) -> Result<(), String> {
+    let enabled_queue = checkpointer.queue;
     let total_count = enabled_queue.len();
 
     for (index, queue_item) in enabled_queue.iter().enumerate() {
+        if checkpointer.completed.contains(&index) {
+            continue;
+        }
+
         if is_cancelled() {
             break;
         }
@@ -414,14 +537,20 @@ fn run_services_sequential(
         emit_progress(app, index, total_count, &queue_item.service_id);
 
         // Run the service
-        let result = services::run_service(&queue_item.service_id, &queue_item.options, app)
-            .ok_or_else(|| format!("Unknown service: {}", queue_item.service_id))?;
+        let result = services::run_service(
+            &queue_item.service_id,
+            &queue_item.options,
+            control.context(app, &queue_item.service_id),
+            timeouts[index],
+        )
+        .ok_or_else(|| format!("Unknown service: {}", queue_item.service_id))?;
 
         if result.success {
             record_service_metrics(queue_item, result.duration_ms);
         }
 
         report.results.push(result);
+        checkpointer.complete(index, report);
 
         sync_results_to_state(&report.results);
         let _ = app.emit("service-state-changed", get_service_run_state());
@@ -434,6 +563,104 @@ fn run_services_sequential(
 // Shared Runner Helpers
 // =============================================================================
 
+/// Tracks which services of a run have finished and checkpoints the run to disk
+struct RunCheckpointer<'a> {
+    /// Enabled queue items in execution order
+    queue: &'a [ServiceQueueItem],
+    /// Indices into `queue` of finished services
+    completed: HashSet<usize>,
+    started: Instant,
+    /// Run time before this session (when resuming an interrupted run)
+    prior_elapsed_ms: u64,
+}
+
+impl RunCheckpointer<'_> {
+    /// Total run time including time before an interruption
+    fn elapsed_ms(&self) -> u64 {
+        self.prior_elapsed_ms + self.started.elapsed().as_millis() as u64
+    }
+
+    /// Write the current progress to disk. Failures are logged; a missing
+    /// checkpoint only matters if the app dies before the run finishes.
+    fn save(&self, report: &ServiceReport) {
+        let mut completed_indices: Vec<usize> = self.completed.iter().copied().collect();
+        completed_indices.sort_unstable();
+        let checkpoint = RunCheckpoint {
+            report: report.clone(),
+            queue: self.queue.to_vec(),
+            completed_indices,
+            elapsed_ms: self.elapsed_ms(),
+            checkpointed_at: Utc::now().to_rfc3339(),
+        };
+        if let Err(e) = save_run_checkpoint(&checkpoint) {
+            eprintln!("[Services] Failed to checkpoint run: {}", e);
+        }
+    }
+
+    /// Mark a service as finished and checkpoint the run
+    fn complete(&mut self, index: usize, report: &ServiceReport) {
+        self.completed.insert(index);
+        self.save(report);
+    }
+}
+
+/// Run time explicitly requested in a service's options, in seconds
+/// (e.g. the length of a stress test).
+fn requested_duration_secs(options: &serde_json::Value) -> u64 {
+    let minutes = options["duration_minutes"].as_f64().map(|m| m * 60.0);
+    let seconds = options["duration_seconds"]
+        .as_f64()
+        .or_else(|| options["duration"].as_f64());
+    minutes.into_iter().chain(seconds).fold(0.0, f64::max) as u64
+}
+
+/// Lowest computed timeout for services whose work can legitimately take
+/// hours, such as installing a backlog of cumulative updates. The watchdog
+/// still stops them if they hang past it.
+const LONG_RUNNING_MIN_TIMEOUT_SECS: &[(&str, u64)] = &[("windows-update", 4 * 60 * 60)];
+
+/// Watchdog timeout for each queue item (`None` = no limit).
+///
+/// Unless overridden in settings, the timeout is a multiple of the expected
+/// duration: the time-tracking estimate for this PC, or the service's
+/// `estimated_duration_secs` before enough runs have been recorded, but
+/// never less than a duration requested in the options or the service's
+/// entry in `LONG_RUNNING_MIN_TIMEOUT_SECS`.
+fn service_timeouts(queue: &[ServiceQueueItem]) -> Vec<Option<Duration>> {
+    let settings = get_settings().map(|s| s.services).unwrap_or_default();
+    if !settings.timeouts_enabled {
+        return vec![None; queue.len()];
+    }
+
+    let estimated_secs: HashMap<String, u32> = services::get_all_definitions()
+        .into_iter()
+        .map(|d| (d.id, d.estimated_duration_secs))
+        .collect();
+
+    queue
+        .iter()
+        .map(|item| {
+            if let Some(&secs) = settings.timeout_overrides.get(&item.service_id) {
+                return (secs > 0).then(|| Duration::from_secs(secs));
+            }
+            let estimate_ms = super::time_tracking::get_estimated_time(
+                item.service_id.clone(),
+                Some(item.options.clone()),
+                estimated_secs.get(&item.service_id).copied().unwrap_or(0),
+            );
+            let expected_secs = (estimate_ms / 1000).max(requested_duration_secs(&item.options));
+            let secs = (expected_secs as f64 * settings.timeout_multiplier) as u64;
+            let floor = LONG_RUNNING_MIN_TIMEOUT_SECS
+                .iter()
+                .find(|(id, _)| *id == item.service_id)
+                .map_or(settings.min_timeout_secs, |(_, secs)| {
+                    (*secs).max(settings.min_timeout_secs)
+                });
+            Some(Duration::from_secs(secs.max(floor)))
+        })
+        .collect()
+}
+
 /// Check whether the service run has been cancelled.
 fn is_cancelled() -> bool {
     let state = SERVICE_STATE.lock().unwrap();
@@ -556,9 +783,13 @@ fn can_start_service(
 /// Services sharing any resource tag are serialized.
 fn run_services_parallel(
     app: &AppHandle,
-    enabled_queue: &[ServiceQueueItem],
     report: &mut ServiceReport,
+    checkpointer: &mut RunCheckpointer,
+    timeouts: &[Option<Duration>],
+    control: &RunControl,
 ) -> Result<(), String> {
+    let enabled_queue = checkpointer.queue;
+
     // Build maps of service_id -> exclusive_resources / dependencies
     let all_defs = services::get_all_definitions();
     let resource_map: HashMap<String, Vec<String>> = all_defs
@@ -574,8 +805,9 @@ fn run_services_parallel(
     let results_collector: Arc<Mutex<Vec<(usize, ServiceResult)>>> =
         Arc::new(Mutex::new(Vec::new()));
 
-    let mut started: HashSet<usize> = HashSet::new();
-    let mut completed: HashSet<usize> = HashSet::new();
+    // Services finished before an interruption count as started and completed
+    let mut started: HashSet<usize> = checkpointer.completed.clone();
+    let mut completed: HashSet<usize> = checkpointer.completed.clone();
     let mut held_resources: HashSet<String> = HashSet::new();
     let mut running: Vec<(usize, Vec<String>, std::thread::JoinHandle<()>)> = Vec::new();
 
@@ -612,6 +844,7 @@ fn run_services_parallel(
                     record_service_metrics(&enabled_queue[idx], result.duration_ms);
                 }
                 report.results.push(result);
+                checkpointer.complete(idx, report);
             }
         }
 
@@ -668,10 +901,11 @@ fn run_services_parallel(
             let notify = Arc::clone(&notify_pair);
             let service_id = queue_item.service_id.clone();
             let options = queue_item.options.clone();
-            let app_handle = app.clone();
+            let timeout = timeouts[index];
+            let ctx = control.context(app, &service_id);
 
             let handle = std::thread::spawn(move || {
-                let result = services::run_service(&service_id, &options, &app_handle)
+                let result = services::run_service(&service_id, &options, ctx, timeout)
                     .unwrap_or_else(|| ServiceResult {
                         service_id: service_id.clone(),
                         success: false,
@@ -680,6 +914,7 @@ fn run_services_parallel(
                         findings: vec![],
                         logs: vec![],
                         agent_analysis: None,
+                        timed_out: false,
                     });
 
                 {
@@ -731,6 +966,7 @@ fn run_services_parallel(
                 record_service_metrics(&enabled_queue[idx], result.duration_ms);
             }
             report.results.push(result);
+            checkpointer.complete(idx, report);
         }
     }
 
@@ -748,22 +984,40 @@ pub fn cancel_service_run() -> Result<(), String> {
                 report.status = ServiceRunStatus::Cancelled;
                 report.completed_at = Some(Utc::now().to_rfc3339());
             }
+            // Stop the running services too, not just the queue
+            if let Some(ref control) = *RUN_CONTROL.lock().unwrap() {
+                control.cancel.cancel();
+            }
+            PAUSE_CONDVAR.notify_all();
             return Ok(());
         }
     }
     Err("No service run in progress".to_string())
 }
 
-/// Get a saved report by ID
+/// Get a saved report by ID.
+///
+/// When `audience` names a redaction profile, the report is redacted for it.
 #[tauri::command]
-pub fn get_service_report(report_id: String) -> Result<ServiceReport, String> {
-    let file_path = get_reports_dir().join(format!("{}.json", report_id));
-    let json =
-        fs::read_to_string(&file_path).map_err(|e| format!("Failed to read report: {}", e))?;
-    serde_json::from_str(&json).map_err(|e| format!("Failed to parse report: {}", e))
+pub fn get_service_report(
+    report_id: String,
+    audience: Option<String>,
+) -> Result<ServiceReport, String> {
+    let mut report = load_report(&report_id)?;
+    if let Some(ref name) = audience {
+        let settings = get_settings()?;
+        reports::redaction::apply_profile(
+            &mut report,
+            reports::redaction::find_profile(&settings.reports, name)?,
+        );
+    }
+    Ok(report)
 }
 
-/// List all saved reports
+/// List all saved reports, loading every report file.
+///
+/// List views should page through `search_reports` instead, which reads the
+/// report index.
 #[tauri::command]
 pub fn list_service_reports() -> Result<Vec<ServiceReport>, String> {
     let reports_dir = get_reports_dir();
@@ -794,13 +1048,19 @@ pub fn list_service_reports() -> Result<Vec<ServiceReport>, String> {
 /// Delete a saved report by ID
 #[tauri::command]
 pub fn delete_report(report_id: String) -> Result<(), String> {
-    let file_path = get_reports_dir().join(format!("{}.json", report_id));
+    let file_path = report_file_path(&report_id)?;
 
     if !file_path.exists() {
         return Err(format!("Report not found: {}", report_id));
     }
 
-    fs::remove_file(&file_path).map_err(|e| format!("Failed to delete report: {}", e))
+    fs::remove_file(&file_path).map_err(|e| format!("Failed to delete report: {}", e))?;
+
+    if let Err(e) = unindex_reports(&[report_id]) {
+        eprintln!("Failed to remove report from index: {}", e);
+    }
+
+    Ok(())
 }
 
 /// Delete all saved reports
@@ -812,6 +1072,7 @@ pub fn clear_all_reports() -> Result<u32, String> {
     }
 
     let mut deleted_count = 0u32;
+    let mut deleted_ids = Vec::new();
     let entries =
         fs::read_dir(&reports_dir).map_err(|e| format!("Failed to read reports dir: {}", e))?;
 
@@ -819,9 +1080,16 @@ pub fn clear_all_reports() -> Result<u32, String> {
         if entry.path().extension().is_some_and(|ext| ext == "json")
             && fs::remove_file(entry.path()).is_ok() {
                 deleted_count += 1;
+                if let Some(stem) = entry.path().file_stem() {
+                    deleted_ids.push(stem.to_string_lossy().to_string());
+                }
             }
     }
 
+    if let Err(e) = unindex_reports(&deleted_ids) {
+        eprintln!("Failed to remove reports from index: {}", e);
+    }
+
     Ok(deleted_count)
 }
 
@@ -829,7 +1097,8 @@ pub fn clear_all_reports() -> Result<u32, String> {
 // Pause / Resume Commands
 // =============================================================================
 
-/// Pause the current service run (takes effect between services)
+/// Pause the current service run (takes effect between services, or at the
+/// next pause point of a service that supports it)
 #[tauri::command]
 pub fn pause_service_run(app: AppHandle) -> Result<(), String> {
     let mut state = SERVICE_STATE.lock().unwrap();
@@ -839,6 +1108,9 @@ pub fn pause_service_run(app: AppHandle) -> Result<(), String> {
             if let Some(ref mut report) = s.current_report {
                 report.status = ServiceRunStatus::Paused;
             }
+            if let Some(ref control) = *RUN_CONTROL.lock().unwrap() {
+                control.pause.pause();
+            }
             drop(state);
             let _ = app.emit("service-state-changed", get_service_run_state());
             return Ok(());
@@ -857,6 +1129,9 @@ pub fn resume_service_run(app: AppHandle) -> Result<(), String> {
             if let Some(ref mut report) = s.current_report {
                 report.status = ServiceRunStatus::Running;
             }
+            if let Some(ref control) = *RUN_CONTROL.lock().unwrap() {
+                control.pause.resume();
+            }
             PAUSE_CONDVAR.notify_all();
             drop(state);
             let _ = app.emit("service-state-changed", get_service_run_state());
@@ -870,9 +1145,20 @@ pub fn resume_service_run(app: AppHandle) -> Result<(), String> {
 // Report Editing Commands (for agent)
 // =============================================================================
 
+/// Path of a saved report's JSON file.
+///
+/// Report IDs are UUIDs; anything else (e.g. `../settings`) is rejected so a
+/// caller-supplied ID can't reach files outside the reports folder.
+fn report_file_path(report_id: &str) -> Result<std::path::PathBuf, String> {
+    if Uuid::parse_str(report_id).is_err() {
+        return Err(format!("Invalid report ID: {}", report_id));
+    }
+    Ok(get_reports_dir().join(format!("{}.json", report_id)))
+}
+
 /// Load a report from disk (helper)
-fn load_report(report_id: &str) -> Result<ServiceReport, String> {
-    let file_path = get_reports_dir().join(format!("{}.json", report_id));
+pub(crate) fn load_report(report_id: &str) -> Result<ServiceReport, String> {
+    let file_path = report_file_path(report_id)?;
     let json =
         fs::read_to_string(&file_path).map_err(|e| format!("Failed to read report: {}", e))?;
     serde_json::from_str(&json).map_err(|e| format!("Failed to parse report: {}", e))
@@ -1013,6 +1299,14 @@ pub fn set_service_analysis(
     save_report(&report)
 }
 
+/// Pin or unpin a report (pinned reports are exempt from retention pruning)
+#[tauri::command]
+pub fn set_report_pinned(report_id: String, pinned: bool) -> Result<(), String> {
+    let mut report = load_report(&report_id)?;
+    report.pinned = pinned;
+    save_report(&report)
+}
+
 /// Set the health score on a report
 #[tauri::command]
 pub fn set_report_health_score(report_id: String, score: u8) -> Result<(), String> {
@@ -1106,162 +1400,223 @@ pub fn get_report_statistics(report_id: String) -> Result<ReportStatistics, Stri
 }
 
 // =============================================================================
-// PDF Report Generation
+// Report Export
 // =============================================================================
 
-/// Format the PDF header section (report metadata).
-fn format_pdf_header(report: &ServiceReport) -> Vec<String> {
-    let mut lines = Vec::new();
-    lines.push("═══════════════════════════════════════════════════════".to_string());
-    lines.push("                   SERVICE REPORT                     ".to_string());
-    lines.push("═══════════════════════════════════════════════════════".to_string());
-    lines.push(String::new());
-    lines.push(format!("Report ID:    {}", report.id));
-    lines.push(format!("Date:         {}", report.started_at));
-    if let Some(ref name) = report.technician_name {
-        lines.push(format!("Technician:   {}", name));
-    }
-    if let Some(ref name) = report.customer_name {
-        lines.push(format!("Customer:     {}", name));
-    }
-    lines.push(format!("Status:       {:?}", report.status));
-    if let Some(score) = report.health_score {
-        lines.push(format!("Health Score: {}/100", score));
-    }
-    lines.push(String::new());
-
-    if let Some(ref summary) = report.agent_summary {
-        lines.push("───────────────────────────────────────────────────────".to_string());
-        lines.push("  AI ANALYSIS SUMMARY                                 ".to_string());
-        lines.push("───────────────────────────────────────────────────────".to_string());
-        lines.push(summary.clone());
-        lines.push(String::new());
-    }
+/// Load a report and its statistics, redacted for `audience` if given.
+///
+/// Statistics are recomputed over what the audience sees, but the health
+/// score always reflects the full report.
+fn load_report_for_export(
+    report_id: &str,
+    audience: Option<&str>,
+    settings: &AppSettings,
+) -> Result<(ServiceReport, ReportStatistics), String> {
+    let mut report = load_report(report_id)?;
+    let Some(name) = audience else {
+        let stats = compute_report_statistics(&report);
+        return Ok((report, stats));
+    };
 
-    lines
+    let health_score = compute_report_statistics(&report).health_score;
+    reports::redaction::apply_profile(
+        &mut report,
+        reports::redaction::find_profile(&settings.reports, name)?,
+    );
+    let mut stats = compute_report_statistics(&report);
+    stats.health_score = health_score;
+    Ok((report, stats))
 }
 
-/// Format the statistics section of the PDF.
-fn format_pdf_summary(stats: &ReportStatistics) -> Vec<String> {
-    let mut lines = Vec::new();
-    lines.push("───────────────────────────────────────────────────────".to_string());
-    lines.push("  STATISTICS                                          ".to_string());
-    lines.push("───────────────────────────────────────────────────────".to_string());
-    lines.push(format!("Services Run:     {}", stats.total_services));
-    lines.push(format!("Passed:           {}", stats.passed));
-    lines.push(format!("Failed:           {}", stats.failed));
-    lines.push(format!(
-        "Total Duration:   {:.1}s",
-        stats.total_duration_ms as f64 / MS_PER_SECOND
-    ));
-    lines.push(format!(
-        "Avg per Service:  {:.1}s",
-        stats.avg_duration_ms as f64 / MS_PER_SECOND
-    ));
-    lines.push(String::new());
-    lines.push(format!(
-        "Findings:  {} critical, {} errors, {} warnings, {} info, {} success",
-        stats.findings_by_severity.critical,
-        stats.findings_by_severity.error,
-        stats.findings_by_severity.warning,
-        stats.findings_by_severity.info,
-        stats.findings_by_severity.success
-    ));
-    lines.push(String::new());
-    lines
-}
+/// Generate a PDF report and return the file path
+#[tauri::command]
+pub fn generate_report_pdf(
+    report_id: String,
+    output_path: Option<String>,
+    audience: Option<String>,
+) -> Result<String, String> {
+    let settings = get_settings()?;
+    let (report, stats) = load_report_for_export(&report_id, audience.as_deref(), &settings)?;
 
-/// Format the per-service findings section of the PDF.
-fn format_pdf_findings(results: &[ServiceResult]) -> Vec<String> {
-    let definitions = services::get_all_definitions();
-    let def_map: HashMap<String, &crate::types::ServiceDefinition> =
-        definitions.iter().map(|d| (d.id.clone(), d)).collect();
-
-    let mut lines = Vec::new();
-
-    for result in results {
-        let service_name = def_map
-            .get(&result.service_id)
-            .map(|d| d.name.as_str())
-            .unwrap_or(&result.service_id);
-
-        lines.push("───────────────────────────────────────────────────────".to_string());
-        lines.push(format!(
-            "  {} — {}",
-            service_name,
-            if result.success { "PASSED" } else { "FAILED" }
-        ));
-        lines.push(format!(
-            "  Duration: {:.1}s",
-            result.duration_ms as f64 / MS_PER_SECOND
-        ));
-        lines.push("───────────────────────────────────────────────────────".to_string());
-
-        if let Some(ref err) = result.error {
-            lines.push(format!("  ERROR: {}", err));
-        }
+    let pdf_path = match output_path {
+        Some(p) => std::path::PathBuf::from(p),
+        None => get_reports_dir().join(format!("{}.pdf", report.id)),
+    };
 
-        for finding in &result.findings {
-            let sev = match finding.severity {
-                FindingSeverity::Info => "INFO",
-                FindingSeverity::Success => "OK",
-                FindingSeverity::Warning => "WARN",
-                FindingSeverity::Error => "ERROR",
-                FindingSeverity::Critical => "CRITICAL",
-            };
-            lines.push(format!("  [{}] {}", sev, finding.title));
-            if !finding.description.is_empty() {
-                lines.push(format!("         {}", finding.description));
-            }
-            if let Some(ref rec) = finding.recommendation {
-                lines.push(format!("         → {}", rec));
-            }
-        }
+    // Branding is only shown when business mode is enabled
+    let business = settings.business.enabled.then_some(&settings.business);
+    let logo = business
+        .and_then(|b| b.logo_path.as_deref())
+        .filter(|p| !p.is_empty())
+        .and_then(|p| reports::pdf::load_logo(&get_data_dir_path().join(p)));
 
-        if let Some(ref analysis) = result.agent_analysis {
-            lines.push(String::new());
-            lines.push(format!("  AI Analysis: {}", analysis));
-        }
+    let content = reports::pdf::render_report(&report, &stats, business, logo.as_ref());
 
-        lines.push(String::new());
+    if let Some(parent) = pdf_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create dir: {}", e))?;
     }
 
-    lines
+    fs::write(&pdf_path, &content).map_err(|e| format!("Failed to write report: {}", e))?;
+
+    Ok(pdf_path.to_string_lossy().to_string())
 }
 
-/// Generate a PDF report and return the file path
+/// Render a report as HTML or Markdown text
+fn render_report_document(
+    report_id: &str,
+    format: ReportExportFormat,
+    detail_level: ReportDetailLevel,
+    audience: Option<&str>,
+) -> Result<String, String> {
+    let settings = get_settings()?;
+    let (report, stats) = load_report_for_export(report_id, audience, &settings)?;
+    let business = settings.business.enabled.then_some(&settings.business);
+
+    Ok(match format {
+        ReportExportFormat::Html => {
+            let logo = business
+                .and_then(|b| b.logo_path.clone())
+                .and_then(|p| get_business_logo(p).ok().flatten());
+            reports::html::render_report(&report, &stats, business, logo.as_deref(), detail_level)
+        }
+        ReportExportFormat::Markdown => {
+            reports::markdown::render_report(&report, &stats, business, detail_level)
+        }
+    })
+}
+
+/// Render a report as HTML or Markdown and return the document text
+/// (for copying into emails and ticket systems)
 #[tauri::command]
-pub fn generate_report_pdf(
+pub fn render_service_report(
     report_id: String,
-    output_path: Option<String>,
+    format: ReportExportFormat,
+    detail_level: Option<ReportDetailLevel>,
+    audience: Option<String>,
 ) -> Result<String, String> {
-    let report = load_report(&report_id)?;
-    let stats = compute_report_statistics(&report);
+    render_report_document(
+        &report_id,
+        format,
+        detail_level.unwrap_or_default(),
+        audience.as_deref(),
+    )
+}
 
-    let pdf_path = match output_path {
+/// Export a report as an HTML or Markdown file and return the file path
+#[tauri::command]
+pub fn export_service_report(
+    report_id: String,
+    format: ReportExportFormat,
+    detail_level: Option<ReportDetailLevel>,
+    audience: Option<String>,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    let content = render_report_document(
+        &report_id,
+        format,
+        detail_level.unwrap_or_default(),
+        audience.as_deref(),
+    )?;
+
+    let export_path = match output_path {
         Some(p) => std::path::PathBuf::from(p),
-        None => get_reports_dir().join(format!("{}.pdf", report.id)),
+        None => get_reports_dir().join(format!("{}.{}", report_id, format.extension())),
     };
 
-    let mut lines = format_pdf_header(&report);
-    lines.extend(format_pdf_summary(&stats));
-    lines.extend(format_pdf_findings(&report.results));
+    if let Some(parent) = export_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create dir: {}", e))?;
+    }
 
-    // Footer
-    lines.push("═══════════════════════════════════════════════════════".to_string());
-    lines.push(format!(
-        "Generated by RustService AI Agent — {}",
-        Utc::now().to_rfc3339()
-    ));
-    lines.push("═══════════════════════════════════════════════════════".to_string());
+    fs::write(&export_path, content).map_err(|e| format!("Failed to write report: {}", e))?;
 
-    let content = lines.join("\n");
+    Ok(export_path.to_string_lossy().to_string())
+}
 
-    if let Some(parent) = pdf_path.parent() {
+// =============================================================================
+// Report Comparison
+// =============================================================================
+
+/// Load two reports and compare them, redacted for `audience` if given
+fn load_report_diff(
+    before_report_id: &str,
+    after_report_id: &str,
+    audience: Option<&str>,
+    settings: &AppSettings,
+) -> Result<ReportDiff, String> {
+    let mut before = load_report(before_report_id)?;
+    let mut after = load_report(after_report_id)?;
+
+    if let Some(name) = audience {
+        // The diff only shows measurements derived from finding data, never the
+        // raw payloads, so keep the data for metric extraction
+        let mut profile = reports::redaction::find_profile(&settings.reports, name)?.clone();
+        profile.include_finding_data = true;
+        reports::redaction::apply_profile(&mut before, &profile);
+        reports::redaction::apply_profile(&mut after, &profile);
+    }
+
+    Ok(reports::diff::diff_reports(&before, &after))
+}
+
+/// Compare two reports ("before vs after") and return a structured diff
+#[tauri::command]
+pub fn compare_reports(
+    before_report_id: String,
+    after_report_id: String,
+    audience: Option<String>,
+) -> Result<ReportDiff, String> {
+    let settings = get_settings()?;
+    load_report_diff(
+        &before_report_id,
+        &after_report_id,
+        audience.as_deref(),
+        &settings,
+    )
+}
+
+/// Export a comparison of two reports as an HTML or Markdown file and return the file path
+#[tauri::command]
+pub fn export_report_diff(
+    before_report_id: String,
+    after_report_id: String,
+    format: ReportExportFormat,
+    audience: Option<String>,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    let settings = get_settings()?;
+    let diff = load_report_diff(
+        &before_report_id,
+        &after_report_id,
+        audience.as_deref(),
+        &settings,
+    )?;
+    let business = settings.business.enabled.then_some(&settings.business);
+
+    let content = match format {
+        ReportExportFormat::Html => {
+            let logo = business
+                .and_then(|b| b.logo_path.clone())
+                .and_then(|p| get_business_logo(p).ok().flatten());
+            reports::html::render_diff(&diff, business, logo.as_deref())
+        }
+        ReportExportFormat::Markdown => reports::markdown::render_diff(&diff, business),
+    };
+
+    let export_path = match output_path {
+        Some(p) => std::path::PathBuf::from(p),
+        None => get_reports_dir().join(format!(
+            "{}-vs-{}.{}",
+            before_report_id,
+            after_report_id,
+            format.extension()
+        )),
+    };
+
+    if let Some(parent) = export_path.parent() {
         fs::create_dir_all(parent).map_err(|e| format!("Failed to create dir: {}", e))?;
     }
 
-    fs::write(&pdf_path, &content).map_err(|e| format!("Failed to write report: {}", e))?;
+    fs::write(&export_path, content).map_err(|e| format!("Failed to write comparison: {}", e))?;
 
-    Ok(pdf_path.to_string_lossy().to_string())
+    Ok(export_path.to_string_lossy().to_string())
 }
@@ -3,6 +3,7 @@
 use std::fs;
 
 use crate::commands::get_data_dir_path;
+use crate::commands::mcp_keys::import_legacy_mcp_api_key;
 use crate::types::{migrate_settings, AppSettings};
 
 /// Retrieves the current application settings
@@ -63,7 +64,9 @@ pub fn get_settings() -> Result<AppSettings, String> {
     };
 
     // Apply any necessary migrations
-    let migrated = migrate_settings(settings);
+    let mut migrated = migrate_settings(settings);
+
+    import_legacy_key(&mut migrated);
 
     // Save migrated settings if version changed
     let _ = save_settings_internal(&migrated);
@@ -71,6 +74,24 @@ pub fn get_settings() -> Result<AppSettings, String> {
     Ok(migrated)
 }
 
+/// The single plaintext MCP key moves into the hashed key store, where it
+/// is accepted from the next request on
+fn import_legacy_key(settings: &mut AppSettings) {
+    if let Some(legacy_key) = settings.agent.mcp_api_key.clone() {
+        match import_legacy_mcp_api_key(&legacy_key) {
+            Ok(()) => settings.agent.mcp_api_key = None,
+            Err(e) => eprintln!("[MCP] Failed to migrate API key: {}", e),
+        }
+    }
+}
+
+/// Bring the MCP server and external MCP connections in line with newly
+/// saved settings
+fn apply_saved_settings(settings: &AppSettings) {
+    crate::mcp::sync_mcp_clients(&settings.agent.mcp_servers);
+    crate::mcp::apply_mcp_server_settings(&settings.agent);
+}
+
 /// Internal function to save settings without going through tauri command
 fn save_settings_internal(settings: &AppSettings) -> Result<(), String> {
     let data_dir = get_data_dir_path();
@@ -91,9 +112,22 @@ fn save_settings_internal(settings: &AppSettings) -> Result<(), String> {
 ///
 /// Writes the full settings object to `data/settings.json` in a
 /// human-readable, pretty-printed JSON format.
+///
+/// Runs off the main thread: applying the settings can restart the MCP
+/// server, which takes seconds.
 #[tauri::command]
-pub fn save_settings(settings: AppSettings) -> Result<(), String> {
-    save_settings_internal(&settings)
+pub async fn save_settings(settings: AppSettings) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || save_settings_blocking(settings))
+        .await
+        .map_err(|e| format!("Save settings task failed: {e}"))?
+}
+
+/// Save and apply settings on the calling thread
+pub(crate) fn save_settings_blocking(mut settings: AppSettings) -> Result<(), String> {
+    import_legacy_key(&mut settings);
+    save_settings_internal(&settings)?;
+    apply_saved_settings(&settings);
+    Ok(())
 }
 
 /// Updates a single setting value by key path
@@ -102,7 +136,13 @@ pub fn save_settings(settings: AppSettings) -> Result<(), String> {
 /// * `key` - Dot-separated path to the setting (e.g., "appearance.theme")
 /// * `value` - JSON value to set
 #[tauri::command]
-pub fn update_setting(key: String, value: String) -> Result<AppSettings, String> {
+pub async fn update_setting(key: String, value: String) -> Result<AppSettings, String> {
+    tokio::task::spawn_blocking(move || update_setting_blocking(key, value))
+        .await
+        .map_err(|e| format!("Update setting task failed: {e}"))?
+}
+
+fn update_setting_blocking(key: String, value: String) -> Result<AppSettings, String> {
     let mut settings = get_settings()?;
 
     let parts: Vec<&str> = key.split('.').collect();
@@ -136,6 +176,14 @@ pub fn update_setting(key: String, value: String) -> Result<AppSettings, String>
             settings.reports.include_logs_in_report = serde_json::from_str(&value)
                 .map_err(|e| format!("Invalid includeLogsInReport value: {}", e))?;
         }
+        ["reports", "archiveExpiredReports"] => {
+            settings.reports.archive_expired_reports = serde_json::from_str(&value)
+                .map_err(|e| format!("Invalid archiveExpiredReports value: {}", e))?;
+        }
+        ["reports", "redactionProfiles"] => {
+            settings.reports.redaction_profiles = serde_json::from_str(&value)
+                .map_err(|e| format!("Invalid redactionProfiles value: {}", e))?;
+        }
         // Business settings
         ["business", "enabled"] => {
             settings.business.enabled = serde_json::from_str(&value)
@@ -191,6 +239,23 @@ pub fn update_setting(key: String, value: String) -> Result<AppSettings, String>
             settings.presets.custom_presets = serde_json::from_str(&value)
                 .map_err(|e| format!("Invalid presets.customPresets value: {}", e))?;
         }
+        // Service runner settings
+        ["services", "timeoutsEnabled"] => {
+            settings.services.timeouts_enabled = serde_json::from_str(&value)
+                .map_err(|e| format!("Invalid services.timeoutsEnabled value: {}", e))?;
+        }
+        ["services", "timeoutMultiplier"] => {
+            settings.services.timeout_multiplier = serde_json::from_str(&value)
+                .map_err(|e| format!("Invalid services.timeoutMultiplier value: {}", e))?;
+        }
+        ["services", "minTimeoutSecs"] => {
+            settings.services.min_timeout_secs = serde_json::from_str(&value)
+                .map_err(|e| format!("Invalid services.minTimeoutSecs value: {}", e))?;
+        }
+        ["services", "timeoutOverrides"] => {
+            settings.services.timeout_overrides = serde_json::from_str(&value)
+                .map_err(|e| format!("Invalid services.timeoutOverrides value: {}", e))?;
+        }
         // Agent settings (entire object)
         ["agent"] => {
             settings.agent = serde_json::from_str(&value)
@@ -201,7 +266,9 @@ pub fn update_setting(key: String, value: String) -> Result<AppSettings, String>
         }
     }
 
+    import_legacy_key(&mut settings);
     save_settings_internal(&settings)?;
+    apply_saved_settings(&settings);
     Ok(settings)
 }
 
@@ -14,6 +14,7 @@
 mod commands;
 pub mod error;
 mod mcp;
+mod reports;
 mod services;
 mod types;
 
@@ -30,26 +31,43 @@ pub use commands::*;
 /// Tauri application entry point
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Load settings and start MCP server in background if enabled
-    if let Ok(settings) = get_settings() {
-        if settings.agent.mcp_server_enabled {
-            if let Some(api_key) = settings.agent.mcp_api_key {
-                eprintln!("[MCP] Starting server on port {}", settings.agent.mcp_port);
-                mcp::start_mcp_server_background(
-                    settings.agent.mcp_port,
-                    api_key,
-                    settings.agent.tavily_api_key,
-                    settings.agent.searxng_url,
-                );
-            } else {
-                eprintln!("[MCP] Server enabled but no API key configured");
-            }
-        }
+    // Headless MCP server for clients that launch the binary as a subprocess
+    if std::env::args().skip(1).any(|arg| arg == mcp::STDIO_FLAG) {
+        mcp::run_mcp_server_stdio();
+        return;
+    }
+
+    // A checkpoint left on disk means the last run was cut short (crash or reboot);
+    // the frontend offers to resume it via `get_interrupted_run`
+    if let Some(checkpoint) = commands::load_run_checkpoint() {
+        eprintln!(
+            "[Services] Found interrupted run {} ({} of {} services completed)",
+            checkpoint.report.id,
+            checkpoint.completed_indices.len(),
+            checkpoint.queue.len()
+        );
     }
 
+    // Enforce report retention in the background so startup isn't delayed
+    std::thread::spawn(|| {
+        if let Err(e) = commands::apply_report_retention() {
+            eprintln!("[Reports] Retention pass failed: {}", e);
+        }
+    });
+
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
+        .setup(|app| {
+            // Connect to configured external MCP servers, and start our own MCP
+            // server if enabled. It needs the app handle so its service tools
+            // can drive the service runner.
+            if let Ok(settings) = get_settings() {
+                mcp::sync_mcp_clients(&settings.agent.mcp_servers);
+                mcp::init_mcp_server(app.handle().clone(), &settings.agent);
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             commands::get_data_dir,
             commands::ensure_data_dir,
@@ -84,6 +102,9 @@ pub fn run() {
             commands::cancel_service_run,
             commands::pause_service_run,
             commands::resume_service_run,
+            commands::resume_interrupted_run,
+            commands::get_interrupted_run,
+            commands::discard_interrupted_run,
             commands::get_service_report,
             commands::list_service_reports,
             commands::delete_report,
@@ -97,6 +118,18 @@ pub fn run() {
             commands::set_report_health_score,
             commands::get_report_statistics,
             commands::generate_report_pdf,
+            commands::render_service_report,
+            commands::export_service_report,
+            commands::set_report_pinned,
+            commands::prune_reports,
+            commands::search_reports,
+            commands::rebuild_report_index,
+            commands::compare_reports,
+            commands::export_report_diff,
+            commands::get_machine_identity,
+            commands::list_machines,
+            commands::list_machine_reports,
+            commands::get_machine_trends,
             // Service presets management
             commands::save_service_preset,
             commands::delete_service_preset,
@@ -145,10 +178,17 @@ pub fn run() {
             // Agent commands
             commands::queue_agent_command,
             commands::execute_agent_command,
+            commands::assess_agent_command,
             commands::get_pending_commands,
             commands::clear_pending_commands,
             commands::approve_command,
             commands::reject_command,
+            commands::get_command_status,
+            commands::reset_agent_shell_session,
+            commands::close_agent_shell_session,
+            commands::list_file_journal,
+            commands::undo_file_operation,
+            commands::rollback_file_journal,
             commands::search_tavily,
             commands::search_searxng,
             commands::get_agent_settings,
@@ -181,6 +221,24 @@ pub fn run() {
             commands::save_conversation_messages,
             commands::update_conversation_title,
             commands::delete_conversation,
+            // MCP API key commands
+            commands::create_mcp_api_key,
+            commands::list_mcp_api_keys,
+            commands::revoke_mcp_api_key,
+            commands::list_mcp_tool_names,
+            // MCP audit log
+            commands::query_mcp_audit_log,
+            commands::export_mcp_audit_log,
+            // MCP TLS
+            commands::get_mcp_tls_certificate,
+            commands::list_mcp_client_servers,
+            commands::list_mcp_client_tools,
+            commands::call_mcp_client_tool,
+            commands::reconnect_mcp_client,
+            commands::start_mcp_server,
+            commands::stop_mcp_server,
+            commands::restart_mcp_server,
+            commands::get_mcp_server_status,
             // Disk health commands
             commands::get_disk_health,
             // Restore point commands
@@ -0,0 +1,185 @@
+//! MCP Request Auditing
+//!
+//! Builds audit log entries for the requests each transport handles, and
+//! redacts them first: argument values under secret-looking names are
+//! dropped, inline credentials in command lines are masked, and long values
+//! (file contents, scripts) are cut down. Entries are stored by
+//! `commands::mcp_audit`.
+
+use std::net::SocketAddr;
+use std::sync::LazyLock;
+use std::time::Instant;
+
+use chrono::{SecondsFormat, Utc};
+use regex::Regex;
+use serde_json::Value;
+
+use super::protocol::{JsonRpcMessage, JsonRpcResponse};
+use crate::commands::mcp_audit::record_mcp_audit;
+use crate::types::{McpApiKey, McpAuditEntry};
+
+// =============================================================================
+// Redaction
+// =============================================================================
+
+const REDACTED: &str = "[redacted]";
+
+/// String values longer than this are truncated in the log
+const MAX_VALUE_CHARS: usize = 1000;
+
+/// Argument names whose values are never logged
+static SECRET_NAME_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)pass(word|wd|phrase)?|secret|token|api[_-]?key|auth|credential|private[_-]?key",
+    )
+    .unwrap()
+});
+
+/// Inline credentials: `password=x`, `-Password x`, `token: x`, `Bearer x`, key secrets
+static SECRET_TEXT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)(\b(?:password|passwd|pwd|secret|token|api[_-]?key)\b\s*[:=]?\s*|\bbearer\s+)("[^"]*"|'[^']*'|[^\s"']+)"#,
+    )
+    .unwrap()
+});
+
+/// MCP key secrets, wherever they appear
+static KEY_SECRET_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"rsk_[0-9a-f]{16,}").unwrap());
+
+pub(super) fn redact_text(text: &str) -> String {
+    let masked = SECRET_TEXT_RE.replace_all(text, format!("${{1}}{}", REDACTED));
+    let masked = KEY_SECRET_RE.replace_all(&masked, REDACTED).into_owned();
+    match masked.char_indices().nth(MAX_VALUE_CHARS) {
+        Some((cut, _)) => format!("{}... ({} chars)", &masked[..cut], masked.chars().count()),
+        None => masked,
+    }
+}
+
+/// Copy of `value` that is safe to store in the audit log (or print)
+pub(super) fn redact_value(value: &Value) -> Value {
+    match value {
+        Value::String(text) => Value::String(redact_text(text)),
+        Value::Array(items) => Value::Array(items.iter().map(redact_value).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(name, value)| {
+                    let value = if SECRET_NAME_RE.is_match(name) && !value.is_null() {
+                        Value::String(REDACTED.to_string())
+                    } else {
+                        redact_value(value)
+                    };
+                    (name.clone(), value)
+                })
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+// =============================================================================
+// Audit Entries
+// =============================================================================
+
+/// Who sent a request, for the audit log
+#[derive(Debug, Clone)]
+pub struct AuditCaller {
+    transport: &'static str,
+    remote_addr: Option<String>,
+    key_id: Option<String>,
+    key_name: Option<String>,
+}
+
+impl AuditCaller {
+    pub fn stdio() -> Self {
+        Self {
+            transport: "stdio",
+            remote_addr: None,
+            key_id: None,
+            key_name: None,
+        }
+    }
+
+    /// An HTTP client, with the key it authenticated with if any
+    pub fn http(remote_addr: SocketAddr, key: Option<&McpApiKey>) -> Self {
+        Self {
+            transport: "http",
+            remote_addr: Some(remote_addr.to_string()),
+            key_id: key.map(|k| k.id.clone()),
+            key_name: key.map(|k| k.name.clone()),
+        }
+    }
+
+    fn entry(&self, method: Option<String>) -> McpAuditEntry {
+        McpAuditEntry {
+            id: 0,
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            transport: self.transport.to_string(),
+            remote_addr: self.remote_addr.clone(),
+            key_id: self.key_id.clone(),
+            key_name: self.key_name.clone(),
+            method,
+            tool: None,
+            arguments: None,
+            duration_ms: 0,
+            success: false,
+            error: None,
+            output_bytes: 0,
+        }
+    }
+
+    /// Record a request that was refused before it could be handled
+    /// (bad credentials, unknown session, unparseable body)
+    pub fn record_rejected(&self, error: &str) {
+        let mut entry = self.entry(None);
+        entry.error = Some(error.to_string());
+        record_mcp_audit(&entry);
+    }
+}
+
+/// A request being handled; `finish` writes it to the audit log
+pub struct AuditRecord {
+    entry: McpAuditEntry,
+    started: Instant,
+}
+
+impl AuditRecord {
+    /// Start auditing a message. Notifications and client responses are not
+    /// audited, so this returns `None` for them.
+    pub fn start(caller: &AuditCaller, message: &JsonRpcMessage) -> Option<Self> {
+        if !message.is_request() {
+            return None;
+        }
+        let mut entry = caller.entry(message.method.clone());
+
+        let arguments = if entry.method.as_deref() == Some("tools/call") {
+            entry.tool = message
+                .params
+                .get("name")
+                .and_then(|v| v.as_str())
+                .map(String::from);
+            message.params.get("arguments")
+        } else {
+            Some(&message.params)
+        };
+        entry.arguments = arguments
+            .filter(|v| !v.is_null() && v.as_object().is_none_or(|m| !m.is_empty()))
+            .map(|v| redact_value(v).to_string());
+
+        Some(Self {
+            entry,
+            started: Instant::now(),
+        })
+    }
+
+    pub fn finish(mut self, response: Option<&JsonRpcResponse>) {
+        self.entry.duration_ms = self.started.elapsed().as_millis() as u64;
+        if let Some(response) = response {
+            self.entry.output_bytes = serde_json::to_vec(response)
+                .map(|bytes| bytes.len() as u64)
+                .unwrap_or(0);
+            self.entry.error = response.error_message().map(|e| redact_text(&e));
+            self.entry.success = self.entry.error.is_none();
+        }
+        record_mcp_audit(&self.entry);
+    }
+}
@@ -0,0 +1,837 @@
+//! MCP Client
+//!
+//! Connects to the external MCP servers configured in
+//! `AgentSettings::mcp_servers`, keeps their tool lists current, and proxies
+//! tool calls to them. Each enabled server gets a supervisor task that
+//! connects, re-lists the server's tools as a periodic health check, and
+//! reconnects with backoff when the connection fails.
+//!
+//! Both transports are supported: streamable HTTP (`http`), where each
+//! request is a POST answered with JSON or an SSE stream, and the older
+//! HTTP+SSE transport (`sse`), where a long-lived GET stream carries the
+//! responses to requests POSTed to the endpoint it announces.
+//!
+//! Tools are namespaced as `mcp_<serverId>_<toolName>`. Client work runs on
+//! Tauri's async runtime, so the agent's commands and the MCP server (which
+//! has its own runtime) share the same connections.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
+use std::time::Duration;
+
+use chrono::{SecondsFormat, Utc};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE};
+use serde_json::{json, Value};
+use tauri::async_runtime::{self, JoinHandle};
+use tokio::sync::{oneshot, Notify};
+
+use crate::types::{
+    MCPServerConfig, MCPTransportType, McpClientServerStatus, McpClientState, McpClientTool,
+};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Protocol revision requested in `initialize`
+const PROTOCOL_VERSION: &str = "2025-03-26";
+
+const CLIENT_NAME: &str = "RustService";
+
+/// Timeout for protocol requests other than tool calls
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Timeout for a proxied tool call
+const TOOL_CALL_TIMEOUT: Duration = Duration::from_secs(300);
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often a connected server's tool list is re-read
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(300);
+
+const SESSION_HEADER: &str = "mcp-session-id";
+
+/// Prefix of namespaced tool names
+const TOOL_PREFIX: &str = "mcp_";
+
+// =============================================================================
+// Errors
+// =============================================================================
+
+/// Why a request failed. Transport errors mean the connection is unusable
+/// and trigger a reconnect; server errors are ordinary JSON-RPC errors.
+#[derive(Debug)]
+enum ClientError {
+    Transport(String),
+    Server(String),
+}
+
+impl std::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::Transport(message) | ClientError::Server(message) => f.write_str(message),
+        }
+    }
+}
+
+fn transport_error(context: &str, e: impl std::fmt::Display) -> ClientError {
+    ClientError::Transport(format!("{}: {}", context, e))
+}
+
+/// The result of a JSON-RPC response, or its error
+fn rpc_result(message: Value) -> Result<Value, ClientError> {
+    if let Some(error) = message.get("error") {
+        return Err(ClientError::Server(format!(
+            "Server error {}: {}",
+            error.get("code").unwrap_or(&Value::Null),
+            error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown error")
+        )));
+    }
+    message
+        .get("result")
+        .cloned()
+        .ok_or_else(|| ClientError::Transport("Response has no result".to_string()))
+}
+
+/// Whether `message` is the response to request `id`
+fn is_response_to(message: &Value, id: u64) -> bool {
+    message.get("id").and_then(Value::as_u64) == Some(id)
+        && (message.get("result").is_some() || message.get("error").is_some())
+}
+
+// =============================================================================
+// SSE Parsing
+// =============================================================================
+
+struct SseEvent {
+    event: String,
+    data: String,
+}
+
+/// Incremental `text/event-stream` parser
+#[derive(Default)]
+struct SseParser {
+    buffer: Vec<u8>,
+    event: String,
+    data: String,
+}
+
+impl SseParser {
+    /// Feed a chunk and return the events it completed
+    fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let raw: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&raw);
+            let line = line.trim_end_matches(['\r', '\n']);
+
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    events.push(SseEvent {
+                        event: std::mem::take(&mut self.event),
+                        data: std::mem::take(&mut self.data),
+                    });
+                }
+                self.event.clear();
+                continue;
+            }
+            if line.starts_with(':') {
+                continue;
+            }
+            let (field, value) = match line.split_once(':') {
+                Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+                None => (line, ""),
+            };
+            match field {
+                "event" => self.event = value.to_string(),
+                "data" => {
+                    if !self.data.is_empty() {
+                        self.data.push('\n');
+                    }
+                    self.data.push_str(value);
+                }
+                _ => {}
+            }
+        }
+        events
+    }
+}
+
+// =============================================================================
+// Connection
+// =============================================================================
+
+/// Lock a mutex, carrying on past a panic in another holder
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Requests awaiting a response on the SSE stream, by request ID
+type PendingResponses = Arc<Mutex<HashMap<u64, oneshot::Sender<Value>>>>;
+
+enum Transport {
+    Http {
+        url: String,
+        session_id: Mutex<Option<String>>,
+    },
+    Sse {
+        /// URL requests are POSTed to, announced by the stream
+        endpoint: String,
+        pending: PendingResponses,
+        reader: JoinHandle<()>,
+    },
+}
+
+/// An initialized session with one external server
+struct Connection {
+    http: reqwest::Client,
+    transport: Transport,
+    next_id: AtomicU64,
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        if let Transport::Sse { reader, .. } = &self.transport {
+            reader.abort();
+        }
+    }
+}
+
+/// HTTP client that sends the server's API key and custom headers
+fn build_http_client(config: &MCPServerConfig) -> Result<reqwest::Client, ClientError> {
+    let mut headers = HeaderMap::new();
+    if let Some(key) = config.api_key.as_deref().filter(|k| !k.is_empty()) {
+        let value = HeaderValue::from_str(&format!("Bearer {}", key))
+            .map_err(|e| transport_error("Invalid API key", e))?;
+        headers.insert(AUTHORIZATION, value);
+    }
+    for (name, value) in config.headers.iter().flatten() {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| transport_error(&format!("Invalid header name '{}'", name), e))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|e| transport_error(&format!("Invalid value for header '{}'", name), e))?;
+        headers.insert(name, value);
+    }
+    reqwest::Client::builder()
+        .default_headers(headers)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build()
+        .map_err(|e| transport_error("Failed to create HTTP client", e))
+}
+
+/// Read the legacy SSE stream, delivering the endpoint and then responses
+async fn read_sse_stream(
+    mut response: reqwest::Response,
+    endpoint_tx: oneshot::Sender<Result<String, String>>,
+    pending: PendingResponses,
+) {
+    let base = response.url().clone();
+    let mut endpoint_tx = Some(endpoint_tx);
+    let mut parser = SseParser::default();
+
+    while let Ok(Some(chunk)) = response.chunk().await {
+        for event in parser.push(&chunk) {
+            match event.event.as_str() {
+                "endpoint" => {
+                    if let Some(tx) = endpoint_tx.take() {
+                        let endpoint = base
+                            .join(event.data.trim())
+                            .map(|url| url.to_string())
+                            .map_err(|e| format!("Invalid endpoint '{}': {}", event.data, e));
+                        let _ = tx.send(endpoint);
+                    }
+                }
+                "" | "message" => {
+                    let Ok(message) = serde_json::from_str::<Value>(&event.data) else {
+                        continue;
+                    };
+                    // Notifications and server requests are not supported and ignored
+                    let id = message.get("id").and_then(Value::as_u64);
+                    if let Some(id) = id.filter(|id| is_response_to(&message, *id)) {
+                        if let Some(tx) = lock(&pending).remove(&id) {
+                            let _ = tx.send(message);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Dropping the senders fails every request still waiting
+    lock(&pending).clear();
+}
+
+impl Connection {
+    /// Open the transport and run the `initialize` handshake. Returns the
+    /// connection and the server's self-description.
+    async fn open(config: &MCPServerConfig) -> Result<(Self, Option<String>), ClientError> {
+        let http = build_http_client(config)?;
+        let transport = match config.transport_type {
+            MCPTransportType::Http => Transport::Http {
+                url: config.url.clone(),
+                session_id: Mutex::new(None),
+            },
+            MCPTransportType::Sse => Self::open_sse(&http, &config.url).await?,
+        };
+        let connection = Self {
+            http,
+            transport,
+            next_id: AtomicU64::new(1),
+        };
+
+        let result = connection
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": { "name": CLIENT_NAME, "version": env!("CARGO_PKG_VERSION") },
+                }),
+                REQUEST_TIMEOUT,
+            )
+            .await?;
+        connection
+            .notify("notifications/initialized", json!({}))
+            .await?;
+
+        let server_info = result.get("serverInfo").map(|info| {
+            let name = info
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown");
+            match info.get("version").and_then(Value::as_str) {
+                Some(version) => format!("{} {}", name, version),
+                None => name.to_string(),
+            }
+        });
+        Ok((connection, server_info))
+    }
+
+    async fn open_sse(http: &reqwest::Client, url: &str) -> Result<Transport, ClientError> {
+        let response = http
+            .get(url)
+            .header(ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .map_err(|e| transport_error("Failed to connect", e))?;
+        if !response.status().is_success() {
+            return Err(ClientError::Transport(format!(
+                "Server returned HTTP {}",
+                response.status()
+            )));
+        }
+
+        let pending = PendingResponses::default();
+        let (endpoint_tx, endpoint_rx) = oneshot::channel();
+        let reader =
+            async_runtime::spawn(read_sse_stream(response, endpoint_tx, Arc::clone(&pending)));
+
+        let endpoint = match tokio::time::timeout(REQUEST_TIMEOUT, endpoint_rx).await {
+            Ok(Ok(Ok(endpoint))) => endpoint,
+            Ok(Ok(Err(e))) => {
+                reader.abort();
+                return Err(ClientError::Transport(e));
+            }
+            _ => {
+                reader.abort();
+                return Err(ClientError::Transport(
+                    "Server did not announce an endpoint on its SSE stream".to_string(),
+                ));
+            }
+        };
+        Ok(Transport::Sse {
+            endpoint,
+            pending,
+            reader,
+        })
+    }
+
+    /// Send a request and wait for its result
+    async fn request(
+        &self,
+        method: &str,
+        params: Value,
+        timeout: Duration,
+    ) -> Result<Value, ClientError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        let exchange = async {
+            match &self.transport {
+                Transport::Http { url, session_id } => {
+                    self.request_http(url, session_id, &message, id).await
+                }
+                Transport::Sse {
+                    endpoint, pending, ..
+                } => self.request_sse(endpoint, pending, &message, id).await,
+            }
+        };
+        match tokio::time::timeout(timeout, exchange).await {
+            Ok(response) => rpc_result(response?),
+            Err(_) => {
+                if let Transport::Sse { pending, .. } = &self.transport {
+                    lock(pending).remove(&id);
+                }
+                Err(ClientError::Transport(format!(
+                    "'{}' timed out after {}s",
+                    method,
+                    timeout.as_secs()
+                )))
+            }
+        }
+    }
+
+    /// Send a notification (no response expected)
+    async fn notify(&self, method: &str, params: Value) -> Result<(), ClientError> {
+        let message = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+        let (url, session) = match &self.transport {
+            Transport::Http { url, session_id } => (url, lock(session_id).clone()),
+            Transport::Sse { endpoint, .. } => (endpoint, None),
+        };
+        let mut request = self.http.post(url).json(&message);
+        if let Some(session) = session {
+            request = request.header(SESSION_HEADER, session);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| transport_error("Failed to send notification", e))?;
+        if !response.status().is_success() {
+            return Err(ClientError::Transport(format!(
+                "Server returned HTTP {} for {}",
+                response.status(),
+                method
+            )));
+        }
+        Ok(())
+    }
+
+    async fn request_http(
+        &self,
+        url: &str,
+        session_id: &Mutex<Option<String>>,
+        message: &Value,
+        id: u64,
+    ) -> Result<Value, ClientError> {
+        let session = lock(session_id).clone();
+        let mut request = self
+            .http
+            .post(url)
+            .header(ACCEPT, "application/json, text/event-stream")
+            .json(message);
+        if let Some(session) = &session {
+            request = request.header(SESSION_HEADER, session);
+        }
+        let mut response = request
+            .send()
+            .await
+            .map_err(|e| transport_error("Request failed", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ClientError::Transport(format!(
+                "Server returned HTTP {}: {}",
+                status,
+                body.chars().take(200).collect::<String>()
+            )));
+        }
+        if let Some(new_session) = response
+            .headers()
+            .get(SESSION_HEADER)
+            .and_then(|h| h.to_str().ok())
+        {
+            *lock(session_id) = Some(new_session.to_string());
+        }
+
+        let is_stream = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|h| h.to_str().ok())
+            .is_some_and(|ct| ct.contains("text/event-stream"));
+        if is_stream {
+            let mut parser = SseParser::default();
+            while let Some(chunk) = response
+                .chunk()
+                .await
+                .map_err(|e| transport_error("Response stream failed", e))?
+            {
+                for event in parser.push(&chunk) {
+                    if let Ok(message) = serde_json::from_str::<Value>(&event.data) {
+                        if is_response_to(&message, id) {
+                            return Ok(message);
+                        }
+                    }
+                }
+            }
+            return Err(ClientError::Transport(
+                "Response stream ended without a result".to_string(),
+            ));
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| transport_error("Invalid response", e))?;
+        let message = match body {
+            Value::Array(messages) => messages.into_iter().find(|m| is_response_to(m, id)),
+            message => Some(message),
+        };
+        message.ok_or_else(|| ClientError::Transport("Response did not answer the request".into()))
+    }
+
+    async fn request_sse(
+        &self,
+        endpoint: &str,
+        pending: &PendingResponses,
+        message: &Value,
+        id: u64,
+    ) -> Result<Value, ClientError> {
+        let (tx, rx) = oneshot::channel();
+        lock(pending).insert(id, tx);
+
+        let sent = self.http.post(endpoint).json(message).send().await;
+        let failure = match sent {
+            Ok(response) if response.status().is_success() => None,
+            Ok(response) => Some(format!("Server returned HTTP {}", response.status())),
+            Err(e) => Some(format!("Request failed: {}", e)),
+        };
+        if let Some(failure) = failure {
+            lock(pending).remove(&id);
+            return Err(ClientError::Transport(failure));
+        }
+
+        rx.await
+            .map_err(|_| ClientError::Transport("SSE stream closed".to_string()))
+    }
+
+    /// Every tool the server offers, following pagination
+    async fn list_tools(&self) -> Result<Vec<Value>, ClientError> {
+        let mut tools = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let params = match &cursor {
+                Some(cursor) => json!({ "cursor": cursor }),
+                None => json!({}),
+            };
+            let result = self.request("tools/list", params, REQUEST_TIMEOUT).await?;
+            if let Some(page) = result.get("tools").and_then(Value::as_array) {
+                tools.extend(page.iter().cloned());
+            }
+            cursor = result
+                .get("nextCursor")
+                .and_then(Value::as_str)
+                .map(String::from);
+            if cursor.is_none() {
+                return Ok(tools);
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Server Supervision
+// =============================================================================
+
+struct ServerStatus {
+    state: McpClientState,
+    server_info: Option<String>,
+    /// Tool definitions as the server returned them
+    tools: Vec<Value>,
+    last_error: Option<String>,
+    connected_at: Option<String>,
+    last_checked_at: Option<String>,
+    reconnect_attempts: u32,
+}
+
+/// One configured external server and its supervisor
+struct ExternalServer {
+    config: MCPServerConfig,
+    status: Mutex<ServerStatus>,
+    connection: Mutex<Option<Arc<Connection>>>,
+    /// Wakes the supervisor early (failed call, reconnect request)
+    wake: Notify,
+    supervisor: Mutex<Option<JoinHandle<()>>>,
+}
+
+fn now_rfc3339() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+impl ExternalServer {
+    fn new(config: MCPServerConfig) -> Self {
+        Self {
+            config,
+            status: Mutex::new(ServerStatus {
+                state: McpClientState::Connecting,
+                server_info: None,
+                tools: Vec::new(),
+                last_error: None,
+                connected_at: None,
+                last_checked_at: None,
+                reconnect_attempts: 0,
+            }),
+            connection: Mutex::new(None),
+            wake: Notify::new(),
+            supervisor: Mutex::new(None),
+        }
+    }
+
+    fn current_connection(&self) -> Option<Arc<Connection>> {
+        lock(&self.connection).clone()
+    }
+
+    async fn connect(&self) -> Result<(), ClientError> {
+        lock(&self.status).state = McpClientState::Connecting;
+        let (connection, server_info) = Connection::open(&self.config).await?;
+        let tools = connection.list_tools().await?;
+        eprintln!(
+            "[MCP client] Connected to '{}' - {} tools",
+            self.config.name,
+            tools.len()
+        );
+
+        *lock(&self.connection) = Some(Arc::new(connection));
+        let now = now_rfc3339();
+        let mut status = lock(&self.status);
+        status.state = McpClientState::Connected;
+        status.server_info = server_info;
+        status.tools = tools;
+        status.last_error = None;
+        status.connected_at = Some(now.clone());
+        status.last_checked_at = Some(now);
+        status.reconnect_attempts = 0;
+        Ok(())
+    }
+
+    /// Re-read the tool list; doubles as the health check
+    async fn check_health(&self) -> Result<(), ClientError> {
+        let Some(connection) = self.current_connection() else {
+            return Err(ClientError::Transport("Not connected".to_string()));
+        };
+        let tools = connection.list_tools().await?;
+        let mut status = lock(&self.status);
+        status.tools = tools;
+        status.last_checked_at = Some(now_rfc3339());
+        Ok(())
+    }
+
+    fn mark_disconnected(&self, error: &str) {
+        *lock(&self.connection) = None;
+        let mut status = lock(&self.status);
+        if status.state == McpClientState::Connected {
+            eprintln!(
+                "[MCP client] Lost connection to '{}': {}",
+                self.config.name, error
+            );
+        }
+        status.state = McpClientState::Disconnected;
+        status.last_error = Some(error.to_string());
+    }
+
+    /// Sleep for `duration`, or until woken
+    async fn pause(&self, duration: Duration) {
+        tokio::select! {
+            _ = tokio::time::sleep(duration) => {}
+            _ = self.wake.notified() => {}
+        }
+    }
+
+    /// Connect, health-check while connected, and reconnect with backoff
+    async fn supervise(self: Arc<Self>) {
+        let mut delay = INITIAL_RECONNECT_DELAY;
+        loop {
+            match self.connect().await {
+                Ok(()) => {
+                    delay = INITIAL_RECONNECT_DELAY;
+                    loop {
+                        self.pause(HEALTH_CHECK_INTERVAL).await;
+                        if self.current_connection().is_none() {
+                            break;
+                        }
+                        if let Err(e) = self.check_health().await {
+                            self.mark_disconnected(&e.to_string());
+                            break;
+                        }
+                    }
+                    // A connection that was working gets one immediate retry
+                    continue;
+                }
+                Err(e) => {
+                    self.mark_disconnected(&e.to_string());
+                    lock(&self.status).reconnect_attempts += 1;
+                }
+            }
+            self.pause(delay).await;
+            delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+        }
+    }
+
+    fn stop(&self) {
+        if let Some(supervisor) = lock(&self.supervisor).take() {
+            supervisor.abort();
+        }
+        *lock(&self.connection) = None;
+    }
+
+    fn to_status(&self) -> McpClientServerStatus {
+        let status = lock(&self.status);
+        McpClientServerStatus {
+            id: self.config.id.clone(),
+            name: self.config.name.clone(),
+            url: self.config.url.clone(),
+            state: status.state,
+            server_info: status.server_info.clone(),
+            tools: status
+                .tools
+                .iter()
+                .filter_map(|t| t.get("name").and_then(Value::as_str).map(String::from))
+                .collect(),
+            last_error: status.last_error.clone(),
+            connected_at: status.connected_at.clone(),
+            last_checked_at: status.last_checked_at.clone(),
+            reconnect_attempts: status.reconnect_attempts,
+        }
+    }
+
+    fn tools(&self) -> Vec<McpClientTool> {
+        let status = lock(&self.status);
+        if status.state != McpClientState::Connected {
+            return Vec::new();
+        }
+        status
+            .tools
+            .iter()
+            .filter_map(|tool| {
+                let tool_name = tool.get("name")?.as_str()?.to_string();
+                Some(McpClientTool {
+                    name: format!("{}{}_{}", TOOL_PREFIX, self.config.id, tool_name),
+                    server_id: self.config.id.clone(),
+                    server_name: self.config.name.clone(),
+                    tool_name,
+                    description: tool
+                        .get("description")
+                        .and_then(Value::as_str)
+                        .map(String::from),
+                    input_schema: tool
+                        .get("inputSchema")
+                        .cloned()
+                        .unwrap_or_else(|| json!({ "type": "object" })),
+                    reexported: self.config.reexport,
+                })
+            })
+            .collect()
+    }
+
+    async fn call_tool(&self, tool_name: &str, arguments: Value) -> Result<Value, String> {
+        let connection = self
+            .current_connection()
+            .ok_or_else(|| format!("MCP server '{}' is not connected", self.config.name))?;
+        let params = json!({ "name": tool_name, "arguments": arguments });
+        match connection
+            .request("tools/call", params, TOOL_CALL_TIMEOUT)
+            .await
+        {
+            Ok(result) => Ok(result),
+            Err(ClientError::Transport(e)) => {
+                self.mark_disconnected(&e);
+                self.wake.notify_one();
+                Err(format!("MCP server '{}': {}", self.config.name, e))
+            }
+            Err(ClientError::Server(e)) => Err(format!("MCP server '{}': {}", self.config.name, e)),
+        }
+    }
+}
+
+// =============================================================================
+// Public API
+// =============================================================================
+
+/// Supervised external servers, by config ID
+static SERVERS: LazyLock<Mutex<HashMap<String, Arc<ExternalServer>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Connect to the enabled servers in `configs` and disconnect from the rest.
+/// Servers whose configuration is unchanged keep their connections.
+pub fn sync_mcp_clients(configs: &[MCPServerConfig]) {
+    let wanted: Vec<&MCPServerConfig> = configs
+        .iter()
+        .filter(|c| c.enabled && !c.url.trim().is_empty())
+        .collect();
+    let mut servers = lock(&SERVERS);
+
+    servers.retain(|id, server| {
+        let keep = wanted.iter().any(|c| &c.id == id && **c == server.config);
+        if !keep {
+            eprintln!("[MCP client] Disconnecting from '{}'", server.config.name);
+            server.stop();
+        }
+        keep
+    });
+
+    for config in wanted {
+        if servers.contains_key(&config.id) {
+            continue;
+        }
+        let server = Arc::new(ExternalServer::new(config.clone()));
+        let supervisor = async_runtime::spawn(Arc::clone(&server).supervise());
+        *lock(&server.supervisor) = Some(supervisor);
+        servers.insert(config.id.clone(), server);
+    }
+}
+
+/// Status of every supervised server, by name
+pub fn mcp_client_statuses() -> Vec<McpClientServerStatus> {
+    let mut statuses: Vec<_> = lock(&SERVERS).values().map(|s| s.to_status()).collect();
+    statuses.sort_by(|a, b| a.name.cmp(&b.name));
+    statuses
+}
+
+/// Tools of every connected server
+pub fn mcp_client_tools() -> Vec<McpClientTool> {
+    let servers: Vec<_> = lock(&SERVERS).values().cloned().collect();
+    let mut tools: Vec<_> = servers.iter().flat_map(|s| s.tools()).collect();
+    tools.sort_by(|a, b| a.name.cmp(&b.name));
+    tools
+}
+
+/// Call a tool by its namespaced name. Returns the server's `tools/call`
+/// result (`content` and `isError`) unchanged.
+pub async fn call_mcp_client_tool(name: &str, arguments: Value) -> Result<Value, String> {
+    let servers: Vec<_> = lock(&SERVERS).values().cloned().collect();
+    let (server, tool) = servers
+        .into_iter()
+        .find_map(|server| {
+            let tool = server
+                .tools()
+                .into_iter()
+                .find(|t| t.name == name)?
+                .tool_name;
+            Some((server, tool))
+        })
+        .ok_or_else(|| format!("Unknown external MCP tool: {}", name))?;
+
+    // Run on the client runtime, whichever runtime the caller is on
+    async_runtime::spawn(async move { server.call_tool(&tool, arguments).await })
+        .await
+        .map_err(|e| format!("MCP tool call failed: {}", e))?
+}
+
+/// Drop a server's connection and reconnect straight away
+pub fn reconnect_mcp_client(server_id: &str) -> Result<(), String> {
+    let server = lock(&SERVERS)
+        .get(server_id)
+        .cloned()
+        .ok_or_else(|| format!("MCP server not connected or not enabled: {}", server_id))?;
+    server.mark_disconnected("Reconnect requested");
+    server.wake.notify_one();
+    Ok(())
+}
@@ -1,8 +1,9 @@
 //! MCP Tool Dispatch Handlers
 //!
 //! Individual handler functions for each MCP tool, called from the
-//! main `dispatch_tool_call` match in `server.rs`.
+//! main `dispatch_tool_call` match in `protocol.rs`.
 
+use serde::de::DeserializeOwned;
 use serde_json::{Map, Value};
 
 use super::tools::RustServiceTools;
@@ -35,11 +36,40 @@ fn get_optional_usize_arg(arguments: &Map<String, Value>, key: &str) -> Option<u
         .map(|v| v as usize)
 }
 
+/// Extract an optional u64 argument.
+fn get_optional_u64_arg(arguments: &Map<String, Value>, key: &str) -> Option<u64> {
+    arguments.get(key).and_then(|v| v.as_u64())
+}
+
 /// Extract an optional bool argument.
 fn get_optional_bool_arg(arguments: &Map<String, Value>, key: &str) -> Option<bool> {
     arguments.get(key).and_then(|v| v.as_bool())
 }
 
+/// Extract a required usize argument.
+fn get_usize_arg(arguments: &Map<String, Value>, key: &str) -> Result<usize, String> {
+    get_optional_usize_arg(arguments, key)
+        .ok_or_else(|| format!("Missing required argument: {}", key))
+}
+
+/// Deserialize an optional structured argument (arrays, objects).
+fn get_optional_json_arg<T: DeserializeOwned>(
+    arguments: &Map<String, Value>,
+    key: &str,
+) -> Result<Option<T>, String> {
+    match arguments.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => serde_json::from_value(value.clone())
+            .map(Some)
+            .map_err(|e| format!("Invalid argument {}: {}", key, e)),
+    }
+}
+
+/// Extract an optional string argument as an owned `String`.
+fn get_owned_string_arg(arguments: &Map<String, Value>, key: &str) -> Option<String> {
+    get_optional_string_arg(arguments, key).map(String::from)
+}
+
 // =============================================================================
 // Tool Handlers
 // =============================================================================
@@ -51,12 +81,29 @@ pub async fn handle_execute_command(
     let command = get_string_arg(arguments, "command")?;
     let reason = get_optional_string_arg(arguments, "reason").unwrap_or("MCP request");
 
+    let wait_seconds = get_optional_u64_arg(arguments, "wait_seconds");
+
     let result = tools
-        .execute_command(command.to_string(), reason.to_string())
+        .execute_command(command.to_string(), reason.to_string(), wait_seconds)
         .await
         .map_err(|e| format!("Tool error: {:?}", e))?;
 
-    Ok(super::server::call_tool_result_to_json(result))
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_get_command_status(
+    tools: &RustServiceTools,
+    arguments: &Map<String, Value>,
+) -> Result<Value, String> {
+    let command_id = get_string_arg(arguments, "command_id")?;
+    let wait_seconds = get_optional_u64_arg(arguments, "wait_seconds");
+
+    let result = tools
+        .get_command_status(command_id.to_string(), wait_seconds)
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
 }
 
 pub async fn handle_read_file(
@@ -73,7 +120,7 @@ pub async fn handle_read_file(
         .await
         .map_err(|e| format!("Tool error: {:?}", e))?;
 
-    Ok(super::server::call_tool_result_to_json(result))
+    Ok(super::protocol::call_tool_result_to_json(result))
 }
 
 pub async fn handle_write_file(
@@ -88,7 +135,29 @@ pub async fn handle_write_file(
         .await
         .map_err(|e| format!("Tool error: {:?}", e))?;
 
-    Ok(super::server::call_tool_result_to_json(result))
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_edit_file(
+    tools: &RustServiceTools,
+    arguments: &Map<String, Value>,
+) -> Result<Value, String> {
+    let path = get_string_arg(arguments, "path")?;
+    let old_string = get_string_arg(arguments, "old_string")?;
+    let new_string = get_string_arg(arguments, "new_string")?;
+    let all = get_optional_bool_arg(arguments, "all");
+
+    let result = tools
+        .edit_file(
+            path.to_string(),
+            old_string.to_string(),
+            new_string.to_string(),
+            all,
+        )
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
 }
 
 pub async fn handle_list_dir(
@@ -102,7 +171,7 @@ pub async fn handle_list_dir(
         .await
         .map_err(|e| format!("Tool error: {:?}", e))?;
 
-    Ok(super::server::call_tool_result_to_json(result))
+    Ok(super::protocol::call_tool_result_to_json(result))
 }
 
 pub async fn handle_move_file(
@@ -117,7 +186,7 @@ pub async fn handle_move_file(
         .await
         .map_err(|e| format!("Tool error: {:?}", e))?;
 
-    Ok(super::server::call_tool_result_to_json(result))
+    Ok(super::protocol::call_tool_result_to_json(result))
 }
 
 pub async fn handle_copy_file(
@@ -132,7 +201,44 @@ pub async fn handle_copy_file(
         .await
         .map_err(|e| format!("Tool error: {:?}", e))?;
 
-    Ok(super::server::call_tool_result_to_json(result))
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_grep(
+    tools: &RustServiceTools,
+    arguments: &Map<String, Value>,
+) -> Result<Value, String> {
+    let pattern = get_string_arg(arguments, "pattern")?;
+
+    let result = tools
+        .grep(
+            pattern.to_string(),
+            get_owned_string_arg(arguments, "path"),
+            get_owned_string_arg(arguments, "file_pattern"),
+            get_optional_usize_arg(arguments, "max_results"),
+        )
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_glob(
+    tools: &RustServiceTools,
+    arguments: &Map<String, Value>,
+) -> Result<Value, String> {
+    let pattern = get_string_arg(arguments, "pattern")?;
+
+    let result = tools
+        .glob(
+            pattern.to_string(),
+            get_owned_string_arg(arguments, "path"),
+            get_optional_usize_arg(arguments, "limit"),
+        )
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
 }
 
 pub async fn handle_get_system_info(
@@ -143,7 +249,7 @@ pub async fn handle_get_system_info(
         .await
         .map_err(|e| format!("Tool error: {:?}", e))?;
 
-    Ok(super::server::call_tool_result_to_json(result))
+    Ok(super::protocol::call_tool_result_to_json(result))
 }
 
 pub async fn handle_search_web(
@@ -157,7 +263,7 @@ pub async fn handle_search_web(
         .await
         .map_err(|e| format!("Tool error: {:?}", e))?;
 
-    Ok(super::server::call_tool_result_to_json(result))
+    Ok(super::protocol::call_tool_result_to_json(result))
 }
 
 pub async fn handle_list_programs(
@@ -168,7 +274,7 @@ pub async fn handle_list_programs(
         .await
         .map_err(|e| format!("Tool error: {:?}", e))?;
 
-    Ok(super::server::call_tool_result_to_json(result))
+    Ok(super::protocol::call_tool_result_to_json(result))
 }
 
 pub async fn handle_list_instruments(
@@ -179,7 +285,7 @@ pub async fn handle_list_instruments(
         .await
         .map_err(|e| format!("Tool error: {:?}", e))?;
 
-    Ok(super::server::call_tool_result_to_json(result))
+    Ok(super::protocol::call_tool_result_to_json(result))
 }
 
 pub async fn handle_run_instrument(
@@ -188,11 +294,278 @@ pub async fn handle_run_instrument(
 ) -> Result<Value, String> {
     let name = get_string_arg(arguments, "name")?;
     let args = get_optional_string_arg(arguments, "args").map(String::from);
+    let wait_seconds = get_optional_u64_arg(arguments, "wait_seconds");
+
+    let result = tools
+        .run_instrument(name.to_string(), args, wait_seconds)
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+// =============================================================================
+// Service Tool Handlers
+// =============================================================================
+
+pub async fn handle_list_services(tools: &RustServiceTools) -> Result<Value, String> {
+    let result = tools
+        .list_services()
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_list_service_presets(tools: &RustServiceTools) -> Result<Value, String> {
+    let result = tools
+        .list_service_presets()
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_check_service_requirements(
+    tools: &RustServiceTools,
+    arguments: &Map<String, Value>,
+) -> Result<Value, String> {
+    let service_ids = get_optional_json_arg(arguments, "service_ids")?
+        .ok_or("Missing required argument: service_ids")?;
+
+    let result = tools
+        .check_service_requirements(service_ids)
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_run_service_queue(
+    tools: &RustServiceTools,
+    arguments: &Map<String, Value>,
+) -> Result<Value, String> {
+    let queue = get_optional_json_arg(arguments, "queue")?;
+
+    let result = tools
+        .run_service_queue(
+            queue,
+            get_owned_string_arg(arguments, "preset"),
+            get_owned_string_arg(arguments, "technician_name"),
+            get_owned_string_arg(arguments, "customer_name"),
+            get_optional_bool_arg(arguments, "parallel"),
+            get_owned_string_arg(arguments, "reason"),
+        )
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_get_service_status(tools: &RustServiceTools) -> Result<Value, String> {
+    let result = tools
+        .get_service_status()
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_pause_service(
+    tools: &RustServiceTools,
+    arguments: &Map<String, Value>,
+) -> Result<Value, String> {
+    let result = tools
+        .pause_service(get_owned_string_arg(arguments, "reason"))
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_resume_service(
+    tools: &RustServiceTools,
+    arguments: &Map<String, Value>,
+) -> Result<Value, String> {
+    let result = tools
+        .resume_service(get_owned_string_arg(arguments, "reason"))
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_cancel_service(
+    tools: &RustServiceTools,
+    arguments: &Map<String, Value>,
+) -> Result<Value, String> {
+    let result = tools
+        .cancel_service(get_owned_string_arg(arguments, "reason"))
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_list_service_reports(
+    tools: &RustServiceTools,
+    arguments: &Map<String, Value>,
+) -> Result<Value, String> {
+    let result = tools
+        .list_service_reports(
+            get_owned_string_arg(arguments, "customer_name"),
+            get_owned_string_arg(arguments, "text"),
+            get_optional_usize_arg(arguments, "limit"),
+            get_optional_usize_arg(arguments, "offset"),
+        )
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_get_service_report(
+    tools: &RustServiceTools,
+    arguments: &Map<String, Value>,
+) -> Result<Value, String> {
+    let report_id = get_string_arg(arguments, "report_id")?;
+    let include_logs = get_optional_bool_arg(arguments, "include_logs");
+    let audience = get_owned_string_arg(arguments, "audience");
+
+    let result = tools
+        .get_service_report(report_id.to_string(), include_logs, audience)
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_get_report_statistics(
+    tools: &RustServiceTools,
+    arguments: &Map<String, Value>,
+) -> Result<Value, String> {
+    let report_id = get_string_arg(arguments, "report_id")?;
+
+    let result = tools
+        .get_report_statistics(report_id.to_string())
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_edit_finding(
+    tools: &RustServiceTools,
+    arguments: &Map<String, Value>,
+) -> Result<Value, String> {
+    let report_id = get_string_arg(arguments, "report_id")?;
+    let service_id = get_string_arg(arguments, "service_id")?;
+    let finding_index = get_usize_arg(arguments, "finding_index")?;
+
+    let result = tools
+        .edit_finding(
+            report_id.to_string(),
+            service_id.to_string(),
+            finding_index,
+            get_owned_string_arg(arguments, "severity"),
+            get_owned_string_arg(arguments, "title"),
+            get_owned_string_arg(arguments, "description"),
+            get_owned_string_arg(arguments, "recommendation"),
+        )
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_add_finding(
+    tools: &RustServiceTools,
+    arguments: &Map<String, Value>,
+) -> Result<Value, String> {
+    let report_id = get_string_arg(arguments, "report_id")?;
+    let service_id = get_string_arg(arguments, "service_id")?;
+    let severity = get_string_arg(arguments, "severity")?;
+    let title = get_string_arg(arguments, "title")?;
+    let description = get_string_arg(arguments, "description")?;
+
+    let result = tools
+        .add_finding(
+            report_id.to_string(),
+            service_id.to_string(),
+            severity.to_string(),
+            title.to_string(),
+            description.to_string(),
+            get_owned_string_arg(arguments, "recommendation"),
+        )
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_remove_finding(
+    tools: &RustServiceTools,
+    arguments: &Map<String, Value>,
+) -> Result<Value, String> {
+    let report_id = get_string_arg(arguments, "report_id")?;
+    let service_id = get_string_arg(arguments, "service_id")?;
+    let finding_index = get_usize_arg(arguments, "finding_index")?;
+
+    let result = tools
+        .remove_finding(report_id.to_string(), service_id.to_string(), finding_index)
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_set_report_summary(
+    tools: &RustServiceTools,
+    arguments: &Map<String, Value>,
+) -> Result<Value, String> {
+    let report_id = get_string_arg(arguments, "report_id")?;
+    let summary = get_string_arg(arguments, "summary")?;
+
+    let result = tools
+        .set_report_summary(report_id.to_string(), summary.to_string())
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_set_service_analysis(
+    tools: &RustServiceTools,
+    arguments: &Map<String, Value>,
+) -> Result<Value, String> {
+    let report_id = get_string_arg(arguments, "report_id")?;
+    let service_id = get_string_arg(arguments, "service_id")?;
+    let analysis = get_string_arg(arguments, "analysis")?;
+
+    let result = tools
+        .set_service_analysis(
+            report_id.to_string(),
+            service_id.to_string(),
+            analysis.to_string(),
+        )
+        .await
+        .map_err(|e| format!("Tool error: {:?}", e))?;
+
+    Ok(super::protocol::call_tool_result_to_json(result))
+}
+
+pub async fn handle_set_health_score(
+    tools: &RustServiceTools,
+    arguments: &Map<String, Value>,
+) -> Result<Value, String> {
+    let report_id = get_string_arg(arguments, "report_id")?;
+    let score = get_usize_arg(arguments, "score")?;
+    let score = u8::try_from(score).map_err(|_| "Health score must be 0-100".to_string())?;
 
     let result = tools
-        .run_instrument(name.to_string(), args)
+        .set_health_score(report_id.to_string(), score)
         .await
         .map_err(|e| format!("Tool error: {:?}", e))?;
 
-    Ok(super::server::call_tool_result_to_json(result))
+    Ok(super::protocol::call_tool_result_to_json(result))
 }
@@ -0,0 +1,283 @@
+//! MCP Server Lifecycle
+//!
+//! Runs the MCP HTTP server on its own thread and runtime, and keeps the
+//! handle of the running instance so it can be started, stopped and
+//! restarted while the app runs.
+//!
+//! Saved settings are applied as they change: toggling `mcp_server_enabled`
+//! starts or stops the server, and changing how it serves (port, bind
+//! address, TLS, limits, search settings) restarts it. API keys are checked
+//! on every request, so key changes need no restart.
+
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use chrono::{SecondsFormat, Utc};
+use tauri::AppHandle;
+use tokio::sync::Notify;
+
+use super::server::{McpHttpServer, McpServerMonitor};
+use crate::types::{AgentSettings, McpServerStatus};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// How long to wait for the server to bind (includes generating a certificate)
+const START_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How long to wait for the listener to close when stopping
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time open connections get to finish once the listener has closed
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(2);
+
+// =============================================================================
+// Server Handle
+// =============================================================================
+
+/// A server instance running on its own thread
+struct RunningServer {
+    /// Settings the instance was started with
+    settings: AgentSettings,
+    url: String,
+    monitor: McpServerMonitor,
+    started: Instant,
+    started_at: String,
+    shutdown: Arc<Notify>,
+    /// Signalled once the listener has closed
+    stopped: mpsc::Receiver<()>,
+}
+
+#[derive(Default)]
+struct ServerManager {
+    running: Option<RunningServer>,
+    /// Agent settings last passed to [`apply_mcp_server_settings`]
+    applied: Option<AgentSettings>,
+    last_error: Option<String>,
+}
+
+static MANAGER: Mutex<Option<ServerManager>> = Mutex::new(None);
+
+/// App handle for the service tools, kept from the first start
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Run `f` with the manager locked; start, stop and restart never overlap
+fn with_manager<T>(f: impl FnOnce(&mut ServerManager) -> T) -> T {
+    let mut guard = MANAGER.lock().unwrap_or_else(|e| e.into_inner());
+    f(guard.get_or_insert_with(ServerManager::default))
+}
+
+/// Whether moving from `old` to `new` changes how the server serves
+fn needs_restart(old: &AgentSettings, new: &AgentSettings) -> bool {
+    old.mcp_port != new.mcp_port
+        || old.mcp_bind_address != new.mcp_bind_address
+        || old.mcp_tls != new.mcp_tls
+        || old.mcp_limits != new.mcp_limits
+        || old.tavily_api_key != new.tavily_api_key
+        || old.searxng_url != new.searxng_url
+}
+
+/// Bind a server on a new thread and wait until it listens
+fn spawn_server(app: AppHandle, settings: &AgentSettings) -> Result<RunningServer, String> {
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let (stopped_tx, stopped_rx) = mpsc::channel();
+    let shutdown = Arc::new(Notify::new());
+
+    let thread_settings = settings.clone();
+    let thread_shutdown = Arc::clone(&shutdown);
+    std::thread::Builder::new()
+        .name("mcp-server".to_string())
+        .spawn(move || {
+            let rt = match tokio::runtime::Runtime::new() {
+                Ok(rt) => rt,
+                Err(e) => {
+                    let _ = ready_tx.send(Err(format!(
+                        "Failed to create tokio runtime for MCP server: {}",
+                        e
+                    )));
+                    return;
+                }
+            };
+
+            match rt.block_on(McpHttpServer::bind(app, thread_settings)) {
+                Ok(server) => {
+                    let _ = ready_tx.send(Ok((server.url().to_string(), server.monitor())));
+                    rt.block_on(server.serve(thread_shutdown));
+                }
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                }
+            }
+            let _ = stopped_tx.send(());
+            rt.shutdown_timeout(SHUTDOWN_GRACE);
+        })
+        .map_err(|e| format!("Failed to start MCP server thread: {}", e))?;
+
+    let (url, monitor) = match ready_rx.recv_timeout(START_TIMEOUT) {
+        Ok(ready) => ready?,
+        Err(_) => {
+            // Stop it as soon as it does bind
+            shutdown.notify_one();
+            return Err("MCP server did not start in time".to_string());
+        }
+    };
+
+    Ok(RunningServer {
+        settings: settings.clone(),
+        url,
+        monitor,
+        started: Instant::now(),
+        started_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        shutdown,
+        stopped: stopped_rx,
+    })
+}
+
+impl ServerManager {
+    fn start(&mut self, app: AppHandle, settings: &AgentSettings) -> Result<(), String> {
+        if self.running.is_some() {
+            return Err("MCP server is already running".to_string());
+        }
+        match spawn_server(app, settings) {
+            Ok(server) => {
+                self.running = Some(server);
+                self.last_error = None;
+                Ok(())
+            }
+            Err(e) => {
+                eprintln!("[MCP] {}", e);
+                self.last_error = Some(e.clone());
+                Err(e)
+            }
+        }
+    }
+
+    /// Stop the running server. Returns whether one was running.
+    fn stop(&mut self) -> bool {
+        let Some(server) = self.running.take() else {
+            return false;
+        };
+        server.shutdown.notify_one();
+        // The port is free once the listener has closed
+        if server.stopped.recv_timeout(STOP_TIMEOUT).is_err() {
+            eprintln!("[MCP] Server on {} did not stop in time", server.url);
+        }
+        true
+    }
+
+    fn status(&self) -> McpServerStatus {
+        let Some(server) = &self.running else {
+            return McpServerStatus {
+                running: false,
+                listening_address: None,
+                started_at: None,
+                uptime_secs: 0,
+                requests_total: 0,
+                requests_rejected: 0,
+                active_sessions: 0,
+                connected_clients: Vec::new(),
+                last_error: self.last_error.clone(),
+            };
+        };
+        let (requests_total, requests_rejected) = server.monitor.request_counts();
+        McpServerStatus {
+            running: true,
+            listening_address: Some(server.url.clone()),
+            started_at: Some(server.started_at.clone()),
+            uptime_secs: server.started.elapsed().as_secs(),
+            requests_total,
+            requests_rejected,
+            active_sessions: server.monitor.active_sessions(),
+            connected_clients: server.monitor.connected_clients(),
+            last_error: self.last_error.clone(),
+        }
+    }
+}
+
+// =============================================================================
+// Public API
+// =============================================================================
+
+/// Start the MCP server with `settings`
+pub fn start_mcp_server(
+    app: AppHandle,
+    settings: &AgentSettings,
+) -> Result<McpServerStatus, String> {
+    let _ = APP.set(app.clone());
+    with_manager(|manager| {
+        manager.start(app, settings)?;
+        Ok(manager.status())
+    })
+}
+
+/// Stop the MCP server. Returns whether it was running.
+pub fn stop_mcp_server() -> bool {
+    with_manager(|manager| manager.stop())
+}
+
+/// Stop the MCP server if it is running, then start it with `settings`
+pub fn restart_mcp_server(
+    app: AppHandle,
+    settings: &AgentSettings,
+) -> Result<McpServerStatus, String> {
+    let _ = APP.set(app.clone());
+    with_manager(|manager| {
+        manager.stop();
+        manager.start(app, settings)?;
+        Ok(manager.status())
+    })
+}
+
+pub fn mcp_server_status() -> McpServerStatus {
+    with_manager(|manager| manager.status())
+}
+
+/// Make the server follow saved settings. The first call (at startup) starts
+/// the server if it is enabled; later calls act only on what changed, so a
+/// server stopped by hand stays stopped until it is enabled again. A server
+/// that failed to start is retried when its settings change.
+///
+/// Does nothing until the app handle is known, i.e. outside the app.
+pub fn apply_mcp_server_settings(settings: &AgentSettings) {
+    let Some(app) = APP.get().cloned() else {
+        return;
+    };
+    with_manager(|manager| {
+        let previous = manager.applied.replace(settings.clone());
+        let enabled_changed = previous
+            .as_ref()
+            .is_none_or(|p| p.mcp_server_enabled != settings.mcp_server_enabled);
+
+        // Failures are logged and shown in the server status
+        if enabled_changed {
+            if settings.mcp_server_enabled {
+                if manager.running.is_none() {
+                    let _ = manager.start(app, settings);
+                }
+            } else if manager.stop() {
+                eprintln!("[MCP] Server disabled in settings");
+            }
+        } else if let Some(running) = &manager.running {
+            if needs_restart(&running.settings, settings) {
+                eprintln!("[MCP] Settings changed, restarting server");
+                manager.stop();
+                let _ = manager.start(app, settings);
+            }
+        } else if settings.mcp_server_enabled
+            && manager.last_error.is_some()
+            && previous.is_some_and(|p| needs_restart(&p, settings))
+        {
+            // The last start failed (port in use, bad certificate); the
+            // changed settings may fix it
+            let _ = manager.start(app, settings);
+        }
+    });
+}
+
+/// Remember the app handle and start the server if settings enable it
+pub fn init_mcp_server(app: AppHandle, settings: &AgentSettings) {
+    let _ = APP.set(app);
+    apply_mcp_server_settings(settings);
+}
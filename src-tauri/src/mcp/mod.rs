@@ -1,10 +1,30 @@
 //! MCP Server Module
 //!
 //! Implements a Model Context Protocol server for remote LLM control.
-//! Uses rmcp crate with streamable HTTP transport.
+//! Tools are declared with rmcp `#[tool]` annotations; the protocol and the
+//! streamable HTTP and stdio transports are implemented here, as is a client
+//! for the external MCP servers configured in the agent settings.
 
+mod audit;
+mod client;
 mod handlers;
+mod lifecycle;
+mod permissions;
+mod prompts;
+mod protocol;
+mod resources;
 mod server;
+mod service_tools;
+mod stdio;
 mod tools;
 
-pub use server::start_mcp_server_background;
+pub use client::{
+    call_mcp_client_tool, mcp_client_statuses, mcp_client_tools, reconnect_mcp_client,
+    sync_mcp_clients,
+};
+pub use lifecycle::{
+    apply_mcp_server_settings, init_mcp_server, mcp_server_status, restart_mcp_server,
+    start_mcp_server, stop_mcp_server,
+};
+pub use protocol::tool_names;
+pub use stdio::{run_mcp_server_stdio, STDIO_FLAG};
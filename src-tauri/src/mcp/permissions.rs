@@ -0,0 +1,180 @@
+//! MCP Permissions
+//!
+//! What a caller may do, derived from the API key it authenticated with:
+//! which tools it can see and call, and where the file tools may read or
+//! write. The stdio transport and the legacy full-access key are
+//! unrestricted.
+//!
+//! `execute_command` and `run_instrument` are not covered by the filesystem
+//! scope; leave them out of a key's tool list to keep it confined.
+//!
+//! A key's scope narrows the agent's filesystem policy; the file tools
+//! enforce that policy for every caller.
+
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use crate::commands::{normalize_path, path_within};
+use crate::types::{McpApiKey, McpFsAccess};
+
+// =============================================================================
+// File Tool Arguments
+// =============================================================================
+
+/// Whether a path argument is read or written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PathUse {
+    Read,
+    Write,
+}
+
+/// Path arguments of each file tool. `None` for tools that don't touch files.
+fn path_arguments(tool: &str) -> Option<&'static [(&'static str, PathUse)]> {
+    Some(match tool {
+        "read_file" | "list_dir" | "grep" | "glob" => &[("path", PathUse::Read)],
+        "write_file" | "edit_file" => &[("path", PathUse::Write)],
+        // Moving removes the source, so both ends are written
+        "move_file" => &[("src", PathUse::Write), ("dest", PathUse::Write)],
+        "copy_file" => &[("src", PathUse::Read), ("dest", PathUse::Write)],
+        _ => return None,
+    })
+}
+
+// =============================================================================
+// Permissions
+// =============================================================================
+
+/// What one caller is allowed to do
+#[derive(Debug, Clone)]
+pub struct McpPermissions {
+    /// Key name for log messages; `None` when unrestricted
+    key_name: Option<String>,
+    /// `None` allows every tool
+    allowed_tools: Option<HashSet<String>>,
+    fs_access: McpFsAccess,
+    /// Normalized path prefixes; empty allows any path
+    fs_roots: Vec<PathBuf>,
+}
+
+impl McpPermissions {
+    /// Full access, for local transports
+    pub fn unrestricted() -> Self {
+        Self {
+            key_name: None,
+            allowed_tools: None,
+            fs_access: McpFsAccess::ReadWrite,
+            fs_roots: Vec::new(),
+        }
+    }
+
+    pub fn from_key(key: &McpApiKey) -> Self {
+        let fs_roots = key
+            .fs_paths
+            .iter()
+            .filter_map(|p| match normalize_path(p) {
+                Ok(root) => Some(root),
+                Err(e) => {
+                    // An unresolvable root grants nothing
+                    eprintln!(
+                        "MCP: Ignoring filesystem scope for key '{}': {}",
+                        key.name, e
+                    );
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        // If every configured root failed to resolve, deny rather than allow all
+        let fs_access = if !key.fs_paths.is_empty() && fs_roots.is_empty() {
+            McpFsAccess::None
+        } else {
+            key.fs_access
+        };
+
+        Self {
+            key_name: Some(key.name.clone()),
+            allowed_tools: key
+                .allowed_tools
+                .as_ref()
+                .map(|tools| tools.iter().cloned().collect()),
+            fs_access,
+            fs_roots,
+        }
+    }
+
+    pub fn key_name(&self) -> Option<&str> {
+        self.key_name.as_deref()
+    }
+
+    /// Whether the tool shows up for this caller: on the allowlist, and for
+    /// file tools, with enough filesystem access for every path it takes
+    pub fn allows_tool(&self, tool: &str) -> bool {
+        if let Some(allowed) = &self.allowed_tools {
+            if !allowed.contains(tool) {
+                return false;
+            }
+        }
+        match path_arguments(tool) {
+            Some(paths) => paths.iter().all(|(_, usage)| self.allows_use(*usage)),
+            None => true,
+        }
+    }
+
+    fn allows_use(&self, usage: PathUse) -> bool {
+        match self.fs_access {
+            McpFsAccess::None => false,
+            McpFsAccess::ReadOnly => usage == PathUse::Read,
+            McpFsAccess::ReadWrite => true,
+        }
+    }
+
+    /// Check a tool call against the allowlist and filesystem scope
+    pub fn check_tool_call(
+        &self,
+        tool: &str,
+        arguments: &Map<String, Value>,
+    ) -> Result<(), String> {
+        if !self.allows_tool(tool) {
+            return Err(format!("Tool '{}' is not permitted for this API key", tool));
+        }
+        let Some(paths) = path_arguments(tool) else {
+            return Ok(());
+        };
+        if self.fs_roots.is_empty() {
+            return Ok(());
+        }
+
+        for (name, _) in paths {
+            // grep and glob default to the current directory
+            let value = arguments.get(*name).and_then(|v| v.as_str()).unwrap_or(".");
+            let path = normalize_path(value)?;
+            if !self.fs_roots.iter().any(|root| path_within(&path, root)) {
+                return Err(format!(
+                    "Path '{}' is outside the folders this API key may access",
+                    value
+                ));
+            }
+        }
+
+        // glob joins its pattern onto the path, so the pattern must not climb out
+        if tool == "glob" {
+            let pattern = arguments
+                .get("pattern")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let pattern_path = Path::new(pattern);
+            if pattern_path.has_root()
+                || pattern_path
+                    .components()
+                    .any(|c| matches!(c, Component::ParentDir | Component::Prefix(_)))
+            {
+                return Err(
+                    "Glob patterns may not be absolute or contain '..' for this API key"
+                        .to_string(),
+                );
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+//! MCP Prompts
+//!
+//! Ready-made instructions for common technician workflows. Clients surface
+//! these to the user (e.g. as slash commands); `prompts/get` fills in the
+//! arguments and, where useful, embeds the report being discussed.
+
+use serde_json::{json, Value};
+
+use super::permissions::McpPermissions;
+use super::protocol::McpError;
+use super::resources::{report_logs_text, REPORTS_TOOL};
+use super::service_tools::read_report;
+
+// =============================================================================
+// Prompt Definitions
+// =============================================================================
+
+/// Tools a caller needs for a prompt to be of any use
+fn required_tools(prompt: &str) -> &'static [&'static str] {
+    match prompt {
+        "diagnose_machine" => &["run_service_queue", "get_service_status", REPORTS_TOOL],
+        "summarize_report" | "troubleshoot_failures" => &[REPORTS_TOOL],
+        _ => &[],
+    }
+}
+
+fn allows_prompt(prompt: &str, permissions: &McpPermissions) -> bool {
+    required_tools(prompt)
+        .iter()
+        .all(|tool| permissions.allows_tool(tool))
+}
+
+/// List the prompts available to the caller
+pub fn list_prompts(permissions: &McpPermissions) -> Value {
+    let mut prompts = all_prompts();
+    if let Some(list) = prompts["prompts"].as_array_mut() {
+        list.retain(|p| {
+            p["name"]
+                .as_str()
+                .is_some_and(|name| allows_prompt(name, permissions))
+        });
+    }
+    prompts
+}
+
+fn all_prompts() -> Value {
+    json!({
+        "prompts": [
+            {
+                "name": "diagnose_machine",
+                "description": "Run a service preset on this machine and write up the results",
+                "arguments": [
+                    {
+                        "name": "preset",
+                        "description": "Preset ID to run (default: diagnostics)",
+                        "required": false
+                    },
+                    {
+                        "name": "customer_name",
+                        "description": "Customer name for the report",
+                        "required": false
+                    }
+                ]
+            },
+            {
+                "name": "summarize_report",
+                "description": "Write an executive summary and health score for a saved report",
+                "arguments": [
+                    {
+                        "name": "report_id",
+                        "description": "ID of the report to summarize",
+                        "required": true
+                    }
+                ]
+            },
+            {
+                "name": "troubleshoot_failures",
+                "description": "Investigate why services in a report failed, using their logs",
+                "arguments": [
+                    {
+                        "name": "report_id",
+                        "description": "ID of the report to investigate",
+                        "required": true
+                    }
+                ]
+            }
+        ]
+    })
+}
+
+// =============================================================================
+// Prompt Rendering
+// =============================================================================
+
+fn text_message(text: String) -> Value {
+    json!({ "role": "user", "content": { "type": "text", "text": text } })
+}
+
+fn resource_message(uri: String, mime_type: &str, text: String) -> Value {
+    json!({
+        "role": "user",
+        "content": {
+            "type": "resource",
+            "resource": { "uri": uri, "mimeType": mime_type, "text": text }
+        }
+    })
+}
+
+fn required_argument<'a>(arguments: &'a Value, name: &str) -> Result<&'a str, McpError> {
+    arguments
+        .get(name)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| McpError::invalid_params(format!("Missing required argument: {}", name)))
+}
+
+/// Render a prompt with the given arguments
+pub fn get_prompt(params: &Value, permissions: &McpPermissions) -> Result<Value, McpError> {
+    let name = params
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| McpError::invalid_params("Missing required parameter: name"))?;
+    if !allows_prompt(name, permissions) {
+        return Err(McpError::invalid_params(format!(
+            "Unknown prompt: {}",
+            name
+        )));
+    }
+    let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+
+    match name {
+        "diagnose_machine" => {
+            let preset = arguments
+                .get("preset")
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .unwrap_or("diagnostics");
+            let customer = match arguments.get("customer_name").and_then(|v| v.as_str()) {
+                Some(name) if !name.is_empty() => {
+                    format!(" Use \"{}\" as the customer name.", name)
+                }
+                _ => String::new(),
+            };
+            Ok(json!({
+                "description": "Run a service preset and write up the results",
+                "messages": [text_message(format!(
+                    "Diagnose this machine with the \"{preset}\" preset.{customer}\n\n\
+                     1. Call check_service_requirements for the preset's services and tell me about any missing programs.\n\
+                     2. Start the run with run_service_queue (preset: \"{preset}\").\n\
+                     3. Poll get_service_status every 30 seconds or so until isRunning is false.\n\
+                     4. Read the finished report with get_service_report.\n\
+                     5. Write an executive summary with set_report_summary and set a health score with set_health_score.\n\
+                     6. Give me a short list of the most important problems and what to do about them."
+                ))]
+            }))
+        }
+        "summarize_report" => {
+            let report_id = required_argument(&arguments, "report_id")?;
+            let report = read_report(report_id.to_string(), None).map_err(|_| {
+                McpError::invalid_params(format!("Report not found: {}", report_id))
+            })?;
+            let report_json = serde_json::to_string_pretty(&report)
+                .map_err(|e| McpError::internal(format!("Failed to serialize report: {}", e)))?;
+            Ok(json!({
+                "description": "Summarize a saved service report",
+                "messages": [
+                    resource_message(
+                        format!("rustservice://reports/{}", report.id),
+                        "application/json",
+                        report_json
+                    ),
+                    text_message(format!(
+                        "Above is service report {}. Write a professional executive summary for the customer: \
+                         overall condition, the problems found in order of importance, and recommended next steps. \
+                         Save it with set_report_summary, then set a health score (0-100) with set_health_score.",
+                        report.id
+                    ))
+                ]
+            }))
+        }
+        "troubleshoot_failures" => {
+            let report_id = required_argument(&arguments, "report_id")?;
+            let report = read_report(report_id.to_string(), None).map_err(|_| {
+                McpError::invalid_params(format!("Report not found: {}", report_id))
+            })?;
+            let failed: Vec<String> = report
+                .results
+                .iter()
+                .filter(|r| !r.success)
+                .map(|r| match &r.error {
+                    Some(error) => format!("- {}: {}", r.service_id, error),
+                    None => format!("- {}", r.service_id),
+                })
+                .collect();
+            let failed = if failed.is_empty() {
+                "No services failed in this report.".to_string()
+            } else {
+                format!("Failed services:\n{}", failed.join("\n"))
+            };
+            Ok(json!({
+                "description": "Investigate failed services in a report",
+                "messages": [
+                    resource_message(
+                        format!("rustservice://reports/{}/logs", report.id),
+                        "text/plain",
+                        report_logs_text(&report)
+                    ),
+                    text_message(format!(
+                        "Above are the service logs of report {}.\n\n{}\n\n\
+                         For each failed service, work out the likely cause from its log, check the machine with the \
+                         available tools where that helps, and suggest a fix. Record your conclusions on each service \
+                         with set_service_analysis.",
+                        report.id, failed
+                    ))
+                ]
+            }))
+        }
+        _ => Err(McpError::invalid_params(format!(
+            "Unknown prompt: {}",
+            name
+        ))),
+    }
+}
@@ -0,0 +1,668 @@
+//! MCP Protocol
+//!
+//! Transport-independent handling of Model Context Protocol messages:
+//! JSON-RPC framing, `initialize` version/capability negotiation, and the
+//! tools, resources and prompts methods. Transports (`server` for HTTP,
+//! `stdio` for subprocess clients) parse incoming messages, hand them to
+//! [`McpHandler::handle`] and deliver whatever response it returns.
+//!
+//! Tools of external servers marked for re-export (see `client`) are listed
+//! and called alongside our own.
+
+use std::cell::RefCell;
+use std::sync::Arc;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use rmcp::model::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use super::audit::{redact_value, AuditCaller, AuditRecord};
+use super::client;
+use super::handlers;
+use super::permissions::McpPermissions;
+use super::prompts;
+use super::resources;
+use super::tools::RustServiceTools;
+use crate::types::McpClientTool;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Protocol revisions this server speaks, newest first
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-03-26", "2024-11-05"];
+
+pub const SERVER_NAME: &str = "RustService MCP";
+
+/// Instructions returned from `initialize` to orient the client's model
+const SERVER_INSTRUCTIONS: &str = "RustService is a Windows maintenance and diagnostics toolkit. \
+Use list_services and list_service_presets to see what can be run, run_service_queue to start a run, \
+get_service_status to follow it, and get_service_report to read the results. \
+Saved reports, their logs and system information are also available as resources.";
+
+// JSON-RPC and MCP error codes
+pub const PARSE_ERROR: i32 = -32700;
+pub const INVALID_REQUEST: i32 = -32600;
+pub const METHOD_NOT_FOUND: i32 = -32601;
+pub const INVALID_PARAMS: i32 = -32602;
+pub const INTERNAL_ERROR: i32 = -32603;
+pub const RESOURCE_NOT_FOUND: i32 = -32002;
+/// A server limit (size, rate, concurrency) refused the request
+pub const LIMIT_EXCEEDED: i32 = -32005;
+
+// =============================================================================
+// JSON-RPC Types
+// =============================================================================
+
+/// An incoming JSON-RPC message: a request (has `id`), a notification (no
+/// `id`), or a response to a server request (no `method`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct JsonRpcMessage {
+    #[serde(default)]
+    pub jsonrpc: String,
+    pub method: Option<String>,
+    #[serde(default)]
+    pub params: Value,
+    pub id: Option<Value>,
+}
+
+impl JsonRpcMessage {
+    pub fn is_request(&self) -> bool {
+        self.method.is_some() && self.id.is_some()
+    }
+
+    /// Progress token the client attached to this request, if any
+    pub fn progress_token(&self) -> Option<&Value> {
+        self.params.get("_meta")?.get("progressToken")
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonRpcResponse {
+    jsonrpc: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<JsonRpcError>,
+    id: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonRpcError {
+    code: i32,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<Value>,
+}
+
+impl JsonRpcResponse {
+    pub fn success(result: Value, id: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            result: Some(result),
+            error: None,
+            id,
+        }
+    }
+
+    pub fn error(code: i32, message: &str, id: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            result: None,
+            error: Some(JsonRpcError {
+                code,
+                message: message.to_string(),
+                data: None,
+            }),
+            id,
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Why the request failed: the JSON-RPC error, or the text of a tool
+    /// result flagged `isError`. `None` on success.
+    pub fn error_message(&self) -> Option<String> {
+        if let Some(error) = &self.error {
+            return Some(error.message.clone());
+        }
+        let result = self.result.as_ref()?;
+        if !result
+            .get("isError")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+        {
+            return None;
+        }
+        let text = result
+            .get("content")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|c| c.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Some(if text.is_empty() {
+            "Tool reported an error".to_string()
+        } else {
+            text
+        })
+    }
+}
+
+/// A JSON-RPC notification sent by the server
+pub fn notification(method: &str, params: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": method,
+        "params": params,
+    })
+}
+
+/// Error from an MCP method, turned into a JSON-RPC error response
+#[derive(Debug)]
+pub struct McpError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl McpError {
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self {
+            code: INVALID_PARAMS,
+            message: message.into(),
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self {
+            code: RESOURCE_NOT_FOUND,
+            message: message.into(),
+        }
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self {
+            code: INTERNAL_ERROR,
+            message: message.into(),
+        }
+    }
+
+    pub fn limit_exceeded(message: impl Into<String>) -> Self {
+        Self {
+            code: LIMIT_EXCEEDED,
+            message: message.into(),
+        }
+    }
+}
+
+// =============================================================================
+// Tools
+// =============================================================================
+
+/// Every tool offered over MCP, in `tools/list` order.
+///
+/// Names, descriptions and input schemas come from the `#[tool]`
+/// annotations on `RustServiceTools`.
+pub fn tool_definitions() -> Vec<Tool> {
+    vec![
+        RustServiceTools::execute_command_tool_attr(),
+        RustServiceTools::get_command_status_tool_attr(),
+        RustServiceTools::read_file_tool_attr(),
+        RustServiceTools::write_file_tool_attr(),
+        RustServiceTools::edit_file_tool_attr(),
+        RustServiceTools::list_dir_tool_attr(),
+        RustServiceTools::move_file_tool_attr(),
+        RustServiceTools::copy_file_tool_attr(),
+        RustServiceTools::grep_tool_attr(),
+        RustServiceTools::glob_tool_attr(),
+        RustServiceTools::get_system_info_tool_attr(),
+        RustServiceTools::search_web_tool_attr(),
+        RustServiceTools::list_programs_tool_attr(),
+        RustServiceTools::list_instruments_tool_attr(),
+        RustServiceTools::run_instrument_tool_attr(),
+        RustServiceTools::list_services_tool_attr(),
+        RustServiceTools::list_service_presets_tool_attr(),
+        RustServiceTools::check_service_requirements_tool_attr(),
+        RustServiceTools::run_service_queue_tool_attr(),
+        RustServiceTools::get_service_status_tool_attr(),
+        RustServiceTools::pause_service_tool_attr(),
+        RustServiceTools::resume_service_tool_attr(),
+        RustServiceTools::cancel_service_tool_attr(),
+        RustServiceTools::list_service_reports_tool_attr(),
+        RustServiceTools::get_service_report_tool_attr(),
+        RustServiceTools::get_report_statistics_tool_attr(),
+        RustServiceTools::edit_finding_tool_attr(),
+        RustServiceTools::add_finding_tool_attr(),
+        RustServiceTools::remove_finding_tool_attr(),
+        RustServiceTools::set_report_summary_tool_attr(),
+        RustServiceTools::set_service_analysis_tool_attr(),
+        RustServiceTools::set_health_score_tool_attr(),
+    ]
+}
+
+/// External tools offered through this server, from connected servers
+/// with re-export enabled
+fn reexported_tools() -> Vec<McpClientTool> {
+    client::mcp_client_tools()
+        .into_iter()
+        .filter(|t| t.reexported)
+        .collect()
+}
+
+/// Names of all tools, in `tools/list` order, re-exported tools last
+pub fn tool_names() -> Vec<String> {
+    tool_definitions()
+        .into_iter()
+        .map(|t| t.name.into_owned())
+        .chain(reexported_tools().into_iter().map(|t| t.name))
+        .collect()
+}
+
+/// `tools/list` entries the caller may see
+fn list_tools(permissions: &McpPermissions) -> Vec<Value> {
+    let own = tool_definitions()
+        .into_iter()
+        .filter(|t| permissions.allows_tool(&t.name))
+        .map(|t| serde_json::to_value(t).unwrap_or_default());
+    let external = reexported_tools()
+        .into_iter()
+        .filter(|t| permissions.allows_tool(&t.name))
+        .map(|t| {
+            let description = format!(
+                "[{}] {}",
+                t.server_name,
+                t.description.as_deref().unwrap_or("")
+            );
+            json!({
+                "name": t.name,
+                "description": description.trim_end(),
+                "inputSchema": t.input_schema,
+            })
+        });
+    own.chain(external).collect()
+}
+
+/// Run a tool by name. Must cover every tool in [`tool_definitions`].
+async fn dispatch_tool_call(
+    tools: &RustServiceTools,
+    name: &str,
+    arguments: &Map<String, Value>,
+) -> Result<Value, String> {
+    match name {
+        "execute_command" => handlers::handle_execute_command(tools, arguments).await,
+        "get_command_status" => handlers::handle_get_command_status(tools, arguments).await,
+        "read_file" => handlers::handle_read_file(tools, arguments).await,
+        "write_file" => handlers::handle_write_file(tools, arguments).await,
+        "edit_file" => handlers::handle_edit_file(tools, arguments).await,
+        "list_dir" => handlers::handle_list_dir(tools, arguments).await,
+        "move_file" => handlers::handle_move_file(tools, arguments).await,
+        "copy_file" => handlers::handle_copy_file(tools, arguments).await,
+        "grep" => handlers::handle_grep(tools, arguments).await,
+        "glob" => handlers::handle_glob(tools, arguments).await,
+        "get_system_info" => handlers::handle_get_system_info(tools).await,
+        "search_web" => handlers::handle_search_web(tools, arguments).await,
+        "list_programs" => handlers::handle_list_programs(tools).await,
+        "list_instruments" => handlers::handle_list_instruments(tools).await,
+        "run_instrument" => handlers::handle_run_instrument(tools, arguments).await,
+        "list_services" => handlers::handle_list_services(tools).await,
+        "list_service_presets" => handlers::handle_list_service_presets(tools).await,
+        "check_service_requirements" => {
+            handlers::handle_check_service_requirements(tools, arguments).await
+        }
+        "run_service_queue" => handlers::handle_run_service_queue(tools, arguments).await,
+        "get_service_status" => handlers::handle_get_service_status(tools).await,
+        "pause_service" => handlers::handle_pause_service(tools, arguments).await,
+        "resume_service" => handlers::handle_resume_service(tools, arguments).await,
+        "cancel_service" => handlers::handle_cancel_service(tools, arguments).await,
+        "list_service_reports" => handlers::handle_list_service_reports(tools, arguments).await,
+        "get_service_report" => handlers::handle_get_service_report(tools, arguments).await,
+        "get_report_statistics" => handlers::handle_get_report_statistics(tools, arguments).await,
+        "edit_finding" => handlers::handle_edit_finding(tools, arguments).await,
+        "add_finding" => handlers::handle_add_finding(tools, arguments).await,
+        "remove_finding" => handlers::handle_remove_finding(tools, arguments).await,
+        "set_report_summary" => handlers::handle_set_report_summary(tools, arguments).await,
+        "set_service_analysis" => handlers::handle_set_service_analysis(tools, arguments).await,
+        "set_health_score" => handlers::handle_set_health_score(tools, arguments).await,
+        _ => Err(format!("Unknown tool: {}", name)),
+    }
+}
+
+/// Convert rmcp CallToolResult to JSON
+pub(crate) fn call_tool_result_to_json(result: rmcp::model::CallToolResult) -> Value {
+    // rmcp Content is Annotated<RawContent>; the raw content serializes to the
+    // MCP wire shape ({"type": "text", "text": ...})
+    let content: Vec<Value> = result
+        .content
+        .iter()
+        .map(|c| serde_json::to_value(&c.raw).unwrap_or_default())
+        .collect();
+
+    json!({
+        "content": content,
+        "isError": result.is_error.unwrap_or(false)
+    })
+}
+
+// =============================================================================
+// Message Handling
+// =============================================================================
+
+/// Tools that don't count toward the tool-call limit. Polling for a command's
+/// result mostly waits, and must work while approvals fill every slot.
+const UNLIMITED_TOOLS: &[&str] = &["get_command_status"];
+
+tokio::task_local! {
+    /// Tool-call slot held by the call running in this task
+    static TOOL_SLOT: RefCell<Option<OwnedSemaphorePermit>>;
+}
+
+/// Give up the current call's tool-call slot, e.g. before waiting for the
+/// technician, so other calls can run meanwhile. No-op outside a limited call.
+pub(super) fn release_tool_slot() {
+    let _ = TOOL_SLOT.try_with(|slot| slot.borrow_mut().take());
+}
+
+/// Handles MCP messages for one server instance, whatever the transport
+pub struct McpHandler {
+    tools: RustServiceTools,
+    /// Caps concurrent tool calls; `None` is unlimited
+    tool_slots: Option<Arc<Semaphore>>,
+    max_tool_calls: u32,
+}
+
+impl McpHandler {
+    pub fn new(tools: RustServiceTools) -> Self {
+        Self {
+            tools,
+            tool_slots: None,
+            max_tool_calls: 0,
+        }
+    }
+
+    /// Refuse tool calls while `max` are already running. Zero is unlimited.
+    pub fn with_tool_call_limit(mut self, max: u32) -> Self {
+        self.tool_slots = (max > 0).then(|| Arc::new(Semaphore::new(max as usize)));
+        self.max_tool_calls = max;
+        self
+    }
+
+    /// Handle one incoming message. Returns the response for requests and
+    /// `None` for notifications and client responses.
+    pub async fn handle(
+        &self,
+        message: JsonRpcMessage,
+        permissions: &McpPermissions,
+    ) -> Option<JsonRpcResponse> {
+        let Some(method) = message.method.as_deref() else {
+            // A response to a server request; this server sends none
+            return None;
+        };
+        let Some(id) = message.id.clone() else {
+            self.handle_notification(method, &message.params);
+            return None;
+        };
+
+        if message.jsonrpc != "2.0" {
+            return Some(JsonRpcResponse::error(
+                INVALID_REQUEST,
+                "Invalid request - jsonrpc must be \"2.0\"",
+                id,
+            ));
+        }
+
+        eprintln!("MCP: Received method '{}' id={}", method, id);
+
+        let result = match method {
+            "initialize" => Ok(initialize_result(&message.params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": list_tools(permissions) })),
+            "tools/call" => {
+                let limited = message
+                    .params
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .is_none_or(|name| !UNLIMITED_TOOLS.contains(&name));
+                // Held until the call finishes or releases it
+                let slot = self
+                    .tool_slots
+                    .as_ref()
+                    .filter(|_| limited)
+                    .map(|slots| Arc::clone(slots).try_acquire_owned())
+                    .transpose();
+                match slot {
+                    Ok(slot) => {
+                        TOOL_SLOT
+                            .scope(
+                                RefCell::new(slot),
+                                self.call_tool(&message.params, permissions),
+                            )
+                            .await
+                    }
+                    Err(_) => Err(McpError::limit_exceeded(format!(
+                        "Too many tool calls in progress (limit {}); retry shortly",
+                        self.max_tool_calls
+                    ))),
+                }
+            }
+            "resources/list" => resources::list_resources(cursor(&message.params), permissions),
+            "resources/templates/list" => Ok(resources::list_resource_templates(permissions)),
+            "resources/read" => match message.params.get("uri").and_then(|v| v.as_str()) {
+                Some(uri) => resources::read_resource(uri, permissions).await,
+                None => Err(McpError::invalid_params("Missing required parameter: uri")),
+            },
+            "prompts/list" => Ok(prompts::list_prompts(permissions)),
+            "prompts/get" => prompts::get_prompt(&message.params, permissions),
+            _ => {
+                eprintln!("MCP: Method not found: {}", method);
+                Err(McpError {
+                    code: METHOD_NOT_FOUND,
+                    message: format!("Method not found: {}", method),
+                })
+            }
+        };
+
+        Some(match result {
+            Ok(result) => JsonRpcResponse::success(result, id),
+            Err(e) => JsonRpcResponse::error(e.code, &e.message, id),
+        })
+    }
+
+    /// Handle one message on the blocking pool.
+    ///
+    /// Tool implementations do blocking work (processes, file IO), which would
+    /// otherwise stall the transport's other tasks (keepalives, other requests).
+    ///
+    /// Requests are recorded in the audit log under `caller`.
+    pub async fn handle_blocking(
+        self: Arc<Self>,
+        message: JsonRpcMessage,
+        permissions: McpPermissions,
+        caller: AuditCaller,
+    ) -> Option<JsonRpcResponse> {
+        let id = message.id.clone();
+        let audit = AuditRecord::start(&caller, &message);
+        let runtime = tokio::runtime::Handle::current();
+        let handle = move || runtime.block_on(self.handle(message, &permissions));
+        let response = match tokio::task::spawn_blocking(handle).await {
+            Ok(response) => response,
+            Err(e) => {
+                eprintln!("MCP: Request handler panicked: {}", e);
+                id.map(|id| JsonRpcResponse::error(INTERNAL_ERROR, "Internal error", id))
+            }
+        };
+        if let Some(audit) = audit {
+            audit.finish(response.as_ref());
+        }
+        response
+    }
+
+    fn handle_notification(&self, method: &str, params: &Value) {
+        match method {
+            "notifications/initialized" => eprintln!("MCP: Client initialized"),
+            // Tool calls run to completion; a cancelled call's response is
+            // simply ignored by the client
+            "notifications/cancelled" => eprintln!(
+                "MCP: Client cancelled request {} ({})",
+                params.get("requestId").unwrap_or(&Value::Null),
+                params
+                    .get("reason")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("no reason given")
+            ),
+            _ => eprintln!("MCP: Ignoring notification '{}'", method),
+        }
+    }
+
+    async fn call_tool(
+        &self,
+        params: &Value,
+        permissions: &McpPermissions,
+    ) -> Result<Value, McpError> {
+        let tool_name = params
+            .get("name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| McpError::invalid_params("Missing required parameter: name"))?;
+        let external = !tool_definitions().iter().any(|t| t.name == tool_name);
+        if external && !reexported_tools().iter().any(|t| t.name == tool_name) {
+            return Err(McpError::invalid_params(format!(
+                "Unknown tool: {}",
+                tool_name
+            )));
+        }
+
+        let empty_map = Map::new();
+        let arguments = params
+            .get("arguments")
+            .and_then(|v| v.as_object())
+            .unwrap_or(&empty_map);
+
+        if let Err(e) = permissions.check_tool_call(tool_name, arguments) {
+            eprintln!(
+                "MCP: Denied '{}' for key '{}': {}",
+                tool_name,
+                permissions.key_name().unwrap_or("-"),
+                e
+            );
+            return Ok(json!({
+                "content": [{ "type": "text", "text": e }],
+                "isError": true
+            }));
+        }
+
+        // stderr ends up in the client's logs under stdio, so mask secrets
+        eprintln!(
+            "MCP: Calling tool '{}' with args: {}",
+            tool_name,
+            redact_value(&Value::Object(arguments.clone()))
+        );
+
+        // External tools answer with a complete `tools/call` result
+        if external {
+            return Ok(
+                match client::call_mcp_client_tool(tool_name, Value::Object(arguments.clone()))
+                    .await
+                {
+                    Ok(result) => result,
+                    Err(e) => json!({
+                        "content": [{ "type": "text", "text": e }],
+                        "isError": true
+                    }),
+                },
+            );
+        }
+
+        // Tool failures (bad arguments included) are reported in the result so
+        // the model can see and correct them
+        let tools = self
+            .tools
+            .clone()
+            .with_caller(permissions.key_name().map(String::from));
+        match dispatch_tool_call(&tools, tool_name, arguments).await {
+            Ok(result) => Ok(result),
+            Err(e) => Ok(json!({
+                "content": [{ "type": "text", "text": e }],
+                "isError": true
+            })),
+        }
+    }
+}
+
+/// Negotiate the protocol version and describe the server's capabilities
+fn initialize_result(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(|v| v.as_str());
+    // Answer with the client's version if we speak it, otherwise our newest;
+    // the client disconnects if it can't use that
+    let version = requested
+        .filter(|v| SUPPORTED_PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(SUPPORTED_PROTOCOL_VERSIONS[0]);
+
+    if let Some(client) = params.get("clientInfo") {
+        eprintln!(
+            "MCP: Initializing {} {} (protocol {})",
+            client["name"].as_str().unwrap_or("unknown client"),
+            client["version"].as_str().unwrap_or(""),
+            requested.unwrap_or("unspecified")
+        );
+    }
+
+    json!({
+        "protocolVersion": version,
+        "capabilities": {
+            "tools": { "listChanged": false },
+            "resources": { "subscribe": false, "listChanged": false },
+            "prompts": { "listChanged": false }
+        },
+        "serverInfo": {
+            "name": SERVER_NAME,
+            "version": env!("CARGO_PKG_VERSION")
+        },
+        "instructions": SERVER_INSTRUCTIONS
+    })
+}
+
+/// Pagination cursor from list request params
+fn cursor(params: &Value) -> Option<&str> {
+    params.get("cursor").and_then(|v| v.as_str())
+}
+
+/// Parse a request body: a single message or a batch (array) of messages.
+/// Returns the messages and whether they came as a batch.
+pub fn parse_messages(body: &[u8]) -> Result<(Vec<JsonRpcMessage>, bool), McpError> {
+    let value: Value = serde_json::from_slice(body).map_err(|e| {
+        eprintln!("MCP: Failed to parse JSON-RPC: {}", e);
+        McpError {
+            code: PARSE_ERROR,
+            message: "Parse error - invalid JSON".to_string(),
+        }
+    })?;
+
+    let is_batch = value.is_array();
+    let values = match value {
+        Value::Array(values) if values.is_empty() => {
+            return Err(McpError {
+                code: INVALID_REQUEST,
+                message: "Invalid request - empty batch".to_string(),
+            })
+        }
+        Value::Array(values) => values,
+        value => vec![value],
+    };
+
+    let messages = values
+        .into_iter()
+        .map(serde_json::from_value)
+        .collect::<Result<Vec<JsonRpcMessage>, _>>()
+        .map_err(|e| {
+            eprintln!("MCP: Invalid JSON-RPC message: {}", e);
+            McpError {
+                code: INVALID_REQUEST,
+                message: "Invalid request".to_string(),
+            }
+        })?;
+    Ok((messages, is_batch))
+}
@@ -0,0 +1,229 @@
+//! MCP Resources
+//!
+//! Read-only context a client can pull without calling a tool: system
+//! information, the state of the current service run, and saved service
+//! reports with their logs.
+//!
+//! URIs:
+//! - `rustservice://system/info`
+//! - `rustservice://services/state`
+//! - `rustservice://reports/{reportId}`
+//! - `rustservice://reports/{reportId}/logs`
+
+use serde_json::{json, Value};
+
+use super::permissions::McpPermissions;
+use super::protocol::McpError;
+use super::service_tools::read_report;
+use crate::commands::{get_service_run_state, get_system_info, search_reports};
+use crate::types::{ReportIndexEntry, ReportQuery, ServiceReport};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+const URI_SCHEME: &str = "rustservice://";
+const SYSTEM_INFO_URI: &str = "rustservice://system/info";
+const SERVICE_STATE_URI: &str = "rustservice://services/state";
+const REPORTS_PREFIX: &str = "rustservice://reports/";
+
+/// Reports listed per `resources/list` page (each yields two resources)
+const REPORTS_PAGE_SIZE: usize = 25;
+
+/// Tool a key must be allowed to call to read the same data as a resource
+const SYSTEM_INFO_TOOL: &str = "get_system_info";
+const SERVICE_STATE_TOOL: &str = "get_service_status";
+pub(super) const REPORTS_TOOL: &str = "get_service_report";
+
+// =============================================================================
+// Listing
+// =============================================================================
+
+fn report_uri(report_id: &str) -> String {
+    format!("{}{}", REPORTS_PREFIX, report_id)
+}
+
+fn report_label(entry: &ReportIndexEntry) -> String {
+    match entry.customer_name.as_deref().filter(|s| !s.is_empty()) {
+        Some(customer) => format!("Service report {} ({})", entry.started_at, customer),
+        None => format!("Service report {}", entry.started_at),
+    }
+}
+
+/// List resources. The fixed resources come first, then saved reports newest
+/// first, paged with an opaque cursor (the report offset).
+pub fn list_resources(
+    cursor: Option<&str>,
+    permissions: &McpPermissions,
+) -> Result<Value, McpError> {
+    let offset = match cursor {
+        Some(cursor) => cursor
+            .parse::<usize>()
+            .map_err(|_| McpError::invalid_params(format!("Invalid cursor: {}", cursor)))?,
+        None => 0,
+    };
+
+    let mut resources = Vec::new();
+    if offset == 0 {
+        if permissions.allows_tool(SYSTEM_INFO_TOOL) {
+            resources.push(json!({
+                "uri": SYSTEM_INFO_URI,
+                "name": "System information",
+                "description": "OS, CPU, memory, disks and hostname of this machine",
+                "mimeType": "application/json"
+            }));
+        }
+        if permissions.allows_tool(SERVICE_STATE_TOOL) {
+            resources.push(json!({
+                "uri": SERVICE_STATE_URI,
+                "name": "Service run state",
+                "description": "Whether a service run is active, and its report so far",
+                "mimeType": "application/json"
+            }));
+        }
+    }
+    if !permissions.allows_tool(REPORTS_TOOL) {
+        return Ok(json!({ "resources": resources }));
+    }
+
+    let page = search_reports(ReportQuery {
+        offset: Some(offset),
+        limit: Some(REPORTS_PAGE_SIZE),
+        ..Default::default()
+    })
+    .map_err(McpError::internal)?;
+
+    for entry in &page.reports {
+        let uri = report_uri(&entry.id);
+        let label = report_label(entry);
+        resources.push(json!({
+            "uri": uri,
+            "name": label,
+            "description": format!(
+                "{:?} run of {} services, {} findings",
+                entry.status,
+                entry.service_ids.len(),
+                entry.finding_count
+            ),
+            "mimeType": "application/json"
+        }));
+        resources.push(json!({
+            "uri": format!("{}/logs", uri),
+            "name": format!("{} - logs", label),
+            "description": "Log output of every service in the report",
+            "mimeType": "text/plain"
+        }));
+    }
+
+    let next_offset = offset + page.reports.len();
+    let mut result = json!({ "resources": resources });
+    if !page.reports.is_empty() && next_offset < page.total {
+        result["nextCursor"] = json!(next_offset.to_string());
+    }
+    Ok(result)
+}
+
+/// URI templates for resources that aren't enumerated individually
+pub fn list_resource_templates(permissions: &McpPermissions) -> Value {
+    if !permissions.allows_tool(REPORTS_TOOL) {
+        return json!({ "resourceTemplates": [] });
+    }
+    json!({
+        "resourceTemplates": [
+            {
+                "uriTemplate": "rustservice://reports/{reportId}",
+                "name": "Service report",
+                "description": "A saved service report with all results and findings",
+                "mimeType": "application/json"
+            },
+            {
+                "uriTemplate": "rustservice://reports/{reportId}/logs",
+                "name": "Service report logs",
+                "description": "Log output of every service in a saved report",
+                "mimeType": "text/plain"
+            }
+        ]
+    })
+}
+
+// =============================================================================
+// Reading
+// =============================================================================
+
+fn json_contents(uri: &str, value: &impl serde::Serialize) -> Result<Value, McpError> {
+    let text = serde_json::to_string_pretty(value)
+        .map_err(|e| McpError::internal(format!("Failed to serialize resource: {}", e)))?;
+    Ok(json!({
+        "contents": [{ "uri": uri, "mimeType": "application/json", "text": text }]
+    }))
+}
+
+/// All service logs of a report as plain text, one section per service
+pub fn report_logs_text(report: &ServiceReport) -> String {
+    let mut text = String::new();
+    for result in &report.results {
+        text.push_str(&format!("=== {} ===\n", result.service_id));
+        if result.logs.is_empty() {
+            text.push_str("(no log output)\n");
+        }
+        for line in &result.logs {
+            text.push_str(line);
+            text.push('\n');
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Read a resource by URI
+pub async fn read_resource(uri: &str, permissions: &McpPermissions) -> Result<Value, McpError> {
+    if !uri.starts_with(URI_SCHEME) {
+        return Err(McpError::not_found(format!("Resource not found: {}", uri)));
+    }
+
+    let required_tool = match uri {
+        SYSTEM_INFO_URI => SYSTEM_INFO_TOOL,
+        SERVICE_STATE_URI => SERVICE_STATE_TOOL,
+        _ => REPORTS_TOOL,
+    };
+    if !permissions.allows_tool(required_tool) {
+        // Reported as missing so restricted keys can't probe for reports
+        return Err(McpError::not_found(format!("Resource not found: {}", uri)));
+    }
+
+    match uri {
+        SYSTEM_INFO_URI => {
+            let info = get_system_info().await.map_err(McpError::internal)?;
+            json_contents(uri, &info)
+        }
+        SERVICE_STATE_URI => json_contents(uri, &get_service_run_state()),
+        _ => {
+            let path = uri
+                .strip_prefix(REPORTS_PREFIX)
+                .ok_or_else(|| McpError::not_found(format!("Resource not found: {}", uri)))?;
+            let (report_id, logs) = match path.strip_suffix("/logs") {
+                Some(report_id) => (report_id, true),
+                None => (path, false),
+            };
+            // Report IDs are UUIDs; anything else could escape the reports folder
+            if report_id.is_empty() || !report_id.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+            {
+                return Err(McpError::not_found(format!("Resource not found: {}", uri)));
+            }
+
+            let report = read_report(report_id.to_string(), None)
+                .map_err(|_| McpError::not_found(format!("Report not found: {}", report_id)))?;
+            if logs {
+                Ok(json!({
+                    "contents": [{
+                        "uri": uri,
+                        "mimeType": "text/plain",
+                        "text": report_logs_text(&report)
+                    }]
+                }))
+            } else {
+                json_contents(uri, &report)
+            }
+        }
+    }
+}
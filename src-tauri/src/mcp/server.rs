@@ -1,295 +1,363 @@
 //! MCP HTTP Server
 //!
-//! Implements full MCP JSON-RPC protocol over HTTP transport.
-//! Handles tools/list and tools/call methods for external LLM control.
-
-use std::net::SocketAddr;
-use std::sync::Arc;
-
-use http_body_util::{BodyExt, Full};
-use hyper::body::{Bytes, Incoming};
+//! Streamable HTTP transport for the MCP protocol (see `protocol`).
+//! Clients POST JSON-RPC messages (single or batched) to `/mcp` with a
+//! bearer token. Responses are plain JSON, or an SSE stream for tool calls
+//! when the client accepts `text/event-stream`; the stream carries progress
+//! notifications while a long-running tool works, then the response.
+//!
+//! `initialize` opens a session whose ID is returned in `Mcp-Session-Id`;
+//! clients that send the header must use a live session, and can end it
+//! with `DELETE /mcp`. Sessions idle for `SESSION_IDLE_TIMEOUT` expire, and
+//! a key opening more than `MAX_SESSIONS_PER_KEY` loses its least recently
+//! used one. Clients that never send the header are also served.
+//!
+//! The listener binds the interfaces chosen in settings and can serve HTTPS
+//! with a user-supplied or generated self-signed certificate.
+//!
+//! `McpServerLimits` bounds what clients can ask of the machine: body size,
+//! open connections, concurrent tool calls, requests per key per minute,
+//! and tool output size. Refused requests get `LIMIT_EXCEEDED` errors.
+//! An address that fails authentication `MAX_REJECTED_PER_WINDOW` times in
+//! a rate window is refused outright until the window ends, and those
+//! refusals are not audited.
+//!
+//! [`McpHttpServer`] is one bound instance; `lifecycle` runs it and stops it.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use chrono::{SecondsFormat, Utc};
+use http_body_util::combinators::UnsyncBoxBody;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::{Bytes, Frame, Incoming};
 use hyper::server::conn::http1;
 use hyper::service::service_fn;
 use hyper::{Method, Request, Response, StatusCode};
 use hyper_util::rt::TokioIo;
-use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::AppHandle;
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::TcpListener;
-
-use super::handlers;
+use tokio::sync::{mpsc, Notify, Semaphore};
+use tokio_rustls::TlsAcceptor;
+use uuid::Uuid;
+
+use super::audit::{AuditCaller, AuditRecord};
+use super::permissions::McpPermissions;
+use super::protocol::{
+    notification, parse_messages, tool_names, JsonRpcMessage, JsonRpcResponse, McpHandler,
+    LIMIT_EXCEEDED, PARSE_ERROR, SERVER_NAME,
+};
 use super::tools::RustServiceTools;
+use crate::commands::mcp_keys::{authenticate_mcp_api_key, has_active_mcp_api_keys};
+use crate::commands::mcp_tls::load_mcp_tls_config;
+use crate::types::{AgentSettings, McpBindAddress, McpConnectedClient, McpServerLimits};
 
-type BoxBody = Full<Bytes>;
+type BoxBody = UnsyncBoxBody<Bytes, Infallible>;
 
 // =============================================================================
-// JSON-RPC Types
+// Constants
 // =============================================================================
 
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct JsonRpcRequest {
-    jsonrpc: String,
-    method: String,
-    #[serde(default)]
-    params: serde_json::Value,
-    id: serde_json::Value,
+const SESSION_HEADER: &str = "mcp-session-id";
+
+/// How often a streamed tool call reports progress (or sends a keepalive)
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// SSE events buffered per stream before the tool call waits for the client
+const STREAM_BUFFER: usize = 16;
+
+/// How long a client gets to complete the TLS handshake
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Length of the per-key request rate window
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Failed authentications one address may make per rate window
+const MAX_REJECTED_PER_WINDOW: u32 = 20;
+
+/// How long a session lasts without requests
+const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Sessions one key can have open at once
+const MAX_SESSIONS_PER_KEY: usize = 32;
+
+// =============================================================================
+// Server State
+// =============================================================================
+
+struct ServerState {
+    handler: Arc<McpHandler>,
+    /// Sessions opened by `initialize`, by session ID
+    sessions: Mutex<HashMap<String, Session>>,
+    limits: McpServerLimits,
+    /// Per-key request count for the current rate window, keyed by key ID
+    rate_windows: Mutex<HashMap<String, RateWindow>>,
+    /// Failed authentications for the current rate window, by remote address
+    rejected_windows: Mutex<HashMap<IpAddr, RateWindow>>,
+    /// Requests to `/mcp`, and how many of them were refused or failed
+    requests: AtomicU64,
+    rejected: AtomicU64,
+    /// Open connections, by connection number
+    clients: Mutex<HashMap<u64, McpConnectedClient>>,
+    next_connection: AtomicU64,
 }
 
-#[derive(Debug, Serialize)]
-struct JsonRpcResponse {
-    jsonrpc: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    result: Option<serde_json::Value>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    error: Option<JsonRpcError>,
-    id: serde_json::Value,
+struct RateWindow {
+    started: Instant,
+    requests: u32,
 }
 
-#[derive(Debug, Serialize)]
-struct JsonRpcError {
-    code: i32,
-    message: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    data: Option<serde_json::Value>,
+struct Session {
+    /// ID of the key that opened the session
+    key_id: String,
+    last_used: Instant,
+}
+
+impl Session {
+    fn is_live(&self, now: Instant) -> bool {
+        now.duration_since(self.last_used) < SESSION_IDLE_TIMEOUT
+    }
 }
 
-impl JsonRpcResponse {
-    fn success(result: serde_json::Value, id: serde_json::Value) -> Self {
-        Self {
-            jsonrpc: "2.0".to_string(),
-            result: Some(result),
-            error: None,
-            id,
+/// Forget sessions that have been idle too long
+fn expire_sessions(sessions: &mut HashMap<String, Session>, now: Instant) {
+    sessions.retain(|id, session| {
+        let live = session.is_live(now);
+        if !live {
+            eprintln!("MCP: Session {} expired", id);
+        }
+        live
+    });
+}
+
+impl ServerState {
+    /// Count `requests` against the key's rate limit. Returns the seconds
+    /// until the window resets if they would go over it.
+    fn check_rate(&self, key_id: &str, requests: u32) -> Result<(), u64> {
+        let limit = self.limits.requests_per_minute;
+        if limit == 0 {
+            return Ok(());
+        }
+        let now = Instant::now();
+        let mut windows = self.rate_windows.lock().unwrap();
+        // Forget keys whose window has lapsed so the map doesn't grow
+        windows.retain(|_, w| now.duration_since(w.started) < RATE_WINDOW);
+        let window = windows.entry(key_id.to_string()).or_insert(RateWindow {
+            started: now,
+            requests: 0,
+        });
+        if window.requests.saturating_add(requests) > limit {
+            let reset = RATE_WINDOW.saturating_sub(now.duration_since(window.started));
+            return Err(reset.as_secs().max(1));
         }
+        window.requests += requests;
+        Ok(())
     }
 
-    fn error(code: i32, message: &str, id: serde_json::Value) -> Self {
-        Self {
-            jsonrpc: "2.0".to_string(),
-            result: None,
-            error: Some(JsonRpcError {
-                code,
-                message: message.to_string(),
-                data: None,
-            }),
-            id,
+    /// Seconds until the address may try to authenticate again, if it has
+    /// already failed too often in the current window
+    fn rejected_lockout(&self, addr: IpAddr) -> Option<u64> {
+        let now = Instant::now();
+        let mut windows = self.rejected_windows.lock().unwrap();
+        windows.retain(|_, w| now.duration_since(w.started) < RATE_WINDOW);
+        let window = windows.get(&addr)?;
+        (window.requests >= MAX_REJECTED_PER_WINDOW).then(|| {
+            let reset = RATE_WINDOW.saturating_sub(now.duration_since(window.started));
+            reset.as_secs().max(1)
+        })
+    }
+
+    /// Count a failed authentication from the address
+    fn note_rejected(&self, addr: IpAddr) {
+        let mut windows = self.rejected_windows.lock().unwrap();
+        let window = windows.entry(addr).or_insert(RateWindow {
+            started: Instant::now(),
+            requests: 0,
+        });
+        window.requests += 1;
+    }
+
+    /// Whether `id` is a live session opened by the key, marking it used
+    fn use_session(&self, id: &str, key_id: &str) -> bool {
+        let now = Instant::now();
+        let mut sessions = self.sessions.lock().unwrap();
+        expire_sessions(&mut sessions, now);
+        match sessions.get_mut(id) {
+            Some(session) if session.key_id == key_id => {
+                session.last_used = now;
+                true
+            }
+            _ => false,
         }
     }
-}
 
-// =============================================================================
-// Tool Definitions for tools/list
-// =============================================================================
+    /// Open a session for the key, closing its least recently used one if
+    /// it already has as many as it may
+    fn open_session(&self, key_id: &str) -> String {
+        let now = Instant::now();
+        let mut sessions = self.sessions.lock().unwrap();
+        expire_sessions(&mut sessions, now);
+
+        let held = sessions.values().filter(|s| s.key_id == key_id).count();
+        if held >= MAX_SESSIONS_PER_KEY {
+            let oldest = sessions
+                .iter()
+                .filter(|(_, s)| s.key_id == key_id)
+                .min_by_key(|(_, s)| s.last_used)
+                .map(|(id, _)| id.clone());
+            if let Some(oldest) = oldest {
+                sessions.remove(&oldest);
+                eprintln!("MCP: Session {} closed to make room for a new one", oldest);
+            }
+        }
 
-fn get_tool_definitions() -> serde_json::Value {
-    serde_json::json!({
-        "tools": [
-            {
-                "name": "execute_command",
-                "description": "Execute a PowerShell command on the system. Returns stdout, stderr, and exit code.",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "command": {
-                            "type": "string",
-                            "description": "The PowerShell command to execute"
-                        },
-                        "reason": {
-                            "type": "string",
-                            "description": "Brief explanation of why this command is needed"
-                        }
-                    },
-                    "required": ["command", "reason"]
-                }
+        let id = Uuid::new_v4().to_string();
+        sessions.insert(
+            id.clone(),
+            Session {
+                key_id: key_id.to_string(),
+                last_used: now,
             },
-            {
-                "name": "read_file",
-                "description": "Read the contents of a file. Use this to examine configuration files, logs, or other text files.",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "path": {
-                            "type": "string",
-                            "description": "Full path to the file"
-                        }
-                    },
-                    "required": ["path"]
-                }
-            },
-            {
-                "name": "write_file",
-                "description": "Write content to a file. Creates the file if it doesn't exist.",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "path": {
-                            "type": "string",
-                            "description": "Full path to the file"
-                        },
-                        "content": {
-                            "type": "string",
-                            "description": "Content to write"
-                        }
-                    },
-                    "required": ["path", "content"]
-                }
-            },
-            {
-                "name": "list_dir",
-                "description": "List files and directories in a specific path. Use this to explore the file system.",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "path": {
-                            "type": "string",
-                            "description": "Path to list content for"
-                        }
-                    },
-                    "required": ["path"]
-                }
-            },
-            {
-                "name": "move_file",
-                "description": "Move or rename a file from source to destination.",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "src": {
-                            "type": "string",
-                            "description": "Source file path"
-                        },
-                        "dest": {
-                            "type": "string",
-                            "description": "Destination file path"
-                        }
-                    },
-                    "required": ["src", "dest"]
-                }
-            },
-            {
-                "name": "copy_file",
-                "description": "Copy a file from source to destination.",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "src": {
-                            "type": "string",
-                            "description": "Source file path"
-                        },
-                        "dest": {
-                            "type": "string",
-                            "description": "Destination file path"
-                        }
-                    },
-                    "required": ["src", "dest"]
-                }
-            },
-            {
-                "name": "get_system_info",
-                "description": "Get detailed system information including OS version, CPU, memory, disks, and hostname.",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {},
-                    "additionalProperties": false
-                }
-            },
-            {
-                "name": "search_web",
-                "description": "Search the web for information. Returns search results with titles, URLs, and snippets.",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "query": {
-                            "type": "string",
-                            "description": "The search query"
-                        }
-                    },
-                    "required": ["query"]
-                }
-            },
-            {
-                "name": "list_programs",
-                "description": "List all portable programs available in the data/programs folder.",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {},
-                    "additionalProperties": false
-                }
-            },
-            {
-                "name": "list_instruments",
-                "description": "List available custom instruments (scripts) that can be run.",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {},
-                    "additionalProperties": false
-                }
-            },
-            {
-                "name": "run_instrument",
-                "description": "Run a custom instrument (script) by name.",
-                "inputSchema": {
-                    "type": "object",
-                    "properties": {
-                        "name": {
-                            "type": "string",
-                            "description": "Name of the instrument to run"
-                        },
-                        "args": {
-                            "type": "string",
-                            "description": "Optional arguments to pass to the instrument"
-                        }
-                    },
-                    "required": ["name"]
-                }
-            }
-        ]
-    })
+        );
+        id
+    }
+
+    /// Count a request made on a connection
+    fn record_request(&self, connection: u64, failed: bool) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        if failed {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+        }
+        if let Some(client) = self.clients.lock().unwrap().get_mut(&connection) {
+            client.requests += 1;
+        }
+    }
 }
 
 // =============================================================================
-// Tool Dispatch
+// Response Helpers
 // =============================================================================
 
-async fn dispatch_tool_call(
-    tools: &RustServiceTools,
-    name: &str,
-    arguments: &serde_json::Map<String, serde_json::Value>,
-) -> Result<serde_json::Value, String> {
-    match name {
-        "execute_command" => handlers::handle_execute_command(tools, arguments).await,
-        "read_file" => handlers::handle_read_file(tools, arguments).await,
-        "write_file" => handlers::handle_write_file(tools, arguments).await,
-        "list_dir" => handlers::handle_list_dir(tools, arguments).await,
-        "move_file" => handlers::handle_move_file(tools, arguments).await,
-        "copy_file" => handlers::handle_copy_file(tools, arguments).await,
-        "get_system_info" => handlers::handle_get_system_info(tools).await,
-        "search_web" => handlers::handle_search_web(tools, arguments).await,
-        "list_programs" => handlers::handle_list_programs(tools).await,
-        "list_instruments" => handlers::handle_list_instruments(tools).await,
-        "run_instrument" => handlers::handle_run_instrument(tools, arguments).await,
-        _ => Err(format!("Unknown tool: {}", name)),
+fn full(body: impl Into<Bytes>) -> BoxBody {
+    Full::new(body.into()).boxed_unsync()
+}
+
+fn response_builder(status: StatusCode) -> hyper::http::response::Builder {
+    Response::builder()
+        .status(status)
+        .header("Access-Control-Allow-Origin", "*")
+        .header("Access-Control-Expose-Headers", "Mcp-Session-Id")
+}
+
+fn json_response(status: StatusCode, body: impl Into<Bytes>) -> Response<BoxBody> {
+    response_builder(status)
+        .header("Content-Type", "application/json")
+        .body(full(body))
+        .unwrap()
+}
+
+fn rpc_response(status: StatusCode, response: &JsonRpcResponse) -> Response<BoxBody> {
+    json_response(status, serde_json::to_string(response).unwrap())
+}
+
+/// 413 with a JSON-RPC error, for bodies over `max_body_bytes`
+fn body_too_large(caller: &AuditCaller, max: u64) -> Response<BoxBody> {
+    let message = format!("Request body exceeds the {} byte limit", max);
+    caller.record_rejected(&message);
+    let response = JsonRpcResponse::error(LIMIT_EXCEEDED, &message, serde_json::Value::Null);
+    rpc_response(StatusCode::PAYLOAD_TOO_LARGE, &response)
+}
+
+/// Format a JSON-RPC message as an SSE event
+fn sse_event(message: &impl serde::Serialize) -> Bytes {
+    Bytes::from(format!(
+        "event: message\ndata: {}\n\n",
+        serde_json::to_string(message).unwrap()
+    ))
+}
+
+/// Response body fed from a channel, for SSE streams
+struct ChannelBody(mpsc::Receiver<Bytes>);
+
+impl hyper::body::Body for ChannelBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+        self.0
+            .poll_recv(cx)
+            .map(|chunk| chunk.map(|bytes| Ok(Frame::data(bytes))))
     }
 }
 
-/// Convert rmcp CallToolResult to JSON
-pub(crate) fn call_tool_result_to_json(result: rmcp::model::CallToolResult) -> serde_json::Value {
-    let content: Vec<serde_json::Value> = result
-        .content
-        .iter()
-        .map(|c| {
-            // rmcp Content is Annotated<RawContent>, extract the raw content
-            // The raw field contains the actual content
-            serde_json::json!({
-                "type": "text",
-                "text": format!("{:?}", c.raw)
-            })
-        })
-        .collect();
+// =============================================================================
+// Message Execution
+// =============================================================================
+
+/// Handle one message, emitting progress (or keepalives) to the stream
+/// until it finishes. Returns `false` once the client has gone away.
+async fn stream_message(
+    handler: Arc<McpHandler>,
+    message: JsonRpcMessage,
+    permissions: McpPermissions,
+    caller: AuditCaller,
+    tx: &mpsc::Sender<Bytes>,
+) -> bool {
+    let token = message.progress_token().cloned();
+    let label = match message.params.get("name").and_then(|v| v.as_str()) {
+        Some(tool) => format!("Running {}", tool),
+        None => "Working".to_string(),
+    };
+    let started = Instant::now();
 
-    serde_json::json!({
-        "content": content,
-        "isError": result.is_error.unwrap_or(false)
-    })
+    let call = handler.handle_blocking(message, permissions, caller);
+    tokio::pin!(call);
+    let mut ticker = tokio::time::interval_at(
+        tokio::time::Instant::now() + PROGRESS_INTERVAL,
+        PROGRESS_INTERVAL,
+    );
+
+    loop {
+        tokio::select! {
+            response = &mut call => {
+                return match response {
+                    Some(response) => tx.send(sse_event(&response)).await.is_ok(),
+                    None => true,
+                };
+            }
+            _ = ticker.tick() => {
+                let elapsed = started.elapsed().as_secs();
+                let event = match &token {
+                    // No total is known, so progress is elapsed seconds
+                    Some(token) => sse_event(&notification(
+                        "notifications/progress",
+                        json!({
+                            "progressToken": token,
+                            "progress": elapsed,
+                            "message": format!("{} ({}s)", label, elapsed),
+                        }),
+                    )),
+                    None => Bytes::from_static(b": keepalive\n\n"),
+                };
+                if tx.send(event).await.is_err() {
+                    // The call keeps running on the blocking pool; only its output is lost
+                    eprintln!("MCP: Client disconnected during {}", label);
+                    return false;
+                }
+            }
+        }
+    }
 }
 
 // =============================================================================
@@ -298,271 +366,499 @@ pub(crate) fn call_tool_result_to_json(result: rmcp::model::CallToolResult) -> s
 
 async fn handle_mcp_request(
     req: Request<Incoming>,
-    api_key: Arc<String>,
-    tools: Arc<RustServiceTools>,
+    remote_addr: SocketAddr,
+    state: Arc<ServerState>,
 ) -> Result<Response<BoxBody>, hyper::Error> {
     let method = req.method().clone();
     let path = req.uri().path().to_string();
 
     // Health check endpoint (no auth required)
     if path == "/" || path == "/health" {
-        let response = serde_json::json!({
+        let response = json!({
             "status": "ok",
-            "service": "RustService MCP Server",
-            "version": "1.0.0",
-            "tools_available": 11
+            "service": SERVER_NAME,
+            "version": env!("CARGO_PKG_VERSION"),
+            "tools_available": tool_names().len()
         });
-        return Ok(Response::builder()
-            .status(StatusCode::OK)
-            .header("Content-Type", "application/json")
-            .header("Access-Control-Allow-Origin", "*")
-            .body(BoxBody::new(Bytes::from(response.to_string())))
-            .unwrap());
+        return Ok(json_response(StatusCode::OK, response.to_string()));
     }
 
     // Handle CORS preflight
     if method == Method::OPTIONS {
-        return Ok(Response::builder()
-            .status(StatusCode::OK)
-            .header("Access-Control-Allow-Origin", "*")
-            .header("Access-Control-Allow-Methods", "GET, POST, OPTIONS")
+        return Ok(response_builder(StatusCode::OK)
+            .header("Access-Control-Allow-Methods", "GET, POST, DELETE, OPTIONS")
             .header(
                 "Access-Control-Allow-Headers",
-                "Authorization, Content-Type",
+                "Authorization, Content-Type, Accept, Mcp-Session-Id",
             )
-            .body(BoxBody::new(Bytes::new()))
+            .body(full(Bytes::new()))
             .unwrap());
     }
 
-    // Check authorization for /mcp endpoint
-    let auth_header = req.headers().get("authorization");
-    let expected = format!("Bearer {}", api_key);
-
-    if auth_header.map(|h| h.to_str().ok()) != Some(Some(expected.as_str())) {
-        eprintln!("MCP: Unauthorized request to {}", path);
-        return Ok(Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
+    // Refuse addresses that keep failing to authenticate before doing any
+    // work for them, including auditing
+    if let Some(retry_after) = state.rejected_lockout(remote_addr.ip()) {
+        return Ok(response_builder(StatusCode::TOO_MANY_REQUESTS)
             .header("Content-Type", "application/json")
-            .header("Access-Control-Allow-Origin", "*")
-            .body(BoxBody::new(Bytes::from(
-                r#"{"error":"Unauthorized - Bearer token required"}"#,
-            )))
+            .header("Retry-After", retry_after.to_string())
+            .body(full(
+                r#"{"error":"Too many failed authentication attempts"}"#,
+            ))
             .unwrap());
     }
 
-    // Only accept POST to /mcp
+    // Check authorization for /mcp endpoint
+    let token = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|t| !t.is_empty());
+    let key = match token.map(authenticate_mcp_api_key) {
+        Some(Ok(key)) => key,
+        Some(Err(e)) => {
+            eprintln!("MCP: Unauthorized request to {}: {}", path, e);
+            state.note_rejected(remote_addr.ip());
+            AuditCaller::http(remote_addr, None).record_rejected(&e);
+            return Ok(json_response(
+                StatusCode::UNAUTHORIZED,
+                r#"{"error":"Unauthorized - invalid, revoked or expired API key"}"#,
+            ));
+        }
+        None => {
+            eprintln!("MCP: Unauthorized request to {}", path);
+            state.note_rejected(remote_addr.ip());
+            AuditCaller::http(remote_addr, None).record_rejected("Bearer token required");
+            return Ok(json_response(
+                StatusCode::UNAUTHORIZED,
+                r#"{"error":"Unauthorized - Bearer token required"}"#,
+            ));
+        }
+    };
+    let permissions = McpPermissions::from_key(&key);
+    let caller = AuditCaller::http(remote_addr, Some(&key));
+
     if path != "/mcp" {
-        return Ok(Response::builder()
-            .status(StatusCode::NOT_FOUND)
-            .header("Content-Type", "application/json")
-            .header("Access-Control-Allow-Origin", "*")
-            .body(BoxBody::new(Bytes::from(
-                r#"{"error":"Not Found - use POST /mcp"}"#,
-            )))
-            .unwrap());
+        return Ok(json_response(
+            StatusCode::NOT_FOUND,
+            r#"{"error":"Not Found - use POST /mcp"}"#,
+        ));
     }
 
-    if method != Method::POST {
-        return Ok(Response::builder()
-            .status(StatusCode::METHOD_NOT_ALLOWED)
-            .header("Content-Type", "application/json")
-            .header("Access-Control-Allow-Origin", "*")
-            .body(BoxBody::new(Bytes::from(
-                r#"{"error":"Method not allowed - use POST"}"#,
-            )))
-            .unwrap());
+    // Sessions are optional, but a session ID that was sent must be live
+    let session_id = req
+        .headers()
+        .get(SESSION_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .map(String::from);
+    if let Some(ref id) = session_id {
+        // A session belongs to the key that opened it
+        if !state.use_session(id, &key.id) {
+            caller.record_rejected(&format!("Unknown session {}", id));
+            return Ok(json_response(
+                StatusCode::NOT_FOUND,
+                r#"{"error":"Session not found - send initialize to start a new session"}"#,
+            ));
+        }
     }
 
+    match method {
+        Method::POST => {}
+        Method::DELETE => {
+            // Client is ending its session
+            return Ok(match session_id {
+                Some(id) => {
+                    state.sessions.lock().unwrap().remove(&id);
+                    eprintln!("MCP: Session {} closed", id);
+                    response_builder(StatusCode::OK)
+                        .body(full(Bytes::new()))
+                        .unwrap()
+                }
+                None => json_response(
+                    StatusCode::BAD_REQUEST,
+                    r#"{"error":"Mcp-Session-Id header required"}"#,
+                ),
+            });
+        }
+        // No server-initiated stream: this server never sends requests or
+        // unsolicited notifications
+        _ => {
+            return Ok(response_builder(StatusCode::METHOD_NOT_ALLOWED)
+                .header("Allow", "POST, DELETE")
+                .header("Content-Type", "application/json")
+                .body(full(r#"{"error":"Method not allowed - use POST"}"#))
+                .unwrap());
+        }
+    }
+
+    let accepts_sse = req
+        .headers()
+        .get("accept")
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/event-stream"));
+
+    // Refuse oversized bodies up front when the client declares the length,
+    // and stop reading at the limit when it doesn't
+    let max_body = state.limits.max_body_bytes;
+    let declared_length = req
+        .headers()
+        .get(hyper::header::CONTENT_LENGTH)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.parse::<u64>().ok());
+    if max_body > 0 && declared_length.is_some_and(|len| len > max_body) {
+        return Ok(body_too_large(&caller, max_body));
+    }
+    let collected = if max_body > 0 {
+        Limited::new(req.into_body(), max_body as usize)
+            .collect()
+            .await
+    } else {
+        req.into_body().collect().await.map_err(Into::into)
+    };
+
     // Collect request body
-    let body_bytes = match req.collect().await {
+    let body_bytes = match collected {
         Ok(collected) => collected.to_bytes(),
+        Err(e) if e.is::<http_body_util::LengthLimitError>() => {
+            return Ok(body_too_large(&caller, max_body));
+        }
         Err(e) => {
             eprintln!("MCP: Failed to read request body: {}", e);
+            caller.record_rejected(&format!("Failed to read request body: {}", e));
             let response = JsonRpcResponse::error(
-                -32700,
+                PARSE_ERROR,
                 "Failed to read request body",
                 serde_json::Value::Null,
             );
-            return Ok(Response::builder()
-                .status(StatusCode::BAD_REQUEST)
-                .header("Content-Type", "application/json")
-                .header("Access-Control-Allow-Origin", "*")
-                .body(BoxBody::new(Bytes::from(
-                    serde_json::to_string(&response).unwrap(),
-                )))
-                .unwrap());
+            return Ok(rpc_response(StatusCode::BAD_REQUEST, &response));
         }
     };
 
-    // Parse JSON-RPC request
-    let rpc_request: JsonRpcRequest = match serde_json::from_slice(&body_bytes) {
-        Ok(req) => req,
+    let (messages, is_batch) = match parse_messages(&body_bytes) {
+        Ok(parsed) => parsed,
         Err(e) => {
-            eprintln!("MCP: Failed to parse JSON-RPC: {}", e);
-            let response = JsonRpcResponse::error(
-                -32700,
-                "Parse error - invalid JSON",
-                serde_json::Value::Null,
-            );
-            return Ok(Response::builder()
-                .status(StatusCode::BAD_REQUEST)
-                .header("Content-Type", "application/json")
-                .header("Access-Control-Allow-Origin", "*")
-                .body(BoxBody::new(Bytes::from(
-                    serde_json::to_string(&response).unwrap(),
-                )))
-                .unwrap());
+            caller.record_rejected(&e.message);
+            let response = JsonRpcResponse::error(e.code, &e.message, serde_json::Value::Null);
+            return Ok(rpc_response(StatusCode::BAD_REQUEST, &response));
         }
     };
 
-    eprintln!(
-        "MCP: Received method '{}' id={}",
-        rpc_request.method, rpc_request.id
-    );
-
-    // Handle MCP methods
-    let response = match rpc_request.method.as_str() {
-        "initialize" => {
-            // MCP initialization - return server capabilities
-            JsonRpcResponse::success(
-                serde_json::json!({
-                    "protocolVersion": "2024-11-05",
-                    "capabilities": {
-                        "tools": {
-                            "listChanged": true
-                        }
-                    },
-                    "serverInfo": {
-                        "name": "RustService MCP",
-                        "version": "1.0.0"
-                    }
-                }),
-                rpc_request.id,
-            )
-        }
-        "tools/list" => {
-            // Return list of available tools
-            JsonRpcResponse::success(get_tool_definitions(), rpc_request.id)
-        }
-        "tools/call" => {
-            // Extract tool name and arguments
-            let tool_name = rpc_request
-                .params
-                .get("name")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            let empty_map = serde_json::Map::new();
-            let arguments = rpc_request
-                .params
-                .get("arguments")
-                .and_then(|v| v.as_object())
-                .unwrap_or(&empty_map);
-
-            eprintln!("MCP: Calling tool '{}' with args: {:?}", tool_name, arguments);
-
-            match dispatch_tool_call(&tools, tool_name, arguments).await {
-                Ok(result) => JsonRpcResponse::success(result, rpc_request.id),
-                Err(e) => JsonRpcResponse::error(-32603, &e, rpc_request.id),
+    // Every request in a batch counts against the key's rate limit
+    let request_count = messages.iter().filter(|m| m.is_request()).count() as u32;
+    if let Err(retry_after) = state.check_rate(&key.id, request_count) {
+        let message = format!(
+            "Rate limit of {} requests per minute exceeded; retry in {}s",
+            state.limits.requests_per_minute, retry_after
+        );
+        eprintln!("MCP: Key '{}': {}", key.name, message);
+        let mut responses = Vec::new();
+        for request in messages.iter().filter(|m| m.is_request()) {
+            let response = JsonRpcResponse::error(
+                LIMIT_EXCEEDED,
+                &message,
+                request.id.clone().unwrap_or_default(),
+            );
+            if let Some(audit) = AuditRecord::start(&caller, request) {
+                audit.finish(Some(&response));
             }
+            responses.push(response);
         }
-        "notifications/initialized" => {
-            // Client notification that initialization is complete - no response needed
-            // But we return success anyway for non-notification requests
-            JsonRpcResponse::success(serde_json::json!({}), rpc_request.id)
+        let body = if is_batch {
+            serde_json::to_string(&responses).unwrap()
+        } else {
+            serde_json::to_string(&responses.first()).unwrap()
+        };
+        return Ok(response_builder(StatusCode::TOO_MANY_REQUESTS)
+            .header("Content-Type", "application/json")
+            .header("Retry-After", retry_after.to_string())
+            .body(full(body))
+            .unwrap());
+    }
+
+    // Only notifications and client responses: acknowledge without a body
+    if !messages.iter().any(JsonRpcMessage::is_request) {
+        for message in messages {
+            Arc::clone(&state.handler)
+                .handle_blocking(message, permissions.clone(), caller.clone())
+                .await;
         }
-        _ => {
-            eprintln!("MCP: Method not found: {}", rpc_request.method);
-            JsonRpcResponse::error(
-                -32601,
-                &format!("Method not found: {}", rpc_request.method),
-                rpc_request.id,
-            )
+        return Ok(response_builder(StatusCode::ACCEPTED)
+            .body(full(Bytes::new()))
+            .unwrap());
+    }
+
+    let is_method = |m: &JsonRpcMessage, name: &str| m.method.as_deref() == Some(name);
+    let has_initialize = messages.iter().any(|m| is_method(m, "initialize"));
+    let has_tool_call = messages.iter().any(|m| is_method(m, "tools/call"));
+
+    // Stream tool calls so long-running tools can report progress
+    if accepts_sse && has_tool_call && !has_initialize {
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        let handler = Arc::clone(&state.handler);
+        tokio::spawn(async move {
+            for message in messages {
+                let streamed = stream_message(
+                    Arc::clone(&handler),
+                    message,
+                    permissions.clone(),
+                    caller.clone(),
+                    &tx,
+                )
+                .await;
+                if !streamed {
+                    break;
+                }
+            }
+        });
+        return Ok(response_builder(StatusCode::OK)
+            .header("Content-Type", "text/event-stream")
+            .header("Cache-Control", "no-cache")
+            .body(ChannelBody(rx).boxed_unsync())
+            .unwrap());
+    }
+
+    let mut responses = Vec::new();
+    for message in messages {
+        if let Some(response) = Arc::clone(&state.handler)
+            .handle_blocking(message, permissions.clone(), caller.clone())
+            .await
+        {
+            responses.push(response);
         }
-    };
+    }
 
-    Ok(Response::builder()
-        .status(StatusCode::OK)
-        .header("Content-Type", "application/json")
-        .header("Access-Control-Allow-Origin", "*")
-        .body(BoxBody::new(Bytes::from(
-            serde_json::to_string(&response).unwrap(),
-        )))
-        .unwrap())
+    // A successful initialize opens a session
+    let mut builder = response_builder(StatusCode::OK).header("Content-Type", "application/json");
+    if has_initialize && !is_batch && responses.first().is_some_and(|r| r.is_success()) {
+        let id = state.open_session(&key.id);
+        eprintln!("MCP: Session {} opened", id);
+        builder = builder.header("Mcp-Session-Id", id);
+    }
+
+    let body = if is_batch {
+        serde_json::to_string(&responses).unwrap()
+    } else {
+        serde_json::to_string(&responses[0]).unwrap()
+    };
+    Ok(builder.body(full(body)).unwrap())
 }
 
 // =============================================================================
-// Server Lifecycle
+// Server Instance
 // =============================================================================
 
-/// Run the MCP server with HTTP transport
-pub async fn run_mcp_server_http(
-    port: u16,
-    api_key: String,
-    tavily_key: Option<String>,
-    searxng_url: Option<String>,
-) {
-    let addr = SocketAddr::from(([0, 0, 0, 0], port));
-
-    eprintln!("Starting MCP HTTP server on http://0.0.0.0:{}/mcp", port);
+/// Address the listener binds, from the bind setting
+fn bind_ip(bind: &McpBindAddress) -> Result<IpAddr, String> {
+    match bind {
+        McpBindAddress::Loopback => Ok(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+        McpBindAddress::All => Ok(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+        McpBindAddress::Interface { address } => address
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid bind address: {}", address)),
+    }
+}
 
-    let tools = Arc::new(RustServiceTools::with_settings(tavily_key, searxng_url));
-    let api_key = Arc::new(api_key);
+/// Serve HTTP on one accepted connection, plain or TLS
+async fn serve_connection<S>(stream: S, remote_addr: SocketAddr, state: Arc<ServerState>)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let connection = state.next_connection.fetch_add(1, Ordering::Relaxed);
+    state.clients.lock().unwrap().insert(
+        connection,
+        McpConnectedClient {
+            remote_addr: remote_addr.to_string(),
+            connected_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            requests: 0,
+        },
+    );
 
-    let listener = match TcpListener::bind(addr).await {
-        Ok(l) => l,
-        Err(e) => {
-            eprintln!("Failed to bind MCP server to {}: {}", addr, e);
-            return;
+    let service_state = Arc::clone(&state);
+    let service = service_fn(move |req| {
+        let state = service_state.clone();
+        async move {
+            // Health checks and CORS preflights aren't counted
+            let counted =
+                req.method() != Method::OPTIONS && !matches!(req.uri().path(), "/" | "/health");
+            let response = handle_mcp_request(req, remote_addr, Arc::clone(&state)).await?;
+            if counted {
+                let status = response.status();
+                state.record_request(
+                    connection,
+                    status.is_client_error() || status.is_server_error(),
+                );
+            }
+            Ok::<_, hyper::Error>(response)
         }
-    };
+    });
 
-    eprintln!("MCP HTTP server listening on http://{}/mcp", addr);
-    eprintln!("Available tools: execute_command, read_file, write_file, list_dir, move_file, copy_file, get_system_info, search_web, list_programs, list_instruments, run_instrument");
+    if let Err(e) = http1::Builder::new()
+        .serve_connection(TokioIo::new(stream), service)
+        .await
+    {
+        // Connection errors are usually just clients disconnecting
+        eprintln!("Connection from {} ended: {}", remote_addr, e);
+    }
+    state.clients.lock().unwrap().remove(&connection);
+}
 
-    loop {
-        let (stream, remote_addr) = match listener.accept().await {
-            Ok(conn) => conn,
-            Err(e) => {
-                eprintln!("Failed to accept connection: {}", e);
-                continue;
-            }
+/// A bound MCP HTTP server, ready to serve
+pub struct McpHttpServer {
+    listener: TcpListener,
+    tls: Option<TlsAcceptor>,
+    state: Arc<ServerState>,
+    connection_slots: Option<Arc<Semaphore>>,
+    url: String,
+}
+
+impl McpHttpServer {
+    /// Load TLS if enabled and bind the listener the settings ask for
+    pub async fn bind(app: AppHandle, settings: AgentSettings) -> Result<Self, String> {
+        let ip = bind_ip(&settings.mcp_bind_address)?;
+        let addr = SocketAddr::new(ip, settings.mcp_port);
+
+        // Never fall back to cleartext when TLS was asked for
+        let tls = if settings.mcp_tls.enabled {
+            let config = load_mcp_tls_config(&settings.mcp_tls)
+                .map_err(|e| format!("TLS is enabled but unusable: {}", e))?;
+            Some(TlsAcceptor::from(config))
+        } else {
+            None
         };
+        let scheme = if tls.is_some() { "https" } else { "http" };
+
+        eprintln!("Starting MCP HTTP server on {}://{}/mcp", scheme, addr);
+
+        let limits = settings.mcp_limits;
+        let tools = RustServiceTools::with_settings(settings.tavily_api_key, settings.searxng_url)
+            .with_app(app)
+            .with_output_limit(limits.max_output_bytes);
+        let handler = McpHandler::new(tools).with_tool_call_limit(limits.max_concurrent_tool_calls);
+        let connection_slots = (limits.max_connections > 0)
+            .then(|| Arc::new(Semaphore::new(limits.max_connections as usize)));
+        let state = Arc::new(ServerState {
+            handler: Arc::new(handler),
+            sessions: Mutex::new(HashMap::new()),
+            limits,
+            rate_windows: Mutex::new(HashMap::new()),
+            rejected_windows: Mutex::new(HashMap::new()),
+            requests: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+            clients: Mutex::new(HashMap::new()),
+            next_connection: AtomicU64::new(0),
+        });
 
-        let io = TokioIo::new(stream);
-        let api_key = api_key.clone();
-        let tools = tools.clone();
+        if !has_active_mcp_api_keys() {
+            eprintln!(
+                "MCP: No active API keys - all requests will be rejected until one is created"
+            );
+        }
 
-        tokio::spawn(async move {
-            let service = service_fn(move |req| {
-                let api_key = api_key.clone();
-                let tools = tools.clone();
-                async move { handle_mcp_request(req, api_key, tools).await }
-            });
+        let listener = TcpListener::bind(addr)
+            .await
+            .map_err(|e| format!("Failed to bind MCP server to {}: {}", addr, e))?;
+        let url = format!("{}://{}/mcp", scheme, addr);
 
-            if let Err(e) = http1::Builder::new().serve_connection(io, service).await {
-                // Connection errors are usually just clients disconnecting
-                eprintln!("Connection from {} ended: {}", remote_addr, e);
-            }
-        });
+        eprintln!("MCP HTTP server listening on {}", url);
+        eprintln!("Available tools: {}", tool_names().join(", "));
+
+        Ok(Self {
+            listener,
+            tls,
+            state,
+            connection_slots,
+            url,
+        })
+    }
+
+    /// Endpoint URL, e.g. `https://0.0.0.0:8377/mcp`
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn monitor(&self) -> McpServerMonitor {
+        McpServerMonitor(Arc::clone(&self.state))
+    }
+
+    /// Accept connections until `shutdown` is notified, then close the
+    /// listener. Connections already open are left to the runtime.
+    pub async fn serve(self, shutdown: Arc<Notify>) {
+        loop {
+            let next = async {
+                // At the connection limit, further clients wait in the listen backlog
+                let slot = match &self.connection_slots {
+                    Some(slots) => Some(Arc::clone(slots).acquire_owned().await.ok()?),
+                    None => None,
+                };
+                Some((slot, self.listener.accept().await))
+            };
+            let (slot, accepted) = tokio::select! {
+                _ = shutdown.notified() => break,
+                next = next => match next {
+                    Some(next) => next,
+                    None => break,
+                },
+            };
+            let (stream, remote_addr) = match accepted {
+                Ok(conn) => conn,
+                Err(e) => {
+                    eprintln!("Failed to accept connection: {}", e);
+                    continue;
+                }
+            };
+
+            let state = self.state.clone();
+            let tls = self.tls.clone();
+
+            tokio::spawn(async move {
+                match tls {
+                    Some(acceptor) => {
+                        match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream))
+                            .await
+                        {
+                            Ok(Ok(stream)) => serve_connection(stream, remote_addr, state).await,
+                            Ok(Err(e)) => {
+                                eprintln!("MCP: TLS handshake with {} failed: {}", remote_addr, e)
+                            }
+                            Err(_) => {
+                                eprintln!("MCP: TLS handshake with {} timed out", remote_addr)
+                            }
+                        }
+                    }
+                    None => serve_connection(stream, remote_addr, state).await,
+                }
+                drop(slot);
+            });
+        }
+        eprintln!("MCP HTTP server on {} stopped", self.url);
     }
 }
 
-/// Start the MCP server in a background thread
-pub fn start_mcp_server_background(
-    port: u16,
-    api_key: String,
-    tavily_key: Option<String>,
-    searxng_url: Option<String>,
-) {
-    std::thread::spawn(move || {
-        let rt = match tokio::runtime::Runtime::new() {
-            Ok(rt) => rt,
-            Err(e) => {
-                eprintln!("Failed to create tokio runtime for MCP server: {}", e);
-                return;
-            }
-        };
+/// Live counters of a running server, for status reporting
+#[derive(Clone)]
+pub struct McpServerMonitor(Arc<ServerState>);
+
+impl McpServerMonitor {
+    /// Requests to `/mcp` so far, and how many were refused or failed
+    pub fn request_counts(&self) -> (u64, u64) {
+        (
+            self.0.requests.load(Ordering::Relaxed),
+            self.0.rejected.load(Ordering::Relaxed),
+        )
+    }
 
-        rt.block_on(run_mcp_server_http(port, api_key, tavily_key, searxng_url));
-    });
+    pub fn active_sessions(&self) -> usize {
+        let now = Instant::now();
+        let sessions = self.0.sessions.lock().unwrap();
+        sessions.values().filter(|s| s.is_live(now)).count()
+    }
+
+    /// Open connections, oldest first
+    pub fn connected_clients(&self) -> Vec<McpConnectedClient> {
+        let mut clients: Vec<_> = self.0.clients.lock().unwrap().values().cloned().collect();
+        clients.sort_by(|a, b| a.connected_at.cmp(&b.connected_at));
+        clients
+    }
 }
@@ -0,0 +1,660 @@
+//! MCP Service Tools
+//!
+//! Exposes the service runner and saved reports over MCP so a remote LLM can
+//! do the toolkit's real work: pick services or a preset, start a run, poll
+//! its progress, pause/resume/cancel it, then read and annotate the report.
+//!
+//! These tools go through the same commands the frontend uses, so a run
+//! started over MCP shows up in the app like any other run. Starting and
+//! controlling runs needs the app handle; without it (e.g. a headless
+//! server) only the report tools work.
+
+use std::sync::mpsc::{self, TryRecvError};
+use std::time::{Duration, Instant};
+
+use rmcp::model::{CallToolResult, Content};
+use rmcp::tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::AppHandle;
+
+use super::tools::RustServiceTools;
+use crate::commands::{
+    add_report_finding, cancel_service_run, edit_report_finding, get_report_statistics,
+    get_service_definitions, get_service_presets, get_service_report, get_service_run_state,
+    get_settings, pause_service_run, remove_report_finding, resume_service_run, run_services,
+    search_reports, set_report_health_score, set_report_summary, set_service_analysis,
+    validate_service_requirements,
+};
+use crate::types::{ReportQuery, ServiceQueueItem, ServiceReport};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// How long `run_service_queue` waits for the run to show up as started
+const RUN_START_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often `run_service_queue` checks whether the run has started
+const RUN_START_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Reports returned by `list_service_reports` when no limit is given
+const DEFAULT_REPORT_LIST_LIMIT: usize = 20;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// A queue entry as sent by an MCP client. Only the service ID is required.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct McpQueueItem {
+    /// Service ID (see list_services)
+    pub service_id: String,
+    /// Whether the service runs (default true)
+    pub enabled: Option<bool>,
+    /// Position in the queue (default: position in the list)
+    pub order: Option<u32>,
+    /// Service options keyed by option ID (default: service defaults)
+    pub options: Option<serde_json::Value>,
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn json_result<T: Serialize>(value: &T) -> Result<CallToolResult, rmcp::Error> {
+    Ok(CallToolResult::success(vec![Content::json(value)?]))
+}
+
+fn error_result(message: String) -> Result<CallToolResult, rmcp::Error> {
+    Ok(CallToolResult::error(vec![Content::text(message)]))
+}
+
+fn message_result(message: &str) -> Result<CallToolResult, rmcp::Error> {
+    json_result(&json!({ "status": "success", "message": message }))
+}
+
+/// Load a report for an MCP caller, redacted for `audience` or, if none is
+/// given, for the MCP report audience set in the app
+pub(super) fn read_report(
+    report_id: String,
+    audience: Option<String>,
+) -> Result<ServiceReport, String> {
+    let audience = match audience.filter(|a| !a.trim().is_empty()) {
+        Some(audience) => Some(audience),
+        None => get_settings()?.agent.mcp_report_audience,
+    };
+    get_service_report(report_id, audience)
+}
+
+/// Build the run queue from either an explicit queue or a preset ID
+fn build_queue(
+    queue: Option<Vec<McpQueueItem>>,
+    preset: Option<String>,
+) -> Result<Vec<ServiceQueueItem>, String> {
+    let items: Vec<ServiceQueueItem> = match (queue, preset) {
+        (Some(_), Some(_)) => return Err("Provide either queue or preset, not both".to_string()),
+        (None, None) => return Err("Provide a queue or a preset".to_string()),
+        (None, Some(preset_id)) => get_service_presets()
+            .into_iter()
+            .find(|p| p.id == preset_id)
+            .ok_or_else(|| format!("Preset not found: {}", preset_id))?
+            .services
+            .into_iter()
+            .enumerate()
+            .map(|(i, s)| ServiceQueueItem {
+                service_id: s.service_id,
+                enabled: s.enabled,
+                order: i as u32,
+                options: s.options,
+            })
+            .collect(),
+        (Some(queue), None) => queue
+            .into_iter()
+            .enumerate()
+            .map(|(i, q)| ServiceQueueItem {
+                service_id: q.service_id,
+                enabled: q.enabled.unwrap_or(true),
+                order: q.order.unwrap_or(i as u32),
+                options: q.options.unwrap_or_else(|| json!({})),
+            })
+            .collect(),
+    };
+
+    let definitions = get_service_definitions();
+    let unknown: Vec<&str> = items
+        .iter()
+        .filter(|q| !definitions.iter().any(|d| d.id == q.service_id))
+        .map(|q| q.service_id.as_str())
+        .collect();
+    if !unknown.is_empty() {
+        return Err(format!("Unknown service IDs: {}", unknown.join(", ")));
+    }
+
+    Ok(items)
+}
+
+impl RustServiceTools {
+    fn app_handle(&self) -> Result<AppHandle, String> {
+        self.app
+            .clone()
+            .ok_or_else(|| "The service runner is not available on this MCP server".to_string())
+    }
+}
+
+// =============================================================================
+// MCP Tools
+// =============================================================================
+
+// Kept out of the `tool_box` in `tools`: these methods carry their own
+// `#[tool]` metadata and are dispatched by name like the other tools.
+impl RustServiceTools {
+    /// List service definitions
+    #[tool(
+        description = "List all available diagnostic and maintenance services with their ID, name, description, category, estimated duration, required programs and configurable options."
+    )]
+    pub async fn list_services(&self) -> Result<CallToolResult, rmcp::Error> {
+        eprintln!("MCP list_services");
+        json_result(&get_service_definitions())
+    }
+
+    /// List service presets
+    #[tool(
+        description = "List the service presets (built-in and user-created). Each preset is a pre-configured list of services with options."
+    )]
+    pub async fn list_service_presets(&self) -> Result<CallToolResult, rmcp::Error> {
+        eprintln!("MCP list_service_presets");
+        json_result(&get_service_presets())
+    }
+
+    /// Check required programs for services
+    #[tool(
+        description = "Check whether the external programs required by the given services are installed. Returns the missing program IDs per service."
+    )]
+    pub async fn check_service_requirements(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Service IDs to check")]
+        service_ids: Vec<String>,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        eprintln!("MCP check_service_requirements: {:?}", service_ids);
+
+        match validate_service_requirements(service_ids) {
+            Ok(missing) => json_result(&json!({
+                "allRequirementsMet": missing.is_empty(),
+                "missingPrograms": missing,
+            })),
+            Err(e) => error_result(format!("Error checking requirements: {}", e)),
+        }
+    }
+
+    /// Start a service run
+    #[tool(
+        description = "Start a service run from a queue of services or a preset ID. Returns once the run has started; poll get_service_status for progress and fetch the report with get_service_report when it finishes."
+    )]
+    pub async fn run_service_queue(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Services to run, in order (use list_services for IDs and options)"
+        )]
+        queue: Option<Vec<McpQueueItem>>,
+        #[tool(param)]
+        #[schemars(description = "Preset ID to run instead of a queue (see list_service_presets)")]
+        preset: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Technician name for the report (business mode)")]
+        technician_name: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Customer name for the report (business mode)")]
+        customer_name: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Run independent services concurrently")]
+        parallel: Option<bool>,
+        #[tool(param)]
+        #[schemars(description = "Brief explanation of why these services are being run")]
+        reason: Option<String>,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        eprintln!(
+            "MCP run_service_queue ({})",
+            reason.as_deref().unwrap_or("no reason given")
+        );
+
+        let app = match self.app_handle() {
+            Ok(app) => app,
+            Err(e) => return error_result(e),
+        };
+        let queue = match build_queue(queue, preset) {
+            Ok(queue) => queue,
+            Err(e) => return error_result(e),
+        };
+        if get_service_run_state().is_running {
+            return error_result("A service run is already in progress".to_string());
+        }
+
+        // run_services blocks until the whole run is done, so it gets its own thread
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let result = tauri::async_runtime::block_on(run_services(
+                app,
+                queue,
+                technician_name,
+                customer_name,
+                parallel,
+            ));
+            let _ = tx.send(result);
+        });
+
+        let started = Instant::now();
+        loop {
+            match rx.try_recv() {
+                Ok(Ok(report)) => {
+                    return json_result(&json!({
+                        "status": "completed",
+                        "reportId": report.id,
+                        "reportStatus": report.status,
+                    }))
+                }
+                Ok(Err(e)) => return error_result(format!("Failed to start service run: {}", e)),
+                Err(TryRecvError::Disconnected) => {
+                    return error_result("Service run stopped unexpectedly".to_string())
+                }
+                Err(TryRecvError::Empty) => {}
+            }
+
+            let state = get_service_run_state();
+            if let (true, Some(report)) = (state.is_running, state.current_report) {
+                return json_result(&json!({
+                    "status": "started",
+                    "reportId": report.id,
+                    "totalServices": report.queue.iter().filter(|q| q.enabled).count(),
+                }));
+            }
+
+            if started.elapsed() >= RUN_START_TIMEOUT {
+                return json_result(&json!({
+                    "status": "starting",
+                    "message": "The run has not started yet; check get_service_status",
+                }));
+            }
+            tokio::time::sleep(RUN_START_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Get the state of the current run
+    #[tool(
+        description = "Get the state of the current service run: whether it is running or paused, which services are running, and a summary of the results so far."
+    )]
+    pub async fn get_service_status(&self) -> Result<CallToolResult, rmcp::Error> {
+        eprintln!("MCP get_service_status");
+
+        let state = get_service_run_state();
+        let Some(report) = state.current_report else {
+            return json_result(&json!({
+                "isRunning": state.is_running,
+                "isPaused": state.is_paused,
+            }));
+        };
+
+        let enabled: Vec<&ServiceQueueItem> = report.queue.iter().filter(|q| q.enabled).collect();
+        let mut running_indices = report.current_service_indices.clone();
+        if running_indices.is_empty() {
+            running_indices.extend(report.current_service_index);
+        }
+        let current_service_ids: Vec<&str> = running_indices
+            .iter()
+            .filter_map(|&i| enabled.get(i))
+            .map(|q| q.service_id.as_str())
+            .collect();
+        let results: Vec<serde_json::Value> = report
+            .results
+            .iter()
+            .map(|r| {
+                json!({
+                    "serviceId": r.service_id,
+                    "success": r.success,
+                    "error": r.error,
+                    "timedOut": r.timed_out,
+                    "durationMs": r.duration_ms,
+                    "findingsCount": r.findings.len(),
+                })
+            })
+            .collect();
+
+        json_result(&json!({
+            "isRunning": state.is_running,
+            "isPaused": state.is_paused,
+            "reportId": report.id,
+            "reportStatus": report.status,
+            "startedAt": report.started_at,
+            "totalServices": enabled.len(),
+            "completedServices": report.results.len(),
+            "currentServiceIds": current_service_ids,
+            "results": results,
+        }))
+    }
+
+    /// Pause the current run
+    #[tool(
+        description = "Pause the current service run. Takes effect between services, or at the next pause point of a service that supports it."
+    )]
+    pub async fn pause_service(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Why the run is being paused")]
+        reason: Option<String>,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        eprintln!(
+            "MCP pause_service ({})",
+            reason.as_deref().unwrap_or("no reason given")
+        );
+
+        let app = match self.app_handle() {
+            Ok(app) => app,
+            Err(e) => return error_result(e),
+        };
+        match pause_service_run(app) {
+            Ok(()) => message_result("Service run paused"),
+            Err(e) => error_result(e),
+        }
+    }
+
+    /// Resume a paused run
+    #[tool(description = "Resume a paused service run.")]
+    pub async fn resume_service(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Why it is safe to resume")]
+        reason: Option<String>,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        eprintln!(
+            "MCP resume_service ({})",
+            reason.as_deref().unwrap_or("no reason given")
+        );
+
+        let app = match self.app_handle() {
+            Ok(app) => app,
+            Err(e) => return error_result(e),
+        };
+        match resume_service_run(app) {
+            Ok(()) => message_result("Service run resumed"),
+            Err(e) => error_result(e),
+        }
+    }
+
+    /// Cancel the current run
+    #[tool(
+        description = "Cancel the current service run. Running services are stopped and the partial report is saved."
+    )]
+    pub async fn cancel_service(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Why the run is being cancelled")]
+        reason: Option<String>,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        eprintln!(
+            "MCP cancel_service ({})",
+            reason.as_deref().unwrap_or("no reason given")
+        );
+
+        match cancel_service_run() {
+            Ok(()) => message_result("Service run cancelled"),
+            Err(e) => error_result(e),
+        }
+    }
+
+    /// List saved reports
+    #[tool(
+        description = "List saved service reports, newest first. Optionally filter by customer name or by text in finding titles."
+    )]
+    pub async fn list_service_reports(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Only reports whose customer name contains this text")]
+        customer_name: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Only reports with a finding title containing this text")]
+        text: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Maximum number of reports to return (default 20)")]
+        limit: Option<usize>,
+        #[tool(param)]
+        #[schemars(description = "Number of reports to skip")]
+        offset: Option<usize>,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        eprintln!("MCP list_service_reports");
+
+        let query = ReportQuery {
+            customer_name,
+            text,
+            limit: Some(limit.unwrap_or(DEFAULT_REPORT_LIST_LIMIT)),
+            offset,
+            ..Default::default()
+        };
+        match search_reports(query) {
+            Ok(result) => json_result(&result),
+            Err(e) => error_result(format!("Error listing reports: {}", e)),
+        }
+    }
+
+    /// Get a saved report
+    #[tool(
+        description = "Get a saved service report by ID with all results and findings. Service logs are left out unless include_logs is true. The report is redacted for the given audience, or for the default audience set in the app."
+    )]
+    pub async fn get_service_report(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Report ID")]
+        report_id: String,
+        #[tool(param)]
+        #[schemars(description = "Include each service's log output")]
+        include_logs: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Redaction profile to apply, e.g. \"customer\" or \"technician\" (default: the app's MCP report audience)"
+        )]
+        audience: Option<String>,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        eprintln!("MCP get_service_report: {}", report_id);
+
+        match read_report(report_id, audience) {
+            Ok(mut report) => {
+                if !include_logs.unwrap_or(false) {
+                    for result in &mut report.results {
+                        result.logs.clear();
+                    }
+                }
+                json_result(&report)
+            }
+            Err(e) => error_result(e),
+        }
+    }
+
+    /// Get report statistics
+    #[tool(
+        description = "Get computed statistics for a report: pass/fail counts, findings by severity, health score and duration metrics."
+    )]
+    pub async fn get_report_statistics(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Report ID")]
+        report_id: String,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        eprintln!("MCP get_report_statistics: {}", report_id);
+
+        match get_report_statistics(report_id) {
+            Ok(stats) => json_result(&stats),
+            Err(e) => error_result(e),
+        }
+    }
+
+    /// Edit a finding
+    #[tool(
+        description = "Edit an existing finding in a saved report. Severity is one of info, success, warning, error, critical. Omitted fields are left unchanged."
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn edit_finding(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Report ID")]
+        report_id: String,
+        #[tool(param)]
+        #[schemars(description = "Service ID within the report")]
+        service_id: String,
+        #[tool(param)]
+        #[schemars(description = "Zero-based index of the finding")]
+        finding_index: usize,
+        #[tool(param)]
+        #[schemars(description = "New severity: info, success, warning, error or critical")]
+        severity: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "New title")]
+        title: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "New description")]
+        description: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "New recommendation")]
+        recommendation: Option<String>,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        eprintln!("MCP edit_finding: {} {}", report_id, service_id);
+
+        match edit_report_finding(
+            report_id,
+            service_id,
+            finding_index,
+            severity,
+            title,
+            description,
+            recommendation,
+        ) {
+            Ok(()) => message_result("Finding updated"),
+            Err(e) => error_result(e),
+        }
+    }
+
+    /// Add a finding
+    #[tool(
+        description = "Add a finding to a service result in a saved report. Severity is one of info, success, warning, error, critical."
+    )]
+    pub async fn add_finding(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Report ID")]
+        report_id: String,
+        #[tool(param)]
+        #[schemars(description = "Service ID to add the finding to")]
+        service_id: String,
+        #[tool(param)]
+        #[schemars(description = "Severity: info, success, warning, error or critical")]
+        severity: String,
+        #[tool(param)]
+        #[schemars(description = "Short finding title")]
+        title: String,
+        #[tool(param)]
+        #[schemars(description = "Detailed finding description")]
+        description: String,
+        #[tool(param)]
+        #[schemars(description = "Recommended action")]
+        recommendation: Option<String>,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        eprintln!("MCP add_finding: {} {}", report_id, service_id);
+
+        match add_report_finding(
+            report_id,
+            service_id,
+            severity,
+            title,
+            description,
+            recommendation,
+        ) {
+            Ok(()) => message_result("Finding added"),
+            Err(e) => error_result(e),
+        }
+    }
+
+    /// Remove a finding
+    #[tool(description = "Remove a finding from a service result in a saved report by index.")]
+    pub async fn remove_finding(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Report ID")]
+        report_id: String,
+        #[tool(param)]
+        #[schemars(description = "Service ID")]
+        service_id: String,
+        #[tool(param)]
+        #[schemars(description = "Zero-based index of the finding")]
+        finding_index: usize,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        eprintln!("MCP remove_finding: {} {}", report_id, service_id);
+
+        match remove_report_finding(report_id, service_id, finding_index) {
+            Ok(()) => message_result("Finding removed"),
+            Err(e) => error_result(e),
+        }
+    }
+
+    /// Set the executive summary
+    #[tool(description = "Set the executive summary of a saved report.")]
+    pub async fn set_report_summary(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Report ID")]
+        report_id: String,
+        #[tool(param)]
+        #[schemars(description = "Executive summary text")]
+        summary: String,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        eprintln!("MCP set_report_summary: {}", report_id);
+
+        match set_report_summary(report_id, summary) {
+            Ok(()) => message_result("Summary set"),
+            Err(e) => error_result(e),
+        }
+    }
+
+    /// Set analysis for one service result
+    #[tool(description = "Set the analysis text for one service result in a saved report.")]
+    pub async fn set_service_analysis(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Report ID")]
+        report_id: String,
+        #[tool(param)]
+        #[schemars(description = "Service ID to annotate")]
+        service_id: String,
+        #[tool(param)]
+        #[schemars(description = "Analysis of the service's results")]
+        analysis: String,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        eprintln!("MCP set_service_analysis: {} {}", report_id, service_id);
+
+        match set_service_analysis(report_id, service_id, analysis) {
+            Ok(()) => message_result("Analysis set"),
+            Err(e) => error_result(e),
+        }
+    }
+
+    /// Set the health score
+    #[tool(
+        description = "Set the overall health score (0-100) of a saved report. 90-100 excellent, 70-89 good, 50-69 fair, 30-49 poor, 0-29 critical."
+    )]
+    pub async fn set_health_score(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Report ID")]
+        report_id: String,
+        #[tool(param)]
+        #[schemars(description = "Health score from 0 to 100")]
+        score: u8,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        eprintln!("MCP set_health_score: {} = {}", report_id, score);
+
+        match set_report_health_score(report_id, score) {
+            Ok(()) => message_result(&format!("Health score set to {}", score)),
+            Err(e) => error_result(e),
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! MCP stdio Transport
+//!
+//! Headless MCP server for clients that launch RustService as a subprocess
+//! (`rustservice --mcp-stdio`). Messages are newline-delimited JSON-RPC on
+//! stdin/stdout; stdout carries nothing but protocol messages, so all
+//! logging goes to stderr.
+//!
+//! No window is opened and there is no `AppHandle`, so the service runner
+//! tools report that they are unavailable; everything else matches the HTTP
+//! server. There is no API key: the client already runs with the user's
+//! privileges when it launches the process.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+
+use super::audit::AuditCaller;
+use super::permissions::McpPermissions;
+use super::protocol::{parse_messages, tool_names, JsonRpcResponse, McpHandler};
+use super::tools::RustServiceTools;
+use crate::commands::get_settings;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Command-line flag that selects stdio mode instead of the desktop app
+pub const STDIO_FLAG: &str = "--mcp-stdio";
+
+/// How long in-flight tool calls get to finish after stdin closes
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+// =============================================================================
+// Server
+// =============================================================================
+
+/// Queue a message for stdout as a single line
+fn send(tx: &mpsc::UnboundedSender<String>, message: &impl Serialize) {
+    match serde_json::to_string(message) {
+        Ok(line) => {
+            let _ = tx.send(line);
+        }
+        Err(e) => eprintln!("MCP: Failed to serialize response: {}", e),
+    }
+}
+
+async fn serve(handler: Arc<McpHandler>) {
+    // A single writer keeps concurrent responses from interleaving
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    tokio::spawn(async move {
+        let mut stdout = tokio::io::stdout();
+        while let Some(line) = rx.recv().await {
+            let written = async {
+                stdout.write_all(line.as_bytes()).await?;
+                stdout.write_all(b"\n").await?;
+                stdout.flush().await
+            };
+            if let Err(e) = written.await {
+                eprintln!("MCP: Failed to write to stdout: {}", e);
+                break;
+            }
+        }
+    });
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(e) => {
+                eprintln!("MCP: Failed to read from stdin: {}", e);
+                break;
+            }
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let (messages, is_batch) = match parse_messages(line.as_bytes()) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                AuditCaller::stdio().record_rejected(&e.message);
+                send(
+                    &tx,
+                    &JsonRpcResponse::error(e.code, &e.message, Value::Null),
+                );
+                continue;
+            }
+        };
+
+        // Each line is handled in its own task so a long tool call doesn't
+        // hold up pings or other requests
+        let handler = Arc::clone(&handler);
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let mut responses = Vec::new();
+            for message in messages {
+                let permissions = McpPermissions::unrestricted();
+                if let Some(response) = Arc::clone(&handler)
+                    .handle_blocking(message, permissions, AuditCaller::stdio())
+                    .await
+                {
+                    responses.push(response);
+                }
+            }
+            if is_batch {
+                if !responses.is_empty() {
+                    send(&tx, &responses);
+                }
+            } else if let Some(response) = responses.pop() {
+                send(&tx, &response);
+            }
+        });
+    }
+
+    eprintln!("MCP: stdin closed, shutting down");
+}
+
+/// Serve MCP over stdin/stdout until the client closes stdin
+pub fn run_mcp_server_stdio() {
+    let settings = get_settings().unwrap_or_else(|e| {
+        eprintln!("MCP: Failed to load settings, using defaults: {}", e);
+        Default::default()
+    });
+    super::sync_mcp_clients(&settings.agent.mcp_servers);
+    let tools =
+        RustServiceTools::with_settings(settings.agent.tavily_api_key, settings.agent.searxng_url);
+
+    let rt = match tokio::runtime::Runtime::new() {
+        Ok(rt) => rt,
+        Err(e) => {
+            eprintln!("Failed to create tokio runtime for MCP server: {}", e);
+            return;
+        }
+    };
+
+    eprintln!("MCP stdio server ready");
+    eprintln!("Available tools: {}", tool_names().join(", "));
+
+    rt.block_on(serve(Arc::new(McpHandler::new(tools))));
+    rt.shutdown_timeout(SHUTDOWN_GRACE);
+}
@@ -6,20 +6,23 @@
 use rmcp::model::{CallToolResult, Content};
 use rmcp::tool;
 use std::fs;
-use std::path::Path;
-use std::process::Command;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use sysinfo::System;
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::{
+    approval_decision, get_settings, submit_command, wait_for_command_decision, FilesystemSandbox,
+};
+use crate::types::{CommandStatus, PendingCommand};
+use super::audit::redact_text;
+use super::protocol::release_tool_slot;
 
 // =============================================================================
 // Types
 // =============================================================================
 
-struct CommandResult {
-    exit_code: i32,
-    stdout: String,
-    stderr: String,
-}
-
 struct FileEntry {
     name: String,
     is_dir: bool,
@@ -50,6 +53,12 @@ pub struct RustServiceTools {
     pub tavily_api_key: Option<String>,
     /// SearXNG URL for web search
     pub searxng_url: Option<String>,
+    /// App handle used by the service tools to start and control runs
+    pub app: Option<AppHandle>,
+    /// Name of the API key making the current call, recorded on commands
+    pub caller: Option<String>,
+    /// Cap on output returned by `read_file` and the command tools
+    pub max_output_bytes: Option<usize>,
 }
 
 impl RustServiceTools {
@@ -58,6 +67,9 @@ impl RustServiceTools {
         Self {
             tavily_api_key: None,
             searxng_url: None,
+            app: None,
+            caller: None,
+            max_output_bytes: None,
         }
     }
 
@@ -65,50 +77,214 @@ impl RustServiceTools {
         Self {
             tavily_api_key,
             searxng_url,
+            app: None,
+            caller: None,
+            max_output_bytes: None,
+        }
+    }
+
+    /// Give the tools access to the running app (needed to run services)
+    pub fn with_app(mut self, app: AppHandle) -> Self {
+        self.app = Some(app);
+        self
+    }
+
+    /// Tools acting for the named caller
+    pub fn with_caller(mut self, caller: Option<String>) -> Self {
+        self.caller = caller;
+        self
+    }
+
+    /// Truncate tool output beyond `max_bytes`. Zero is unlimited.
+    pub fn with_output_limit(mut self, max_bytes: u64) -> Self {
+        self.max_output_bytes = (max_bytes > 0).then_some(max_bytes as usize);
+        self
+    }
+
+    /// `text` cut to the output limit, with a note saying how much was left out
+    fn limit_output(&self, text: String) -> String {
+        let Some(max) = self.max_output_bytes.filter(|max| text.len() > *max) else {
+            return text;
+        };
+        let mut cut = max;
+        while !text.is_char_boundary(cut) {
+            cut -= 1;
         }
+        format!(
+            "{}\n\n[Output truncated: {} of {} bytes shown]",
+            &text[..cut],
+            cut,
+            text.len()
+        )
     }
 }
 
 // =============================================================================
-// Internal Helper Functions
+// Command Approval
 // =============================================================================
 
-fn execute_shell_command(command: &str) -> Result<CommandResult, String> {
-    #[cfg(windows)]
-    {
-        let output = Command::new("powershell")
-            .args(["-NoProfile", "-Command", command])
-            .output()
-            .map_err(|e| format!("Failed to execute command: {}", e))?;
-
-        Ok(CommandResult {
-            exit_code: output.status.code().unwrap_or(-1),
-            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-        })
+/// How long command tools wait for the technician by default
+const DEFAULT_APPROVAL_WAIT_SECS: u64 = 120;
+
+/// Upper bound on `wait_seconds`
+const MAX_APPROVAL_WAIT_SECS: u64 = 600;
+
+impl RustServiceTools {
+    /// Run a command through the agent's approval pipeline: straight away if
+    /// the approval mode allows it, otherwise once the technician approves it
+    /// in the app. Returns the command still pending if the wait runs out.
+    fn run_with_approval(
+        &self,
+        command: String,
+        reason: String,
+        wait_seconds: Option<u64>,
+    ) -> Result<PendingCommand, String> {
+        let settings = get_settings()?;
+        if self.app.is_none() {
+            let decision = approval_decision(&command, &settings.agent);
+            if decision.needs_approval {
+                return Err(format!(
+                    "This command needs the technician's approval ({}), but the app isn't \
+                     running to approve it. Whitelist the command or change the approval mode.",
+                    decision.explanation
+                ));
+            }
+        }
+
+        let source = match &self.caller {
+            Some(name) => format!("MCP ({})", name),
+            None => "MCP".to_string(),
+        };
+        let submitted = submit_command(self.app.as_ref(), command, reason, Some(source))?;
+        if submitted.status != CommandStatus::Pending {
+            return Ok(submitted);
+        }
+
+        eprintln!("MCP: Command {} awaiting approval", submitted.id);
+        if let Some(app) = &self.app {
+            let _ = app.emit("command-pending", &submitted);
+        }
+        // Waiting on the technician shouldn't keep other calls out
+        release_tool_slot();
+        wait_for_approval(&submitted.id, wait_seconds).map(|cmd| cmd.unwrap_or(submitted))
     }
+}
 
-    #[cfg(not(windows))]
-    {
-        let output = Command::new("sh")
-            .args(["-c", command])
-            .output()
-            .map_err(|e| format!("Failed to execute command: {}", e))?;
-
-        Ok(CommandResult {
-            exit_code: output.status.code().unwrap_or(-1),
-            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-        })
+fn wait_for_approval(
+    command_id: &str,
+    wait_seconds: Option<u64>,
+) -> Result<Option<PendingCommand>, String> {
+    let wait = wait_seconds
+        .unwrap_or(DEFAULT_APPROVAL_WAIT_SECS)
+        .min(MAX_APPROVAL_WAIT_SECS);
+    wait_for_command_decision(command_id, Duration::from_secs(wait))
+}
+
+impl RustServiceTools {
+    /// Tool result for a command that went through the approval pipeline
+    fn command_outcome(&self, cmd: &PendingCommand, label: &str) -> CallToolResult {
+        let output = self.limit_output(cmd.output.clone().unwrap_or_default());
+        let stderr = self.limit_output(cmd.error.clone().unwrap_or_default());
+        match cmd.status {
+            CommandStatus::Executed => CallToolResult::success(vec![Content::text(format!(
+                "{} completed successfully.\n\nOutput:\n{}",
+                label, output
+            ))]),
+            CommandStatus::Failed => CallToolResult::success(vec![Content::text(format!(
+                "{} failed.\n\nStdout:\n{}\n\nStderr:\n{}",
+                label, output, stderr
+            ))]),
+            CommandStatus::Rejected => CallToolResult::error(vec![Content::text(format!(
+                "{} was rejected by the technician. Command ID: {}",
+                label, cmd.id
+            ))]),
+            CommandStatus::Pending | CommandStatus::Approved => {
+                CallToolResult::success(vec![Content::text(format!(
+                    "{} is waiting for the technician's approval. Command ID: {}\n\n\
+                 Call get_command_status with this ID to wait for the result.",
+                    label, cmd.id
+                ))])
+            }
+        }
     }
 }
 
-fn read_file_contents(path: &str) -> Result<String, String> {
-    fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))
+// =============================================================================
+// Internal Helper Functions
+// =============================================================================
+
+/// Load the filesystem policy for a tool call, as an error result if it can't be read
+fn load_sandbox() -> Result<FilesystemSandbox, CallToolResult> {
+    FilesystemSandbox::load().map_err(|e| CallToolResult::error(vec![Content::text(e)]))
+}
+
+/// `path` resolved through the filesystem policy, or an error result saying
+/// why it was denied
+fn sandboxed(sandbox: &FilesystemSandbox, path: &str) -> Result<PathBuf, CallToolResult> {
+    sandbox
+        .check(path)
+        .map_err(|e| CallToolResult::error(vec![Content::text(e)]))
+}
+
+/// Lines of a file from `offset`, read only as far as needed
+struct FileLines {
+    text: String,
+    /// Whether the file goes on past `text`
+    has_more: bool,
+}
+
+/// Read up to `limit` lines starting at line `offset`, stopping once
+/// `max_bytes` have been read so a large file is never loaded whole
+fn read_file_lines(
+    path: &Path,
+    offset: usize,
+    limit: Option<usize>,
+    max_bytes: Option<usize>,
+) -> Result<FileLines, String> {
+    let read_error = |e: std::io::Error| format!("Failed to read file: {}", e);
+    let file = fs::File::open(path).map_err(read_error)?;
+    let mut reader = BufReader::new(file);
+
+    for _ in 0..offset {
+        if reader.skip_until(b'\n').map_err(read_error)? == 0 {
+            return Ok(FileLines {
+                text: String::new(),
+                has_more: false,
+            });
+        }
+    }
+
+    let budget = max_bytes.unwrap_or(usize::MAX);
+    let mut bytes = Vec::new();
+    let mut count = 0;
+    while count < limit.unwrap_or(usize::MAX) && bytes.len() < budget {
+        let room = (budget - bytes.len()) as u64;
+        let read = (&mut reader)
+            .take(room)
+            .read_until(b'\n', &mut bytes)
+            .map_err(read_error)?;
+        if read == 0 {
+            break;
+        }
+        count += 1;
+    }
+    let has_more = !reader.fill_buf().map_err(read_error)?.is_empty();
+
+    // A cut at the byte budget may split the last character
+    let text = match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) if has_more && e.utf8_error().error_len().is_none() => {
+            let valid = e.utf8_error().valid_up_to();
+            let mut bytes = e.into_bytes();
+            bytes.truncate(valid);
+            String::from_utf8(bytes).unwrap_or_default()
+        }
+        Err(_) => return Err("Failed to read file: stream did not contain valid UTF-8".to_string()),
+    };
+    Ok(FileLines { text, has_more })
 }
 
-fn list_directory(path: &str) -> Result<Vec<FileEntry>, String> {
-    let path = Path::new(path);
+fn list_directory(path: &Path) -> Result<Vec<FileEntry>, String> {
     if !path.exists() {
         return Err("Path does not exist".to_string());
     }
@@ -256,30 +432,58 @@ fn format_bytes(bytes: u64) -> String {
 impl RustServiceTools {
     /// Execute a PowerShell command on the system
     #[tool(
-        description = "Execute a PowerShell command on the system. Returns stdout, stderr, and exit code."
+        description = "Execute a PowerShell command on the system. Returns stdout and stderr. Depending on the approval mode the technician may have to approve the command first; if they haven't within wait_seconds, a command ID is returned for get_command_status."
     )]
     pub async fn execute_command(
         &self,
-        #[tool(param)] command: String,
-        #[tool(param)] reason: String,
+        #[tool(param)]
+        #[schemars(description = "The PowerShell command to execute")]
+        command: String,
+        #[tool(param)]
+        #[schemars(description = "Brief explanation of why this command is needed")]
+        reason: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Seconds to wait for approval before returning the command ID (default 120, max 600)"
+        )]
+        wait_seconds: Option<u64>,
     ) -> Result<CallToolResult, rmcp::Error> {
-        eprintln!("MCP execute_command: {} ({})", command, reason);
+        eprintln!(
+            "MCP execute_command: {} ({})",
+            redact_text(&command),
+            redact_text(&reason)
+        );
 
-        match execute_shell_command(&command) {
-            Ok(result) => {
-                let output = if result.exit_code == 0 {
-                    format!(
-                        "Exit code: {}\n\nOutput:\n{}",
-                        result.exit_code, result.stdout
-                    )
-                } else {
-                    format!(
-                        "Exit code: {}\n\nStdout:\n{}\n\nStderr:\n{}",
-                        result.exit_code, result.stdout, result.stderr
-                    )
-                };
-                Ok(CallToolResult::success(vec![Content::text(output)]))
-            }
+        match self.run_with_approval(command, reason, wait_seconds) {
+            Ok(cmd) => Ok(self.command_outcome(&cmd, "Command")),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Error: {}",
+                e
+            ))])),
+        }
+    }
+
+    /// Check on a command that was waiting for approval
+    #[tool(
+        description = "Get the result of a command that was waiting for the technician's approval. Waits up to wait_seconds for a decision."
+    )]
+    pub async fn get_command_status(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Command ID returned by execute_command or run_instrument")]
+        command_id: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Seconds to wait if the command is still pending (default 120, max 600, 0 to check without waiting)"
+        )]
+        wait_seconds: Option<u64>,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let status = wait_for_approval(&command_id, wait_seconds).and_then(|cmd| match cmd {
+            Some(cmd) => Ok(cmd),
+            None => crate::commands::get_command_status(command_id.clone()),
+        });
+        match status {
+            Ok(cmd) => Ok(self.command_outcome(&cmd, "Command")),
             Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
                 "Error: {}",
                 e
@@ -293,26 +497,30 @@ impl RustServiceTools {
     )]
     pub async fn read_file(
         &self,
-        #[tool(param)] path: String,
-        #[tool(param)] offset: Option<usize>,
-        #[tool(param)] limit: Option<usize>,
-        #[tool(param)] line_numbers: Option<bool>,
+        #[tool(param)]
+        #[schemars(description = "Full path to the file")]
+        path: String,
+        #[tool(param)]
+        #[schemars(description = "Zero-based line to start reading from")]
+        offset: Option<usize>,
+        #[tool(param)]
+        #[schemars(description = "Maximum number of lines to read")]
+        limit: Option<usize>,
+        #[tool(param)]
+        #[schemars(description = "Prefix each line with its number (default true)")]
+        line_numbers: Option<bool>,
     ) -> Result<CallToolResult, rmcp::Error> {
         eprintln!("MCP read_file: {}", path);
+        let resolved = match load_sandbox().and_then(|sandbox| sandboxed(&sandbox, &path)) {
+            Ok(resolved) => resolved,
+            Err(denied) => return Ok(denied),
+        };
 
-        match read_file_contents(&path) {
+        let offset_val = offset.unwrap_or(0);
+        match read_file_lines(&resolved, offset_val, limit, self.max_output_bytes) {
             Ok(content) => {
-                let lines: Vec<&str> = content.lines().collect();
-                let total_lines = lines.len();
-                let offset_val = offset.unwrap_or(0);
-                let limit_val = limit.unwrap_or(total_lines);
-                let end = std::cmp::min(offset_val + limit_val, total_lines);
-
-                let selected: Vec<&str> = if offset_val < total_lines {
-                    lines[offset_val..end].to_vec()
-                } else {
-                    Vec::new()
-                };
+                let selected: Vec<&str> = content.text.lines().collect();
+                let end = offset_val.saturating_add(selected.len());
 
                 let show_line_numbers = line_numbers.unwrap_or(true);
                 let formatted = if show_line_numbers {
@@ -326,12 +534,22 @@ impl RustServiceTools {
                     selected.join("\n")
                 };
 
-                let has_more = end < total_lines;
-                let result = if has_more {
-                    format!("{}\n\n[{} more lines...]", formatted, total_lines - end)
+                let result = if content.has_more {
+                    // A line cut at the output limit is read again in full,
+                    // unless it is the only one and would never fit
+                    let next = if content.text.ends_with('\n') || selected.len() <= 1 {
+                        end
+                    } else {
+                        end - 1
+                    };
+                    format!(
+                        "{}\n\n[More lines follow; read from offset {} to continue]",
+                        formatted, next
+                    )
                 } else {
                     formatted
                 };
+                let result = self.limit_output(result);
 
                 Ok(CallToolResult::success(vec![Content::text(result)]))
             }
@@ -348,14 +566,26 @@ impl RustServiceTools {
     )]
     pub async fn edit_file(
         &self,
-        #[tool(param)] path: String,
-        #[tool(param)] old_string: String,
-        #[tool(param)] new_string: String,
-        #[tool(param)] all: Option<bool>,
+        #[tool(param)]
+        #[schemars(description = "Full path to the file")]
+        path: String,
+        #[tool(param)]
+        #[schemars(description = "Exact text to replace")]
+        old_string: String,
+        #[tool(param)]
+        #[schemars(description = "Replacement text")]
+        new_string: String,
+        #[tool(param)]
+        #[schemars(description = "Replace every occurrence instead of requiring a unique match")]
+        all: Option<bool>,
     ) -> Result<CallToolResult, rmcp::Error> {
         eprintln!("MCP edit_file: {}", path);
+        let resolved = match load_sandbox().and_then(|sandbox| sandboxed(&sandbox, &path)) {
+            Ok(resolved) => resolved,
+            Err(denied) => return Ok(denied),
+        };
 
-        let text = match fs::read_to_string(&path) {
+        let text = match fs::read_to_string(&resolved) {
             Ok(t) => t,
             Err(e) => {
                 return Ok(CallToolResult::error(vec![Content::text(format!(
@@ -387,7 +617,7 @@ impl RustServiceTools {
             text.replacen(&old_string, &new_string, 1)
         };
 
-        match fs::write(&path, replacement) {
+        match fs::write(&resolved, replacement) {
             Ok(_) => {
                 let replacements = if replace_all { count } else { 1 };
                 Ok(CallToolResult::success(vec![Content::text(format!(
@@ -410,10 +640,18 @@ impl RustServiceTools {
     )]
     pub async fn grep(
         &self,
-        #[tool(param)] pattern: String,
-        #[tool(param)] path: Option<String>,
-        #[tool(param)] file_pattern: Option<String>,
-        #[tool(param)] max_results: Option<usize>,
+        #[tool(param)]
+        #[schemars(description = "Regular expression to search for")]
+        pattern: String,
+        #[tool(param)]
+        #[schemars(description = "Directory to search (default: current directory)")]
+        path: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Only search files matching this glob (e.g. *.log)")]
+        file_pattern: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Maximum number of matching lines to return")]
+        max_results: Option<usize>,
     ) -> Result<CallToolResult, rmcp::Error> {
         eprintln!("MCP grep: {}", pattern);
 
@@ -428,6 +666,13 @@ impl RustServiceTools {
         };
 
         let base_path = path.unwrap_or_else(|| ".".to_string());
+        let sandbox = match load_sandbox() {
+            Ok(sandbox) => sandbox,
+            Err(denied) => return Ok(denied),
+        };
+        if let Err(denied) = sandboxed(&sandbox, &base_path) {
+            return Ok(denied);
+        }
         let max = max_results.unwrap_or(50);
         let glob_pat = file_pattern.unwrap_or_else(|| "*".to_string());
         let full_pattern = format!("{}/**/ {}", base_path, glob_pat);
@@ -445,7 +690,8 @@ impl RustServiceTools {
         };
 
         for entry in glob_result.flatten() {
-            if !entry.is_file() {
+            // Skip files the policy protects, e.g. `.ssh` under the home folder
+            if !entry.is_file() || !sandbox.permits(&entry) {
                 continue;
             }
 
@@ -492,13 +738,26 @@ impl RustServiceTools {
     )]
     pub async fn glob(
         &self,
-        #[tool(param)] pattern: String,
-        #[tool(param)] path: Option<String>,
-        #[tool(param)] limit: Option<usize>,
+        #[tool(param)]
+        #[schemars(description = "Glob pattern (e.g. **/*.log)")]
+        pattern: String,
+        #[tool(param)]
+        #[schemars(description = "Directory to search from (default: current directory)")]
+        path: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Maximum number of files to return")]
+        limit: Option<usize>,
     ) -> Result<CallToolResult, rmcp::Error> {
         eprintln!("MCP glob: {}", pattern);
 
         let base_path = path.unwrap_or_else(|| ".".to_string());
+        let sandbox = match load_sandbox() {
+            Ok(sandbox) => sandbox,
+            Err(denied) => return Ok(denied),
+        };
+        if let Err(denied) = sandboxed(&sandbox, &base_path) {
+            return Ok(denied);
+        }
         let max = limit.unwrap_or(100);
         let full_pattern = format!("{}/{}", base_path, pattern);
 
@@ -514,7 +773,7 @@ impl RustServiceTools {
             }
         };
 
-        for entry in glob_result.flatten() {
+        for entry in glob_result.flatten().filter(|entry| sandbox.permits(entry)) {
             if let Ok(metadata) = fs::metadata(&entry) {
                 let mtime = metadata
                     .modified()
@@ -554,13 +813,21 @@ impl RustServiceTools {
     #[tool(description = "Write content to a file. Creates the file if it doesn't exist.")]
     pub async fn write_file(
         &self,
-        #[tool(param)] path: String,
-        #[tool(param)] content: String,
+        #[tool(param)]
+        #[schemars(description = "Full path to the file")]
+        path: String,
+        #[tool(param)]
+        #[schemars(description = "Content to write")]
+        content: String,
     ) -> Result<CallToolResult, rmcp::Error> {
         eprintln!("MCP write_file: {}", path);
+        let resolved = match load_sandbox().and_then(|sandbox| sandboxed(&sandbox, &path)) {
+            Ok(resolved) => resolved,
+            Err(denied) => return Ok(denied),
+        };
 
         // Create parent directories if they don't exist
-        if let Some(parent) = Path::new(&path).parent() {
+        if let Some(parent) = resolved.parent() {
             if !parent.exists() {
                 if let Err(e) = fs::create_dir_all(parent) {
                     return Ok(CallToolResult::error(vec![Content::text(format!(
@@ -571,7 +838,7 @@ impl RustServiceTools {
             }
         }
 
-        match fs::write(&path, &content) {
+        match fs::write(&resolved, &content) {
             Ok(_) => Ok(CallToolResult::success(vec![Content::text(format!(
                 "Successfully wrote {} bytes to {}",
                 content.len(),
@@ -590,11 +857,17 @@ impl RustServiceTools {
     )]
     pub async fn list_dir(
         &self,
-        #[tool(param)] path: String,
+        #[tool(param)]
+        #[schemars(description = "Path to list content for")]
+        path: String,
     ) -> Result<CallToolResult, rmcp::Error> {
         eprintln!("MCP list_dir: {}", path);
+        let resolved = match load_sandbox().and_then(|sandbox| sandboxed(&sandbox, &path)) {
+            Ok(resolved) => resolved,
+            Err(denied) => return Ok(denied),
+        };
 
-        match list_directory(&path) {
+        match list_directory(&resolved) {
             Ok(entries) => {
                 let listing: Vec<String> = entries
                     .iter()
@@ -632,12 +905,22 @@ impl RustServiceTools {
     #[tool(description = "Move or rename a file from source to destination.")]
     pub async fn move_file(
         &self,
-        #[tool(param)] src: String,
-        #[tool(param)] dest: String,
+        #[tool(param)]
+        #[schemars(description = "Source file path")]
+        src: String,
+        #[tool(param)]
+        #[schemars(description = "Destination file path")]
+        dest: String,
     ) -> Result<CallToolResult, rmcp::Error> {
         eprintln!("MCP move_file: {} -> {}", src, dest);
+        let (src_path, dest_path) = match load_sandbox()
+            .and_then(|sandbox| Ok((sandboxed(&sandbox, &src)?, sandboxed(&sandbox, &dest)?)))
+        {
+            Ok(paths) => paths,
+            Err(denied) => return Ok(denied),
+        };
 
-        match fs::rename(&src, &dest) {
+        match fs::rename(&src_path, &dest_path) {
             Ok(_) => Ok(CallToolResult::success(vec![Content::text(format!(
                 "Moved {} to {}",
                 src, dest
@@ -653,12 +936,22 @@ impl RustServiceTools {
     #[tool(description = "Copy a file from source to destination.")]
     pub async fn copy_file(
         &self,
-        #[tool(param)] src: String,
-        #[tool(param)] dest: String,
+        #[tool(param)]
+        #[schemars(description = "Source file path")]
+        src: String,
+        #[tool(param)]
+        #[schemars(description = "Destination file path")]
+        dest: String,
     ) -> Result<CallToolResult, rmcp::Error> {
         eprintln!("MCP copy_file: {} -> {}", src, dest);
+        let (src_path, dest_path) = match load_sandbox()
+            .and_then(|sandbox| Ok((sandboxed(&sandbox, &src)?, sandboxed(&sandbox, &dest)?)))
+        {
+            Ok(paths) => paths,
+            Err(denied) => return Ok(denied),
+        };
 
-        match fs::copy(&src, &dest) {
+        match fs::copy(&src_path, &dest_path) {
             Ok(bytes) => Ok(CallToolResult::success(vec![Content::text(format!(
                 "Copied {} ({}) to {}",
                 src,
@@ -753,7 +1046,9 @@ impl RustServiceTools {
     )]
     pub async fn search_web(
         &self,
-        #[tool(param)] query: String,
+        #[tool(param)]
+        #[schemars(description = "The search query")]
+        query: String,
     ) -> Result<CallToolResult, rmcp::Error> {
         eprintln!("MCP search_web: {}", query);
 
@@ -922,10 +1217,23 @@ impl RustServiceTools {
     #[tool(description = "Run a custom instrument (script) by name.")]
     pub async fn run_instrument(
         &self,
-        #[tool(param)] name: String,
-        #[tool(param)] args: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Name of the instrument to run")]
+        name: String,
+        #[tool(param)]
+        #[schemars(description = "Optional arguments to pass to the instrument")]
+        args: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Seconds to wait for approval before returning the command ID (default 120, max 600)"
+        )]
+        wait_seconds: Option<u64>,
     ) -> Result<CallToolResult, rmcp::Error> {
-        eprintln!("MCP run_instrument: {} args={:?}", name, args);
+        eprintln!(
+            "MCP run_instrument: {} args={:?}",
+            name,
+            args.as_deref().map(redact_text)
+        );
 
         // Find the instrument
         let instruments = match list_instruments_in_folder() {
@@ -971,22 +1279,10 @@ impl RustServiceTools {
             }
         };
 
-        // Execute the instrument
-        match execute_shell_command(&command) {
-            Ok(result) => {
-                let output = if result.exit_code == 0 {
-                    format!(
-                        "Instrument '{}' completed successfully.\n\nOutput:\n{}",
-                        name, result.stdout
-                    )
-                } else {
-                    format!(
-                        "Instrument '{}' failed (exit code {}).\n\nStdout:\n{}\n\nStderr:\n{}",
-                        name, result.exit_code, result.stdout, result.stderr
-                    )
-                };
-                Ok(CallToolResult::success(vec![Content::text(output)]))
-            }
+        // Execute the instrument, subject to the approval mode
+        let reason = format!("Running instrument: {}", instrument.name);
+        match self.run_with_approval(command, reason, wait_seconds) {
+            Ok(cmd) => Ok(self.command_outcome(&cmd, &format!("Instrument '{}'", name))),
             Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
                 "Error running instrument '{}': {}",
                 name, e
@@ -0,0 +1,209 @@
+//! Report diff
+//!
+//! Compares two reports of the same machine ("before vs after") and
+//! produces a structured `ReportDiff`.
+//!
+//! Findings are matched per service by title with numbers masked out, so
+//! "Drive C: - 91% Used" and "Drive C: - 62% Used" count as the same
+//! finding; the numbers themselves are compared through `metrics`.
+
+use std::collections::{HashMap, HashSet};
+
+use super::metrics::extract_metrics;
+use crate::types::{
+    FindingChange, FindingSeverity, MetricDelta, ReportDiff, ServiceFinding, ServiceReport,
+    ServiceResult, ServiceStatusChange,
+};
+
+/// Title with every run of digits (and decimal separators between them) replaced by `#`
+fn finding_key(title: &str) -> String {
+    let mut key = String::with_capacity(title.len());
+    let mut chars = title.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch.is_ascii_digit() {
+            while chars
+                .peek()
+                .is_some_and(|c| c.is_ascii_digit() || *c == '.' || *c == ',')
+            {
+                chars.next();
+            }
+            key.push('#');
+        } else {
+            key.push(ch.to_ascii_lowercase());
+        }
+    }
+    key
+}
+
+/// Last result for each service (a service queued twice reports its final run)
+fn results_by_service(report: &ServiceReport) -> HashMap<&str, &ServiceResult> {
+    report
+        .results
+        .iter()
+        .map(|r| (r.service_id.as_str(), r))
+        .collect()
+}
+
+/// Service IDs in result order, without duplicates
+fn ordered_services(report: &ServiceReport) -> Vec<&str> {
+    let mut seen = HashSet::new();
+    report
+        .results
+        .iter()
+        .map(|r| r.service_id.as_str())
+        .filter(|id| seen.insert(*id))
+        .collect()
+}
+
+fn change(
+    service_id: &str,
+    finding: &ServiceFinding,
+    before: Option<&FindingSeverity>,
+    after: Option<&FindingSeverity>,
+) -> FindingChange {
+    FindingChange {
+        service_id: service_id.to_string(),
+        title: finding.title.clone(),
+        before_severity: before.cloned(),
+        after_severity: after.cloned(),
+    }
+}
+
+/// Compare the findings of one service between two runs
+fn diff_findings(before: &ServiceResult, after: &ServiceResult, diff: &mut ReportDiff) {
+    let service_id = after.service_id.as_str();
+
+    // Findings with the same key are paired up in order
+    let mut unmatched: HashMap<String, Vec<&ServiceFinding>> = HashMap::new();
+    for finding in before.findings.iter().rev() {
+        unmatched
+            .entry(finding_key(&finding.title))
+            .or_default()
+            .push(finding);
+    }
+
+    for finding in &after.findings {
+        match unmatched
+            .get_mut(&finding_key(&finding.title))
+            .and_then(Vec::pop)
+        {
+            Some(previous) if previous.severity != finding.severity => {
+                diff.severity_changes.push(change(
+                    service_id,
+                    finding,
+                    Some(&previous.severity),
+                    Some(&finding.severity),
+                ));
+            }
+            Some(_) => {}
+            None => {
+                diff.findings_appeared.push(change(
+                    service_id,
+                    finding,
+                    None,
+                    Some(&finding.severity),
+                ));
+            }
+        }
+    }
+
+    // Whatever wasn't matched is gone; report it in original order
+    for finding in &before.findings {
+        let key = finding_key(&finding.title);
+        if let Some(remaining) = unmatched.get_mut(&key) {
+            if let Some(pos) = remaining.iter().position(|f| std::ptr::eq(*f, finding)) {
+                remaining.remove(pos);
+                diff.findings_resolved.push(change(
+                    service_id,
+                    finding,
+                    Some(&finding.severity),
+                    None,
+                ));
+            }
+        }
+    }
+}
+
+/// Pair up metrics by (metric, subject); metrics in only one report have a single side
+fn diff_metrics(before: &ServiceReport, after: &ServiceReport) -> Vec<MetricDelta> {
+    let mut deltas: Vec<MetricDelta> = Vec::new();
+    let mut positions: HashMap<(&'static str, Option<String>), usize> = HashMap::new();
+
+    for (is_after, report) in [(false, before), (true, after)] {
+        for sample in extract_metrics(report) {
+            let key = (sample.metric, sample.subject.clone());
+            let index = *positions.entry(key).or_insert_with(|| {
+                deltas.push(MetricDelta {
+                    metric: sample.metric.to_string(),
+                    label: sample.label.to_string(),
+                    unit: sample.unit.to_string(),
+                    subject: sample.subject.clone(),
+                    before: None,
+                    after: None,
+                    delta: None,
+                });
+                deltas.len() - 1
+            });
+            // Later values of a repeated metric win, as with results_by_service
+            if is_after {
+                deltas[index].after = Some(sample.value);
+            } else {
+                deltas[index].before = Some(sample.value);
+            }
+        }
+    }
+
+    for delta in &mut deltas {
+        if let (Some(before), Some(after)) = (delta.before, delta.after) {
+            delta.delta = Some(after - before);
+        }
+    }
+    deltas
+}
+
+/// Compare two reports. `before` should be the earlier run.
+pub fn diff_reports(before: &ServiceReport, after: &ServiceReport) -> ReportDiff {
+    let mut diff = ReportDiff {
+        before_report_id: before.id.clone(),
+        after_report_id: after.id.clone(),
+        before_started_at: before.started_at.clone(),
+        after_started_at: after.started_at.clone(),
+        before_health_score: before.health_score,
+        after_health_score: after.health_score,
+        services_added: Vec::new(),
+        services_removed: Vec::new(),
+        status_changes: Vec::new(),
+        findings_appeared: Vec::new(),
+        findings_resolved: Vec::new(),
+        severity_changes: Vec::new(),
+        metric_deltas: diff_metrics(before, after),
+    };
+
+    let before_results = results_by_service(before);
+    let after_results = results_by_service(after);
+
+    diff.services_removed = ordered_services(before)
+        .into_iter()
+        .filter(|id| !after_results.contains_key(id))
+        .map(String::from)
+        .collect();
+
+    for service_id in ordered_services(after) {
+        let after_result = after_results[service_id];
+        let Some(before_result) = before_results.get(service_id) else {
+            diff.services_added.push(service_id.to_string());
+            continue;
+        };
+
+        if before_result.success != after_result.success {
+            diff.status_changes.push(ServiceStatusChange {
+                service_id: service_id.to_string(),
+                before_success: before_result.success,
+                after_success: after_result.success,
+            });
+        }
+        diff_findings(before_result, after_result, &mut diff);
+    }
+
+    diff
+}
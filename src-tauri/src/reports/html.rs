@@ -0,0 +1,478 @@
+//! HTML report writer
+//!
+//! Renders a self-contained HTML document: all styling is inline in a
+//! `<style>` block and the business logo is embedded as a data URI, so the
+//! file can be emailed or opened offline without any assets.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use super::{
+    format_duration, format_metric, format_metric_delta, format_timestamp, service_names,
+    severity_label, status_label,
+};
+use crate::types::{
+    BusinessSettings, FindingChange, FindingSeverity, ReportDetailLevel, ReportDiff,
+    ReportStatistics, ServiceFinding, ServiceReport, ServiceResult,
+};
+
+/// Stylesheet embedded in every exported document
+const STYLESHEET: &str = r#"
+body { font-family: "Segoe UI", Helvetica, Arial, sans-serif; color: #1a1a1f; background: #f4f5f7; margin: 0; padding: 24px; }
+.report { max-width: 860px; margin: 0 auto; background: #fff; padding: 40px 48px; border-radius: 8px; box-shadow: 0 1px 4px rgba(0,0,0,0.08); }
+.business { display: flex; justify-content: space-between; align-items: flex-start; border-bottom: 1px solid #d0d4da; padding-bottom: 16px; margin-bottom: 16px; }
+.business img { max-width: 180px; max-height: 80px; }
+.business .contact { text-align: right; font-size: 13px; color: #6b7280; }
+.business .contact .name { font-size: 18px; font-weight: 700; color: #1a1a1f; }
+h1 { font-size: 26px; margin: 8px 0 16px; }
+h2 { font-size: 16px; background: #eff1f4; padding: 8px 12px; border-radius: 4px; margin: 24px 0 8px; display: flex; justify-content: space-between; }
+table.meta { border-collapse: collapse; font-size: 14px; }
+table.meta th { text-align: left; color: #6b7280; padding: 2px 24px 2px 0; font-weight: 600; }
+table.meta td { padding: 2px 0; }
+.badges { display: flex; gap: 6px; flex-wrap: wrap; margin-top: 8px; }
+.badge { color: #fff; font-size: 11px; font-weight: 700; padding: 2px 8px; border-radius: 3px; white-space: nowrap; }
+.passed { color: #218c45; }
+.failed { color: #cc2626; }
+.muted { color: #6b7280; font-size: 13px; }
+.error { color: #cc2626; font-weight: 600; }
+.finding { display: flex; gap: 12px; margin: 10px 0; }
+.finding .badge { align-self: flex-start; min-width: 56px; text-align: center; }
+.finding .title { font-weight: 600; }
+.finding p { margin: 2px 0; font-size: 14px; }
+.recommendation { color: #6b7280; }
+pre { background: #f6f7f9; border: 1px solid #e3e5e8; border-radius: 4px; padding: 8px; font-size: 12px; overflow-x: auto; white-space: pre-wrap; word-break: break-all; }
+table.data { border-collapse: collapse; width: 100%; font-size: 14px; }
+table.data th, table.data td { text-align: left; padding: 6px 8px; border-bottom: 1px solid #e3e5e8; }
+table.data th { color: #6b7280; font-weight: 600; }
+footer { margin-top: 32px; border-top: 1px solid #d0d4da; padding-top: 8px; font-size: 12px; color: #6b7280; }
+"#;
+
+/// Badge colour for a finding severity (matches the frontend palette)
+fn severity_color(severity: &FindingSeverity) -> &'static str {
+    match severity {
+        FindingSeverity::Info => "#3b82f6",
+        FindingSeverity::Success => "#22a34a",
+        FindingSeverity::Warning => "#d99a05",
+        FindingSeverity::Error => "#dc2626",
+        FindingSeverity::Critical => "#7c3aed",
+    }
+}
+
+/// Escape text for inclusion in HTML content or attribute values
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Escape text and preserve line breaks
+fn escape_multiline(text: &str) -> String {
+    escape(text).replace('\n', "<br>")
+}
+
+fn badge(severity: &FindingSeverity) -> String {
+    format!(
+        r#"<span class="badge" style="background:{}">{}</span>"#,
+        severity_color(severity),
+        severity_label(severity)
+    )
+}
+
+// =============================================================================
+// Report Sections
+// =============================================================================
+
+/// Business branding block: logo on the left, name and contact details on the right
+fn write_business_block(out: &mut String, business: &BusinessSettings, logo: Option<&str>) {
+    out.push_str("<div class=\"business\">\n<div>");
+    if let Some(src) = logo {
+        let _ = write!(out, r#"<img src="{}" alt="Logo">"#, escape(src));
+    }
+    out.push_str("</div>\n<div class=\"contact\">\n");
+    if !business.name.is_empty() {
+        let _ = writeln!(out, "<div class=\"name\">{}</div>", escape(&business.name));
+    }
+    let contact = [
+        ("", &business.address),
+        ("Phone: ", &business.phone),
+        ("Email: ", &business.email),
+        ("", &business.website),
+        ("ABN: ", &business.abn),
+        ("TFN: ", &business.tfn),
+    ];
+    for (label, value) in contact {
+        if !value.is_empty() {
+            let _ = writeln!(out, "<div>{}{}</div>", label, escape(value));
+        }
+    }
+    out.push_str("</div>\n</div>\n");
+}
+
+/// Title and report metadata
+fn write_header(out: &mut String, report: &ServiceReport, detail: ReportDetailLevel) {
+    out.push_str("<h1>Service Report</h1>\n<table class=\"meta\">\n");
+
+    let mut row = |label: &str, value: &str| {
+        let _ = writeln!(out, "<tr><th>{}</th><td>{}</td></tr>", label, escape(value));
+    };
+    if let Some(ref name) = report.customer_name {
+        row("Customer", name);
+    }
+    if let Some(ref name) = report.technician_name {
+        row("Technician", name);
+    }
+    row("Date", &format_timestamp(&report.started_at));
+    if detail == ReportDetailLevel::Technician {
+        if let Some(ref completed) = report.completed_at {
+            row("Completed", &format_timestamp(completed));
+        }
+    }
+    row("Status", status_label(&report.status));
+    if let Some(score) = report.health_score {
+        row("Health Score", &format!("{}/100", score));
+    }
+    row("Report ID", &report.id);
+    out.push_str("</table>\n");
+
+    if let Some(ref summary) = report.agent_summary {
+        let _ = writeln!(
+            out,
+            "<h2>Summary</h2>\n<p>{}</p>",
+            escape_multiline(summary)
+        );
+    }
+}
+
+/// Statistics overview
+fn write_summary(out: &mut String, stats: &ReportStatistics) {
+    out.push_str("<h2>Statistics</h2>\n<table class=\"meta\">\n");
+    let rows = [
+        ("Services Run", stats.total_services.to_string()),
+        ("Passed", stats.passed.to_string()),
+        ("Failed", stats.failed.to_string()),
+        ("Total Duration", format_duration(stats.total_duration_ms)),
+        ("Avg per Service", format_duration(stats.avg_duration_ms)),
+    ];
+    for (label, value) in rows {
+        let _ = writeln!(out, "<tr><th>{}</th><td>{}</td></tr>", label, value);
+    }
+    out.push_str("</table>\n<div class=\"badges\">\n");
+
+    let counts = [
+        (
+            FindingSeverity::Critical,
+            stats.findings_by_severity.critical,
+        ),
+        (FindingSeverity::Error, stats.findings_by_severity.error),
+        (FindingSeverity::Warning, stats.findings_by_severity.warning),
+        (FindingSeverity::Info, stats.findings_by_severity.info),
+        (FindingSeverity::Success, stats.findings_by_severity.success),
+    ];
+    for (severity, count) in counts {
+        let _ = writeln!(
+            out,
+            r#"<span class="badge" style="background:{}">{} {}</span>"#,
+            severity_color(&severity),
+            count,
+            severity_label(&severity)
+        );
+    }
+    out.push_str("</div>\n");
+}
+
+/// A single finding: coloured severity badge, title, description and recommendation
+fn write_finding(out: &mut String, finding: &ServiceFinding, detail: ReportDetailLevel) {
+    let _ = writeln!(
+        out,
+        "<div class=\"finding\">{}<div>\n<p class=\"title\">{}</p>",
+        badge(&finding.severity),
+        escape(&finding.title)
+    );
+    if !finding.description.is_empty() {
+        let _ = writeln!(out, "<p>{}</p>", escape_multiline(&finding.description));
+    }
+    if let Some(ref rec) = finding.recommendation {
+        let _ = writeln!(
+            out,
+            "<p class=\"recommendation\">Recommendation: {}</p>",
+            escape_multiline(rec)
+        );
+    }
+    if detail == ReportDetailLevel::Technician {
+        if let Some(ref data) = finding.data {
+            let json = serde_json::to_string_pretty(data).unwrap_or_default();
+            let _ = writeln!(out, "<pre>{}</pre>", escape(&json));
+        }
+    }
+    out.push_str("</div></div>\n");
+}
+
+/// Per-service sections with findings, analysis and (for technicians) logs
+fn write_findings(out: &mut String, results: &[ServiceResult], detail: ReportDetailLevel) {
+    let names = service_names();
+
+    for result in results {
+        let service_name = names
+            .get(&result.service_id)
+            .map(String::as_str)
+            .unwrap_or(&result.service_id);
+        let (status, class) = if result.success {
+            ("PASSED", "passed")
+        } else if result.timed_out {
+            ("TIMED OUT", "failed")
+        } else {
+            ("FAILED", "failed")
+        };
+
+        let _ = writeln!(
+            out,
+            "<section>\n<h2><span>{}</span><span class=\"{}\">{}</span></h2>",
+            escape(service_name),
+            class,
+            status
+        );
+        let _ = writeln!(
+            out,
+            "<p class=\"muted\">Duration: {}</p>",
+            format_duration(result.duration_ms)
+        );
+
+        if let Some(ref err) = result.error {
+            let _ = writeln!(out, "<p class=\"error\">Error: {}</p>", escape(err));
+        }
+
+        for finding in &result.findings {
+            write_finding(out, finding, detail);
+        }
+
+        if let Some(ref analysis) = result.agent_analysis {
+            let _ = writeln!(
+                out,
+                "<p><strong>Analysis</strong><br>{}</p>",
+                escape_multiline(analysis)
+            );
+        }
+
+        if detail == ReportDetailLevel::Technician && !result.logs.is_empty() {
+            let _ = writeln!(
+                out,
+                "<details>\n<summary class=\"muted\">Logs ({} lines)</summary>\n<pre>{}</pre>\n</details>",
+                result.logs.len(),
+                escape(&result.logs.join("\n"))
+            );
+        }
+
+        out.push_str("</section>\n");
+    }
+}
+
+/// Doctype, head with the embedded stylesheet, and the opening report container
+fn write_document_start(out: &mut String, title: &str) {
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<div class=\"report\">\n",
+        escape(title),
+        STYLESHEET
+    );
+}
+
+/// Footer and closing tags
+fn write_document_end(out: &mut String) {
+    let _ = write!(
+        out,
+        "<footer>Generated by RustService - {}</footer>\n</div>\n</body>\n</html>\n",
+        escape(&format_timestamp(&chrono::Utc::now().to_rfc3339()))
+    );
+}
+
+// =============================================================================
+// Report Diff Sections
+// =============================================================================
+
+/// Findings under a heading, skipped when empty
+fn write_finding_changes(
+    out: &mut String,
+    heading: &str,
+    changes: &[FindingChange],
+    names: &HashMap<String, String>,
+) {
+    if changes.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "<h3>{}</h3>", heading);
+    for change in changes {
+        let service = names
+            .get(&change.service_id)
+            .map(String::as_str)
+            .unwrap_or(&change.service_id);
+        let badges = match (&change.before_severity, &change.after_severity) {
+            (Some(before), Some(after)) => format!("{} &rarr; {}", badge(before), badge(after)),
+            (Some(severity), None) | (None, Some(severity)) => badge(severity),
+            (None, None) => String::new(),
+        };
+        let _ = writeln!(
+            out,
+            "<div class=\"finding\">{}<div>\n<p class=\"title\">{}</p>\n<p class=\"muted\">{}</p>\n</div></div>",
+            badges,
+            escape(&change.title),
+            escape(service)
+        );
+    }
+}
+
+/// Services added, removed, or whose pass/fail result changed
+fn write_service_changes(out: &mut String, diff: &ReportDiff, names: &HashMap<String, String>) {
+    if diff.services_added.is_empty()
+        && diff.services_removed.is_empty()
+        && diff.status_changes.is_empty()
+    {
+        return;
+    }
+    let name = |id: &str| escape(names.get(id).map(String::as_str).unwrap_or(id));
+    let status = |ok: bool| {
+        if ok {
+            r#"<span class="passed">PASSED</span>"#
+        } else {
+            r#"<span class="failed">FAILED</span>"#
+        }
+    };
+
+    out.push_str("<h2>Services</h2>\n<table class=\"data\">\n");
+    for id in &diff.services_added {
+        let _ = writeln!(out, "<tr><td>{}</td><td>Added</td></tr>", name(id));
+    }
+    for id in &diff.services_removed {
+        let _ = writeln!(out, "<tr><td>{}</td><td>Not run</td></tr>", name(id));
+    }
+    for change in &diff.status_changes {
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td>{} &rarr; {}</td></tr>",
+            name(&change.service_id),
+            status(change.before_success),
+            status(change.after_success)
+        );
+    }
+    out.push_str("</table>\n");
+}
+
+/// Table of numeric measurements before and after
+fn write_metric_deltas(out: &mut String, diff: &ReportDiff) {
+    if diff.metric_deltas.is_empty() {
+        return;
+    }
+    out.push_str("<h2>Measurements</h2>\n<table class=\"data\">\n<tr><th>Measurement</th><th>Before</th><th>After</th><th>Change</th></tr>\n");
+    for delta in &diff.metric_deltas {
+        let label = match delta.subject {
+            Some(ref subject) => format!("{} ({})", delta.label, subject),
+            None => delta.label.clone(),
+        };
+        let value = |v: Option<f64>| {
+            v.map(|v| format_metric(v, &delta.unit))
+                .unwrap_or_else(|| "-".into())
+        };
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape(&label),
+            escape(&value(delta.before)),
+            escape(&value(delta.after)),
+            escape(
+                &delta
+                    .delta
+                    .map(|d| format_metric_delta(d, &delta.unit))
+                    .unwrap_or_else(|| "-".into())
+            )
+        );
+    }
+    out.push_str("</table>\n");
+}
+
+// =============================================================================
+// Public API
+// =============================================================================
+
+/// Render a service report as a standalone HTML document.
+///
+/// `logo` is a `data:` URI; it is only used together with `business`.
+pub fn render_report(
+    report: &ServiceReport,
+    stats: &ReportStatistics,
+    business: Option<&BusinessSettings>,
+    logo: Option<&str>,
+    detail: ReportDetailLevel,
+) -> String {
+    let title = match report.customer_name {
+        Some(ref name) => format!("Service Report - {}", name),
+        None => "Service Report".to_string(),
+    };
+
+    let mut out = String::new();
+    write_document_start(&mut out, &title);
+
+    if let Some(business) = business {
+        write_business_block(&mut out, business, logo);
+    }
+    write_header(&mut out, report, detail);
+    write_summary(&mut out, stats);
+    write_findings(&mut out, &report.results, detail);
+
+    write_document_end(&mut out);
+    out
+}
+
+/// Render a before/after comparison of two reports as a standalone HTML document
+pub fn render_diff(
+    diff: &ReportDiff,
+    business: Option<&BusinessSettings>,
+    logo: Option<&str>,
+) -> String {
+    let names = service_names();
+    let mut out = String::new();
+    write_document_start(&mut out, "Service Comparison");
+
+    if let Some(business) = business {
+        write_business_block(&mut out, business, logo);
+    }
+
+    let score = |s: Option<u8>| {
+        s.map(|s| format!("{}/100", s))
+            .unwrap_or_else(|| "-".into())
+    };
+    let _ = writeln!(
+        out,
+        "<h1>Service Comparison</h1>\n<table class=\"data\">\n<tr><th></th><th>Before</th><th>After</th></tr>\n<tr><th>Date</th><td>{}</td><td>{}</td></tr>\n<tr><th>Health Score</th><td>{}</td><td>{}</td></tr>\n<tr><th>Report ID</th><td>{}</td><td>{}</td></tr>\n</table>",
+        escape(&format_timestamp(&diff.before_started_at)),
+        escape(&format_timestamp(&diff.after_started_at)),
+        score(diff.before_health_score),
+        score(diff.after_health_score),
+        escape(&diff.before_report_id),
+        escape(&diff.after_report_id)
+    );
+
+    write_service_changes(&mut out, diff, &names);
+    write_metric_deltas(&mut out, diff);
+
+    out.push_str("<h2>Findings</h2>\n");
+    write_finding_changes(&mut out, "Resolved", &diff.findings_resolved, &names);
+    write_finding_changes(&mut out, "New", &diff.findings_appeared, &names);
+    write_finding_changes(&mut out, "Severity Changed", &diff.severity_changes, &names);
+    if diff.findings_resolved.is_empty()
+        && diff.findings_appeared.is_empty()
+        && diff.severity_changes.is_empty()
+    {
+        out.push_str("<p class=\"muted\">No changes in findings.</p>\n");
+    }
+
+    write_document_end(&mut out);
+    out
+}
@@ -0,0 +1,352 @@
+//! Markdown report writer
+//!
+//! Renders a report as GitHub-flavoured Markdown for pasting into
+//! ticket systems and emails. The logo is omitted; branding is text only.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use super::{
+    format_duration, format_metric, format_metric_delta, format_timestamp, service_names,
+    severity_label, status_label,
+};
+use crate::types::{
+    BusinessSettings, FindingChange, ReportDetailLevel, ReportDiff, ReportStatistics,
+    ServiceFinding, ServiceReport, ServiceResult,
+};
+
+/// Escape characters that would otherwise be treated as inline Markdown
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(
+            ch,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|'
+        ) {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+/// Fence text in a code block, lengthening the fence if the text contains one
+fn code_block(out: &mut String, lang: &str, text: &str) {
+    let mut fence = "```".to_string();
+    while text.contains(&fence) {
+        fence.push('`');
+    }
+    let _ = writeln!(out, "{}{}\n{}\n{}\n", fence, lang, text, fence);
+}
+
+// =============================================================================
+// Report Sections
+// =============================================================================
+
+/// Business name and contact details
+fn write_business_block(out: &mut String, business: &BusinessSettings) {
+    if !business.name.is_empty() {
+        let _ = writeln!(out, "**{}**  ", escape(&business.name));
+    }
+    let contact = [
+        ("", &business.address),
+        ("Phone: ", &business.phone),
+        ("Email: ", &business.email),
+        ("", &business.website),
+        ("ABN: ", &business.abn),
+        ("TFN: ", &business.tfn),
+    ];
+    for (label, value) in contact {
+        if !value.is_empty() {
+            let _ = writeln!(out, "{}{}  ", label, escape(value));
+        }
+    }
+    out.push_str("\n---\n\n");
+}
+
+/// Title and report metadata
+fn write_header(out: &mut String, report: &ServiceReport, detail: ReportDetailLevel) {
+    out.push_str("# Service Report\n\n| | |\n|---|---|\n");
+
+    let mut row = |label: &str, value: &str| {
+        let _ = writeln!(out, "| **{}** | {} |", label, escape(value));
+    };
+    if let Some(ref name) = report.customer_name {
+        row("Customer", name);
+    }
+    if let Some(ref name) = report.technician_name {
+        row("Technician", name);
+    }
+    row("Date", &format_timestamp(&report.started_at));
+    if detail == ReportDetailLevel::Technician {
+        if let Some(ref completed) = report.completed_at {
+            row("Completed", &format_timestamp(completed));
+        }
+    }
+    row("Status", status_label(&report.status));
+    if let Some(score) = report.health_score {
+        row("Health Score", &format!("{}/100", score));
+    }
+    row("Report ID", &report.id);
+    out.push('\n');
+
+    if let Some(ref summary) = report.agent_summary {
+        let _ = writeln!(out, "## Summary\n\n{}\n", summary.trim());
+    }
+}
+
+/// Statistics overview
+fn write_summary(out: &mut String, stats: &ReportStatistics) {
+    let _ = writeln!(
+        out,
+        "## Statistics\n\n- **Services Run:** {}\n- **Passed:** {}\n- **Failed:** {}\n- **Total Duration:** {}\n- **Avg per Service:** {}",
+        stats.total_services,
+        stats.passed,
+        stats.failed,
+        format_duration(stats.total_duration_ms),
+        format_duration(stats.avg_duration_ms)
+    );
+    let counts = &stats.findings_by_severity;
+    let _ = writeln!(
+        out,
+        "- **Findings:** {} critical, {} errors, {} warnings, {} info, {} success\n",
+        counts.critical, counts.error, counts.warning, counts.info, counts.success
+    );
+}
+
+/// A single finding as a list item with its severity tag
+fn write_finding(out: &mut String, finding: &ServiceFinding, detail: ReportDetailLevel) {
+    let _ = writeln!(
+        out,
+        "- **[{}]** {}",
+        severity_label(&finding.severity),
+        escape(&finding.title)
+    );
+    if !finding.description.is_empty() {
+        for line in finding.description.lines() {
+            let _ = writeln!(out, "  {}", escape(line));
+        }
+    }
+    if let Some(ref rec) = finding.recommendation {
+        let _ = writeln!(out, "  *Recommendation:* {}", escape(rec));
+    }
+    if detail == ReportDetailLevel::Technician {
+        if let Some(ref data) = finding.data {
+            let json = serde_json::to_string_pretty(data).unwrap_or_default();
+            out.push('\n');
+            for line in json.lines() {
+                let _ = writeln!(out, "      {}", line);
+            }
+        }
+    }
+}
+
+/// Per-service sections with findings, analysis and (for technicians) logs
+fn write_findings(out: &mut String, results: &[ServiceResult], detail: ReportDetailLevel) {
+    let names = service_names();
+
+    for result in results {
+        let service_name = names
+            .get(&result.service_id)
+            .map(String::as_str)
+            .unwrap_or(&result.service_id);
+        let status = if result.success {
+            "PASSED"
+        } else if result.timed_out {
+            "TIMED OUT"
+        } else {
+            "FAILED"
+        };
+
+        let _ = writeln!(
+            out,
+            "## {} — {}\n\n*Duration: {}*\n",
+            escape(service_name),
+            status,
+            format_duration(result.duration_ms)
+        );
+
+        if let Some(ref err) = result.error {
+            let _ = writeln!(out, "**Error:** {}\n", escape(err));
+        }
+
+        if !result.findings.is_empty() {
+            for finding in &result.findings {
+                write_finding(out, finding, detail);
+            }
+            out.push('\n');
+        }
+
+        if let Some(ref analysis) = result.agent_analysis {
+            let _ = writeln!(out, "**Analysis:** {}\n", analysis.trim());
+        }
+
+        if detail == ReportDetailLevel::Technician && !result.logs.is_empty() {
+            out.push_str("<details><summary>Logs</summary>\n\n");
+            code_block(out, "text", &result.logs.join("\n"));
+            out.push_str("</details>\n\n");
+        }
+    }
+}
+
+// =============================================================================
+// Public API
+// =============================================================================
+
+/// Render a service report as Markdown
+pub fn render_report(
+    report: &ServiceReport,
+    stats: &ReportStatistics,
+    business: Option<&BusinessSettings>,
+    detail: ReportDetailLevel,
+) -> String {
+    let mut out = String::new();
+
+    if let Some(business) = business {
+        write_business_block(&mut out, business);
+    }
+    write_header(&mut out, report, detail);
+    write_summary(&mut out, stats);
+    write_findings(&mut out, &report.results, detail);
+
+    let _ = writeln!(
+        out,
+        "---\n\n*Generated by RustService - {}*",
+        format_timestamp(&chrono::Utc::now().to_rfc3339())
+    );
+    out
+}
+
+// =============================================================================
+// Report Diff
+// =============================================================================
+
+/// Bulleted list of findings under a heading, skipped when empty
+fn write_finding_changes(
+    out: &mut String,
+    heading: &str,
+    changes: &[FindingChange],
+    names: &HashMap<String, String>,
+) {
+    if changes.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "### {}\n", heading);
+    for change in changes {
+        let service = names
+            .get(&change.service_id)
+            .map(String::as_str)
+            .unwrap_or(&change.service_id);
+        let severity = match (&change.before_severity, &change.after_severity) {
+            (Some(before), Some(after)) => {
+                format!("{} → {}", severity_label(before), severity_label(after))
+            }
+            (Some(severity), None) | (None, Some(severity)) => severity_label(severity).to_string(),
+            (None, None) => String::new(),
+        };
+        let _ = writeln!(
+            out,
+            "- **[{}]** {} *({})*",
+            severity,
+            escape(&change.title),
+            escape(service)
+        );
+    }
+    out.push('\n');
+}
+
+/// Render a before/after comparison of two reports as Markdown
+pub fn render_diff(diff: &ReportDiff, business: Option<&BusinessSettings>) -> String {
+    let names = service_names();
+    let name = |id: &str| escape(names.get(id).map(String::as_str).unwrap_or(id));
+    let mut out = String::new();
+
+    if let Some(business) = business {
+        write_business_block(&mut out, business);
+    }
+
+    let score = |s: Option<u8>| {
+        s.map(|s| format!("{}/100", s))
+            .unwrap_or_else(|| "-".into())
+    };
+    let _ = writeln!(
+        out,
+        "# Service Comparison\n\n| | Before | After |\n|---|---|---|\n| **Date** | {} | {} |\n| **Health Score** | {} | {} |\n| **Report ID** | {} | {} |\n",
+        format_timestamp(&diff.before_started_at),
+        format_timestamp(&diff.after_started_at),
+        score(diff.before_health_score),
+        score(diff.after_health_score),
+        diff.before_report_id,
+        diff.after_report_id
+    );
+
+    if !diff.services_added.is_empty()
+        || !diff.services_removed.is_empty()
+        || !diff.status_changes.is_empty()
+    {
+        out.push_str("## Services\n\n");
+        for id in &diff.services_added {
+            let _ = writeln!(out, "- Added: {}", name(id));
+        }
+        for id in &diff.services_removed {
+            let _ = writeln!(out, "- Not run: {}", name(id));
+        }
+        for change in &diff.status_changes {
+            let label = |ok: bool| if ok { "PASSED" } else { "FAILED" };
+            let _ = writeln!(
+                out,
+                "- {}: {} → {}",
+                name(&change.service_id),
+                label(change.before_success),
+                label(change.after_success)
+            );
+        }
+        out.push('\n');
+    }
+
+    if !diff.metric_deltas.is_empty() {
+        out.push_str(
+            "## Measurements\n\n| Measurement | Before | After | Change |\n|---|---|---|---|\n",
+        );
+        for delta in &diff.metric_deltas {
+            let label = match delta.subject {
+                Some(ref subject) => format!("{} ({})", delta.label, subject),
+                None => delta.label.clone(),
+            };
+            let value = |v: Option<f64>| {
+                v.map(|v| format_metric(v, &delta.unit))
+                    .unwrap_or_else(|| "-".into())
+            };
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} |",
+                escape(&label),
+                value(delta.before),
+                value(delta.after),
+                delta
+                    .delta
+                    .map(|d| format_metric_delta(d, &delta.unit))
+                    .unwrap_or_else(|| "-".into())
+            );
+        }
+        out.push('\n');
+    }
+
+    out.push_str("## Findings\n\n");
+    write_finding_changes(&mut out, "Resolved", &diff.findings_resolved, &names);
+    write_finding_changes(&mut out, "New", &diff.findings_appeared, &names);
+    write_finding_changes(&mut out, "Severity Changed", &diff.severity_changes, &names);
+    if diff.findings_resolved.is_empty()
+        && diff.findings_appeared.is_empty()
+        && diff.severity_changes.is_empty()
+    {
+        out.push_str("No changes in findings.\n\n");
+    }
+
+    let _ = writeln!(
+        out,
+        "---\n\n*Generated by RustService - {}*",
+        format_timestamp(&chrono::Utc::now().to_rfc3339())
+    );
+    out
+}
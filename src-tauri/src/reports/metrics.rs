@@ -0,0 +1,292 @@
+//! Report metrics
+//!
+//! Pulls well-known numeric values (drive wear, free space, battery
+//! capacity, startup items, cleanup results, benchmark speeds) out of the
+//! findings' `data` payloads so they can be compared across reports.
+
+use serde_json::Value;
+
+use crate::types::ServiceReport;
+
+/// A numeric value extracted from a report
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricSample {
+    /// Metric identifier (e.g. "smart_wear")
+    pub metric: &'static str,
+    /// Human-readable metric name
+    pub label: &'static str,
+    /// Unit of `value`
+    pub unit: &'static str,
+    /// Drive, mount point, etc. when a report can contain several values
+    pub subject: Option<String>,
+    pub value: f64,
+}
+
+impl MetricSample {
+    fn new(
+        metric: &'static str,
+        label: &'static str,
+        unit: &'static str,
+        subject: Option<String>,
+        value: f64,
+    ) -> Self {
+        Self {
+            metric,
+            label,
+            unit,
+            subject,
+            value,
+        }
+    }
+}
+
+// =============================================================================
+// Per-Service Extractors
+// =============================================================================
+
+/// SSD wear per drive from the smartctl result
+fn smartctl_metrics(data: &Value, out: &mut Vec<MetricSample>) {
+    if data["type"] != "smartctl_result" {
+        return;
+    }
+    for drive in data["drives"].as_array().into_iter().flatten() {
+        let Some(wear) = drive["wearLevelPercent"].as_f64() else {
+            continue;
+        };
+        let model = drive["modelName"].as_str().unwrap_or("Unknown drive");
+        let subject = match drive["serialNumber"].as_str() {
+            Some(serial) => format!("{} ({})", model, serial),
+            None => model.to_string(),
+        };
+        out.push(MetricSample::new(
+            "smart_wear",
+            "Drive Wear",
+            "%",
+            Some(subject),
+            wear,
+        ));
+    }
+}
+
+/// Free space per mount point from the disk space summary
+fn disk_space_metrics(data: &Value, out: &mut Vec<MetricSample>) {
+    if data["type"] != "disk_summary" {
+        return;
+    }
+    for drive in data["drives"].as_array().into_iter().flatten() {
+        let (Some(mount), Some(free)) = (
+            drive["mountPoint"].as_str(),
+            drive["availableBytes"].as_f64(),
+        ) else {
+            continue;
+        };
+        out.push(MetricSample::new(
+            "disk_free",
+            "Free Disk Space",
+            "bytes",
+            Some(mount.to_string()),
+            free,
+        ));
+    }
+}
+
+/// Battery health and full charge capacity from the battery report
+fn battery_metrics(data: &Value, out: &mut Vec<MetricSample>) {
+    if data["type"] != "battery_report" {
+        return;
+    }
+    // Both capacities are 0 when powercfg couldn't report them
+    let design = data["designCapacityMwh"].as_f64().unwrap_or(0.0);
+    let full = data["fullChargeCapacityMwh"].as_f64().unwrap_or(0.0);
+    if design <= 0.0 || full <= 0.0 {
+        return;
+    }
+    let subject = data["batteryName"]
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .map(String::from);
+    if let Some(health) = data["healthPercent"].as_f64() {
+        out.push(MetricSample::new(
+            "battery_health",
+            "Battery Health",
+            "%",
+            subject.clone(),
+            health,
+        ));
+    }
+    out.push(MetricSample::new(
+        "battery_capacity",
+        "Battery Full Charge Capacity",
+        "mWh",
+        subject,
+        full,
+    ));
+}
+
+/// Download/upload speed and latency from the speed test
+fn speedtest_metrics(data: &Value, out: &mut Vec<MetricSample>) {
+    if data["type"] != "speedtest_result" {
+        return;
+    }
+    let values = [
+        (
+            "speedtest_download",
+            "Download Speed",
+            "Mbps",
+            "downloadMbps",
+        ),
+        ("speedtest_upload", "Upload Speed", "Mbps", "uploadMbps"),
+        ("speedtest_ping", "Ping", "ms", "pingMs"),
+    ];
+    for (metric, label, unit, key) in values {
+        if let Some(value) = data[key].as_f64() {
+            out.push(MetricSample::new(metric, label, unit, None, value));
+        }
+    }
+}
+
+/// Startup item counts from the startup optimizer
+fn startup_metrics(data: &Value, out: &mut Vec<MetricSample>) {
+    if data["type"] != "startup_optimize" {
+        return;
+    }
+    if let Some(total) = data["totalItems"].as_f64() {
+        out.push(MetricSample::new(
+            "startup_items",
+            "Startup Items",
+            "items",
+            None,
+            total,
+        ));
+    }
+    if let Some(items) = data["items"].as_array() {
+        let enabled = items.iter().filter(|i| i["enabled"] == true).count();
+        out.push(MetricSample::new(
+            "startup_enabled",
+            "Enabled Startup Items",
+            "items",
+            None,
+            enabled as f64,
+        ));
+    }
+    if let Some(unnecessary) = data["unnecessaryCount"].as_f64() {
+        out.push(MetricSample::new(
+            "startup_unnecessary",
+            "Unnecessary Startup Items",
+            "items",
+            None,
+            unnecessary,
+        ));
+    }
+}
+
+/// Space reclaimed and files removed by BleachBit
+fn bleachbit_metrics(data: &Value, out: &mut Vec<MetricSample>) {
+    if data["type"] != "bleachbit_summary" {
+        return;
+    }
+    if let Some(bytes) = data["space_recovered_bytes"].as_f64() {
+        out.push(MetricSample::new(
+            "bleachbit_reclaimed",
+            "Space Reclaimed",
+            "bytes",
+            None,
+            bytes,
+        ));
+    }
+    if let Some(files) = data["files_deleted"].as_f64() {
+        out.push(MetricSample::new(
+            "bleachbit_files_deleted",
+            "Files Deleted",
+            "files",
+            None,
+            files,
+        ));
+    }
+}
+
+/// Disk throughput per drive from the WinSAT summary
+fn winsat_metrics(data: &Value, out: &mut Vec<MetricSample>) {
+    if data["type"] != "winsat_summary" {
+        return;
+    }
+    let drive = data["drive"].as_str().map(String::from);
+    let values = [
+        ("winsat_seq_read", "Sequential Read", "sequentialRead"),
+        ("winsat_seq_write", "Sequential Write", "sequentialWrite"),
+        ("winsat_random_read", "Random Read", "randomRead"),
+        ("winsat_random_write", "Random Write", "randomWrite"),
+    ];
+    for (metric, label, key) in values {
+        if let Some(speed) = data["metrics"][key].as_f64() {
+            out.push(MetricSample::new(
+                metric,
+                label,
+                "MB/s",
+                drive.clone(),
+                speed,
+            ));
+        }
+    }
+}
+
+/// Network throughput from iPerf, per test direction
+fn iperf_metrics(data: &Value, out: &mut Vec<MetricSample>) {
+    if data["type"] != "iperf_result" {
+        return;
+    }
+    if let Some(throughput) = data["throughputMbps"].as_f64() {
+        out.push(MetricSample::new(
+            "iperf_throughput",
+            "Network Throughput",
+            "Mbps",
+            data["direction"].as_str().map(String::from),
+            throughput,
+        ));
+    }
+}
+
+/// Average frame rate from the FurMark stress test
+fn furmark_metrics(data: &Value, out: &mut Vec<MetricSample>) {
+    if data["type"] != "furmark_result" {
+        return;
+    }
+    if let Some(fps) = data["fps"]["avg"].as_f64() {
+        out.push(MetricSample::new(
+            "furmark_fps",
+            "GPU Average FPS",
+            "fps",
+            None,
+            fps,
+        ));
+    }
+}
+
+// =============================================================================
+// Public API
+// =============================================================================
+
+/// Extract every known metric from a report's finding data
+pub fn extract_metrics(report: &ServiceReport) -> Vec<MetricSample> {
+    let mut out = Vec::new();
+
+    for result in &report.results {
+        let extract: fn(&Value, &mut Vec<MetricSample>) = match result.service_id.as_str() {
+            "smartctl" => smartctl_metrics,
+            "disk-space" => disk_space_metrics,
+            "battery-report" => battery_metrics,
+            "speedtest" => speedtest_metrics,
+            "startup-optimize" => startup_metrics,
+            "bleachbit" => bleachbit_metrics,
+            "winsat" => winsat_metrics,
+            "iperf" => iperf_metrics,
+            "furmark" => furmark_metrics,
+            _ => continue,
+        };
+        for data in result.findings.iter().filter_map(|f| f.data.as_ref()) {
+            extract(data, &mut out);
+        }
+    }
+
+    out
+}
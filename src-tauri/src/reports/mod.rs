@@ -0,0 +1,103 @@
+//! Service report rendering
+//!
+//! Turns a stored `ServiceReport` into customer-facing documents.
+//! Each output format lives in its own file; shared labels and
+//! formatting helpers are defined here so every format reads the same.
+
+pub mod diff;
+pub mod html;
+pub mod markdown;
+pub mod metrics;
+pub mod pdf;
+pub mod redaction;
+
+use std::collections::HashMap;
+
+use crate::services;
+use crate::types::{FindingSeverity, ServiceRunStatus};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Milliseconds per second (for duration formatting)
+pub const MS_PER_SECOND: f64 = 1000.0;
+
+/// Byte size units, largest first (for metric formatting)
+const BYTE_UNITS: [(&str, f64); 4] = [
+    ("TB", 1_099_511_627_776.0),
+    ("GB", 1_073_741_824.0),
+    ("MB", 1_048_576.0),
+    ("KB", 1024.0),
+];
+
+// =============================================================================
+// Shared Formatting Helpers
+// =============================================================================
+
+/// Short uppercase label for a finding severity
+pub fn severity_label(severity: &FindingSeverity) -> &'static str {
+    match severity {
+        FindingSeverity::Info => "INFO",
+        FindingSeverity::Success => "OK",
+        FindingSeverity::Warning => "WARN",
+        FindingSeverity::Error => "ERROR",
+        FindingSeverity::Critical => "CRITICAL",
+    }
+}
+
+/// Human-readable label for a run status
+pub fn status_label(status: &ServiceRunStatus) -> &'static str {
+    match status {
+        ServiceRunStatus::Pending => "Pending",
+        ServiceRunStatus::Running => "Running",
+        ServiceRunStatus::Paused => "Paused",
+        ServiceRunStatus::Completed => "Completed",
+        ServiceRunStatus::Failed => "Failed",
+        ServiceRunStatus::Cancelled => "Cancelled",
+    }
+}
+
+/// Format an RFC 3339 timestamp in local time, falling back to the raw string
+pub fn format_timestamp(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|dt| {
+            dt.with_timezone(&chrono::Local)
+                .format("%d %B %Y, %I:%M %p")
+                .to_string()
+        })
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+/// Format a millisecond duration as seconds with one decimal place
+pub fn format_duration(duration_ms: u64) -> String {
+    format!("{:.1}s", duration_ms as f64 / MS_PER_SECOND)
+}
+
+/// Map of service ID to display name, used for section headings
+pub fn service_names() -> HashMap<String, String> {
+    services::get_all_definitions()
+        .into_iter()
+        .map(|d| (d.id, d.name))
+        .collect()
+}
+
+/// Format a metric value with its unit; byte counts are scaled to KB/MB/GB/TB
+pub fn format_metric(value: f64, unit: &str) -> String {
+    match unit {
+        "bytes" => BYTE_UNITS
+            .iter()
+            .find(|(_, size)| value.abs() >= *size)
+            .map(|(label, size)| format!("{:.1} {}", value / size, label))
+            .unwrap_or_else(|| format!("{} B", value)),
+        "%" => format!("{:.0}%", value),
+        "items" | "files" => format!("{:.0} {}", value, unit),
+        _ => format!("{:.1} {}", value, unit),
+    }
+}
+
+/// Format a metric change with an explicit sign (e.g. "+2.5 GB", "-3 items")
+pub fn format_metric_delta(delta: f64, unit: &str) -> String {
+    let sign = if delta > 0.0 { "+" } else { "" };
+    format!("{}{}", sign, format_metric(delta, unit))
+}
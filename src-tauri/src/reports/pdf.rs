@@ -0,0 +1,875 @@
+//! PDF report writer
+//!
+//! Minimal PDF 1.4 generator for service reports. Text is set in the
+//! standard Helvetica fonts (no font embedding needed) using WinAnsi
+//! encoding, and the business logo is embedded as a JPEG image.
+
+use std::path::Path;
+
+use chrono::Utc;
+
+use super::{format_duration, format_timestamp, service_names, severity_label, status_label};
+use crate::types::{
+    BusinessSettings, FindingSeverity, ReportStatistics, ServiceFinding, ServiceReport,
+    ServiceResult,
+};
+
+// =============================================================================
+// Page Geometry
+// =============================================================================
+
+/// A4 page size in points
+const PAGE_WIDTH: f32 = 595.28;
+const PAGE_HEIGHT: f32 = 841.89;
+
+const MARGIN: f32 = 50.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+
+/// Space reserved at the bottom of each page for the footer
+const FOOTER_HEIGHT: f32 = 30.0;
+
+/// Indent for finding descriptions (past the severity badge)
+const FINDING_INDENT: f32 = 70.0;
+
+/// Maximum rendered logo size in points
+const LOGO_MAX_WIDTH: f32 = 140.0;
+const LOGO_MAX_HEIGHT: f32 = 60.0;
+
+/// Logos larger than this (in pixels) are downscaled before embedding
+const LOGO_MAX_PIXELS: u32 = 400;
+
+/// Line height as a multiple of font size
+const LINE_SPACING: f32 = 1.35;
+
+// =============================================================================
+// Colours
+// =============================================================================
+
+type Rgb = (f32, f32, f32);
+
+const TEXT_COLOR: Rgb = (0.1, 0.1, 0.12);
+const MUTED_COLOR: Rgb = (0.42, 0.45, 0.5);
+const RULE_COLOR: Rgb = (0.8, 0.82, 0.85);
+const SECTION_FILL: Rgb = (0.94, 0.95, 0.96);
+const PASS_COLOR: Rgb = (0.13, 0.55, 0.27);
+const FAIL_COLOR: Rgb = (0.8, 0.15, 0.15);
+const WHITE: Rgb = (1.0, 1.0, 1.0);
+
+/// Badge colour for a finding severity (matches the frontend palette)
+fn severity_color(severity: &FindingSeverity) -> Rgb {
+    match severity {
+        FindingSeverity::Info => (0.23, 0.51, 0.96),
+        FindingSeverity::Success => (0.13, 0.64, 0.29),
+        FindingSeverity::Warning => (0.85, 0.6, 0.02),
+        FindingSeverity::Error => (0.86, 0.15, 0.15),
+        FindingSeverity::Critical => (0.49, 0.23, 0.93),
+    }
+}
+
+// =============================================================================
+// Fonts & Text Metrics
+// =============================================================================
+
+#[derive(Clone, Copy)]
+enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn resource_name(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+        }
+    }
+}
+
+/// Helvetica glyph widths (1/1000 em) for ASCII 32..=126
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Helvetica-Bold glyph widths (1/1000 em) for ASCII 32..=126
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+    611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 333, 278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+    278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+/// Encode text as WinAnsi bytes for the standard Type 1 fonts.
+/// Characters outside the encoding are approximated or replaced with '?'.
+fn encode_win_ansi(text: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\t' => out.push(b' '),
+            ' '..='~' => out.push(ch as u8),
+            '\u{a0}'..='\u{ff}' => out.push(ch as u32 as u8),
+            '€' => out.push(0x80),
+            '…' => out.push(0x85),
+            '‘' => out.push(0x91),
+            '’' => out.push(0x92),
+            '“' => out.push(0x93),
+            '”' => out.push(0x94),
+            '•' => out.push(0x95),
+            '–' => out.push(0x96),
+            '—' => out.push(0x97),
+            '™' => out.push(0x99),
+            '→' => out.extend_from_slice(b"->"),
+            '✓' | '✔' => out.extend_from_slice(b"OK"),
+            c if c.is_control() => {}
+            _ => out.push(b'?'),
+        }
+    }
+    out
+}
+
+/// Width of WinAnsi-encoded text in points
+fn encoded_width(bytes: &[u8], font: Font, size: f32) -> f32 {
+    let table = match font {
+        Font::Regular => &HELVETICA_WIDTHS,
+        Font::Bold => &HELVETICA_BOLD_WIDTHS,
+    };
+    let units: u32 = bytes
+        .iter()
+        .map(|&b| match b {
+            32..=126 => table[(b - 32) as usize] as u32,
+            0x97 => 1000,
+            0x95 => 350,
+            _ => 556,
+        })
+        .sum();
+    units as f32 * size / 1000.0
+}
+
+/// Width of a string in points
+fn text_width(text: &str, font: Font, size: f32) -> f32 {
+    encoded_width(&encode_win_ansi(text), font, size)
+}
+
+/// Word-wrap text to fit within `max_width` points.
+/// Explicit newlines start a new line; words longer than a line are split.
+fn wrap_text(text: &str, font: Font, size: f32, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for paragraph in text.lines() {
+        let mut current = String::new();
+
+        for word in paragraph.split_whitespace() {
+            let candidate = if current.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", current, word)
+            };
+
+            if text_width(&candidate, font, size) <= max_width {
+                current = candidate;
+                continue;
+            }
+
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+
+            // Hard-break words that are wider than a whole line (paths, hashes)
+            if text_width(word, font, size) > max_width {
+                for ch in word.chars() {
+                    current.push(ch);
+                    if text_width(&current, font, size) > max_width {
+                        current.pop();
+                        lines.push(std::mem::take(&mut current));
+                        current.push(ch);
+                    }
+                }
+            } else {
+                current = word.to_string();
+            }
+        }
+
+        lines.push(current);
+    }
+
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+/// Escape a byte string for use as a PDF literal string
+fn pdf_string(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len() + 2);
+    out.push(b'(');
+    for &b in bytes {
+        if matches!(b, b'(' | b')' | b'\\') {
+            out.push(b'\\');
+        }
+        out.push(b);
+    }
+    out.push(b')');
+    out
+}
+
+// =============================================================================
+// Logo Image
+// =============================================================================
+
+/// A JPEG-encoded image ready to embed as a PDF XObject
+pub struct PdfImage {
+    width: u32,
+    height: u32,
+    jpeg: Vec<u8>,
+}
+
+/// Load a logo from disk and re-encode it as JPEG.
+/// Transparent areas are flattened onto white since JPEG has no alpha.
+pub fn load_logo(path: &Path) -> Option<PdfImage> {
+    let img = image::open(path).ok()?;
+    let img = if img.width() > LOGO_MAX_PIXELS || img.height() > LOGO_MAX_PIXELS {
+        img.thumbnail(LOGO_MAX_PIXELS, LOGO_MAX_PIXELS)
+    } else {
+        img
+    };
+
+    let rgba = img.to_rgba8();
+    let mut rgb = image::RgbImage::new(rgba.width(), rgba.height());
+    for (x, y, pixel) in rgba.enumerate_pixels() {
+        let alpha = pixel[3] as u32;
+        let blend = |c: u8| ((c as u32 * alpha + 255 * (255 - alpha)) / 255) as u8;
+        rgb.put_pixel(
+            x,
+            y,
+            image::Rgb([blend(pixel[0]), blend(pixel[1]), blend(pixel[2])]),
+        );
+    }
+
+    let mut jpeg = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, 90)
+        .encode_image(&rgb)
+        .ok()?;
+
+    Some(PdfImage {
+        width: rgb.width(),
+        height: rgb.height(),
+        jpeg,
+    })
+}
+
+// =============================================================================
+// Page Layout
+// =============================================================================
+
+/// Builds page content streams top-down, breaking pages as needed
+struct PageLayout {
+    pages: Vec<Vec<u8>>,
+    content: Vec<u8>,
+    /// Current baseline position (points from the bottom of the page)
+    y: f32,
+}
+
+impl PageLayout {
+    fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            content: Vec::new(),
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    /// Start a new page if fewer than `height` points remain
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN + FOOTER_HEIGHT {
+            self.new_page();
+        }
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(std::mem::take(&mut self.content));
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn gap(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    fn text(&mut self, x: f32, y: f32, font: Font, size: f32, color: Rgb, text: &str) {
+        self.content.extend_from_slice(
+            format!(
+                "BT /{} {:.2} Tf {:.3} {:.3} {:.3} rg {:.2} {:.2} Td ",
+                font.resource_name(),
+                size,
+                color.0,
+                color.1,
+                color.2,
+                x,
+                y
+            )
+            .as_bytes(),
+        );
+        self.content
+            .extend_from_slice(&pdf_string(&encode_win_ansi(text)));
+        self.content.extend_from_slice(b" Tj ET\n");
+    }
+
+    fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Rgb) {
+        self.content.extend_from_slice(
+            format!(
+                "{:.3} {:.3} {:.3} rg {:.2} {:.2} {:.2} {:.2} re f\n",
+                color.0, color.1, color.2, x, y, width, height
+            )
+            .as_bytes(),
+        );
+    }
+
+    fn rule(&mut self, color: Rgb) {
+        self.content.extend_from_slice(
+            format!(
+                "{:.3} {:.3} {:.3} RG 0.75 w {:.2} {:.2} m {:.2} {:.2} l S\n",
+                color.0,
+                color.1,
+                color.2,
+                MARGIN,
+                self.y,
+                PAGE_WIDTH - MARGIN,
+                self.y
+            )
+            .as_bytes(),
+        );
+    }
+
+    fn image(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        self.content.extend_from_slice(
+            format!(
+                "q {:.2} 0 0 {:.2} {:.2} {:.2} cm /Im1 Do Q\n",
+                width, height, x, y
+            )
+            .as_bytes(),
+        );
+    }
+
+    /// Write a wrapped paragraph at the cursor, breaking pages between lines
+    fn paragraph(&mut self, x: f32, width: f32, font: Font, size: f32, color: Rgb, text: &str) {
+        let line_height = size * LINE_SPACING;
+        for line in wrap_text(text, font, size, width) {
+            self.ensure_space(line_height);
+            self.y -= line_height;
+            self.text(x, self.y, font, size, color, &line);
+        }
+    }
+
+    /// Write a "Label: value" row with the label in bold
+    fn field(&mut self, label: &str, value: &str) {
+        let size = 10.0;
+        let label_width = 95.0;
+        let lines = wrap_text(value, Font::Regular, size, CONTENT_WIDTH - label_width);
+        let line_height = size * LINE_SPACING;
+        self.ensure_space(line_height);
+        for (i, line) in lines.iter().enumerate() {
+            if i > 0 {
+                self.ensure_space(line_height);
+            }
+            self.y -= line_height;
+            if i == 0 {
+                self.text(MARGIN, self.y, Font::Bold, size, MUTED_COLOR, label);
+            }
+            self.text(
+                MARGIN + label_width,
+                self.y,
+                Font::Regular,
+                size,
+                TEXT_COLOR,
+                line,
+            );
+        }
+    }
+
+    /// Section heading with a shaded band
+    fn heading(&mut self, title: &str, trailing: Option<(&str, Rgb)>) {
+        let size = 12.0;
+        let band_height = 22.0;
+        self.ensure_space(band_height + 40.0);
+        self.y -= band_height;
+        self.fill_rect(MARGIN, self.y, CONTENT_WIDTH, band_height, SECTION_FILL);
+        self.text(
+            MARGIN + 8.0,
+            self.y + 7.0,
+            Font::Bold,
+            size,
+            TEXT_COLOR,
+            title,
+        );
+        if let Some((label, color)) = trailing {
+            let width = text_width(label, Font::Bold, 10.0);
+            self.text(
+                PAGE_WIDTH - MARGIN - 8.0 - width,
+                self.y + 7.5,
+                Font::Bold,
+                10.0,
+                color,
+                label,
+            );
+        }
+        self.gap(6.0);
+    }
+
+    fn finish(mut self) -> Vec<Vec<u8>> {
+        if !self.content.is_empty() || self.pages.is_empty() {
+            self.pages.push(self.content);
+        }
+        self.pages
+    }
+}
+
+// =============================================================================
+// Report Sections
+// =============================================================================
+
+/// Business branding block: logo on the left, name and contact details on the right
+fn write_business_block(
+    layout: &mut PageLayout,
+    business: &BusinessSettings,
+    logo: Option<&PdfImage>,
+) {
+    let top = layout.y;
+    let mut logo_bottom = top;
+
+    if let Some(img) = logo {
+        let scale = (LOGO_MAX_WIDTH / img.width as f32).min(LOGO_MAX_HEIGHT / img.height as f32);
+        let width = img.width as f32 * scale;
+        let height = img.height as f32 * scale;
+        layout.image(MARGIN, top - height, width, height);
+        logo_bottom = top - height;
+    }
+
+    let mut contact: Vec<String> = Vec::new();
+    if !business.address.is_empty() {
+        contact.push(business.address.clone());
+    }
+    if !business.phone.is_empty() {
+        contact.push(format!("Phone: {}", business.phone));
+    }
+    if !business.email.is_empty() {
+        contact.push(format!("Email: {}", business.email));
+    }
+    if !business.website.is_empty() {
+        contact.push(business.website.clone());
+    }
+    if !business.abn.is_empty() {
+        contact.push(format!("ABN: {}", business.abn));
+    }
+    if !business.tfn.is_empty() {
+        contact.push(format!("TFN: {}", business.tfn));
+    }
+
+    // Right-aligned text column
+    let right = PAGE_WIDTH - MARGIN;
+    let mut y = top;
+    if !business.name.is_empty() {
+        y -= 16.0;
+        let width = text_width(&business.name, Font::Bold, 14.0);
+        layout.text(
+            right - width,
+            y,
+            Font::Bold,
+            14.0,
+            TEXT_COLOR,
+            &business.name,
+        );
+    }
+    for line in &contact {
+        y -= 12.0;
+        let width = text_width(line, Font::Regular, 9.0);
+        layout.text(right - width, y, Font::Regular, 9.0, MUTED_COLOR, line);
+    }
+
+    layout.y = y.min(logo_bottom) - 12.0;
+    layout.rule(RULE_COLOR);
+    layout.gap(8.0);
+}
+
+/// Title and report metadata
+fn write_header(layout: &mut PageLayout, report: &ServiceReport) {
+    layout.gap(22.0);
+    layout.text(
+        MARGIN,
+        layout.y,
+        Font::Bold,
+        20.0,
+        TEXT_COLOR,
+        "Service Report",
+    );
+    layout.gap(8.0);
+
+    if let Some(ref name) = report.customer_name {
+        layout.field("Customer", name);
+    }
+    if let Some(ref name) = report.technician_name {
+        layout.field("Technician", name);
+    }
+    layout.field("Date", &format_timestamp(&report.started_at));
+    layout.field("Status", status_label(&report.status));
+    if let Some(score) = report.health_score {
+        layout.field("Health Score", &format!("{}/100", score));
+    }
+    layout.field("Report ID", &report.id);
+    layout.gap(12.0);
+
+    if let Some(ref summary) = report.agent_summary {
+        layout.heading("Summary", None);
+        layout.paragraph(
+            MARGIN,
+            CONTENT_WIDTH,
+            Font::Regular,
+            10.0,
+            TEXT_COLOR,
+            summary,
+        );
+        layout.gap(12.0);
+    }
+}
+
+/// Statistics overview
+fn write_summary(layout: &mut PageLayout, stats: &ReportStatistics) {
+    layout.heading("Statistics", None);
+    layout.field("Services Run", &stats.total_services.to_string());
+    layout.field("Passed", &stats.passed.to_string());
+    layout.field("Failed", &stats.failed.to_string());
+    layout.field("Total Duration", &format_duration(stats.total_duration_ms));
+    layout.field("Avg per Service", &format_duration(stats.avg_duration_ms));
+    layout.gap(8.0);
+
+    // Severity count badges
+    let counts = [
+        (
+            FindingSeverity::Critical,
+            stats.findings_by_severity.critical,
+        ),
+        (FindingSeverity::Error, stats.findings_by_severity.error),
+        (FindingSeverity::Warning, stats.findings_by_severity.warning),
+        (FindingSeverity::Info, stats.findings_by_severity.info),
+        (FindingSeverity::Success, stats.findings_by_severity.success),
+    ];
+    layout.ensure_space(20.0);
+    layout.y -= 16.0;
+    let mut x = MARGIN;
+    for (severity, count) in counts {
+        let label = format!("{} {}", count, severity_label(&severity));
+        let width = text_width(&label, Font::Bold, 9.0) + 12.0;
+        layout.fill_rect(x, layout.y - 4.0, width, 15.0, severity_color(&severity));
+        layout.text(x + 6.0, layout.y, Font::Bold, 9.0, WHITE, &label);
+        x += width + 6.0;
+    }
+    layout.gap(20.0);
+}
+
+/// A single finding: coloured severity badge, title, description and recommendation
+fn write_finding(layout: &mut PageLayout, finding: &ServiceFinding) {
+    let text_x = MARGIN + FINDING_INDENT;
+    let text_width_avail = CONTENT_WIDTH - FINDING_INDENT;
+    let title_lines = wrap_text(&finding.title, Font::Bold, 10.0, text_width_avail);
+
+    layout.ensure_space(10.0 * LINE_SPACING * (title_lines.len() as f32 + 1.0));
+    layout.y -= 10.0 * LINE_SPACING;
+
+    let label = severity_label(&finding.severity);
+    let color = severity_color(&finding.severity);
+    layout.fill_rect(MARGIN, layout.y - 3.5, FINDING_INDENT - 10.0, 13.0, color);
+    let label_width = text_width(label, Font::Bold, 8.0);
+    layout.text(
+        MARGIN + (FINDING_INDENT - 10.0 - label_width) / 2.0,
+        layout.y,
+        Font::Bold,
+        8.0,
+        WHITE,
+        label,
+    );
+
+    for (i, line) in title_lines.iter().enumerate() {
+        if i > 0 {
+            layout.ensure_space(10.0 * LINE_SPACING);
+            layout.y -= 10.0 * LINE_SPACING;
+        }
+        layout.text(text_x, layout.y, Font::Bold, 10.0, TEXT_COLOR, line);
+    }
+
+    if !finding.description.is_empty() {
+        layout.paragraph(
+            text_x,
+            text_width_avail,
+            Font::Regular,
+            9.5,
+            TEXT_COLOR,
+            &finding.description,
+        );
+    }
+    if let Some(ref rec) = finding.recommendation {
+        layout.paragraph(
+            text_x,
+            text_width_avail,
+            Font::Regular,
+            9.5,
+            MUTED_COLOR,
+            &format!("Recommendation: {}", rec),
+        );
+    }
+    layout.gap(5.0);
+}
+
+/// Per-service sections with findings and analysis
+fn write_findings(layout: &mut PageLayout, results: &[ServiceResult]) {
+    let names = service_names();
+
+    for result in results {
+        let service_name = names
+            .get(&result.service_id)
+            .map(String::as_str)
+            .unwrap_or(&result.service_id);
+        let (status, color) = if result.success {
+            ("PASSED", PASS_COLOR)
+        } else if result.timed_out {
+            ("TIMED OUT", FAIL_COLOR)
+        } else {
+            ("FAILED", FAIL_COLOR)
+        };
+
+        layout.heading(service_name, Some((status, color)));
+        layout.paragraph(
+            MARGIN,
+            CONTENT_WIDTH,
+            Font::Regular,
+            9.0,
+            MUTED_COLOR,
+            &format!("Duration: {}", format_duration(result.duration_ms)),
+        );
+
+        if let Some(ref err) = result.error {
+            layout.paragraph(
+                MARGIN,
+                CONTENT_WIDTH,
+                Font::Bold,
+                10.0,
+                FAIL_COLOR,
+                &format!("Error: {}", err),
+            );
+        }
+
+        for finding in &result.findings {
+            write_finding(layout, finding);
+        }
+
+        if let Some(ref analysis) = result.agent_analysis {
+            layout.gap(4.0);
+            layout.paragraph(
+                MARGIN,
+                CONTENT_WIDTH,
+                Font::Bold,
+                10.0,
+                TEXT_COLOR,
+                "Analysis",
+            );
+            layout.paragraph(
+                MARGIN,
+                CONTENT_WIDTH,
+                Font::Regular,
+                9.5,
+                TEXT_COLOR,
+                analysis,
+            );
+        }
+
+        layout.gap(14.0);
+    }
+}
+
+/// Append the generated-by line and page numbers to every page
+fn write_footers(pages: &mut [Vec<u8>]) {
+    let generated = format!(
+        "Generated by RustService - {}",
+        format_timestamp(&Utc::now().to_rfc3339())
+    );
+    let total = pages.len();
+
+    for (index, content) in pages.iter_mut().enumerate() {
+        let mut footer = PageLayout::new();
+        footer.y = MARGIN;
+        footer.rule(RULE_COLOR);
+        footer.text(
+            MARGIN,
+            MARGIN - 14.0,
+            Font::Regular,
+            8.0,
+            MUTED_COLOR,
+            &generated,
+        );
+        let page_label = format!("Page {} of {}", index + 1, total);
+        let width = text_width(&page_label, Font::Regular, 8.0);
+        footer.text(
+            PAGE_WIDTH - MARGIN - width,
+            MARGIN - 14.0,
+            Font::Regular,
+            8.0,
+            MUTED_COLOR,
+            &page_label,
+        );
+        content.extend_from_slice(&footer.content);
+    }
+}
+
+// =============================================================================
+// Document Assembly
+// =============================================================================
+
+/// Serializes numbered PDF objects and tracks their byte offsets for the xref table
+struct PdfWriter {
+    out: Vec<u8>,
+    offsets: Vec<usize>,
+}
+
+impl PdfWriter {
+    fn new() -> Self {
+        let mut out = Vec::new();
+        // Binary comment marks the file as containing 8-bit data
+        out.extend_from_slice(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n");
+        Self {
+            out,
+            offsets: Vec::new(),
+        }
+    }
+
+    /// Write the next object (IDs are assigned sequentially from 1)
+    fn object(&mut self, body: &[u8]) {
+        self.offsets.push(self.out.len());
+        self.out
+            .extend_from_slice(format!("{} 0 obj\n", self.offsets.len()).as_bytes());
+        self.out.extend_from_slice(body);
+        self.out.extend_from_slice(b"\nendobj\n");
+    }
+
+    fn stream(&mut self, dict: &str, data: &[u8]) {
+        let mut body = format!("<< {} /Length {} >>\nstream\n", dict, data.len()).into_bytes();
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\nendstream");
+        self.object(&body);
+    }
+
+    fn finish(mut self, root_id: usize, info_id: usize) -> Vec<u8> {
+        let xref_offset = self.out.len();
+        let count = self.offsets.len() + 1;
+        self.out
+            .extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", count).as_bytes());
+        for offset in &self.offsets {
+            self.out
+                .extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        self.out.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root {} 0 R /Info {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+                count, root_id, info_id, xref_offset
+            )
+            .as_bytes(),
+        );
+        self.out
+    }
+}
+
+/// Assemble page content streams into a complete PDF file.
+///
+/// Object layout: 1 catalog, 2 page tree, 3-4 fonts, optional logo image,
+/// then a page + content pair per page, and finally the info dictionary.
+fn assemble(pages: &[Vec<u8>], logo: Option<&PdfImage>, title: &str) -> Vec<u8> {
+    let mut writer = PdfWriter::new();
+
+    let first_page_id = if logo.is_some() { 6 } else { 5 };
+    let page_ids: Vec<usize> = (0..pages.len()).map(|i| first_page_id + i * 2).collect();
+    let info_id = first_page_id + pages.len() * 2;
+
+    writer.object(b"<< /Type /Catalog /Pages 2 0 R >>");
+    let kids: Vec<String> = page_ids.iter().map(|id| format!("{} 0 R", id)).collect();
+    writer.object(
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        )
+        .as_bytes(),
+    );
+    writer.object(
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>",
+    );
+    writer.object(
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>",
+    );
+
+    let xobjects = if let Some(img) = logo {
+        writer.stream(
+            &format!(
+                "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode",
+                img.width, img.height
+            ),
+            &img.jpeg,
+        );
+        " /XObject << /Im1 5 0 R >>"
+    } else {
+        ""
+    };
+
+    for (page_id, content) in page_ids.iter().zip(pages) {
+        writer.object(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >>{} >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                xobjects,
+                page_id + 1
+            )
+            .as_bytes(),
+        );
+        writer.stream("", content);
+    }
+
+    let mut info = b"<< /Title ".to_vec();
+    info.extend_from_slice(&pdf_string(&encode_win_ansi(title)));
+    info.extend_from_slice(b" /Producer (RustService) >>");
+    writer.object(&info);
+
+    writer.finish(1, info_id)
+}
+
+// =============================================================================
+// Public API
+// =============================================================================
+
+/// Render a service report as PDF bytes.
+///
+/// `business` adds the branding/contact block when business mode is enabled.
+pub fn render_report(
+    report: &ServiceReport,
+    stats: &ReportStatistics,
+    business: Option<&BusinessSettings>,
+    logo: Option<&PdfImage>,
+) -> Vec<u8> {
+    let mut layout = PageLayout::new();
+
+    if let Some(business) = business {
+        write_business_block(&mut layout, business, logo);
+    }
+    write_header(&mut layout, report);
+    write_summary(&mut layout, stats);
+    write_findings(&mut layout, &report.results);
+
+    let mut pages = layout.finish();
+    write_footers(&mut pages);
+
+    let title = match report.customer_name {
+        Some(ref name) => format!("Service Report - {}", name),
+        None => "Service Report".to_string(),
+    };
+    let logo = if business.is_some() { logo } else { None };
+    assemble(&pages, logo, &title)
+}
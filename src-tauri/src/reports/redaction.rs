@@ -0,0 +1,330 @@
+//! Report redaction
+//!
+//! Applies a `ReportRedactionProfile` to a report before it is shown to
+//! an audience, dropping hidden services/severities, the machine's
+//! hostname and serials, and scrubbing file paths and user names from free
+//! text, agent text and finding data.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::types::{ReportRedactionProfile, ReportsSettings, ServiceReport};
+
+/// Placeholder substituted for redacted file paths
+const PATH_PLACEHOLDER: &str = "[path]";
+
+/// Placeholder substituted for redacted user names
+const USER_PLACEHOLDER: &str = "[user]";
+
+/// User names shorter than this are not replaced as free words (too many false matches)
+const MIN_USERNAME_LEN: usize = 3;
+
+/// User profile folders: `C:\Users\name`, `/home/name`, `/Users/name`
+static PROFILE_DIR_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)(\\Users\\|\\Documents and Settings\\|/home/|/Users/)[^\\/\s"'<>|:]+"#)
+        .unwrap()
+});
+
+/// Windows drive paths, UNC paths and multi-segment Unix paths.
+/// Windows directory names may contain spaces (e.g. `C:\Program Files\`).
+static PATH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)(^|[\s"'(=\[])((?:[a-z]:\\|\\\\[^\\\s"']+\\)(?:[^\\\r\n"'<>|]*\\)*[^\s"'<>|]*|/(?:[\w.\[\]-]+/)+[\w.\[\]-]*)"#,
+    )
+    .unwrap()
+});
+
+/// Look up a redaction profile by name (case-insensitive)
+pub fn find_profile<'a>(
+    settings: &'a ReportsSettings,
+    name: &str,
+) -> Result<&'a ReportRedactionProfile, String> {
+    settings
+        .redaction_profiles
+        .iter()
+        .find(|p| p.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("Unknown redaction profile: {}", name))
+}
+
+/// Name of the user running the app, if long enough to redact safely
+fn current_username() -> Option<String> {
+    std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .ok()
+        .filter(|u| u.len() >= MIN_USERNAME_LEN)
+}
+
+/// Scrub free text according to the profile's path/user name settings
+fn redact_text(text: &str, profile: &ReportRedactionProfile, username: Option<&Regex>) -> String {
+    let mut out = text.to_string();
+
+    if profile.redact_usernames {
+        out = PROFILE_DIR_RE
+            .replace_all(&out, format!("${{1}}{}", USER_PLACEHOLDER))
+            .into_owned();
+        if let Some(re) = username {
+            out = re.replace_all(&out, USER_PLACEHOLDER).into_owned();
+        }
+    }
+
+    if profile.redact_paths {
+        out = PATH_RE
+            .replace_all(&out, format!("${{1}}{}", PATH_PLACEHOLDER))
+            .into_owned();
+    }
+
+    out
+}
+
+/// Scrub every string inside a finding's data
+fn redact_json(value: &mut serde_json::Value, redact: &impl Fn(&str) -> String) {
+    match value {
+        serde_json::Value::String(text) => *text = redact(text),
+        serde_json::Value::Array(items) => {
+            for item in items {
+                redact_json(item, redact);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for item in map.values_mut() {
+                redact_json(item, redact);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Apply a redaction profile to a report in place
+pub fn apply_profile(report: &mut ServiceReport, profile: &ReportRedactionProfile) {
+    let username = if profile.redact_usernames {
+        current_username().and_then(|u| Regex::new(&format!(r"(?i)\b{}\b", regex::escape(&u))).ok())
+    } else {
+        None
+    };
+    let scrub_text = profile.redact_paths || profile.redact_usernames;
+    let redact = |text: &str| redact_text(text, profile, username.as_ref());
+
+    if !profile.include_technician_name {
+        report.technician_name = None;
+    }
+    if !profile.include_agent_summary {
+        report.agent_summary = None;
+    }
+    if !profile.include_machine_identity {
+        // The key is a hash, so the report still links to the machine's history
+        if let Some(machine) = &mut report.machine {
+            machine.hostname.clear();
+            machine.system_uuid = None;
+            machine.system_serial = None;
+            machine.board_serial = None;
+        }
+    }
+    if scrub_text {
+        report.agent_summary = report.agent_summary.as_deref().map(redact);
+    }
+
+    report
+        .results
+        .retain(|r| !profile.hidden_services.contains(&r.service_id));
+    report
+        .queue
+        .retain(|q| !profile.hidden_services.contains(&q.service_id));
+
+    for result in &mut report.results {
+        result
+            .findings
+            .retain(|f| !profile.hidden_severities.contains(&f.severity));
+
+        if !profile.include_logs {
+            result.logs.clear();
+        }
+        if !profile.include_agent_analysis {
+            result.agent_analysis = None;
+        }
+
+        for finding in &mut result.findings {
+            if !profile.include_finding_data {
+                finding.data = None;
+            }
+            if scrub_text {
+                finding.title = redact(&finding.title);
+                finding.description = redact(&finding.description);
+                finding.recommendation = finding.recommendation.as_deref().map(redact);
+                if let Some(data) = &mut finding.data {
+                    redact_json(data, &redact);
+                }
+            }
+        }
+
+        if scrub_text {
+            result.error = result.error.as_deref().map(redact);
+            result.agent_analysis = result.agent_analysis.as_deref().map(redact);
+            for line in &mut result.logs {
+                *line = redact(line);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FindingSeverity;
+
+    fn profile(redact_paths: bool, redact_usernames: bool) -> ReportRedactionProfile {
+        ReportRedactionProfile {
+            name: "test".to_string(),
+            hidden_severities: Vec::new(),
+            hidden_services: Vec::new(),
+            include_logs: true,
+            include_finding_data: true,
+            include_agent_analysis: true,
+            include_agent_summary: true,
+            include_technician_name: true,
+            include_machine_identity: true,
+            redact_paths,
+            redact_usernames,
+        }
+    }
+
+    fn report() -> ServiceReport {
+        serde_json::from_value(serde_json::json!({
+            "id": "3f0c2a52-7f57-4c1f-9d43-2d6a1c9f4e10",
+            "startedAt": "2026-01-01T00:00:00Z",
+            "status": "completed",
+            "queue": [],
+            "currentServiceIndex": null,
+            "technicianName": "Alex",
+            "agentSummary": "Cleaned C:\\Users\\jsmith\\AppData\\Local\\Temp",
+            "machine": {
+                "machineKey": "abc123",
+                "hostname": "JSMITH-LAPTOP",
+                "systemUuid": "4C4C4544-0037-3010-8052-B7C04F4E3732",
+                "systemSerial": "7C3RB72",
+                "boardSerial": "/7C3RB72/CNWS2007A1/",
+                "vendor": "Dell Inc.",
+                "model": "Latitude 5420"
+            },
+            "results": [{
+                "serviceId": "disk-space",
+                "success": true,
+                "durationMs": 10,
+                "findings": [
+                    {
+                        "severity": "info",
+                        "title": "Scanned /home/jsmith/docs",
+                        "description": "ok",
+                        "data": { "path": "D:\\Backups\\old.zip" }
+                    },
+                    { "severity": "warning", "title": "Low space", "description": "C: is 95% full" }
+                ],
+                "logs": ["Reading C:\\Windows\\System32\\config"]
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn redacts_profile_folders() {
+        let text = redact_text(
+            "Opened C:\\Users\\jsmith\\file.txt",
+            &profile(false, true),
+            None,
+        );
+        assert_eq!(text, "Opened C:\\Users\\[user]\\file.txt");
+
+        let text = redact_text("see /home/jsmith/notes", &profile(false, true), None);
+        assert_eq!(text, "see /home/[user]/notes");
+    }
+
+    #[test]
+    fn redacts_current_username_as_a_word() {
+        let re = Regex::new(r"(?i)\bjsmith\b").unwrap();
+        let text = redact_text(
+            "Owner: JSmith (jsmithson)",
+            &profile(false, true),
+            Some(&re),
+        );
+        assert_eq!(text, "Owner: [user] (jsmithson)");
+    }
+
+    #[test]
+    fn redacts_paths() {
+        let p = profile(true, false);
+        assert_eq!(
+            redact_text("Wrote C:\\Program Files\\App\\log.txt today", &p, None),
+            "Wrote [path] today"
+        );
+        assert_eq!(
+            redact_text("share \\\\nas\\backup\\x", &p, None),
+            "share [path]"
+        );
+        assert_eq!(redact_text("file /var/log/syslog", &p, None), "file [path]");
+        assert_eq!(redact_text("C: is 95% full", &p, None), "C: is 95% full");
+    }
+
+    #[test]
+    fn leaves_text_alone_without_redaction() {
+        let text = "C:\\Users\\jsmith\\file.txt";
+        assert_eq!(redact_text(text, &profile(false, false), None), text);
+    }
+
+    #[test]
+    fn applies_customer_profile() {
+        let mut report = report();
+        let customer = ReportRedactionProfile {
+            hidden_severities: vec![FindingSeverity::Info],
+            include_logs: false,
+            include_finding_data: false,
+            include_technician_name: false,
+            include_machine_identity: false,
+            ..profile(true, true)
+        };
+        apply_profile(&mut report, &customer);
+
+        assert_eq!(report.technician_name, None);
+        assert_eq!(report.agent_summary.as_deref(), Some("Cleaned [path]"));
+        let result = &report.results[0];
+        assert!(result.logs.is_empty());
+        assert_eq!(result.findings.len(), 1);
+        assert_eq!(result.findings[0].title, "Low space");
+        assert!(result.findings[0].data.is_none());
+
+        let machine = report.machine.unwrap();
+        assert_eq!(machine.machine_key, "abc123");
+        assert!(machine.hostname.is_empty());
+        assert_eq!(machine.system_uuid, None);
+        assert_eq!(machine.system_serial, None);
+        assert_eq!(machine.board_serial, None);
+        assert_eq!(machine.model.as_deref(), Some("Latitude 5420"));
+    }
+
+    #[test]
+    fn technician_profile_keeps_everything() {
+        let mut report = report();
+        let before = serde_json::to_value(&report).unwrap();
+        apply_profile(&mut report, &profile(false, false));
+        assert_eq!(serde_json::to_value(&report).unwrap(), before);
+    }
+
+    #[test]
+    fn scrubs_finding_data_strings() {
+        let mut report = report();
+        apply_profile(&mut report, &profile(true, false));
+        let data = report.results[0].findings[0].data.as_ref().unwrap();
+        assert_eq!(data["path"], "[path]");
+        assert_eq!(report.results[0].findings[0].title, "Scanned [path]");
+    }
+
+    #[test]
+    fn hides_services() {
+        let mut report = report();
+        let p = ReportRedactionProfile {
+            hidden_services: vec!["disk-space".to_string()],
+            ..profile(false, false)
+        };
+        apply_profile(&mut report, &p);
+        assert!(report.results.is_empty());
+    }
+}
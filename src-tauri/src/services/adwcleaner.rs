@@ -14,7 +14,7 @@ use serde_json::json;
 use tauri::{AppHandle, Emitter};
 
 use crate::commands::get_program_exe_path;
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{FindingSeverity, ServiceDefinition, ServiceFinding, ServiceResult};
 
 // =============================================================================
@@ -39,7 +39,8 @@ impl Service for AdwCleanerService {
         }
     }
 
-    fn run(&self, _options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, _options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -83,6 +84,7 @@ impl Service for AdwCleanerService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
             Err(e) => {
@@ -99,6 +101,7 @@ impl Service for AdwCleanerService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -129,7 +132,7 @@ impl Service for AdwCleanerService {
         let output = match Command::new(&exe_path)
             .args(&args)
             .current_dir(&working_dir)
-            .output()
+            .watched_output()
         {
             Ok(output) => output,
             Err(e) => {
@@ -155,6 +158,7 @@ impl Service for AdwCleanerService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -341,6 +345,7 @@ impl Service for AdwCleanerService {
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
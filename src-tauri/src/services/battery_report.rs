@@ -14,7 +14,7 @@ use chrono::Utc;
 use serde_json::json;
 use tauri::{AppHandle, Emitter};
 
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{FindingSeverity, ServiceDefinition, ServiceFinding, ServiceResult};
 
 // =============================================================================
@@ -41,7 +41,8 @@ impl Service for BatteryReportService {
         }
     }
 
-    fn run(&self, _options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, _options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -107,7 +108,7 @@ impl Service for BatteryReportService {
 
         let result = Command::new("powercfg")
             .args(["/batteryreport", "/output", &output_path_str])
-            .output();
+            .watched_output();
 
         let output = match result {
             Ok(output) => output,
@@ -134,6 +135,7 @@ impl Service for BatteryReportService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -173,6 +175,7 @@ impl Service for BatteryReportService {
                 findings,
                 logs,
                 agent_analysis: None,
+                timed_out: false,
             };
         }
 
@@ -200,6 +203,7 @@ impl Service for BatteryReportService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -324,6 +328,7 @@ impl Service for BatteryReportService {
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
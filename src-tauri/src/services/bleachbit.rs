@@ -13,7 +13,7 @@ use serde_json::json;
 use tauri::{AppHandle, Emitter};
 
 use crate::commands::get_program_exe_path;
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{FindingSeverity, ServiceDefinition, ServiceFinding, ServiceResult};
 
 // =============================================================================
@@ -39,7 +39,8 @@ impl Service for BleachBitService {
         }
     }
 
-    fn run(&self, _options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, _options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -96,6 +97,7 @@ impl Service for BleachBitService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
             Err(e) => {
@@ -112,6 +114,7 @@ impl Service for BleachBitService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -143,7 +146,7 @@ impl Service for BleachBitService {
         );
 
         // Execute BleachBit
-        let output = match Command::new(&exe_path).args(&args).output() {
+        let output = match Command::new(&exe_path).args(&args).watched_output() {
             Ok(output) => output,
             Err(e) => {
                 emit_log(
@@ -168,6 +171,7 @@ impl Service for BleachBitService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -251,6 +255,7 @@ impl Service for BleachBitService {
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
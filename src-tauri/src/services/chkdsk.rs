@@ -11,7 +11,7 @@ use regex::Regex;
 use serde_json::json;
 use tauri::{AppHandle, Emitter};
 
-use crate::services::Service;
+use crate::services::{Service, ServiceContext};
 use crate::types::{
     FindingSeverity, SelectOption, ServiceDefinition, ServiceFinding, ServiceOptionSchema,
     ServiceResult,
@@ -88,7 +88,8 @@ impl Service for ChkdskService {
         }
     }
 
-    fn run(&self, options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -150,6 +151,7 @@ impl Service for ChkdskService {
                 findings,
                 logs,
                 agent_analysis: None,
+                timed_out: false,
             };
         }
 
@@ -185,6 +187,8 @@ impl Service for ChkdskService {
             cmd.stdin(Stdio::piped());
         }
 
+        // Plain `output` rather than `watched_output`: killing CHKDSK mid-repair
+        // is worse than waiting for it, so the watchdog only abandons it
         let output = match cmd.output() {
             Ok(output) => output,
             Err(e) => {
@@ -210,6 +214,7 @@ impl Service for ChkdskService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -356,6 +361,7 @@ impl Service for ChkdskService {
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
@@ -0,0 +1,247 @@
+//! Service run context
+//!
+//! Handed to `Service::run` so a service can cooperate with the runner:
+//! stop early when the run is cancelled, hold at a safe point while the run
+//! is paused, report percent/phase progress, and log through a shared sink.
+//! Logs written to the sink survive even if the watchdog has to abandon the
+//! service.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use serde_json::json;
+use tauri::{AppHandle, Emitter};
+
+use crate::types::ServiceResult;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// How often a paused service rechecks for cancellation
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How often time-based progress is emitted
+const PROGRESS_TICK_INTERVAL: Duration = Duration::from_secs(2);
+
+// =============================================================================
+// Cancellation Token / Pause Gate
+// =============================================================================
+
+/// Flag shared by every service of a run; set when the user cancels
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Gate that services wait at while the run is paused
+#[derive(Debug, Clone, Default)]
+pub struct PauseGate(Arc<(Mutex<bool>, Condvar)>);
+
+impl PauseGate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn pause(&self) {
+        *self.0 .0.lock().unwrap() = true;
+    }
+
+    pub fn resume(&self) {
+        let (lock, cvar) = &*self.0;
+        *lock.lock().unwrap() = false;
+        cvar.notify_all();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.0 .0.lock().unwrap()
+    }
+
+    /// Block while the gate is closed.
+    /// Returns `true` if the run was cancelled (before or while waiting).
+    pub fn wait(&self, cancel: &CancellationToken) -> bool {
+        let (lock, cvar) = &*self.0;
+        let mut paused = lock.lock().unwrap();
+        while *paused && !cancel.is_cancelled() {
+            paused = cvar.wait_timeout(paused, PAUSE_POLL_INTERVAL).unwrap().0;
+        }
+        cancel.is_cancelled()
+    }
+}
+
+// =============================================================================
+// Service Context
+// =============================================================================
+
+/// Everything a running service can see of the run it belongs to
+pub struct ServiceContext {
+    app: AppHandle,
+    service_id: String,
+    cancel: CancellationToken,
+    pause: PauseGate,
+    /// Set when the runner stops this service alone (e.g. it timed out)
+    stopped: AtomicBool,
+    logs: Mutex<Vec<String>>,
+}
+
+impl ServiceContext {
+    pub fn new(
+        app: &AppHandle,
+        service_id: &str,
+        cancel: CancellationToken,
+        pause: PauseGate,
+    ) -> Self {
+        Self {
+            app: app.clone(),
+            service_id: service_id.to_string(),
+            cancel,
+            pause,
+            stopped: AtomicBool::new(false),
+            logs: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn app(&self) -> &AppHandle {
+        &self.app
+    }
+
+    pub fn service_id(&self) -> &str {
+        &self.service_id
+    }
+
+    /// Whether the service should stop: the run was cancelled, or the
+    /// runner has given up on this service
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled() || self.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Ask this service alone to stop; the rest of the run carries on
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.pause.is_paused()
+    }
+
+    /// Hold here while the run is paused.
+    /// Returns `true` if the service should stop.
+    pub fn wait_if_paused(&self) -> bool {
+        self.pause.wait(&self.cancel) || self.is_cancelled()
+    }
+
+    /// Append a line to the service log and stream it to the UI
+    pub fn log(&self, line: &str) {
+        self.logs.lock().unwrap().push(line.to_string());
+        let _ = self.app.emit(
+            "service-log",
+            json!({
+                "serviceId": self.service_id,
+                "log": line,
+                "timestamp": Utc::now().to_rfc3339()
+            }),
+        );
+    }
+
+    /// Lines logged so far
+    pub fn logs(&self) -> Vec<String> {
+        self.logs.lock().unwrap().clone()
+    }
+
+    /// Report progress within the service. `percent` is 0-100, or `None`
+    /// when the current phase has no measurable progress.
+    pub fn progress(&self, percent: Option<f64>, phase: &str) {
+        let _ = self.app.emit(
+            "service-task-progress",
+            json!({
+                "serviceId": self.service_id,
+                "percent": percent.map(|p| p.clamp(0.0, 100.0)),
+                "phase": phase,
+                "timestamp": Utc::now().to_rfc3339()
+            }),
+        );
+    }
+
+    /// Emit time-based progress for a phase expected to take `expected`
+    /// (e.g. a fixed-length stress test) until the returned guard is dropped.
+    /// Progress stops short of 100% so an overrunning phase doesn't look done.
+    pub fn progress_over(&self, phase: &str, expected: Duration) -> ProgressTicker {
+        let stop = Arc::new((Mutex::new(false), Condvar::new()));
+        let ticker_stop = Arc::clone(&stop);
+        let app = self.app.clone();
+        let service_id = self.service_id.clone();
+        let phase = phase.to_string();
+        let started = Instant::now();
+
+        std::thread::spawn(move || {
+            let (lock, cvar) = &*ticker_stop;
+            let mut stopped = lock.lock().unwrap();
+            while !*stopped {
+                let fraction = started.elapsed().as_secs_f64() / expected.as_secs_f64().max(1.0);
+                let _ = app.emit(
+                    "service-task-progress",
+                    json!({
+                        "serviceId": service_id,
+                        "percent": (fraction * 100.0).min(99.0),
+                        "phase": phase,
+                        "timestamp": Utc::now().to_rfc3339()
+                    }),
+                );
+                stopped = cvar
+                    .wait_timeout(stopped, PROGRESS_TICK_INTERVAL)
+                    .unwrap()
+                    .0;
+            }
+        });
+
+        ProgressTicker { stop }
+    }
+
+    /// Result for a service that stopped because the run was cancelled
+    /// or the runner stopped it
+    pub fn cancelled_result(&self, started: Instant) -> ServiceResult {
+        let reason = if self.cancel.is_cancelled() {
+            "Cancelled by user"
+        } else {
+            "Stopped by the runner"
+        };
+        self.log(reason);
+        ServiceResult {
+            service_id: self.service_id.clone(),
+            success: false,
+            error: Some(reason.to_string()),
+            duration_ms: started.elapsed().as_millis() as u64,
+            findings: vec![],
+            logs: self.logs(),
+            agent_analysis: None,
+            timed_out: false,
+        }
+    }
+}
+
+/// Stops time-based progress updates when dropped
+pub struct ProgressTicker {
+    stop: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl Drop for ProgressTicker {
+    fn drop(&mut self) {
+        let (lock, cvar) = &*self.stop;
+        *lock.lock().unwrap() = true;
+        cvar.notify_all();
+    }
+}
@@ -9,7 +9,7 @@ use serde_json::json;
 use sysinfo::Disks;
 use tauri::{AppHandle, Emitter};
 
-use crate::services::Service;
+use crate::services::{Service, ServiceContext};
 use crate::types::{FindingSeverity, ServiceDefinition, ServiceFinding, ServiceResult};
 
 // =============================================================================
@@ -34,7 +34,8 @@ impl Service for DiskSpaceService {
         }
     }
 
-    fn run(&self, _options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, _options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -183,6 +184,7 @@ impl Service for DiskSpaceService {
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
@@ -10,7 +10,7 @@ use chrono::Utc;
 use serde_json::json;
 use tauri::{AppHandle, Emitter};
 
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{
     FindingSeverity, ServiceDefinition, ServiceFinding, ServiceOptionSchema, ServiceResult,
 };
@@ -48,7 +48,8 @@ impl Service for DismService {
         }
     }
 
-    fn run(&self, options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -98,7 +99,7 @@ impl Service for DismService {
 
             let output = match Command::new("dism")
                 .args(["/Online", "/Cleanup-Image", &action_flag])
-                .output()
+                .watched_output()
             {
                 Ok(output) => output,
                 Err(e) => {
@@ -120,6 +121,7 @@ impl Service for DismService {
                         findings,
                         logs,
                         agent_analysis: None,
+                        timed_out: false,
                     };
                 }
             };
@@ -262,6 +264,7 @@ impl Service for DismService {
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
@@ -13,7 +13,7 @@ use serde_json::json;
 use tauri::{AppHandle, Emitter};
 
 use crate::commands::get_program_exe_path;
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{
     FindingSeverity, ServiceDefinition, ServiceFinding, ServiceOptionSchema, ServiceResult,
 };
@@ -52,7 +52,8 @@ impl Service for DriveCleanupService {
         }
     }
 
-    fn run(&self, options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -109,6 +110,7 @@ impl Service for DriveCleanupService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
             Err(e) => {
@@ -125,6 +127,7 @@ impl Service for DriveCleanupService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -154,7 +157,7 @@ impl Service for DriveCleanupService {
         let output = match Command::new(&exe_path)
             .args(&args)
             .current_dir(&working_dir)
-            .output()
+            .watched_output()
         {
             Ok(output) => output,
             Err(e) => {
@@ -180,6 +183,7 @@ impl Service for DriveCleanupService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -289,6 +293,7 @@ impl Service for DriveCleanupService {
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
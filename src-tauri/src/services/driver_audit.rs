@@ -10,7 +10,7 @@ use chrono::Utc;
 use serde_json::json;
 use tauri::{AppHandle, Emitter};
 
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{
     FindingSeverity, ServiceDefinition, ServiceFinding, ServiceOptionSchema, ServiceResult,
 };
@@ -47,7 +47,8 @@ impl Service for DriverAuditService {
         }
     }
 
-    fn run(&self, options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -75,7 +76,7 @@ impl Service for DriverAuditService {
         // Run driverquery with verbose CSV output
         let output = match Command::new("driverquery")
             .args(["/v", "/fo", "csv"])
-            .output()
+            .watched_output()
         {
             Ok(output) => output,
             Err(e) => {
@@ -99,6 +100,7 @@ impl Service for DriverAuditService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -224,6 +226,7 @@ impl Service for DriverAuditService {
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
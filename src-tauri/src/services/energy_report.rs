@@ -11,7 +11,7 @@ use chrono::Utc;
 use serde_json::json;
 use tauri::{AppHandle, Emitter};
 
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{
     FindingSeverity, ServiceDefinition, ServiceFinding, ServiceOptionSchema, ServiceResult,
 };
@@ -51,7 +51,8 @@ impl Service for EnergyReportService {
         }
     }
 
-    fn run(&self, options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -99,7 +100,7 @@ impl Service for EnergyReportService {
                 "/duration",
                 &duration.to_string(),
             ])
-            .output();
+            .watched_output();
 
         let output = match result {
             Ok(output) => output,
@@ -126,6 +127,7 @@ impl Service for EnergyReportService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -172,6 +174,7 @@ impl Service for EnergyReportService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -294,6 +297,7 @@ impl Service for EnergyReportService {
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
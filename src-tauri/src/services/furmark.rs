@@ -13,7 +13,7 @@ use serde_json::json;
 use tauri::{AppHandle, Emitter};
 
 use crate::commands::get_program_exe_path;
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{
     FindingSeverity, ServiceDefinition, ServiceFinding, ServiceOptionSchema, ServiceResult,
 };
@@ -71,7 +71,8 @@ impl Service for FurmarkService {
         }
     }
 
-    fn run(&self, options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -115,6 +116,7 @@ impl Service for FurmarkService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
             Err(e) => {
@@ -131,6 +133,7 @@ impl Service for FurmarkService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -182,7 +185,7 @@ impl Service for FurmarkService {
         let output = match Command::new(&exe_path)
             .args(&args)
             .current_dir(&working_dir)
-            .output()
+            .watched_output()
         {
             Ok(output) => output,
             Err(e) => {
@@ -208,6 +211,7 @@ impl Service for FurmarkService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -246,6 +250,7 @@ impl Service for FurmarkService {
                 findings,
                 logs,
                 agent_analysis: None,
+                timed_out: false,
             };
         }
 
@@ -357,6 +362,7 @@ impl Service for FurmarkService {
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
@@ -4,14 +4,12 @@
 //! Executes headlessly with configurable duration and reports completion status.
 
 use std::process::Command;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use chrono::Utc;
 use serde_json::json;
-use tauri::{AppHandle, Emitter};
 
 use crate::commands::get_program_exe_path;
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{
     FindingSeverity, ServiceDefinition, ServiceFinding, ServiceOptionSchema, ServiceResult,
 };
@@ -79,9 +77,8 @@ impl Service for HeavyLoadService {
         }
     }
 
-    fn run(&self, options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
         let start = Instant::now();
-        let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
         let service_id = "heavyload";
 
@@ -103,28 +100,11 @@ impl Service for HeavyLoadService {
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
-        // Emit log helper
-        let emit_log = |log: &str, logs: &mut Vec<String>, app: &AppHandle| {
-            logs.push(log.to_string());
-            let _ = app.emit(
-                "service-log",
-                json!({
-                    "serviceId": service_id,
-                    "log": log,
-                    "timestamp": Utc::now().to_rfc3339()
-                }),
-            );
-        };
-
-        emit_log("Starting HeavyLoad stress test...", &mut logs, app);
+        ctx.log("Starting HeavyLoad stress test...");
 
         // Validate options
         if !stress_cpu && !stress_memory && !stress_disk {
-            emit_log(
-                "ERROR: At least one stress type must be enabled",
-                &mut logs,
-                app,
-            );
+            ctx.log("ERROR: At least one stress type must be enabled");
             findings.push(ServiceFinding {
                 severity: FindingSeverity::Error,
                 title: "Invalid Configuration".to_string(),
@@ -139,8 +119,9 @@ impl Service for HeavyLoadService {
                 error: Some("No stress test type selected".to_string()),
                 duration_ms: start.elapsed().as_millis() as u64,
                 findings,
-                logs,
+                logs: ctx.logs(),
                 agent_analysis: None,
+                timed_out: false,
             };
         }
 
@@ -148,7 +129,7 @@ impl Service for HeavyLoadService {
         let exe_path = match get_program_exe_path("heavyload".to_string()) {
             Ok(Some(path)) => path,
             Ok(None) => {
-                emit_log("ERROR: HeavyLoad.exe not found", &mut logs, app);
+                ctx.log("ERROR: HeavyLoad.exe not found");
                 findings.push(ServiceFinding {
                     severity: FindingSeverity::Error,
                     title: "HeavyLoad Not Found".to_string(),
@@ -165,29 +146,27 @@ impl Service for HeavyLoadService {
                     error: Some("HeavyLoad.exe not found".to_string()),
                     duration_ms: start.elapsed().as_millis() as u64,
                     findings,
-                    logs,
+                    logs: ctx.logs(),
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
             Err(e) => {
-                emit_log(
-                    &format!("ERROR: Failed to locate HeavyLoad: {}", e),
-                    &mut logs,
-                    app,
-                );
+                ctx.log(&format!("ERROR: Failed to locate HeavyLoad: {}", e));
                 return ServiceResult {
                     service_id: service_id.to_string(),
                     success: false,
                     error: Some(e.to_string()),
                     duration_ms: start.elapsed().as_millis() as u64,
                     findings,
-                    logs,
+                    logs: ctx.logs(),
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
 
-        emit_log(&format!("Found HeavyLoad at: {}", exe_path), &mut logs, app);
+        ctx.log(&format!("Found HeavyLoad at: {}", exe_path));
 
         // Build command arguments
         let mut args: Vec<String> = Vec::new();
@@ -221,29 +200,26 @@ impl Service for HeavyLoadService {
         }
         let test_desc = test_types.join(", ");
 
-        emit_log(
-            &format!(
-                "Running {} stress test for {} minute(s)...",
-                test_desc, duration_minutes
-            ),
-            &mut logs,
-            app,
-        );
-        emit_log(
-            "System will be under heavy load. Do not use the computer during this test.",
-            &mut logs,
-            app,
+        ctx.log(&format!(
+            "Running {} stress test for {} minute(s)...",
+            test_desc, duration_minutes
+        ));
+        ctx.log("System will be under heavy load. Do not use the computer during this test.");
+
+        // Last safe point before the system is put under load
+        if ctx.wait_if_paused() {
+            return ctx.cancelled_result(start);
+        }
+        let ticker = ctx.progress_over(
+            "Running stress test",
+            Duration::from_secs(duration_minutes.max(1) as u64 * 60),
         );
 
         // Execute HeavyLoad
-        let output = match Command::new(&exe_path).args(&args).output() {
+        let output = match Command::new(&exe_path).args(&args).watched_output() {
             Ok(output) => output,
             Err(e) => {
-                emit_log(
-                    &format!("ERROR: Failed to execute HeavyLoad: {}", e),
-                    &mut logs,
-                    app,
-                );
+                ctx.log(&format!("ERROR: Failed to execute HeavyLoad: {}", e));
                 findings.push(ServiceFinding {
                     severity: FindingSeverity::Error,
                     title: "Execution Failed".to_string(),
@@ -259,28 +235,31 @@ impl Service for HeavyLoadService {
                     error: Some(format!("Execution failed: {}", e)),
                     duration_ms: start.elapsed().as_millis() as u64,
                     findings,
-                    logs,
+                    logs: ctx.logs(),
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
 
+        drop(ticker);
+
+        // Cancelling kills HeavyLoad, so its exit code says nothing about stability
+        if ctx.is_cancelled() {
+            return ctx.cancelled_result(start);
+        }
+
         let exit_code = output.status.code().unwrap_or(-1);
         let actual_duration = start.elapsed();
 
-        emit_log(
-            &format!("HeavyLoad completed with exit code: {}", exit_code),
-            &mut logs,
-            app,
-        );
-        emit_log(
-            &format!(
-                "Actual test duration: {:.1} minutes",
-                actual_duration.as_secs_f64() / 60.0
-            ),
-            &mut logs,
-            app,
-        );
+        ctx.log(&format!(
+            "HeavyLoad completed with exit code: {}",
+            exit_code
+        ));
+        ctx.log(&format!(
+            "Actual test duration: {:.1} minutes",
+            actual_duration.as_secs_f64() / 60.0
+        ));
 
         // Determine result
         let success = exit_code == 0;
@@ -326,7 +305,7 @@ impl Service for HeavyLoadService {
             })),
         });
 
-        emit_log("Stress test complete", &mut logs, app);
+        ctx.log("Stress test complete");
 
         ServiceResult {
             service_id: service_id.to_string(),
@@ -334,8 +313,9 @@ impl Service for HeavyLoadService {
             error: None,
             duration_ms: actual_duration.as_millis() as u64,
             findings,
-            logs,
+            logs: ctx.logs(),
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
@@ -10,7 +10,7 @@ use chrono::Utc;
 use serde_json::json;
 use tauri::{AppHandle, Emitter};
 
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{
     FindingSeverity, ServiceDefinition, ServiceFinding, ServiceOptionSchema, ServiceResult,
 };
@@ -48,7 +48,8 @@ impl Service for InstalledSoftwareService {
         }
     }
 
-    fn run(&self, options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -99,7 +100,7 @@ $apps | ConvertTo-Json -Depth 3
                 "-Command",
                 ps_script,
             ])
-            .output()
+            .watched_output()
         {
             Ok(output) => output,
             Err(e) => {
@@ -123,6 +124,7 @@ $apps | ConvertTo-Json -Depth 3
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -308,6 +310,7 @@ $apps | ConvertTo-Json -Depth 3
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
@@ -5,15 +5,13 @@
 //! and provides detailed statistics.
 
 use std::process::Command;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use chrono::Utc;
 use serde::Deserialize;
 use serde_json::json;
-use tauri::{AppHandle, Emitter};
 
 use crate::commands::get_program_exe_path;
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{
     FindingSeverity, ServiceDefinition, ServiceFinding, ServiceOptionSchema, ServiceResult,
 };
@@ -73,27 +71,13 @@ impl Service for IperfService {
         }
     }
 
-    fn run(&self, options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
         let start = Instant::now();
-        let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
         let mut success = true;
         let mut error: Option<String> = None;
         let service_id = "iperf";
 
-        // Emit log helper
-        let emit_log = |log: &str, logs: &mut Vec<String>, app: &AppHandle| {
-            logs.push(log.to_string());
-            let _ = app.emit(
-                "service-log",
-                json!({
-                    "serviceId": service_id,
-                    "log": log,
-                    "timestamp": Utc::now().to_rfc3339()
-                }),
-            );
-        };
-
         // Parse options
         let server = options
             .get("server")
@@ -108,14 +92,13 @@ impl Service for IperfService {
             .and_then(|v| v.as_bool())
             .unwrap_or(true);
 
-        emit_log("Starting network stability test...", &mut logs, app);
-        emit_log(&format!("Server: {}", server), &mut logs, app);
-        emit_log(&format!("Duration: {} seconds", duration), &mut logs, app);
-        emit_log(
-            &format!("Direction: {}", if reverse { "Download" } else { "Upload" }),
-            &mut logs,
-            app,
-        );
+        ctx.log("Starting network stability test...");
+        ctx.log(&format!("Server: {}", server));
+        ctx.log(&format!("Duration: {} seconds", duration));
+        ctx.log(&format!(
+            "Direction: {}",
+            if reverse { "Download" } else { "Upload" }
+        ));
 
         // Get executable path
         let exe_path = match get_program_exe_path("iperf3".to_string()) {
@@ -127,8 +110,9 @@ impl Service for IperfService {
                     error: Some("iPerf3 executable not found".to_string()),
                     duration_ms: start.elapsed().as_millis() as u64,
                     findings: vec![],
-                    logs,
+                    logs: ctx.logs(),
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
             Err(e) => {
@@ -138,14 +122,15 @@ impl Service for IperfService {
                     error: Some(format!("Failed to get executable path: {}", e)),
                     duration_ms: start.elapsed().as_millis() as u64,
                     findings: vec![],
-                    logs,
+                    logs: ctx.logs(),
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
 
-        emit_log(&format!("Using iperf3: {}", exe_path), &mut logs, app);
-        emit_log("Connecting to server...", &mut logs, app);
+        ctx.log(&format!("Using iperf3: {}", exe_path));
+        ctx.log("Connecting to server...");
 
         // Build command
         let mut args = vec![
@@ -162,7 +147,18 @@ impl Service for IperfService {
             args.push("-R".to_string());
         }
 
-        let output = Command::new(&exe_path).args(&args).output();
+        if ctx.wait_if_paused() {
+            return ctx.cancelled_result(start);
+        }
+        let ticker =
+            ctx.progress_over("Measuring throughput", Duration::from_secs(duration as u64));
+        let output = Command::new(&exe_path).args(&args).watched_output();
+        drop(ticker);
+
+        // A killed iperf3 leaves truncated JSON; don't report it as a failure
+        if ctx.is_cancelled() {
+            return ctx.cancelled_result(start);
+        }
 
         match output {
             Ok(output) => {
@@ -170,7 +166,7 @@ impl Service for IperfService {
                 let stderr = String::from_utf8_lossy(&output.stderr);
 
                 if !stderr.is_empty() {
-                    emit_log(&format!("stderr: {}", stderr.trim()), &mut logs, app);
+                    ctx.log(&format!("stderr: {}", stderr.trim()));
                 }
 
                 match serde_json::from_str::<IperfResult>(&stdout) {
@@ -179,7 +175,7 @@ impl Service for IperfService {
                         if let Some(err) = &result.error {
                             success = false;
                             error = Some(err.clone());
-                            emit_log(&format!("iPerf error: {}", err), &mut logs, app);
+                            ctx.log(&format!("iPerf error: {}", err));
                         } else {
                             // Extract interval data for throughput over time
                             let throughput_mbps: Vec<f64> = result
@@ -192,32 +188,16 @@ impl Service for IperfService {
                             // Calculate statistics
                             let stats = calculate_stats(&throughput_mbps);
 
-                            emit_log(
-                                &format!(
-                                    "Test complete. {} samples collected.",
-                                    throughput_mbps.len()
-                                ),
-                                &mut logs,
-                                app,
-                            );
-                            emit_log(
-                                &format!(
-                                    "Mean: {:.2} Mbps, Median: {:.2} Mbps",
-                                    stats.mean, stats.median
-                                ),
-                                &mut logs,
-                                app,
-                            );
-                            emit_log(
-                                &format!("Range: {:.2} - {:.2} Mbps", stats.min, stats.max),
-                                &mut logs,
-                                app,
-                            );
-                            emit_log(
-                                &format!("Variability (CoV): {:.1}%", stats.cov * 100.0),
-                                &mut logs,
-                                app,
-                            );
+                            ctx.log(&format!(
+                                "Test complete. {} samples collected.",
+                                throughput_mbps.len()
+                            ));
+                            ctx.log(&format!(
+                                "Mean: {:.2} Mbps, Median: {:.2} Mbps",
+                                stats.mean, stats.median
+                            ));
+                            ctx.log(&format!("Range: {:.2} - {:.2} Mbps", stats.min, stats.max));
+                            ctx.log(&format!("Variability (CoV): {:.1}%", stats.cov * 100.0));
 
                             // Get aggregates from end section
                             let retransmits = result
@@ -229,11 +209,7 @@ impl Service for IperfService {
                             // Calculate stability score
                             let (score, verdict) = calculate_stability_score(&stats, retransmits);
 
-                            emit_log(
-                                &format!("Stability Score: {:.0}/100 ({})", score, verdict),
-                                &mut logs,
-                                app,
-                            );
+                            ctx.log(&format!("Stability Score: {:.0}/100 ({})", score, verdict));
 
                             // Determine severity
                             let severity = match score as u32 {
@@ -283,16 +259,12 @@ impl Service for IperfService {
                     Err(e) => {
                         success = false;
                         error = Some(format!("Failed to parse iperf output: {}", e));
-                        emit_log(&format!("Error parsing output: {}", e), &mut logs, app);
+                        ctx.log(&format!("Error parsing output: {}", e));
                         // Log partial output for debugging
                         if stdout.len() > 500 {
-                            emit_log(
-                                &format!("Output (truncated): {}...", &stdout[..500]),
-                                &mut logs,
-                                app,
-                            );
+                            ctx.log(&format!("Output (truncated): {}...", &stdout[..500]));
                         } else {
-                            emit_log(&format!("Output: {}", stdout), &mut logs, app);
+                            ctx.log(&format!("Output: {}", stdout));
                         }
                     }
                 }
@@ -300,7 +272,7 @@ impl Service for IperfService {
             Err(e) => {
                 success = false;
                 error = Some(format!("Failed to execute iperf3: {}", e));
-                emit_log(&format!("Error: {}", e), &mut logs, app);
+                ctx.log(&format!("Error: {}", e));
             }
         }
 
@@ -310,8 +282,9 @@ impl Service for IperfService {
             error,
             duration_ms: start.elapsed().as_millis() as u64,
             findings,
-            logs,
+            logs: ctx.logs(),
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
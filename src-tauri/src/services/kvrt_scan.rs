@@ -7,13 +7,11 @@ use std::path::Path;
 use std::process::Command;
 use std::time::Instant;
 
-use chrono::Utc;
 use regex::Regex;
 use serde_json::json;
-use tauri::{AppHandle, Emitter};
 
 use crate::commands::get_program_exe_path;
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{FindingSeverity, ServiceDefinition, ServiceFinding, ServiceResult};
 
 // =============================================================================
@@ -39,32 +37,18 @@ impl Service for KvrtScanService {
         }
     }
 
-    fn run(&self, _options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, _options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
         let start = Instant::now();
-        let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
         let service_id = "kvrt-scan";
 
-        // Emit log helper
-        let emit_log = |log: &str, logs: &mut Vec<String>, app: &AppHandle| {
-            logs.push(log.to_string());
-            let _ = app.emit(
-                "service-log",
-                json!({
-                    "serviceId": service_id,
-                    "log": log,
-                    "timestamp": Utc::now().to_rfc3339()
-                }),
-            );
-        };
-
-        emit_log("Starting KVRT antivirus scan...", &mut logs, app);
+        ctx.log("Starting KVRT antivirus scan...");
 
         // Get KVRT executable path
         let exe_path = match get_program_exe_path("kvrt".to_string()) {
             Ok(Some(path)) => path,
             Ok(None) => {
-                emit_log("ERROR: KVRT.exe not found", &mut logs, app);
+                ctx.log("ERROR: KVRT.exe not found");
                 findings.push(ServiceFinding {
                     severity: FindingSeverity::Error,
                     title: "KVRT Not Found".to_string(),
@@ -82,29 +66,27 @@ impl Service for KvrtScanService {
                     error: Some("KVRT.exe not found".to_string()),
                     duration_ms: start.elapsed().as_millis() as u64,
                     findings,
-                    logs,
+                    logs: ctx.logs(),
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
             Err(e) => {
-                emit_log(
-                    &format!("ERROR: Failed to locate KVRT: {}", e),
-                    &mut logs,
-                    app,
-                );
+                ctx.log(&format!("ERROR: Failed to locate KVRT: {}", e));
                 return ServiceResult {
                     service_id: service_id.to_string(),
                     success: false,
                     error: Some(e.to_string()),
                     duration_ms: start.elapsed().as_millis() as u64,
                     findings,
-                    logs,
+                    logs: ctx.logs(),
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
 
-        emit_log(&format!("Found KVRT at: {}", exe_path), &mut logs, app);
+        ctx.log(&format!("Found KVRT at: {}", exe_path));
 
         // Build command arguments
         let args = vec![
@@ -115,11 +97,7 @@ impl Service for KvrtScanService {
             "-fixednames",
         ];
 
-        emit_log(
-            "Running scan (this may take several minutes)...",
-            &mut logs,
-            app,
-        );
+        ctx.log("Running scan (this may take several minutes)...");
 
         // Get working directory
         let working_dir = Path::new(&exe_path)
@@ -127,19 +105,21 @@ impl Service for KvrtScanService {
             .map(|p| p.to_path_buf())
             .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
 
+        if ctx.wait_if_paused() {
+            return ctx.cancelled_result(start);
+        }
+        // KVRT reports nothing until it exits, so the scan has no percentage
+        ctx.progress(None, "Scanning");
+
         // Execute KVRT
         let output = match Command::new(&exe_path)
             .args(&args)
             .current_dir(&working_dir)
-            .output()
+            .watched_output()
         {
             Ok(output) => output,
             Err(e) => {
-                emit_log(
-                    &format!("ERROR: Failed to execute KVRT: {}", e),
-                    &mut logs,
-                    app,
-                );
+                ctx.log(&format!("ERROR: Failed to execute KVRT: {}", e));
                 findings.push(ServiceFinding {
                     severity: FindingSeverity::Error,
                     title: "Execution Failed".to_string(),
@@ -155,37 +135,39 @@ impl Service for KvrtScanService {
                     error: Some(format!("Execution failed: {}", e)),
                     duration_ms: start.elapsed().as_millis() as u64,
                     findings,
-                    logs,
+                    logs: ctx.logs(),
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
 
+        // A killed scan's partial output would read as "no threats found"
+        if ctx.is_cancelled() {
+            return ctx.cancelled_result(start);
+        }
+        ctx.progress(Some(100.0), "Scan complete");
+
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         let exit_code = output.status.code().unwrap_or(-1);
 
-        emit_log(
-            &format!("KVRT completed with exit code: {}", exit_code),
-            &mut logs,
-            app,
-        );
+        ctx.log(&format!("KVRT completed with exit code: {}", exit_code));
 
         // Parse output
         let parsed = parse_kvrt_output(&stdout);
 
         // Log summary
         if let Some(processed) = parsed.processed {
-            emit_log(&format!("Objects processed: {}", processed), &mut logs, app);
+            ctx.log(&format!("Objects processed: {}", processed));
         }
         if let Some(detected) = parsed.detected {
-            emit_log(&format!("Threats detected: {}", detected), &mut logs, app);
+            ctx.log(&format!("Threats detected: {}", detected));
         }
-        emit_log(
-            &format!("Threats removed/neutralized: {}", parsed.removed_count),
-            &mut logs,
-            app,
-        );
+        ctx.log(&format!(
+            "Threats removed/neutralized: {}",
+            parsed.removed_count
+        ));
 
         // Determine overall severity
         let (severity, status) = if parsed.detected.unwrap_or(0) > 0 {
@@ -258,10 +240,10 @@ impl Service for KvrtScanService {
         }
 
         if !stderr.is_empty() && stderr.len() < 500 {
-            emit_log(&format!("KVRT stderr: {}", stderr.trim()), &mut logs, app);
+            ctx.log(&format!("KVRT stderr: {}", stderr.trim()));
         }
 
-        emit_log("KVRT scan complete", &mut logs, app);
+        ctx.log("KVRT scan complete");
 
         ServiceResult {
             service_id: service_id.to_string(),
@@ -269,8 +251,9 @@ impl Service for KvrtScanService {
             error: None,
             duration_ms: start.elapsed().as_millis() as u64,
             findings,
-            logs,
+            logs: ctx.logs(),
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
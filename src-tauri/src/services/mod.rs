@@ -7,6 +7,7 @@ mod adwcleaner;
 mod battery_report;
 mod bleachbit;
 mod chkdsk;
+mod context;
 mod disk_space;
 mod dism;
 mod drivecleanup;
@@ -26,13 +27,18 @@ mod speedtest;
 mod startup_optimize;
 mod stinger;
 mod usb_stability;
+mod watchdog;
 mod whynotwin11;
 mod windows_update;
 mod winsat;
 
 use std::collections::HashMap;
-use std::sync::LazyLock;
-use tauri::AppHandle;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+pub use context::{CancellationToken, PauseGate, ServiceContext};
+pub(crate) use watchdog::kill_process_tree;
+pub use watchdog::WatchedCommand;
 
 use crate::types::{PresetServiceConfig, ServiceDefinition, ServicePreset, ServiceResult};
 
@@ -45,8 +51,11 @@ pub trait Service: Send + Sync {
     /// Returns the service definition (id, name, description, options, etc.)
     fn definition(&self) -> ServiceDefinition;
 
-    /// Executes the service with the given options
-    fn run(&self, options: &serde_json::Value, app: &AppHandle) -> ServiceResult;
+    /// Executes the service with the given options.
+    ///
+    /// Long-running services should check `ctx` for cancellation and pauses
+    /// between steps and report progress through it.
+    fn run(&self, options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult;
 }
 
 // =============================================================================
@@ -100,13 +109,15 @@ pub fn get_service(id: &str) -> Option<&'static dyn Service> {
     SERVICE_REGISTRY.get(id).map(|s| s.as_ref())
 }
 
-/// Run a service by ID
+/// Run a service by ID, stopping it if it runs longer than `timeout`
+/// or the run is cancelled
 pub fn run_service(
     id: &str,
     options: &serde_json::Value,
-    app: &AppHandle,
+    ctx: Arc<ServiceContext>,
+    timeout: Option<Duration>,
 ) -> Option<ServiceResult> {
-    get_service(id).map(|s| s.run(options, app))
+    get_service(id).map(|s| watchdog::run_watched(s, options, ctx, timeout))
 }
 
 // =============================================================================
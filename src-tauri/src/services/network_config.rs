@@ -10,7 +10,7 @@ use chrono::Utc;
 use serde_json::json;
 use tauri::{AppHandle, Emitter};
 
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{
     FindingSeverity, ServiceDefinition, ServiceFinding, ServiceOptionSchema, ServiceResult,
 };
@@ -48,7 +48,8 @@ impl Service for NetworkConfigService {
         }
     }
 
-    fn run(&self, options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -79,7 +80,7 @@ impl Service for NetworkConfigService {
             &mut logs,
             app,
         );
-        let ipconfig_output = match Command::new("ipconfig").arg("/all").output() {
+        let ipconfig_output = match Command::new("ipconfig").arg("/all").watched_output() {
             Ok(output) => {
                 let stdout = String::from_utf8_lossy(&output.stdout).to_string();
                 emit_log(
@@ -110,7 +111,7 @@ impl Service for NetworkConfigService {
         );
         let netsh_output = match Command::new("netsh")
             .args(["interface", "show", "interface"])
-            .output()
+            .watched_output()
         {
             Ok(output) => String::from_utf8_lossy(&output.stdout).to_string(),
             Err(e) => {
@@ -303,6 +304,7 @@ impl Service for NetworkConfigService {
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
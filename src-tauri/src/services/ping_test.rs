@@ -9,7 +9,7 @@ use chrono::Utc;
 use serde_json::json;
 use tauri::{AppHandle, Emitter};
 
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{
     FindingSeverity, ServiceDefinition, ServiceFinding, ServiceOptionSchema, ServiceResult,
 };
@@ -57,7 +57,8 @@ impl Service for PingTestService {
         }
     }
 
-    fn run(&self, options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -94,7 +95,7 @@ impl Service for PingTestService {
         // Run Windows ping command
         let output = Command::new("ping")
             .args(["-n", &count.to_string(), target])
-            .output();
+            .watched_output();
 
         match output {
             Ok(output) => {
@@ -230,6 +231,7 @@ impl Service for PingTestService {
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
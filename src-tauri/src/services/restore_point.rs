@@ -3,9 +3,9 @@
 //! Creates a Windows system restore point before maintenance work.
 
 use std::time::Instant;
-use tauri::{AppHandle, Emitter};
+use tauri::Emitter;
 
-use crate::services::Service;
+use crate::services::{Service, ServiceContext};
 use crate::types::{
     FindingSeverity, ServiceDefinition, ServiceFinding, ServiceOptionSchema, ServiceResult,
 };
@@ -37,7 +37,8 @@ impl Service for RestorePointService {
         }
     }
 
-    fn run(&self, options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
 
         let description = options
@@ -76,6 +77,7 @@ impl Service for RestorePointService {
                     }],
                     logs: vec![msg],
                     agent_analysis: None,
+                    timed_out: false,
                 }
             }
             Err(e) => {
@@ -117,6 +119,7 @@ impl Service for RestorePointService {
                     }],
                     logs: vec![],
                     agent_analysis: None,
+                    timed_out: false,
                 }
             }
         }
@@ -10,7 +10,7 @@ use chrono::Utc;
 use serde_json::json;
 use tauri::{AppHandle, Emitter};
 
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{FindingSeverity, ServiceDefinition, ServiceFinding, ServiceResult};
 
 // =============================================================================
@@ -35,7 +35,8 @@ impl Service for SfcService {
         }
     }
 
-    fn run(&self, _options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, _options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -62,7 +63,7 @@ impl Service for SfcService {
         );
 
         // Execute SFC
-        let output = match Command::new("sfc").arg("/scannow").output() {
+        let output = match Command::new("sfc").arg("/scannow").watched_output() {
             Ok(output) => output,
             Err(e) => {
                 emit_log(&format!("ERROR: Failed to run SFC: {}", e), &mut logs, app);
@@ -83,6 +84,7 @@ impl Service for SfcService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -220,6 +222,7 @@ impl Service for SfcService {
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
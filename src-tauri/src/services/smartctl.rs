@@ -12,7 +12,7 @@ use serde_json::json;
 use tauri::{AppHandle, Emitter};
 
 use crate::commands::get_program_exe_path;
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{FindingSeverity, ServiceDefinition, ServiceFinding, ServiceResult};
 
 // =============================================================================
@@ -37,7 +37,8 @@ impl Service for SmartctlService {
         }
     }
 
-    fn run(&self, _options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, _options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -72,6 +73,7 @@ impl Service for SmartctlService {
                     findings: vec![],
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
             Err(e) => {
@@ -83,6 +85,7 @@ impl Service for SmartctlService {
                     findings: vec![],
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -91,7 +94,7 @@ impl Service for SmartctlService {
 
         // Scan for devices
         emit_log("Scanning for drives...", &mut logs, app);
-        let scan_output = Command::new(&exe_path).args(["--scan", "-j"]).output();
+        let scan_output = Command::new(&exe_path).args(["--scan", "-j"]).watched_output();
 
         let devices = match scan_output {
             Ok(output) => {
@@ -160,7 +163,7 @@ impl Service for SmartctlService {
 
             let info_output = Command::new(&exe_path)
                 .args(["-a", device_name, "-j"])
-                .output();
+                .watched_output();
 
             if let Ok(output) = info_output {
                 let stdout = String::from_utf8_lossy(&output.stdout);
@@ -274,6 +277,7 @@ impl Service for SmartctlService {
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
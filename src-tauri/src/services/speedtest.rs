@@ -12,7 +12,7 @@ use serde_json::json;
 use tauri::{AppHandle, Emitter};
 
 use crate::commands::get_program_exe_path;
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{FindingSeverity, ServiceDefinition, ServiceFinding, ServiceResult};
 
 // =============================================================================
@@ -37,7 +37,8 @@ impl Service for SpeedtestService {
         }
     }
 
-    fn run(&self, _options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, _options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -72,6 +73,7 @@ impl Service for SpeedtestService {
                     findings: vec![],
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
             Err(e) => {
@@ -83,6 +85,7 @@ impl Service for SpeedtestService {
                     findings: vec![],
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -93,7 +96,7 @@ impl Service for SpeedtestService {
         // Run speedtest with JSON output and accept license
         let output = Command::new(&exe_path)
             .args(["--accept-license", "--accept-gdpr", "--format=json"])
-            .output();
+            .watched_output();
 
         match output {
             Ok(output) => {
@@ -208,6 +211,7 @@ impl Service for SpeedtestService {
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
@@ -20,7 +20,7 @@ use crate::commands::startup::{
     get_startup_folder_items_sync, toggle_registry_startup_item_sync,
     toggle_scheduled_task_sync, StartupItem, StartupSource,
 };
-use crate::services::Service;
+use crate::services::{Service, ServiceContext};
 use crate::types::{
     AgentProvider, FindingSeverity, ServiceDefinition, ServiceFinding, ServiceOptionSchema,
     ServiceResult,
@@ -487,7 +487,8 @@ impl Service for StartupOptimizeService {
         }
     }
 
-    fn run(&self, options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -588,6 +589,7 @@ impl Service for StartupOptimizeService {
                 findings,
                 logs,
                 agent_analysis: None,
+                timed_out: false,
             };
         }
 
@@ -795,6 +797,7 @@ impl Service for StartupOptimizeService {
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
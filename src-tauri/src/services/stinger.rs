@@ -14,7 +14,7 @@ use serde_json::json;
 use tauri::{AppHandle, Emitter};
 
 use crate::commands::{get_data_dir_path, get_program_exe_path};
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{
     FindingSeverity, SelectOption, ServiceDefinition, ServiceFinding, ServiceOptionSchema,
     ServiceResult,
@@ -82,7 +82,8 @@ impl Service for StingerService {
         }
     }
 
-    fn run(&self, options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -126,6 +127,7 @@ impl Service for StingerService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
             Err(e) => {
@@ -142,6 +144,7 @@ impl Service for StingerService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -239,7 +242,7 @@ impl Service for StingerService {
         let output = match Command::new(&exe_path)
             .args(&args)
             .current_dir(stinger_dir)
-            .output()
+            .watched_output()
         {
             Ok(output) => output,
             Err(e) => {
@@ -266,6 +269,7 @@ impl Service for StingerService {
                     findings,
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -417,6 +421,7 @@ impl Service for StingerService {
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
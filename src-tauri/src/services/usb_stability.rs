@@ -9,12 +9,10 @@ use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::PathBuf;
 use std::time::Instant;
 
-use chrono::Utc;
 use serde_json::json;
 use sysinfo::Disks;
-use tauri::{AppHandle, Emitter};
 
-use crate::services::Service;
+use crate::services::{Service, ServiceContext};
 use crate::types::{
     FindingSeverity, SelectOption, ServiceDefinition, ServiceFinding, ServiceOptionSchema,
     ServiceResult,
@@ -104,26 +102,12 @@ impl Service for UsbStabilityService {
         }
     }
 
-    fn run(&self, options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
         let start = Instant::now();
-        let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
         let service_id = "usb-stability";
 
-        // Emit log helper
-        let emit_log = |log: &str, logs: &mut Vec<String>, app: &AppHandle| {
-            logs.push(log.to_string());
-            let _ = app.emit(
-                "service-log",
-                json!({
-                    "serviceId": service_id,
-                    "log": log,
-                    "timestamp": Utc::now().to_rfc3339()
-                }),
-            );
-        };
-
-        emit_log("Starting USB Stability Test...", &mut logs, app);
+        ctx.log("Starting USB Stability Test...");
 
         // Parse options
         let target_drive = options
@@ -153,7 +137,7 @@ impl Service for UsbStabilityService {
         // =====================================================================
         // Phase 1: Drive Detection & Validation
         // =====================================================================
-        emit_log("Phase 1: Detecting USB drives...", &mut logs, app);
+        ctx.log("Phase 1: Detecting USB drives...");
 
         let disks = Disks::new_with_refreshed_list();
         let removable_drives: Vec<_> = disks
@@ -163,7 +147,7 @@ impl Service for UsbStabilityService {
             .collect();
 
         if removable_drives.is_empty() {
-            emit_log("ERROR: No removable USB drives detected!", &mut logs, app);
+            ctx.log("ERROR: No removable USB drives detected!");
             findings.push(ServiceFinding {
                 severity: FindingSeverity::Error,
                 title: "No USB Drives Found".to_string(),
@@ -177,18 +161,15 @@ impl Service for UsbStabilityService {
                 error: Some("No USB drives detected".to_string()),
                 duration_ms: start.elapsed().as_millis() as u64,
                 findings,
-                logs,
+                logs: ctx.logs(),
                 agent_analysis: None,
+                timed_out: false,
             };
         }
 
         // Select the target drive
         let selected_disk = if target_drive.is_empty() {
-            emit_log(
-                "No drive specified — auto-detecting first removable drive...",
-                &mut logs,
-                app,
-            );
+            ctx.log("No drive specified — auto-detecting first removable drive...");
             removable_drives.first().copied()
         } else {
             removable_drives
@@ -209,7 +190,7 @@ impl Service for UsbStabilityService {
                     "Drive '{}' not found or is not a removable USB drive",
                     target_drive
                 );
-                emit_log(&format!("ERROR: {}", msg), &mut logs, app);
+                ctx.log(&format!("ERROR: {}", msg));
                 findings.push(ServiceFinding {
                     severity: FindingSeverity::Error,
                     title: "Drive Not Found".to_string(),
@@ -225,8 +206,9 @@ impl Service for UsbStabilityService {
                     error: Some(msg),
                     duration_ms: start.elapsed().as_millis() as u64,
                     findings,
-                    logs,
+                    logs: ctx.logs(),
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -242,18 +224,14 @@ impl Service for UsbStabilityService {
             volume_name
         };
 
-        emit_log(
-            &format!(
-                "Selected drive: {} ({}) — {:.1} GB total, {:.1} GB free, {}",
-                mount_point,
-                volume_label,
-                total_space as f64 / 1_073_741_824.0,
-                available_space as f64 / 1_073_741_824.0,
-                fs_type
-            ),
-            &mut logs,
-            app,
-        );
+        ctx.log(&format!(
+            "Selected drive: {} ({}) — {:.1} GB total, {:.1} GB free, {}",
+            mount_point,
+            volume_label,
+            total_space as f64 / 1_073_741_824.0,
+            available_space as f64 / 1_073_741_824.0,
+            fs_type
+        ));
 
         // Check free space
         if available_space < test_size_bytes + (10 * 1024 * 1024) {
@@ -263,7 +241,7 @@ impl Service for UsbStabilityService {
                 test_size_bytes as f64 / 1_048_576.0 + 10.0,
                 available_space as f64 / 1_048_576.0
             );
-            emit_log(&format!("ERROR: {}", msg), &mut logs, app);
+            ctx.log(&format!("ERROR: {}", msg));
             findings.push(ServiceFinding {
                 severity: FindingSeverity::Error,
                 title: "Insufficient Free Space".to_string(),
@@ -279,8 +257,9 @@ impl Service for UsbStabilityService {
                 error: Some(msg),
                 duration_ms: start.elapsed().as_millis() as u64,
                 findings,
-                logs,
+                logs: ctx.logs(),
                 agent_analysis: None,
+                timed_out: false,
             };
         }
 
@@ -289,45 +268,53 @@ impl Service for UsbStabilityService {
         let test_file = temp_dir.join("stability_test.bin");
 
         // Ensure cleanup on all exit paths
-        let cleanup = |dir: &PathBuf, logs: &mut Vec<String>, app: &AppHandle| {
+        let cleanup = |dir: &PathBuf| {
             if dir.exists() {
                 match fs::remove_dir_all(dir) {
                     Ok(()) => {
-                        emit_log("Cleanup: Temporary test files removed", logs, app);
+                        ctx.log("Cleanup: Temporary test files removed");
                     }
                     Err(e) => {
-                        emit_log(
-                            &format!("Warning: Failed to clean up temp files: {}", e),
-                            logs,
-                            app,
-                        );
+                        ctx.log(&format!("Warning: Failed to clean up temp files: {}", e));
                     }
                 }
             }
         };
 
+        // Pause point between phases; cleans up and returns true if cancelled
+        let should_stop = || {
+            let cancelled = ctx.wait_if_paused();
+            if cancelled {
+                cleanup(&temp_dir);
+            }
+            cancelled
+        };
+
         if let Err(e) = fs::create_dir_all(&temp_dir) {
             let msg = format!("Failed to create temp directory on {}: {}", mount_point, e);
-            emit_log(&format!("ERROR: {}", msg), &mut logs, app);
+            ctx.log(&format!("ERROR: {}", msg));
             return ServiceResult {
                 service_id: service_id.to_string(),
                 success: false,
                 error: Some(msg),
                 duration_ms: start.elapsed().as_millis() as u64,
                 findings,
-                logs,
+                logs: ctx.logs(),
                 agent_analysis: None,
+                timed_out: false,
             };
         }
 
         // =====================================================================
         // Phase 2: Sequential Write Speed Test
         // =====================================================================
-        emit_log(
-            &format!("Phase 2: Sequential write test ({} MB)...", test_size_mb),
-            &mut logs,
-            app,
-        );
+        if should_stop() {
+            return ctx.cancelled_result(start);
+        }
+        ctx.log(&format!(
+            "Phase 2: Sequential write test ({} MB)...",
+            test_size_mb
+        ));
 
         let pattern_chunk: Vec<u8> = (0..CHUNK_SIZE)
             .map(|i| {
@@ -348,6 +335,9 @@ impl Service for UsbStabilityService {
                 .map_err(|e| format!("Failed to create test file: {}", e))?;
 
             for i in 0..total_chunks {
+                if ctx.is_cancelled() {
+                    return Err("Cancelled".to_string());
+                }
                 file.write_all(&pattern_chunk)
                     .map_err(|e| format!("Write error at chunk {}: {}", i, e))?;
                 bytes_written += CHUNK_SIZE as u64;
@@ -355,15 +345,12 @@ impl Service for UsbStabilityService {
                 // Log progress every 10%
                 let progress = ((i + 1) as f64 / total_chunks as f64 * 100.0) as u32;
                 if progress.is_multiple_of(10) && (i + 1) % (total_chunks / 10).max(1) == 0 {
-                    emit_log(
-                        &format!(
-                            "  Write progress: {}% ({} MB)",
-                            progress,
-                            bytes_written / 1_048_576
-                        ),
-                        &mut logs,
-                        app,
-                    );
+                    ctx.progress(Some(progress as f64), "Sequential write");
+                    ctx.log(&format!(
+                        "  Write progress: {}% ({} MB)",
+                        progress,
+                        bytes_written / 1_048_576
+                    ));
                 }
             }
             file.sync_all()
@@ -378,9 +365,12 @@ impl Service for UsbStabilityService {
             0.0
         };
 
+        if should_stop() {
+            return ctx.cancelled_result(start);
+        }
         if let Err(e) = write_result {
-            emit_log(&format!("ERROR: Write test failed: {}", e), &mut logs, app);
-            cleanup(&temp_dir, &mut logs, app);
+            ctx.log(&format!("ERROR: Write test failed: {}", e));
+            cleanup(&temp_dir);
 
             findings.push(ServiceFinding {
                 severity: FindingSeverity::Critical,
@@ -395,21 +385,18 @@ impl Service for UsbStabilityService {
                 error: Some(e),
                 duration_ms: start.elapsed().as_millis() as u64,
                 findings,
-                logs,
+                logs: ctx.logs(),
                 agent_analysis: None,
+                timed_out: false,
             };
         }
 
-        emit_log(
-            &format!(
-                "Write complete: {:.1} MB/s ({:.1}s for {} MB)",
-                write_speed_mbps,
-                write_duration.as_secs_f64(),
-                test_size_mb
-            ),
-            &mut logs,
-            app,
-        );
+        ctx.log(&format!(
+            "Write complete: {:.1} MB/s ({:.1}s for {} MB)",
+            write_speed_mbps,
+            write_duration.as_secs_f64(),
+            test_size_mb
+        ));
 
         let SpeedRating {
             severity: write_severity,
@@ -439,11 +426,13 @@ impl Service for UsbStabilityService {
         // =====================================================================
         // Phase 3: Sequential Read Speed Test
         // =====================================================================
-        emit_log(
-            &format!("Phase 3: Sequential read test ({} MB)...", test_size_mb),
-            &mut logs,
-            app,
-        );
+        if should_stop() {
+            return ctx.cancelled_result(start);
+        }
+        ctx.log(&format!(
+            "Phase 3: Sequential read test ({} MB)...",
+            test_size_mb
+        ));
 
         let read_start = Instant::now();
         let mut bytes_read: u64 = 0;
@@ -454,6 +443,9 @@ impl Service for UsbStabilityService {
                 .map_err(|e| format!("Failed to open test file for reading: {}", e))?;
 
             loop {
+                if ctx.is_cancelled() {
+                    return Err("Cancelled".to_string());
+                }
                 let n = file
                     .read(&mut read_buf)
                     .map_err(|e| format!("Read error: {}", e))?;
@@ -466,15 +458,12 @@ impl Service for UsbStabilityService {
                 if progress.is_multiple_of(10)
                     && bytes_read % (test_size_bytes / 10).max(1) < CHUNK_SIZE as u64
                 {
-                    emit_log(
-                        &format!(
-                            "  Read progress: {}% ({} MB)",
-                            progress,
-                            bytes_read / 1_048_576
-                        ),
-                        &mut logs,
-                        app,
-                    );
+                    ctx.progress(Some(progress as f64), "Sequential read");
+                    ctx.log(&format!(
+                        "  Read progress: {}% ({} MB)",
+                        progress,
+                        bytes_read / 1_048_576
+                    ));
                 }
             }
             Ok(())
@@ -487,9 +476,12 @@ impl Service for UsbStabilityService {
             0.0
         };
 
+        if should_stop() {
+            return ctx.cancelled_result(start);
+        }
         if let Err(e) = read_result {
-            emit_log(&format!("ERROR: Read test failed: {}", e), &mut logs, app);
-            cleanup(&temp_dir, &mut logs, app);
+            ctx.log(&format!("ERROR: Read test failed: {}", e));
+            cleanup(&temp_dir);
 
             findings.push(ServiceFinding {
                 severity: FindingSeverity::Critical,
@@ -506,21 +498,18 @@ impl Service for UsbStabilityService {
                 error: Some(e),
                 duration_ms: start.elapsed().as_millis() as u64,
                 findings,
-                logs,
+                logs: ctx.logs(),
                 agent_analysis: None,
+                timed_out: false,
             };
         }
 
-        emit_log(
-            &format!(
-                "Read complete: {:.1} MB/s ({:.1}s for {} MB)",
-                read_speed_mbps,
-                read_duration.as_secs_f64(),
-                test_size_mb
-            ),
-            &mut logs,
-            app,
-        );
+        ctx.log(&format!(
+            "Read complete: {:.1} MB/s ({:.1}s for {} MB)",
+            read_speed_mbps,
+            read_duration.as_secs_f64(),
+            test_size_mb
+        ));
 
         let SpeedRating {
             severity: read_severity,
@@ -550,12 +539,15 @@ impl Service for UsbStabilityService {
         // =====================================================================
         // Phase 4: Data Integrity Verification
         // =====================================================================
+        if should_stop() {
+            return ctx.cancelled_result(start);
+        }
         let mut integrity_pass = true;
         let mut integrity_errors: u64 = 0;
         let mut first_error_offset: Option<u64> = None;
 
         if verify_integrity {
-            emit_log("Phase 4: Data integrity verification...", &mut logs, app);
+            ctx.log("Phase 4: Data integrity verification...");
 
             let verify_result = (|| -> Result<(bool, u64, Option<u64>), String> {
                 let mut file = fs::File::open(&test_file)
@@ -567,6 +559,9 @@ impl Service for UsbStabilityService {
                 let mut first_err: Option<u64> = None;
 
                 loop {
+                    if ctx.is_cancelled() {
+                        return Err("Cancelled".to_string());
+                    }
                     let n = file
                         .read(&mut verify_buf)
                         .map_err(|e| format!("Read error during verification: {}", e))?;
@@ -593,13 +588,18 @@ impl Service for UsbStabilityService {
                     if progress.is_multiple_of(20)
                         && offset % (test_size_bytes / 5).max(1) < CHUNK_SIZE as u64
                     {
-                        emit_log(&format!("  Verify progress: {}%", progress), &mut logs, app);
+                        ctx.progress(Some(progress as f64), "Integrity verification");
+                        ctx.log(&format!("  Verify progress: {}%", progress));
                     }
                 }
 
                 Ok((errors == 0, errors, first_err))
             })();
 
+            if should_stop() {
+                return ctx.cancelled_result(start);
+            }
+
             match verify_result {
                 Ok((pass, errors, first_err)) => {
                     integrity_pass = pass;
@@ -607,29 +607,17 @@ impl Service for UsbStabilityService {
                     first_error_offset = first_err;
 
                     if pass {
-                        emit_log(
-                            "Integrity check PASSED — all bytes verified correctly",
-                            &mut logs,
-                            app,
-                        );
+                        ctx.log("Integrity check PASSED — all bytes verified correctly");
                     } else {
-                        emit_log(
-                            &format!(
-                                "Integrity check FAILED — {} byte errors detected (first at offset {})",
-                                errors,
-                                first_err.unwrap_or(0)
-                            ),
-                            &mut logs,
-                            app,
-                        );
+                        ctx.log(&format!(
+                            "Integrity check FAILED — {} byte errors detected (first at offset {})",
+                            errors,
+                            first_err.unwrap_or(0)
+                        ));
                     }
                 }
                 Err(e) => {
-                    emit_log(
-                        &format!("ERROR: Integrity verification failed: {}", e),
-                        &mut logs,
-                        app,
-                    );
+                    ctx.log(&format!("ERROR: Integrity verification failed: {}", e));
                     integrity_pass = false;
                 }
             }
@@ -673,21 +661,17 @@ impl Service for UsbStabilityService {
                 })),
             });
         } else {
-            emit_log(
-                "Phase 4: Skipped (integrity check disabled)",
-                &mut logs,
-                app,
-            );
+            ctx.log("Phase 4: Skipped (integrity check disabled)");
         }
 
         // =====================================================================
         // Phase 5: Random I/O Latency Test
         // =====================================================================
-        emit_log(
-            "Phase 5: Random I/O latency test (100 random 4KB reads)...",
-            &mut logs,
-            app,
-        );
+        if should_stop() {
+            return ctx.cancelled_result(start);
+        }
+        ctx.log("Phase 5: Random I/O latency test (100 random 4KB reads)...");
+        ctx.progress(None, "Random I/O latency");
 
         let random_io_result = (|| -> Result<(f64, f64, f64), String> {
             let mut file = fs::File::open(&test_file)
@@ -720,14 +704,10 @@ impl Service for UsbStabilityService {
 
         match random_io_result {
             Ok((avg_ms, min_ms, max_ms)) => {
-                emit_log(
-                    &format!(
-                        "Random I/O: avg {:.2}ms, min {:.2}ms, max {:.2}ms",
-                        avg_ms, min_ms, max_ms
-                    ),
-                    &mut logs,
-                    app,
-                );
+                ctx.log(&format!(
+                    "Random I/O: avg {:.2}ms, min {:.2}ms, max {:.2}ms",
+                    avg_ms, min_ms, max_ms
+                ));
 
                 let io_severity = if avg_ms > 50.0 {
                     FindingSeverity::Error
@@ -772,11 +752,7 @@ impl Service for UsbStabilityService {
                 });
             }
             Err(e) => {
-                emit_log(
-                    &format!("Warning: Random I/O test failed: {}", e),
-                    &mut logs,
-                    app,
-                );
+                ctx.log(&format!("Warning: Random I/O test failed: {}", e));
                 findings.push(ServiceFinding {
                     severity: FindingSeverity::Warning,
                     title: "Random I/O Test Failed".to_string(),
@@ -790,7 +766,11 @@ impl Service for UsbStabilityService {
         // =====================================================================
         // Phase 6: Capacity Spot-Check
         // =====================================================================
-        emit_log("Phase 6: Capacity verification...", &mut logs, app);
+        if should_stop() {
+            return ctx.cancelled_result(start);
+        }
+        ctx.log("Phase 6: Capacity verification...");
+        ctx.progress(None, "Capacity verification");
 
         // Re-read disk info after writing
         let disks_after = Disks::new_with_refreshed_list();
@@ -812,16 +792,12 @@ impl Service for UsbStabilityService {
 
             let capacity_ok = discrepancy <= tolerance;
 
-            emit_log(
-                &format!(
-                    "Capacity check: expected ~{} MB used, actual ~{} MB used (discrepancy: {} MB)",
-                    expected_used / 1_048_576,
-                    space_used_by_test / 1_048_576,
-                    discrepancy / 1_048_576
-                ),
-                &mut logs,
-                app,
-            );
+            ctx.log(&format!(
+                "Capacity check: expected ~{} MB used, actual ~{} MB used (discrepancy: {} MB)",
+                expected_used / 1_048_576,
+                space_used_by_test / 1_048_576,
+                discrepancy / 1_048_576
+            ));
 
             if capacity_ok {
                 findings.push(ServiceFinding {
@@ -842,11 +818,7 @@ impl Service for UsbStabilityService {
                     })),
                 });
             } else {
-                emit_log(
-                    "WARNING: Significant capacity discrepancy — possible fake drive!",
-                    &mut logs,
-                    app,
-                );
+                ctx.log("WARNING: Significant capacity discrepancy — possible fake drive!");
                 findings.push(ServiceFinding {
                     severity: FindingSeverity::Critical,
                     title: "⚠ FAKE DRIVE SUSPECTED".to_string(),
@@ -867,17 +839,13 @@ impl Service for UsbStabilityService {
                 });
             }
         } else {
-            emit_log(
-                "Warning: Could not re-detect drive for capacity check",
-                &mut logs,
-                app,
-            );
+            ctx.log("Warning: Could not re-detect drive for capacity check");
         }
 
         // =====================================================================
         // Cleanup
         // =====================================================================
-        cleanup(&temp_dir, &mut logs, app);
+        cleanup(&temp_dir);
 
         // =====================================================================
         // Overall Summary
@@ -913,15 +881,11 @@ impl Service for UsbStabilityService {
             FindingSeverity::Success
         };
 
-        emit_log(
-            &format!(
-                "USB Stability Test complete: {} (took {:.1}s)",
-                overall_status,
-                total_duration.as_secs_f64()
-            ),
-            &mut logs,
-            app,
-        );
+        ctx.log(&format!(
+            "USB Stability Test complete: {} (took {:.1}s)",
+            overall_status,
+            total_duration.as_secs_f64()
+        ));
 
         // Insert summary finding at the beginning
         findings.insert(
@@ -974,8 +938,9 @@ impl Service for UsbStabilityService {
             error: None,
             duration_ms: total_duration.as_millis() as u64,
             findings,
-            logs,
+            logs: ctx.logs(),
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
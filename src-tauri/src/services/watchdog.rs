@@ -0,0 +1,289 @@
+//! Service watchdog
+//!
+//! Runs a service on a worker thread with a time limit. Services start their
+//! external tools through [`WatchedCommand::watched_output`], which registers
+//! the child process with the watchdog of the service running on the current
+//! thread. When the limit passes or the run is cancelled, the watchdog kills
+//! each registered child together with everything it spawned, so the
+//! service's blocked call returns and the runner can move on.
+//!
+//! Work done in-process (no child process) can't be interrupted; on timeout
+//! the service's context is stopped so its `is_cancelled` checks end the
+//! work, and a service that doesn't check is abandoned on its thread once the
+//! grace period has passed.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::io;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+use sysinfo::{Pid, ProcessesToUpdate, System};
+
+use super::{Service, ServiceContext};
+use crate::types::{FindingSeverity, ServiceFinding, ServiceResult};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// How long to wait for a service to return after its processes were killed
+const KILL_GRACE_PERIOD: Duration = Duration::from_secs(15);
+
+/// How often the watchdog checks for cancellation and the deadline
+const WATCH_INTERVAL: Duration = Duration::from_millis(250);
+
+// =============================================================================
+// Watchdog
+// =============================================================================
+
+/// Child processes of one service run
+#[derive(Default)]
+struct Watchdog {
+    /// PIDs of child processes currently running for the service
+    children: Mutex<HashSet<u32>>,
+    /// Set once the service has been stopped
+    expired: AtomicBool,
+}
+
+impl Watchdog {
+    fn register(&self, pid: u32) {
+        self.children.lock().unwrap().insert(pid);
+        // A child started after expiry belongs to an abandoned service
+        if self.expired.load(Ordering::SeqCst) {
+            kill_process_tree(pid);
+        }
+    }
+
+    fn unregister(&self, pid: u32) {
+        self.children.lock().unwrap().remove(&pid);
+    }
+
+    /// Mark the service as stopped and kill its running children
+    fn expire(&self) {
+        self.expired.store(true, Ordering::SeqCst);
+        let children: Vec<u32> = self.children.lock().unwrap().iter().copied().collect();
+        for pid in children {
+            kill_process_tree(pid);
+        }
+    }
+}
+
+thread_local! {
+    /// Watchdog of the service running on this thread, if any
+    static CURRENT_WATCHDOG: RefCell<Option<Arc<Watchdog>>> = const { RefCell::new(None) };
+}
+
+/// Kill a process and all of its descendants, children first
+pub(crate) fn kill_process_tree(root: u32) {
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::All, true);
+
+    let mut tree = vec![Pid::from_u32(root)];
+    let mut i = 0;
+    while i < tree.len() {
+        let parent = tree[i];
+        tree.extend(
+            sys.processes()
+                .iter()
+                .filter(|(_, p)| p.parent() == Some(parent))
+                .map(|(pid, _)| *pid),
+        );
+        i += 1;
+    }
+
+    for pid in tree.into_iter().rev() {
+        if let Some(process) = sys.process(pid) {
+            if !process.kill() {
+                eprintln!("[Watchdog] Failed to kill process {}", pid);
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Watched Commands
+// =============================================================================
+
+/// Extension for `Command` used by services to run external tools
+pub trait WatchedCommand {
+    /// Like `Command::output`, but the child is killed if the calling
+    /// service times out or its run is cancelled. Stdin is closed, as with `output`. Outside a
+    /// watched service this is plain `output`.
+    fn watched_output(&mut self) -> io::Result<Output>;
+}
+
+impl WatchedCommand for Command {
+    fn watched_output(&mut self) -> io::Result<Output> {
+        let Some(watchdog) = CURRENT_WATCHDOG.with(|w| w.borrow().clone()) else {
+            return self.output();
+        };
+
+        let child = self
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let pid = child.id();
+        watchdog.register(pid);
+        let output = child.wait_with_output();
+        watchdog.unregister(pid);
+        output
+    }
+}
+
+// =============================================================================
+// Timed Execution
+// =============================================================================
+
+/// Human-readable timeout ("90s", "15 min")
+fn describe_timeout(timeout: Duration) -> String {
+    let secs = timeout.as_secs();
+    if secs < 120 {
+        format!("{}s", secs)
+    } else {
+        format!("{} min", secs / 60)
+    }
+}
+
+fn failed_result(service_id: &str, error: String, elapsed: Duration) -> ServiceResult {
+    ServiceResult {
+        service_id: service_id.to_string(),
+        success: false,
+        error: Some(error),
+        duration_ms: elapsed.as_millis() as u64,
+        findings: vec![],
+        logs: vec![],
+        agent_analysis: None,
+        timed_out: false,
+    }
+}
+
+/// Result for a service stopped by the watchdog.
+///
+/// Logs the service produced before it was stopped are kept; its findings
+/// are not, since they come from truncated tool output.
+fn timed_out_result(
+    ctx: &ServiceContext,
+    timeout: Duration,
+    elapsed: Duration,
+    partial: Option<ServiceResult>,
+) -> ServiceResult {
+    let limit = describe_timeout(timeout);
+    let message = format!("Timed out after {}; the service was stopped", limit);
+
+    // Services that log through the context sink still have logs if abandoned
+    let mut logs = partial
+        .map(|r| r.logs)
+        .filter(|logs| !logs.is_empty())
+        .unwrap_or_else(|| ctx.logs());
+    ctx.log(&message);
+    logs.push(message);
+
+    ServiceResult {
+        service_id: ctx.service_id().to_string(),
+        success: false,
+        error: Some(format!("Timed out after {}", limit)),
+        duration_ms: elapsed.as_millis() as u64,
+        findings: vec![ServiceFinding {
+            severity: FindingSeverity::Error,
+            title: "Service Timed Out".to_string(),
+            description: format!(
+                "The service did not finish within {} and was stopped. Its results are incomplete.",
+                limit
+            ),
+            recommendation: Some(
+                "Run the service again on its own, or raise its timeout in Settings if it legitimately takes longer on this machine.".to_string(),
+            ),
+            data: Some(json!({
+                "type": "service_timeout",
+                "timeoutSecs": timeout.as_secs(),
+            })),
+        }],
+        logs,
+        agent_analysis: None,
+        timed_out: true,
+    }
+}
+
+/// Run a service on a worker thread under a watchdog.
+///
+/// The watchdog kills the service's child processes when it exceeds
+/// `timeout` (time spent paused doesn't count) or when the run is
+/// cancelled. A cancelled service is given the grace period to return its
+/// own result; one stopped for time is reported as timed out.
+pub fn run_watched(
+    service: &'static dyn Service,
+    options: &serde_json::Value,
+    ctx: Arc<ServiceContext>,
+    timeout: Option<Duration>,
+) -> ServiceResult {
+    let service_id = ctx.service_id().to_string();
+    let started = Instant::now();
+    let watchdog = Arc::new(Watchdog::default());
+    let (tx, rx) = mpsc::channel();
+
+    let spawned = {
+        let watchdog = Arc::clone(&watchdog);
+        let options = options.clone();
+        let ctx = Arc::clone(&ctx);
+        std::thread::Builder::new()
+            .name(format!("service-{}", service_id))
+            .spawn(move || {
+                CURRENT_WATCHDOG.with(|w| *w.borrow_mut() = Some(watchdog));
+                let _ = tx.send(service.run(&options, &ctx));
+            })
+    };
+    if let Err(e) = spawned {
+        return failed_result(
+            &service_id,
+            format!("Failed to start service thread: {}", e),
+            started.elapsed(),
+        );
+    }
+
+    let mut deadline = timeout.map(|t| started + t);
+    loop {
+        match rx.recv_timeout(WATCH_INTERVAL) {
+            Ok(result) => return result,
+            Err(RecvTimeoutError::Disconnected) => {
+                return failed_result(
+                    &service_id,
+                    "Service stopped unexpectedly".to_string(),
+                    started.elapsed(),
+                )
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+
+        if ctx.is_cancelled() {
+            watchdog.expire();
+            return rx
+                .recv_timeout(KILL_GRACE_PERIOD)
+                .unwrap_or_else(|_| ctx.cancelled_result(started));
+        }
+
+        if ctx.is_paused() {
+            deadline = deadline.map(|d| d + WATCH_INTERVAL);
+        }
+
+        if let (Some(timeout), Some(deadline)) = (timeout, deadline) {
+            if Instant::now() >= deadline {
+                eprintln!(
+                    "[Watchdog] {} exceeded {}; killing its processes",
+                    service_id,
+                    describe_timeout(timeout)
+                );
+                watchdog.expire();
+                ctx.stop();
+                // Killing the children normally lets the service return promptly
+                let partial = rx.recv_timeout(KILL_GRACE_PERIOD).ok();
+                return timed_out_result(&ctx, timeout, started.elapsed(), partial);
+            }
+        }
+    }
+}
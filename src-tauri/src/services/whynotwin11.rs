@@ -14,7 +14,7 @@ use serde_json::json;
 use tauri::{AppHandle, Emitter};
 
 use crate::commands::get_program_exe_path;
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{FindingSeverity, ServiceDefinition, ServiceFinding, ServiceResult};
 
 // =============================================================================
@@ -39,7 +39,8 @@ impl Service for WhyNotWin11Service {
         }
     }
 
-    fn run(&self, _options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, _options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -74,6 +75,7 @@ impl Service for WhyNotWin11Service {
                     findings: vec![],
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
             Err(e) => {
@@ -85,6 +87,7 @@ impl Service for WhyNotWin11Service {
                     findings: vec![],
                     logs,
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -108,7 +111,7 @@ impl Service for WhyNotWin11Service {
                 "/silent",
             ])
             .current_dir(&work_dir)
-            .output();
+            .watched_output();
 
         match output {
             Ok(output) => {
@@ -252,6 +255,7 @@ impl Service for WhyNotWin11Service {
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
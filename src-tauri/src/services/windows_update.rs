@@ -7,11 +7,9 @@ use std::fs;
 use std::process::Command;
 use std::time::Instant;
 
-use chrono::Utc;
 use serde_json::json;
-use tauri::{AppHandle, Emitter};
 
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{
     FindingSeverity, ServiceDefinition, ServiceFinding, ServiceOptionSchema, ServiceResult,
 };
@@ -61,9 +59,8 @@ impl Service for WindowsUpdateService {
         }
     }
 
-    fn run(&self, options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
         let start = Instant::now();
-        let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
         let service_id = "windows-update";
 
@@ -77,33 +74,16 @@ impl Service for WindowsUpdateService {
             .and_then(|v| v.as_bool())
             .unwrap_or(true);
 
-        // Emit log helper
-        let emit_log = |log: &str, logs: &mut Vec<String>, app: &AppHandle| {
-            logs.push(log.to_string());
-            let _ = app.emit(
-                "service-log",
-                json!({
-                    "serviceId": service_id,
-                    "log": log,
-                    "timestamp": Utc::now().to_rfc3339()
-                }),
-            );
-        };
-
-        emit_log("Starting Windows Update check...", &mut logs, app);
-        emit_log(
-            &format!(
-                "Mode: {}, Include Drivers: {}",
-                if install_updates {
-                    "Install"
-                } else {
-                    "Scan Only"
-                },
-                include_drivers
-            ),
-            &mut logs,
-            app,
-        );
+        ctx.log("Starting Windows Update check...");
+        ctx.log(&format!(
+            "Mode: {}, Include Drivers: {}",
+            if install_updates {
+                "Install"
+            } else {
+                "Scan Only"
+            },
+            include_drivers
+        ));
 
         // Build PowerShell script
         let script = build_update_script(install_updates, include_drivers);
@@ -113,30 +93,37 @@ impl Service for WindowsUpdateService {
         let script_path = temp_dir.join("rustservice_wu_script.ps1");
 
         if let Err(e) = fs::write(&script_path, &script) {
-            emit_log(
-                &format!("ERROR: Could not write script: {}", e),
-                &mut logs,
-                app,
-            );
+            ctx.log(&format!("ERROR: Could not write script: {}", e));
             return ServiceResult {
                 service_id: service_id.to_string(),
                 success: false,
                 error: Some(format!("Failed to write script: {}", e)),
                 duration_ms: start.elapsed().as_millis() as u64,
                 findings,
-                logs,
+                logs: ctx.logs(),
                 agent_analysis: None,
+                timed_out: false,
             };
         }
 
-        emit_log(
-            "Executing Windows Update PowerShell script...",
-            &mut logs,
-            app,
+        ctx.log("Executing Windows Update PowerShell script...");
+        ctx.log("This may take several minutes...");
+
+        if ctx.wait_if_paused() {
+            let _ = fs::remove_file(&script_path);
+            return ctx.cancelled_result(start);
+        }
+        ctx.progress(
+            None,
+            if install_updates {
+                "Searching for and installing updates"
+            } else {
+                "Searching for updates"
+            },
         );
-        emit_log("This may take several minutes...", &mut logs, app);
 
-        // Execute PowerShell
+        // Execute PowerShell. The runner gives this service a long timeout
+        // (installs can take hours), so the watchdog only stops a hung run
         let output = match Command::new("powershell.exe")
             .args([
                 "-NoProfile",
@@ -145,16 +132,12 @@ impl Service for WindowsUpdateService {
                 "-File",
                 &script_path.to_string_lossy(),
             ])
-            .output()
+            .watched_output()
         {
             Ok(output) => output,
             Err(e) => {
                 let _ = fs::remove_file(&script_path);
-                emit_log(
-                    &format!("ERROR: Failed to run PowerShell: {}", e),
-                    &mut logs,
-                    app,
-                );
+                ctx.log(&format!("ERROR: Failed to run PowerShell: {}", e));
                 findings.push(ServiceFinding {
                     severity: FindingSeverity::Error,
                     title: "PowerShell Execution Failed".to_string(),
@@ -170,8 +153,9 @@ impl Service for WindowsUpdateService {
                     error: Some(format!("PowerShell execution failed: {}", e)),
                     duration_ms: start.elapsed().as_millis() as u64,
                     findings,
-                    logs,
+                    logs: ctx.logs(),
                     agent_analysis: None,
+                    timed_out: false,
                 };
             }
         };
@@ -179,46 +163,39 @@ impl Service for WindowsUpdateService {
         // Clean up temp file
         let _ = fs::remove_file(&script_path);
 
+        if ctx.is_cancelled() {
+            ctx.log("Note: Updates already installed before cancelling remain installed");
+            return ctx.cancelled_result(start);
+        }
+        ctx.progress(Some(100.0), "Update check complete");
+
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         let exit_code = output.status.code().unwrap_or(-1);
 
-        emit_log(
-            &format!("PowerShell completed with exit code: {}", exit_code),
-            &mut logs,
-            app,
-        );
+        ctx.log(&format!(
+            "PowerShell completed with exit code: {}",
+            exit_code
+        ));
 
         // Parse JSON output from script
         let parsed = parse_update_output(&stdout);
 
         // Log results
-        emit_log(
-            &format!("Available updates: {}", parsed.available_count),
-            &mut logs,
-            app,
-        );
+        ctx.log(&format!("Available updates: {}", parsed.available_count));
         if install_updates {
-            emit_log(
-                &format!("Installed updates: {}", parsed.installed_count),
-                &mut logs,
-                app,
-            );
-            emit_log(
-                &format!("Failed updates: {}", parsed.failed_count),
-                &mut logs,
-                app,
-            );
+            ctx.log(&format!("Installed updates: {}", parsed.installed_count));
+            ctx.log(&format!("Failed updates: {}", parsed.failed_count));
         }
         if parsed.reboot_required {
-            emit_log("Reboot is required to complete updates", &mut logs, app);
+            ctx.log("Reboot is required to complete updates");
         }
 
         // Log any stderr (usually module installation messages)
         if !stderr.is_empty() {
             for line in stderr.lines().take(10) {
                 if !line.trim().is_empty() {
-                    emit_log(&format!("[PS] {}", line), &mut logs, app);
+                    ctx.log(&format!("[PS] {}", line));
                 }
             }
         }
@@ -320,11 +297,7 @@ impl Service for WindowsUpdateService {
             })),
         });
 
-        emit_log(
-            &format!("Windows Update {} complete", mode_desc),
-            &mut logs,
-            app,
-        );
+        ctx.log(&format!("Windows Update {} complete", mode_desc));
 
         ServiceResult {
             service_id: service_id.to_string(),
@@ -332,8 +305,9 @@ impl Service for WindowsUpdateService {
             error: parsed.error,
             duration_ms: start.elapsed().as_millis() as u64,
             findings,
-            logs,
+            logs: ctx.logs(),
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
@@ -9,7 +9,7 @@ use chrono::Utc;
 use serde_json::json;
 use tauri::{AppHandle, Emitter};
 
-use crate::services::Service;
+use crate::services::{Service, ServiceContext, WatchedCommand};
 use crate::types::{
     FindingSeverity, ServiceDefinition, ServiceFinding, ServiceOptionSchema, ServiceResult,
 };
@@ -47,7 +47,8 @@ impl Service for WinsatService {
         }
     }
 
-    fn run(&self, options: &serde_json::Value, app: &AppHandle) -> ServiceResult {
+    fn run(&self, options: &serde_json::Value, ctx: &ServiceContext) -> ServiceResult {
+        let app = ctx.app();
         let start = Instant::now();
         let mut logs: Vec<String> = Vec::new();
         let mut findings: Vec<ServiceFinding> = Vec::new();
@@ -87,7 +88,7 @@ impl Service for WinsatService {
         // Using "winsat disk" which tests sequential and random read/write
         let output = Command::new("winsat")
             .args(["disk", "-drive", &drive.to_string()])
-            .output();
+            .watched_output();
 
         match output {
             Ok(output) => {
@@ -288,6 +289,7 @@ impl Service for WinsatService {
             findings,
             logs,
             agent_analysis: None,
+            timed_out: false,
         }
     }
 }
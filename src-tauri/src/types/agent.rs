@@ -68,6 +68,64 @@ pub enum ApprovalMode {
 }
 
 
+/// Risk class of a shell command, from least to most severe
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum CommandRiskClass {
+    /// Only reads or reports
+    #[default]
+    ReadOnly,
+    /// Sends data to or fetches data from other machines
+    NetworkEgress,
+    /// Changes files, settings, services or processes
+    ModifiesSystem,
+    /// Reads or exposes stored secrets
+    CredentialAccess,
+    /// Deletes data, wipes disks, logs or backups, or turns off protection
+    Destructive,
+}
+
+impl CommandRiskClass {
+    /// Short name used in approval explanations
+    pub fn label(self) -> &'static str {
+        match self {
+            CommandRiskClass::ReadOnly => "read-only",
+            CommandRiskClass::NetworkEgress => "network",
+            CommandRiskClass::ModifiesSystem => "system-modifying",
+            CommandRiskClass::CredentialAccess => "credential-access",
+            CommandRiskClass::Destructive => "destructive",
+        }
+    }
+}
+
+/// One finding behind a command's risk class
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandRiskReason {
+    pub class: CommandRiskClass,
+    /// The command (cmdlet, program or construct) the finding is about
+    pub command: String,
+    pub detail: String,
+}
+
+/// Risk analysis of a shell command
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandRisk {
+    /// Most severe class among the reasons; read-only when there are none
+    pub class: CommandRiskClass,
+    pub reasons: Vec<CommandRiskReason>,
+}
+
+/// Whether a command can run without the technician's approval, and why
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalDecision {
+    pub needs_approval: bool,
+    pub explanation: String,
+    pub risk: CommandRisk,
+}
+
 /// Status of a pending command
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -95,6 +153,21 @@ pub struct PendingCommand {
     pub output: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Who asked for the command when it wasn't the in-app agent (e.g. an MCP client)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// What the command was judged to do
+    #[serde(default)]
+    pub risk: CommandRisk,
+    /// How long the command ran, once it has run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elapsed_ms: Option<u64>,
+    /// Whether the command was killed for running past the timeout
+    #[serde(default)]
+    pub timed_out: bool,
+    /// Whether output was cut down to the output limit
+    #[serde(default)]
+    pub output_truncated: bool,
 }
 
 // =============================================================================
@@ -192,6 +265,25 @@ pub struct AgentSettings {
     #[serde(default = "default_whitelist")]
     pub whitelisted_commands: Vec<String>,
 
+    /// How long a shell command may run before it is killed along with
+    /// everything it started, in seconds. Zero disables the limit.
+    #[serde(default = "default_command_timeout_secs")]
+    pub command_timeout_secs: u64,
+
+    /// Output kept from each of a command's stdout and stderr, in bytes;
+    /// the middle of longer output is dropped. Zero disables the limit.
+    #[serde(default = "default_command_max_output_bytes")]
+    pub command_max_output_bytes: u64,
+
+    /// Keep one shell running per conversation, so state such as the working
+    /// directory and variables carries over between the agent's commands
+    #[serde(default)]
+    pub persistent_shell: bool,
+
+    /// Where the agent's and MCP's file tools may read and write
+    #[serde(default)]
+    pub filesystem_policy: FilesystemPolicy,
+
     /// Search provider to use
     #[serde(default)]
     pub search_provider: SearchProvider,
@@ -215,7 +307,8 @@ pub struct AgentSettings {
     #[serde(default)]
     pub mcp_server_enabled: bool,
 
-    /// API key for MCP server authentication (auto-generated)
+    /// Legacy single MCP API key. Moved into the hashed key store
+    /// (`data/mcp_keys.json`) as a full-access key when settings are loaded.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub mcp_api_key: Option<String>,
 
@@ -223,6 +316,23 @@ pub struct AgentSettings {
     #[serde(default = "default_mcp_port")]
     pub mcp_port: u16,
 
+    /// Request, concurrency and output limits for the MCP HTTP server
+    #[serde(default)]
+    pub mcp_limits: McpServerLimits,
+
+    /// Which network interfaces the MCP HTTP server listens on
+    #[serde(default)]
+    pub mcp_bind_address: McpBindAddress,
+
+    /// HTTPS for the MCP server
+    #[serde(default)]
+    pub mcp_tls: McpTlsSettings,
+
+    /// Redaction profile applied to reports read over MCP unless the caller
+    /// names another; `None` serves the full report
+    #[serde(default = "default_mcp_report_audience")]
+    pub mcp_report_audience: Option<String>,
+
     // ==========================================================================
     // MCP Client Settings (connecting to external servers)
     // ==========================================================================
@@ -231,8 +341,117 @@ pub struct AgentSettings {
     pub mcp_servers: Vec<MCPServerConfig>,
 }
 
+/// Folders the file tools may use. The data folder, the home folder and the
+/// working directory are always allowed; denied roots win over allowed ones,
+/// and sensitive locations such as `.ssh` are denied regardless.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FilesystemPolicy {
+    /// Extra folders the file tools may use
+    pub allowed_roots: Vec<String>,
+    /// Folders the file tools may never use, even inside an allowed one
+    pub denied_roots: Vec<String>,
+}
+
+/// Limits enforced by the MCP HTTP server. Zero disables a limit.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct McpServerLimits {
+    /// Largest request body accepted, in bytes
+    pub max_body_bytes: u64,
+    /// Client connections served at once; further clients wait to be accepted
+    pub max_connections: u32,
+    /// Tool calls running at once, across all keys
+    pub max_concurrent_tool_calls: u32,
+    /// JSON-RPC requests each API key may send per minute
+    pub requests_per_minute: u32,
+    /// Output returned by `read_file` and command tools, in bytes; longer
+    /// output is truncated
+    pub max_output_bytes: u64,
+}
+
+impl Default for McpServerLimits {
+    fn default() -> Self {
+        Self {
+            max_body_bytes: 1024 * 1024,
+            max_connections: 32,
+            max_concurrent_tool_calls: 4,
+            requests_per_minute: 120,
+            max_output_bytes: 256 * 1024,
+        }
+    }
+}
+
+/// Interfaces the MCP HTTP server listens on
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum McpBindAddress {
+    /// This machine only (`127.0.0.1`)
+    Loopback,
+    /// Every interface (`0.0.0.0`)
+    #[default]
+    All,
+    /// One interface, by its IP address
+    Interface { address: String },
+}
+
+/// TLS for the MCP HTTP server. Without a certificate path, a self-signed
+/// certificate is generated and kept in `data/mcp_tls`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct McpTlsSettings {
+    pub enabled: bool,
+    /// PEM certificate chain, leaf first
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cert_path: Option<String>,
+    /// PEM private key for the certificate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_path: Option<String>,
+}
+
+/// The certificate the MCP server presents, for clients to pin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpCertificateInfo {
+    /// SHA-256 of the leaf certificate, as colon-separated uppercase hex
+    pub sha256_fingerprint: String,
+    pub cert_path: String,
+    /// Whether this is the generated self-signed certificate
+    pub self_signed: bool,
+}
+
+/// Runtime state of the MCP HTTP server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerStatus {
+    pub running: bool,
+    /// Endpoint URL, e.g. `https://0.0.0.0:8377/mcp`
+    pub listening_address: Option<String>,
+    pub started_at: Option<String>,
+    pub uptime_secs: u64,
+    /// Requests to `/mcp` since the server started
+    pub requests_total: u64,
+    /// Of those, how many were refused or failed (HTTP 4xx/5xx)
+    pub requests_rejected: u64,
+    /// Sessions opened by `initialize` and not yet closed
+    pub active_sessions: usize,
+    pub connected_clients: Vec<McpConnectedClient>,
+    /// Why the server last failed to start, if it did
+    pub last_error: Option<String>,
+}
+
+/// An open connection to the MCP HTTP server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpConnectedClient {
+    pub remote_addr: String,
+    pub connected_at: String,
+    /// Requests sent on this connection
+    pub requests: u64,
+}
+
 /// Transport type for MCP server connections
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub enum MCPTransportType {
     #[default]
@@ -241,7 +460,7 @@ pub enum MCPTransportType {
 }
 
 /// Configuration for an external MCP server the agent connects to
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct MCPServerConfig {
     /// Unique identifier
@@ -262,6 +481,9 @@ pub struct MCPServerConfig {
     /// Optional custom headers
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub headers: Option<std::collections::HashMap<String, String>>,
+    /// Offer this server's tools to clients of our own MCP server
+    #[serde(default)]
+    pub reexport: bool,
 }
 
 fn default_model() -> String {
@@ -283,6 +505,18 @@ fn default_mcp_port() -> u16 {
     8377
 }
 
+fn default_mcp_report_audience() -> Option<String> {
+    Some("customer".to_string())
+}
+
+fn default_command_timeout_secs() -> u64 {
+    300
+}
+
+fn default_command_max_output_bytes() -> u64 {
+    128 * 1024
+}
+
 impl Default for AgentSettings {
     fn default() -> Self {
         Self {
@@ -292,6 +526,10 @@ impl Default for AgentSettings {
             base_url: None,
             approval_mode: ApprovalMode::default(),
             whitelisted_commands: default_whitelist(),
+            command_timeout_secs: default_command_timeout_secs(),
+            command_max_output_bytes: default_command_max_output_bytes(),
+            persistent_shell: false,
+            filesystem_policy: FilesystemPolicy::default(),
             search_provider: SearchProvider::default(),
             tavily_api_key: None,
             searxng_url: None,
@@ -300,12 +538,212 @@ impl Default for AgentSettings {
             mcp_server_enabled: false,
             mcp_api_key: None,
             mcp_port: default_mcp_port(),
+            mcp_limits: McpServerLimits::default(),
+            mcp_bind_address: McpBindAddress::default(),
+            mcp_tls: McpTlsSettings::default(),
+            mcp_report_audience: default_mcp_report_audience(),
             // MCP Client Settings
             mcp_servers: Vec::new(),
         }
     }
 }
 
+// =============================================================================
+// MCP API Key Types
+// =============================================================================
+
+/// Filesystem access granted to an MCP API key
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum McpFsAccess {
+    /// File tools are not allowed
+    #[default]
+    None,
+    /// Reading and listing only
+    ReadOnly,
+    /// Reading, writing, moving and copying
+    ReadWrite,
+}
+
+/// An MCP API key as stored (without its secret) and shown in the UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpApiKey {
+    pub id: String,
+    /// Display name, e.g. who or what the key was issued to
+    pub name: String,
+    /// First characters of the secret, to tell keys apart
+    pub key_prefix: String,
+    /// Tools this key may call; `None` allows every tool
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>,
+    #[serde(default)]
+    pub fs_access: McpFsAccess,
+    /// Path prefixes the file tools are limited to; empty allows any path
+    #[serde(default)]
+    pub fs_paths: Vec<String>,
+    pub created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<String>,
+}
+
+/// Request to create an MCP API key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMcpApiKeyRequest {
+    pub name: String,
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>,
+    #[serde(default)]
+    pub fs_access: McpFsAccess,
+    #[serde(default)]
+    pub fs_paths: Vec<String>,
+    /// RFC 3339 expiry time; `None` never expires
+    #[serde(default)]
+    pub expires_at: Option<String>,
+}
+
+/// A newly created key. The secret is only ever returned here.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedMcpApiKey {
+    pub key: McpApiKey,
+    pub secret: String,
+}
+
+// =============================================================================
+// MCP Client Types
+// =============================================================================
+
+/// Connection state of an external MCP server
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum McpClientState {
+    Connecting,
+    Connected,
+    /// Lost or failed; a reconnect is scheduled
+    Disconnected,
+}
+
+/// Status of one configured external MCP server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpClientServerStatus {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub state: McpClientState,
+    /// Name and version the server reported in `initialize`
+    pub server_info: Option<String>,
+    /// Original (un-namespaced) names of the server's tools
+    pub tools: Vec<String>,
+    pub last_error: Option<String>,
+    pub connected_at: Option<String>,
+    /// Last successful health check
+    pub last_checked_at: Option<String>,
+    /// Failed connection attempts since the last success
+    pub reconnect_attempts: u32,
+}
+
+/// A tool offered by an external MCP server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpClientTool {
+    /// Namespaced name, `mcp_<serverId>_<toolName>`
+    pub name: String,
+    pub server_id: String,
+    pub server_name: String,
+    /// Name on the external server
+    pub tool_name: String,
+    pub description: Option<String>,
+    /// JSON Schema for the tool's arguments
+    pub input_schema: serde_json::Value,
+    /// Whether our MCP server offers this tool to its clients
+    pub reexported: bool,
+}
+
+// =============================================================================
+// MCP Audit Types
+// =============================================================================
+
+/// One MCP request as recorded in the audit log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpAuditEntry {
+    pub id: i64,
+    /// RFC 3339 time the request arrived
+    pub timestamp: String,
+    /// `http` or `stdio`
+    pub transport: String,
+    /// Client address for HTTP requests
+    pub remote_addr: Option<String>,
+    /// Key the request authenticated with; `None` for stdio and rejected requests
+    pub key_id: Option<String>,
+    pub key_name: Option<String>,
+    /// JSON-RPC method; `None` when the request never got that far
+    pub method: Option<String>,
+    /// Tool name for `tools/call`
+    pub tool: Option<String>,
+    /// Request arguments as JSON, with secrets redacted and long values cut
+    pub arguments: Option<String>,
+    pub duration_ms: u64,
+    pub success: bool,
+    pub error: Option<String>,
+    /// Size of the serialized response
+    pub output_bytes: u64,
+}
+
+/// Filters for the MCP audit log. All filters are optional and combined with AND.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct McpAuditQuery {
+    pub key_id: Option<String>,
+    pub method: Option<String>,
+    pub tool: Option<String>,
+    pub success: Option<bool>,
+    /// Only requests at or after this time (RFC 3339 or YYYY-MM-DD)
+    pub after: Option<String>,
+    /// Only requests before this time (RFC 3339 or YYYY-MM-DD, exclusive)
+    pub before: Option<String>,
+    /// Free-text search over arguments and errors
+    pub text: Option<String>,
+    /// Number of matching entries to skip
+    pub offset: Option<usize>,
+    /// Maximum number of entries to return
+    pub limit: Option<usize>,
+}
+
+/// A page of audit log entries, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpAuditQueryResult {
+    /// Total number of entries matching the filters (ignoring pagination)
+    pub total: usize,
+    pub entries: Vec<McpAuditEntry>,
+}
+
+/// File format for exporting the audit log
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum McpAuditExportFormat {
+    Csv,
+    Json,
+}
+
+impl McpAuditExportFormat {
+    /// File extension used when writing an export to disk
+    pub fn extension(self) -> &'static str {
+        match self {
+            McpAuditExportFormat::Csv => "csv",
+            McpAuditExportFormat::Json => "json",
+        }
+    }
+}
+
 // =============================================================================
 // Tool Execution Types
 // =============================================================================
@@ -329,9 +767,75 @@ pub struct ToolExecutionResponse {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CommandExecutionResult {
+    /// -1 when the command was killed or its exit code is unknown
     pub exit_code: i32,
     pub stdout: String,
     pub stderr: String,
+    pub elapsed_ms: u64,
+    /// Whether the command was killed for running past the timeout
+    pub timed_out: bool,
+    /// Bytes the command wrote to stdout, including any dropped
+    pub stdout_bytes: u64,
+    /// Bytes the command wrote to stderr, including any dropped
+    pub stderr_bytes: u64,
+    /// Whether the middle of stdout or stderr was dropped to fit the output limit
+    pub truncated: bool,
+}
+
+/// Which stream a piece of command output came from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CommandOutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Output of a running command, emitted as `command-output`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandOutputEvent {
+    /// The command's ID, or the ID the caller asked output to be reported under
+    pub id: String,
+    pub stream: CommandOutputStream,
+    pub text: String,
+}
+
+// =============================================================================
+// File Journal Types
+// =============================================================================
+
+/// A file operation the agent performs
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FileOperationKind {
+    Write,
+    Edit,
+    Move,
+    Copy,
+}
+
+/// A journaled file operation, with what is needed to undo it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileJournalEntry {
+    pub id: String,
+    pub conversation_id: Option<String>,
+    pub operation: FileOperationKind,
+    /// The file written or edited, or the destination of a move or copy
+    pub path: String,
+    /// Where a moved or copied file came from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Size of the file the operation replaced at `path`, whose content is
+    /// kept in a snapshot. `None` when the operation created `path`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replaced_bytes: Option<u64>,
+    /// SHA-256 of `path` after the operation, to spot later changes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result_checksum: Option<String>,
+    pub created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub undone_at: Option<String>,
 }
 
 // =============================================================================
@@ -564,6 +1068,34 @@ pub struct PathValidationResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
     pub within_sandbox: bool,
+    /// The filesystem policy rule that decided access, once the path resolved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule: Option<PathPolicyRule>,
+}
+
+/// Kinds of filesystem policy rule
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PathRuleKind {
+    /// A sensitive location no setting can open up
+    Protected,
+    /// Inside one of the policy's denied roots
+    DeniedRoot,
+    /// Inside an allowed root
+    AllowedRoot,
+    /// Not inside any allowed root
+    OutsideAllowedRoots,
+}
+
+/// The rule that decided whether a path may be used
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathPolicyRule {
+    pub kind: PathRuleKind,
+    /// The root that matched, or the name of the protected location
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched: Option<String>,
+    pub allowed: bool,
 }
 
 /// Request to read a file from the filesystem
@@ -152,6 +152,31 @@ pub enum FindingSeverity {
     Critical,
 }
 
+impl FindingSeverity {
+    /// Numeric rank for ordering, from least (Success) to most severe (Critical)
+    pub fn rank(&self) -> u8 {
+        match self {
+            FindingSeverity::Success => 0,
+            FindingSeverity::Info => 1,
+            FindingSeverity::Warning => 2,
+            FindingSeverity::Error => 3,
+            FindingSeverity::Critical => 4,
+        }
+    }
+
+    /// Inverse of [`FindingSeverity::rank`]
+    pub fn from_rank(rank: u8) -> Option<Self> {
+        match rank {
+            0 => Some(FindingSeverity::Success),
+            1 => Some(FindingSeverity::Info),
+            2 => Some(FindingSeverity::Warning),
+            3 => Some(FindingSeverity::Error),
+            4 => Some(FindingSeverity::Critical),
+            _ => None,
+        }
+    }
+}
+
 /// A single finding from a service
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -190,6 +215,9 @@ pub struct ServiceResult {
     /// Agent-generated analysis for this service result
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub agent_analysis: Option<String>,
+    /// The service was stopped by the watchdog for exceeding its timeout
+    #[serde(default)]
+    pub timed_out: bool,
 }
 
 /// Status of a service run
@@ -253,6 +281,12 @@ pub struct ServiceReport {
     /// Agent-computed health score (0-100)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub health_score: Option<u8>,
+    /// Pinned reports are never removed by retention pruning
+    #[serde(default)]
+    pub pinned: bool,
+    /// Machine the run was performed on (absent in reports from older versions)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub machine: Option<MachineIdentity>,
 }
 
 // =============================================================================
@@ -274,6 +308,23 @@ pub struct ServiceRunState {
 }
 
 
+/// Snapshot of an in-progress run, written to disk after every service so
+/// the run can be resumed after a crash or reboot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunCheckpoint {
+    /// Report with the results gathered so far
+    pub report: ServiceReport,
+    /// Enabled queue items in execution order
+    pub queue: Vec<ServiceQueueItem>,
+    /// Indices into `queue` of services that have finished
+    pub completed_indices: Vec<usize>,
+    /// Run time before the checkpoint in milliseconds
+    pub elapsed_ms: u64,
+    /// When the checkpoint was written (ISO string)
+    pub checkpointed_at: String,
+}
+
 // =============================================================================
 // Report Statistics (computed by agent tooling)
 // =============================================================================
@@ -314,3 +365,313 @@ pub struct FindingSeverityCounts {
     pub error: usize,
     pub critical: usize,
 }
+
+// =============================================================================
+// Report Retention
+// =============================================================================
+
+/// Summary of a retention pass over the reports directory
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportPruneSummary {
+    /// IDs of reports that were removed
+    pub removed: Vec<ReportId>,
+    /// Zip archive the removed reports were moved into (if archiving is enabled)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive_path: Option<String>,
+    /// Number of expired reports kept because they are pinned
+    pub kept_pinned: usize,
+    /// Total size of the removed files in bytes
+    pub bytes_freed: u64,
+    /// Reports that could not be removed, with the reason
+    pub errors: Vec<String>,
+}
+
+// =============================================================================
+// Report Index
+// =============================================================================
+
+/// Lightweight report listing row served from the report index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportIndexEntry {
+    pub id: ReportId,
+    pub started_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+    pub status: ServiceRunStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub technician_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health_score: Option<u8>,
+    /// Services that were run, in result order
+    pub service_ids: Vec<ServiceId>,
+    /// Number of services that failed
+    pub failed_count: usize,
+    /// Number of findings across all services
+    pub finding_count: usize,
+    /// Most severe finding in the report
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_severity: Option<FindingSeverity>,
+    pub pinned: bool,
+    /// Key of the machine the report was produced on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub machine_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+}
+
+/// Order of report search results
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReportSort {
+    #[default]
+    Newest,
+    Oldest,
+    DurationDesc,
+    DurationAsc,
+    /// Technician name, A to Z
+    Technician,
+}
+
+/// Filters for searching the report index. All filters are optional and combined with AND.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReportQuery {
+    /// Case-insensitive substring match on report ID, customer or technician name
+    pub search: Option<String>,
+    /// Case-insensitive substring match on customer name
+    pub customer_name: Option<String>,
+    /// Case-insensitive substring match on technician name
+    pub technician_name: Option<String>,
+    /// Only reports started at or after this time (RFC 3339 or YYYY-MM-DD)
+    pub started_after: Option<String>,
+    /// Only reports started before this time (RFC 3339 or YYYY-MM-DD, exclusive)
+    pub started_before: Option<String>,
+    /// Only reports with one of these statuses
+    pub statuses: Vec<ServiceRunStatus>,
+    /// Only reports that ran at least one of these services
+    pub service_ids: Vec<ServiceId>,
+    /// Only reports with a finding at least this severe
+    pub min_severity: Option<FindingSeverity>,
+    /// Free-text search over finding titles
+    pub text: Option<String>,
+    /// Only reports produced on this machine
+    pub machine_key: Option<String>,
+    /// Result order; newest first by default
+    pub sort: ReportSort,
+    /// Number of matching reports to skip
+    pub offset: Option<usize>,
+    /// Maximum number of reports to return
+    pub limit: Option<usize>,
+}
+
+/// A page of report search results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportQueryResult {
+    /// Total number of reports matching the filters (ignoring pagination)
+    pub total: usize,
+    pub reports: Vec<ReportIndexEntry>,
+}
+
+// =============================================================================
+// Machine History
+// =============================================================================
+
+/// Hardware identity of the machine a report was produced on
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MachineIdentity {
+    /// Stable key derived from the most specific hardware identifier available
+    pub machine_key: String,
+    pub hostname: String,
+    /// SMBIOS system UUID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_uuid: Option<String>,
+    /// System (chassis) serial number
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_serial: Option<String>,
+    /// Motherboard serial number
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub board_serial: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vendor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// A machine that appears in saved reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MachineSummary {
+    pub machine_key: String,
+    /// Hostname at the most recent visit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vendor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Customer name at the most recent visit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer_name: Option<String>,
+    pub report_count: usize,
+    /// Start time of the first report for this machine
+    pub first_seen: String,
+    /// Start time of the most recent report for this machine
+    pub last_seen: String,
+}
+
+/// A single value of a metric, taken from one report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendPoint {
+    pub report_id: ReportId,
+    /// When the report was started (ISO string)
+    pub timestamp: String,
+    pub value: f64,
+}
+
+/// A metric tracked across every visit of a machine, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricTrend {
+    /// Metric identifier (e.g. "smart_wear", "disk_free")
+    pub metric: String,
+    /// Human-readable metric name
+    pub label: String,
+    /// Unit of `value` (e.g. "%", "bytes", "Mbps")
+    pub unit: String,
+    /// What the metric refers to when there can be several (drive, mount point)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    pub points: Vec<TrendPoint>,
+    /// Last value minus first value (absent with fewer than two points)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change: Option<f64>,
+}
+
+/// Metric trends for one machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MachineTrends {
+    pub machine_key: String,
+    /// Number of reports the trends were computed from
+    pub report_count: usize,
+    pub trends: Vec<MetricTrend>,
+}
+
+// =============================================================================
+// Report Diff
+// =============================================================================
+
+/// A service whose pass/fail result differs between two reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceStatusChange {
+    pub service_id: ServiceId,
+    pub before_success: bool,
+    pub after_success: bool,
+}
+
+/// A finding present in only one of two reports, or whose severity changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FindingChange {
+    pub service_id: ServiceId,
+    /// Title in the later report (earlier report if the finding disappeared)
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before_severity: Option<FindingSeverity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_severity: Option<FindingSeverity>,
+}
+
+/// Change of a numeric metric between two reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricDelta {
+    /// Metric identifier (e.g. "disk_free", "bleachbit_reclaimed")
+    pub metric: String,
+    /// Human-readable metric name
+    pub label: String,
+    /// Unit of the values (e.g. "bytes", "items", "MB/s")
+    pub unit: String,
+    /// What the metric refers to when there can be several (drive, mount point)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<f64>,
+    /// `after - before` when both values exist
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta: Option<f64>,
+}
+
+/// Structured "before vs after" comparison of two reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportDiff {
+    pub before_report_id: ReportId,
+    pub after_report_id: ReportId,
+    pub before_started_at: String,
+    pub after_started_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before_health_score: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_health_score: Option<u8>,
+    /// Services run only in the later report
+    pub services_added: Vec<ServiceId>,
+    /// Services run only in the earlier report
+    pub services_removed: Vec<ServiceId>,
+    /// Services run in both reports whose pass/fail result changed
+    pub status_changes: Vec<ServiceStatusChange>,
+    /// Findings only in the later report (services run in both reports)
+    pub findings_appeared: Vec<FindingChange>,
+    /// Findings only in the earlier report (services run in both reports)
+    pub findings_resolved: Vec<FindingChange>,
+    /// Findings in both reports with a different severity
+    pub severity_changes: Vec<FindingChange>,
+    pub metric_deltas: Vec<MetricDelta>,
+}
+
+// =============================================================================
+// Report Export
+// =============================================================================
+
+/// Text-based output format for report export
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportExportFormat {
+    /// Self-contained HTML document (inline CSS, embedded logo)
+    Html,
+    /// Markdown, suitable for pasting into ticket systems
+    Markdown,
+}
+
+impl ReportExportFormat {
+    /// File extension used when writing an export to disk
+    pub fn extension(self) -> &'static str {
+        match self {
+            ReportExportFormat::Html => "html",
+            ReportExportFormat::Markdown => "md",
+        }
+    }
+}
+
+/// How much detail an exported report includes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportDetailLevel {
+    /// Findings and recommendations only
+    #[default]
+    Customer,
+    /// Everything, including service logs and raw finding data
+    Technician,
+}
@@ -6,7 +6,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::types::agent::AgentSettings;
-use crate::types::service::ServicePreset;
+use crate::types::service::{FindingSeverity, ServicePreset};
 
 /// Current settings schema version for migration support
 pub const SETTINGS_VERSION: &str = "0.7.0";
@@ -72,6 +72,12 @@ pub struct ReportsSettings {
     /// Whether to include detailed logs in saved reports
     #[serde(default = "default_include_logs")]
     pub include_logs_in_report: bool,
+    /// Move expired reports into a zip archive instead of deleting them
+    #[serde(default)]
+    pub archive_expired_reports: bool,
+    /// Named redaction profiles applied when exporting for an audience
+    #[serde(default = "default_redaction_profiles")]
+    pub redaction_profiles: Vec<ReportRedactionProfile>,
 }
 
 fn default_auto_save() -> bool {
@@ -82,16 +88,87 @@ fn default_include_logs() -> bool {
     true
 }
 
+fn default_redaction_profiles() -> Vec<ReportRedactionProfile> {
+    vec![
+        ReportRedactionProfile {
+            name: String::from("customer"),
+            hidden_severities: vec![FindingSeverity::Info],
+            hidden_services: Vec::new(),
+            include_logs: false,
+            include_finding_data: false,
+            include_agent_analysis: true,
+            include_agent_summary: true,
+            include_technician_name: true,
+            include_machine_identity: false,
+            redact_paths: true,
+            redact_usernames: true,
+        },
+        ReportRedactionProfile {
+            name: String::from("technician"),
+            hidden_severities: Vec::new(),
+            hidden_services: Vec::new(),
+            include_logs: true,
+            include_finding_data: true,
+            include_agent_analysis: true,
+            include_agent_summary: true,
+            include_technician_name: true,
+            include_machine_identity: true,
+            redact_paths: false,
+            redact_usernames: false,
+        },
+    ]
+}
+
 impl Default for ReportsSettings {
     fn default() -> Self {
         Self {
             auto_save_reports: true,
             report_retention_days: 0,
             include_logs_in_report: true,
+            archive_expired_reports: false,
+            redaction_profiles: default_redaction_profiles(),
         }
     }
 }
 
+/// Named profile deciding which parts of a report an audience sees
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportRedactionProfile {
+    /// Profile name used to select it (e.g., "customer")
+    pub name: String,
+    /// Finding severities to leave out
+    #[serde(default)]
+    pub hidden_severities: Vec<FindingSeverity>,
+    /// Service IDs whose results are left out entirely
+    #[serde(default)]
+    pub hidden_services: Vec<String>,
+    /// Whether service logs are kept
+    #[serde(default)]
+    pub include_logs: bool,
+    /// Whether raw finding `data` blobs are kept
+    #[serde(default)]
+    pub include_finding_data: bool,
+    /// Whether per-service agent analysis is kept
+    #[serde(default)]
+    pub include_agent_analysis: bool,
+    /// Whether the agent executive summary is kept
+    #[serde(default)]
+    pub include_agent_summary: bool,
+    /// Whether the technician's name is kept
+    #[serde(default)]
+    pub include_technician_name: bool,
+    /// Whether the machine's hostname and hardware serials are kept
+    #[serde(default)]
+    pub include_machine_identity: bool,
+    /// Replace file paths in logs, finding text and data, and agent text with a placeholder
+    #[serde(default)]
+    pub redact_paths: bool,
+    /// Replace user names (profile folders, current user) with a placeholder
+    #[serde(default)]
+    pub redact_usernames: bool,
+}
+
 /// Business branding and technician settings
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
@@ -177,6 +254,47 @@ pub struct PresetsSettings {
     pub custom_presets: Vec<ServicePreset>,
 }
 
+/// Service runner settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServicesSettings {
+    /// Whether the watchdog stops services that run far past their expected duration
+    #[serde(default = "default_timeouts_enabled")]
+    pub timeouts_enabled: bool,
+    /// Timeout as a multiple of the expected duration
+    #[serde(default = "default_timeout_multiplier")]
+    pub timeout_multiplier: f64,
+    /// Lower bound for computed timeouts, in seconds
+    #[serde(default = "default_min_timeout_secs")]
+    pub min_timeout_secs: u64,
+    /// Fixed timeouts in seconds keyed by service ID (0 = never time out)
+    #[serde(default)]
+    pub timeout_overrides: std::collections::HashMap<String, u64>,
+}
+
+fn default_timeouts_enabled() -> bool {
+    true
+}
+
+fn default_timeout_multiplier() -> f64 {
+    3.0
+}
+
+fn default_min_timeout_secs() -> u64 {
+    600
+}
+
+impl Default for ServicesSettings {
+    fn default() -> Self {
+        Self {
+            timeouts_enabled: true,
+            timeout_multiplier: default_timeout_multiplier(),
+            min_timeout_secs: default_min_timeout_secs(),
+            timeout_overrides: std::collections::HashMap::new(),
+        }
+    }
+}
+
 /// Main application settings schema
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -204,6 +322,9 @@ pub struct AppSettings {
     /// Custom service presets
     #[serde(default)]
     pub presets: PresetsSettings,
+    /// Service runner settings (timeouts)
+    #[serde(default)]
+    pub services: ServicesSettings,
     /// Agent AI settings
     #[serde(default)]
     pub agent: AgentSettings,
@@ -220,6 +341,7 @@ impl Default for AppSettings {
             programs: ProgramsSettings::default(),
             technician_tabs: TechnicianTabsSettings::default(),
             presets: PresetsSettings::default(),
+            services: ServicesSettings::default(),
             agent: AgentSettings::default(),
         }
     }
@@ -54,6 +54,7 @@ import { SettingsProvider, useSettings } from '@/components/settings-context';
 import { AnimationProvider, useAnimation, motion, AnimatePresence, tabContentVariants } from '@/components/animation-context';
 import { ServiceRunProvider } from '@/components/service-run-context';
 import { FloatingServiceStatus } from '@/components/floating-service-status';
+import { CommandApprovalPrompt } from '@/components/command-approval-prompt';
 import { Titlebar } from '@/components/titlebar';
 import { IframeTabContent } from '@/components/IframeTabContent';
 import { ErrorBoundary } from '@/components/error-boundary';
@@ -389,6 +390,9 @@ function AppContent() {
 
       {/* Floating service status pill - visible when services run on another tab */}
       <FloatingServiceStatus activeTab={activeTab} />
+
+      {/* Commands queued by the approval mode (MCP clients, instruments) */}
+      <CommandApprovalPrompt />
     </div>
   );
 }
@@ -48,6 +48,7 @@ interface AgentActivityItemProps {
   activity: AgentActivity;
   onApprove?: (activityId: string) => void;
   onReject?: (activityId: string) => void;
+  onResetShell?: () => Promise<void>;
 }
 
 /**
@@ -452,7 +453,7 @@ function FileOperationBlock({
   );
 }
 
-export function AgentActivityItem({ activity, onApprove, onReject }: AgentActivityItemProps) {
+export function AgentActivityItem({ activity, onApprove, onReject, onResetShell }: AgentActivityItemProps) {
   const config = getActivityConfig(activity.type, activity.status);
   const [expanded, setExpanded] = useState(false);
   const hasExpandableOutput = !!(activity.output || activity.error) && (activity.output?.length ?? 0) + (activity.error?.length ?? 0) > 80;
@@ -469,6 +470,7 @@ export function AgentActivityItem({ activity, onApprove, onReject }: AgentActivi
         status={activity.status}
         onApprove={onApprove ? () => onApprove(activity.id) : undefined}
         onReject={onReject ? () => onReject(activity.id) : undefined}
+        onResetShell={onResetShell}
       />
     );
   }
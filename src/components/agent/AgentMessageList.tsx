@@ -24,6 +24,8 @@ interface AgentMessageListProps {
   activeServiceRun: ActiveServiceRun | null;
   onActivityApprove: (activityId: string) => void;
   onActivityReject: (activityId: string) => void;
+  /** Replace the conversation's shell session with a fresh one */
+  onResetShell?: () => Promise<void>;
   onSuggestionClick: (text: string) => void;
   onCancelServiceRun: () => void;
 }
@@ -49,6 +51,7 @@ export function AgentMessageList({
   activeServiceRun,
   onActivityApprove,
   onActivityReject,
+  onResetShell,
   onSuggestionClick,
   onCancelServiceRun,
 }: AgentMessageListProps) {
@@ -88,6 +91,7 @@ export function AgentMessageList({
                   }
                   onActivityApprove={onActivityApprove}
                   onActivityReject={onActivityReject}
+                  onResetShell={onResetShell}
                 />
               </motion.div>
             ))}
@@ -1,4 +1,5 @@
 import { InstrumentList } from '@/components/agent/InstrumentList';
+import { FileJournalList } from '@/components/agent/FileJournalList';
 import { FileCode, Info, Plug, AlertCircle, Package } from 'lucide-react';
 import { cn } from '@/lib/utils';
 import { Badge } from '@/components/ui/badge';
@@ -14,6 +15,8 @@ interface AgentRightSidebarProps {
   onRunInstrument: (name: string) => void;
   mcpState?: MCPManagerState;
   toolSummary?: Array<{ id: string; name: string; desc: string; enabled: boolean; requiresApproval?: boolean }>;
+  /** Current conversation, whose file changes are listed for undo */
+  conversationId?: string | null;
 }
 
 /**
@@ -183,7 +186,7 @@ function ProgramList() {
  * Agent Right Sidebar
  * Shows instruments, programs, and agent info
  */
-export function AgentRightSidebar({ className, onRunInstrument, mcpState, toolSummary }: AgentRightSidebarProps) {
+export function AgentRightSidebar({ className, onRunInstrument, mcpState, toolSummary, conversationId }: AgentRightSidebarProps) {
   return (
     <div className={cn("flex flex-col h-full bg-background overflow-hidden", className)}>
       {/* Instruments Section */}
@@ -222,6 +225,7 @@ export function AgentRightSidebar({ className, onRunInstrument, mcpState, toolSu
           </div>
         </div>
         <ScrollArea className="h-full px-4 py-3">
+          {conversationId && <FileJournalList conversationId={conversationId} className="mb-4" />}
           <AgentInfoPanel mcpState={mcpState} toolSummary={toolSummary} />
         </ScrollArea>
       </div>
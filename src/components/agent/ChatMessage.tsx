@@ -35,6 +35,7 @@ interface ChatMessageProps {
   attachments?: FileAttachment[]; // Legacy support for direct attachments
   onActivityApprove?: (activityId: string) => void;
   onActivityReject?: (activityId: string) => void;
+  onResetShell?: () => Promise<void>;
 }
 
 // =============================================================================
@@ -66,6 +67,7 @@ export const ChatMessage = memo(function ChatMessage({
   attachments,
   onActivityApprove,
   onActivityReject,
+  onResetShell,
 }: ChatMessageProps) {
   const isUser = role === 'user';
   const isSystem = role === 'system';
@@ -153,6 +155,7 @@ export const ChatMessage = memo(function ChatMessage({
                     activity={part.activity}
                     onApprove={onActivityApprove}
                     onReject={onActivityReject}
+                    onResetShell={onResetShell}
                   />
                 );
               }
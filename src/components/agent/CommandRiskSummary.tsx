@@ -0,0 +1,59 @@
+/**
+ * Command Risk Summary Component
+ *
+ * Risk class badge and the reasons behind it, shown wherever a command
+ * waits for approval.
+ */
+
+import { Badge } from '@/components/ui/badge';
+import { cn } from '@/lib/utils';
+import type { CommandRisk, CommandRiskClass } from '@/types/agent';
+
+const RISK_LABELS: Record<CommandRiskClass, string> = {
+  readOnly: 'Read-only',
+  networkEgress: 'Network',
+  modifiesSystem: 'Modifies system',
+  credentialAccess: 'Credential access',
+  destructive: 'Destructive',
+};
+
+const RISK_TEXT: Record<CommandRiskClass, string> = {
+  readOnly: 'text-chart-2',
+  networkEgress: 'text-chart-1',
+  modifiesSystem: 'text-chart-4',
+  credentialAccess: 'text-destructive',
+  destructive: 'text-destructive',
+};
+
+const RISK_BORDER: Record<CommandRiskClass, string> = {
+  readOnly: 'border-chart-2/40',
+  networkEgress: 'border-chart-1/40',
+  modifiesSystem: 'border-chart-4/40',
+  credentialAccess: 'border-destructive/40',
+  destructive: 'border-destructive/60 bg-destructive/10',
+};
+
+export function CommandRiskBadge({ risk }: { risk: CommandRisk }) {
+  return (
+    <Badge variant="outline" className={cn('text-[10px] h-5 shrink-0', RISK_BORDER[risk.class], RISK_TEXT[risk.class])}>
+      {RISK_LABELS[risk.class]}
+    </Badge>
+  );
+}
+
+export function CommandRiskSummary({ risk, className }: { risk: CommandRisk; className?: string }) {
+  if (risk.reasons.length === 0) return null;
+
+  return (
+    <ul className={cn('space-y-0.5 text-xs text-muted-foreground', className)}>
+      {risk.reasons.map((reason, i) => (
+        <li key={i} className="flex gap-1.5">
+          <span className={cn('font-mono shrink-0', RISK_TEXT[reason.class])}>
+            {reason.command}
+          </span>
+          <span>{reason.detail}</span>
+        </li>
+      ))}
+    </ul>
+  );
+}
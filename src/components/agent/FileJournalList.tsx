@@ -0,0 +1,130 @@
+/**
+ * File Journal List Component
+ *
+ * The agent's file operations in the current conversation, newest first,
+ * with undo for a single operation or everything from one onwards.
+ */
+
+import { useCallback, useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { Undo2, History } from 'lucide-react';
+
+import { Badge } from '@/components/ui/badge';
+import { Button } from '@/components/ui/button';
+import { cn } from '@/lib/utils';
+import type { FileJournalEntry } from '@/types/agent';
+
+/** The journal isn't evented; poll while the panel is open */
+const POLL_INTERVAL_MS = 5000;
+
+function fileName(path: string) {
+  return path.split(/[\\/]/).pop() || path;
+}
+
+interface FileJournalListProps {
+  conversationId: string;
+  className?: string;
+}
+
+export function FileJournalList({ conversationId, className }: FileJournalListProps) {
+  const [entries, setEntries] = useState<FileJournalEntry[]>([]);
+  const [busy, setBusy] = useState(false);
+  /** Last failed undo, offered again with force */
+  const [failure, setFailure] = useState<{ entryId: string; rollback: boolean; error: string } | null>(null);
+
+  const refresh = useCallback(async () => {
+    try {
+      setEntries(await invoke<FileJournalEntry[]>('list_file_journal', { conversationId }));
+    } catch (e) {
+      console.error('Failed to load file journal:', e);
+    }
+  }, [conversationId]);
+
+  useEffect(() => {
+    setFailure(null);
+    refresh();
+    const timer = setInterval(refresh, POLL_INTERVAL_MS);
+    return () => clearInterval(timer);
+  }, [refresh]);
+
+  const undo = async (entryId: string, rollback: boolean, force = false) => {
+    setBusy(true);
+    setFailure(null);
+    try {
+      await invoke(rollback ? 'rollback_file_journal' : 'undo_file_operation', { entryId, force });
+    } catch (e) {
+      setFailure({ entryId, rollback, error: String(e) });
+    } finally {
+      setBusy(false);
+      await refresh();
+    }
+  };
+
+  if (entries.length === 0) return null;
+
+  return (
+    <div className={className}>
+      <h3 className="text-sm font-medium mb-2 flex items-center gap-1.5">
+        <History className="h-3.5 w-3.5 text-muted-foreground" />
+        File Changes
+      </h3>
+      <div className="space-y-1.5">
+        {entries.map(entry => (
+          <div key={entry.id} className={cn('py-1.5 px-2 rounded-md bg-muted/40', entry.undoneAt && 'opacity-50')}>
+            <div className="flex items-center gap-1.5">
+              <Badge variant="outline" className="text-[10px] h-5 shrink-0">{entry.operation}</Badge>
+              <span className="text-xs font-mono truncate flex-1" title={entry.path}>
+                {fileName(entry.path)}
+              </span>
+              {entry.undoneAt ? (
+                <span className="text-[10px] text-muted-foreground">Undone</span>
+              ) : (
+                <>
+                  <Button
+                    size="sm"
+                    variant="ghost"
+                    className="h-6 px-1.5 text-[10px]"
+                    title="Undo this change"
+                    disabled={busy}
+                    onClick={() => undo(entry.id, false)}
+                  >
+                    <Undo2 className="h-3 w-3" />
+                  </Button>
+                  <Button
+                    size="sm"
+                    variant="ghost"
+                    className="h-6 px-1.5 text-[10px]"
+                    title="Undo this change and every later one"
+                    disabled={busy}
+                    onClick={() => undo(entry.id, true)}
+                  >
+                    To here
+                  </Button>
+                </>
+              )}
+            </div>
+            {entry.source && (
+              <div className="text-[10px] text-muted-foreground font-mono truncate mt-0.5" title={entry.source}>
+                from {entry.source}
+              </div>
+            )}
+            {failure?.entryId === entry.id && (
+              <div className="mt-1 space-y-1">
+                <p className="text-[10px] text-red-400">{failure.error}</p>
+                <Button
+                  size="sm"
+                  variant="outline"
+                  className="h-6 px-2 text-[10px]"
+                  disabled={busy}
+                  onClick={() => undo(entry.id, failure.rollback, true)}
+                >
+                  Undo anyway
+                </Button>
+              </div>
+            )}
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
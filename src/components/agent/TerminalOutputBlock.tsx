@@ -18,10 +18,14 @@ import {
   Loader2,
   Play,
   AlertCircle,
+  RotateCcw,
 } from 'lucide-react';
+import { invoke } from '@tauri-apps/api/core';
 import { cn } from '@/lib/utils';
 import { Button } from '@/components/ui/button';
+import { CommandRiskBadge, CommandRiskSummary } from './CommandRiskSummary';
 import type { ActivityStatus } from '@/types/agent-activity';
+import type { ApprovalDecision } from '@/types/agent';
 
 interface TerminalOutputBlockProps {
   command: string;
@@ -33,6 +37,8 @@ interface TerminalOutputBlockProps {
   defaultExpanded?: boolean;
   onApprove?: () => void;
   onReject?: () => void;
+  /** Replace the conversation's shell session with a fresh one */
+  onResetShell?: () => Promise<void>;
 }
 
 export function TerminalOutputBlock({
@@ -45,9 +51,12 @@ export function TerminalOutputBlock({
   defaultExpanded = false,
   onApprove,
   onReject,
+  onResetShell,
 }: TerminalOutputBlockProps) {
   const [expanded, setExpanded] = useState(defaultExpanded);
   const [copied, setCopied] = useState(false);
+  const [shellReset, setShellReset] = useState<'idle' | 'resetting' | 'done' | 'failed'>('idle');
+  const [assessment, setAssessment] = useState<ApprovalDecision | null>(null);
 
   // Auto-expand when we get output or on error
   useEffect(() => {
@@ -56,6 +65,20 @@ export function TerminalOutputBlock({
     }
   }, [output, error, status]);
 
+  // Show what the command would do while it waits for approval
+  useEffect(() => {
+    if (status !== 'pending_approval' || !command) return;
+    let cancelled = false;
+    invoke<ApprovalDecision>('assess_agent_command', { command })
+      .then(decision => {
+        if (!cancelled) setAssessment(decision);
+      })
+      .catch(err => console.warn('Failed to assess command:', err));
+    return () => {
+      cancelled = true;
+    };
+  }, [command, status]);
+
   const handleCopy = async (e: React.MouseEvent) => {
     e.stopPropagation();
     const textToCopy = output || error || command;
@@ -64,6 +87,20 @@ export function TerminalOutputBlock({
     setTimeout(() => setCopied(false), 2000);
   };
 
+  const handleResetShell = async (e: React.MouseEvent) => {
+    e.stopPropagation();
+    if (!onResetShell) return;
+    setShellReset('resetting');
+    try {
+      await onResetShell();
+      setShellReset('done');
+    } catch (err) {
+      console.error('Failed to reset shell session:', err);
+      setShellReset('failed');
+    }
+    setTimeout(() => setShellReset('idle'), 2000);
+  };
+
   const isRunning = status === 'running';
   const isPending = status === 'pending_approval';
   const isError = status === 'error' || (exitCode !== undefined && exitCode !== 0);
@@ -146,11 +183,36 @@ export function TerminalOutputBlock({
         )}
       </div>
 
+      {/* Risk Assessment */}
+      {isPending && assessment && (
+        <div className="px-3 py-2 border-t border-border/30 font-sans space-y-1">
+          <CommandRiskBadge risk={assessment.risk} />
+          <CommandRiskSummary risk={assessment.risk} />
+        </div>
+      )}
+
       {/* Footer */}
       <div className="px-3 py-2 border-t border-border/30 flex items-center justify-between text-xs">
         <div className="flex items-center gap-2 text-muted-foreground">
           <Terminal className="h-3 w-3" />
           <span>Terminal command</span>
+          {onResetShell && !isPending && (
+            <Button
+              size="sm"
+              variant="ghost"
+              className="h-6 px-2 gap-1 text-xs text-muted-foreground hover:text-foreground"
+              title="Start a fresh shell for this conversation. Ends any running command and forgets variables and the current directory."
+              disabled={shellReset === 'resetting'}
+              onClick={handleResetShell}
+            >
+              {shellReset === 'resetting' ? (
+                <Loader2 className="h-3 w-3 animate-spin" />
+              ) : (
+                <RotateCcw className="h-3 w-3" />
+              )}
+              {shellReset === 'done' ? 'Shell reset' : shellReset === 'failed' ? 'Reset failed' : 'Reset shell'}
+            </Button>
+          )}
         </div>
 
         <div className="flex items-center gap-2">
@@ -0,0 +1,135 @@
+/**
+ * Command Approval Prompt
+ *
+ * Bottom-left floating card listing commands queued for approval by the
+ * approval mode - from MCP clients or agent instruments. The technician
+ * approves or rejects them here; the caller is waiting on the decision.
+ * Approved commands stay on the card with their output until they finish.
+ */
+
+import { useCallback, useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { ShieldAlert, Check, X } from 'lucide-react';
+
+import { Button } from '@/components/ui/button';
+import { Badge } from '@/components/ui/badge';
+import { CommandRiskBadge, CommandRiskSummary } from '@/components/agent/CommandRiskSummary';
+import type { CommandOutputEvent, PendingCommand } from '@/types/agent';
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/** Fallback poll for commands queued without an event (in-app instruments) */
+const POLL_INTERVAL_MS = 3000;
+
+/** Characters of output shown for a running command */
+const OUTPUT_TAIL_CHARS = 4000;
+
+// =============================================================================
+// Component
+// =============================================================================
+
+export function CommandApprovalPrompt() {
+  const [pending, setPending] = useState<PendingCommand[]>([]);
+  const [busyId, setBusyId] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  /** Latest output of running commands, by command ID */
+  const [output, setOutput] = useState<Record<string, string>>({});
+
+  const refresh = useCallback(async () => {
+    try {
+      const commands = await invoke<PendingCommand[]>('get_pending_commands');
+      setPending(commands);
+      setOutput(prev =>
+        Object.fromEntries(Object.entries(prev).filter(([id]) => commands.some(c => c.id === id)))
+      );
+    } catch (e) {
+      console.error('Failed to load pending commands:', e);
+    }
+  }, []);
+
+  useEffect(() => {
+    refresh();
+    const timer = setInterval(refresh, POLL_INTERVAL_MS);
+    const unlisten = listen<PendingCommand>('command-pending', () => refresh());
+    const unlistenOutput = listen<CommandOutputEvent>('command-output', event => {
+      const { id, text } = event.payload;
+      setOutput(prev => ({ ...prev, [id]: ((prev[id] ?? '') + text).slice(-OUTPUT_TAIL_CHARS) }));
+    });
+    return () => {
+      clearInterval(timer);
+      unlisten.then(fn => fn());
+      unlistenOutput.then(fn => fn());
+    };
+  }, [refresh]);
+
+  const decide = async (id: string, approve: boolean) => {
+    setBusyId(id);
+    setError(null);
+    try {
+      await invoke(approve ? 'approve_command' : 'reject_command', { command_id: id });
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setBusyId(null);
+      await refresh();
+    }
+  };
+
+  if (pending.length === 0) return null;
+  const waiting = pending.filter(cmd => cmd.status === 'pending').length;
+
+  return (
+    <div className="fixed bottom-4 left-4 z-50 w-96 max-h-[60vh] overflow-y-auto rounded-xl border border-amber-500/40 bg-card/95 backdrop-blur-md shadow-lg p-4 space-y-3">
+      <div className="flex items-center gap-2">
+        <ShieldAlert className="h-4 w-4 text-amber-500 shrink-0" />
+        <p className="text-sm font-medium">
+          {waiting === 0
+            ? 'Running approved commands'
+            : waiting === 1
+              ? 'Command awaiting approval'
+              : `${waiting} commands awaiting approval`}
+        </p>
+      </div>
+
+      {pending.map(cmd => (
+        <div key={cmd.id} className="space-y-2 p-3 rounded-lg bg-muted/40 border border-border/50">
+          <div className="flex items-center gap-2">
+            <Badge variant="outline" className="text-[10px] h-5 shrink-0">{cmd.source ?? 'Agent'}</Badge>
+            <CommandRiskBadge risk={cmd.risk} />
+            <span className="text-xs text-muted-foreground truncate">{cmd.reason}</span>
+          </div>
+          <pre className="text-xs font-mono whitespace-pre-wrap break-all bg-background/60 rounded p-2 max-h-32 overflow-y-auto">
+            {cmd.command}
+          </pre>
+          <CommandRiskSummary risk={cmd.risk} />
+          {cmd.status === 'approved' ? (
+            <>
+              <p className="text-xs text-muted-foreground">Running...</p>
+              {output[cmd.id] && (
+                <pre className="text-[11px] font-mono whitespace-pre-wrap break-all bg-background/60 rounded p-2 max-h-40 overflow-y-auto text-muted-foreground">
+                  {output[cmd.id]}
+                </pre>
+              )}
+            </>
+          ) : (
+            <div className="flex gap-2">
+              <Button size="sm" onClick={() => decide(cmd.id, true)} disabled={busyId !== null}>
+                <Check className="h-3.5 w-3.5 mr-1" />
+                {busyId === cmd.id ? 'Running...' : 'Approve & Run'}
+              </Button>
+              <Button size="sm" variant="outline" onClick={() => decide(cmd.id, false)} disabled={busyId !== null}>
+                <X className="h-3.5 w-3.5 mr-1" />
+                Reject
+              </Button>
+            </div>
+          )}
+        </div>
+      ))}
+
+      {error && <p className="text-xs text-red-500">{error}</p>}
+    </div>
+  );
+}
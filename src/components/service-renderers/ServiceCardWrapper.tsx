@@ -75,10 +75,12 @@ export function ServiceCardWrapper({
   const iconBg = CATEGORY_ICON_BG[definition.category] ?? FALLBACK_ICON_BG;
 
   // Determine badge
-  const badge = statusBadge ?? {
-    label: result.success ? 'PASS' : 'FAIL',
-    color: result.success ? 'green' : 'red',
-  } satisfies StatusBadge;
+  const badge = statusBadge ?? (result.timedOut
+    ? { label: 'TIMED OUT', color: 'yellow' }
+    : {
+        label: result.success ? 'PASS' : 'FAIL',
+        color: result.success ? 'green' : 'red',
+      }) satisfies StatusBadge;
 
   const badgeClass = BADGE_COLORS[badge.color] ?? BADGE_COLORS.blue;
 
@@ -11,10 +11,12 @@ import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 
 import type {
+  RunCheckpoint,
   ServicePreset,
   ServiceDefinition,
   ServiceReport,
   ServiceRunState,
+  ServiceTaskProgress,
 } from '@/types/service';
 
 // =============================================================================
@@ -34,6 +36,8 @@ interface ServiceRunContextValue {
   report: ServiceReport | null;
   /** Live log lines from the current run */
   logs: string[];
+  /** Latest progress reported by each running service, keyed by service ID */
+  taskProgress: Record<string, ServiceTaskProgress>;
   /** Number of completed services */
   completedCount: number;
   /** Total services in the queue */
@@ -56,6 +60,12 @@ interface ServiceRunContextValue {
   loadError: string | null;
   /** Reload presets from backend */
   reloadPresets: () => void;
+  /** Run interrupted by a crash or reboot, if one can be resumed */
+  interruptedRun: RunCheckpoint | null;
+  /** Resume the interrupted run from the next un-run service */
+  resumeInterruptedRun: () => Promise<void>;
+  /** Discard the interrupted run, keeping its partial results as a report */
+  discardInterruptedRun: () => Promise<void>;
 }
 
 const ServiceRunContext = createContext<ServiceRunContextValue | null>(null);
@@ -74,11 +84,13 @@ export function ServiceRunProvider({ children }: ServiceRunProviderProps) {
   const [phase, setPhase] = useState<ServiceRunPhase>('idle');
   const [report, setReport] = useState<ServiceReport | null>(null);
   const [logs, setLogs] = useState<string[]>([]);
+  const [taskProgress, setTaskProgress] = useState<Record<string, ServiceTaskProgress>>({});
   const [presets, setPresets] = useState<ServicePreset[]>([]);
   const [definitions, setDefinitions] = useState<ServiceDefinition[]>([]);
   const [isLoading, setIsLoading] = useState(true);
   const [listenerError, setListenerError] = useState<string | null>(null);
   const [loadError, setLoadError] = useState<string | null>(null);
+  const [interruptedRun, setInterruptedRun] = useState<RunCheckpoint | null>(null);
 
   // Derived state
   const enabledServices = (report?.queue ?? []).filter(q => q.enabled);
@@ -95,10 +107,11 @@ export function ServiceRunProvider({ children }: ServiceRunProviderProps) {
   // Load initial data
   const loadData = useCallback(async () => {
     try {
-      const [presetsResult, defsResult, stateResult] = await Promise.allSettled([
+      const [presetsResult, defsResult, stateResult, interruptedResult] = await Promise.allSettled([
         invoke<ServicePreset[]>('get_service_presets'),
         invoke<ServiceDefinition[]>('get_service_definitions'),
         invoke<ServiceRunState>('get_service_run_state'),
+        invoke<RunCheckpoint | null>('get_interrupted_run'),
       ]);
 
       const errors: string[] = [];
@@ -133,6 +146,10 @@ export function ServiceRunProvider({ children }: ServiceRunProviderProps) {
         errors.push('Failed to load service state');
       }
 
+      if (interruptedResult.status === 'fulfilled') {
+        setInterruptedRun(interruptedResult.value);
+      }
+
       if (errors.length > 0) {
         setLoadError(errors.join('. '));
       }
@@ -178,6 +195,12 @@ export function ServiceRunProvider({ children }: ServiceRunProviderProps) {
       );
       unsubscribers.push(unsubProgress);
 
+      // Progress reported from inside a running service
+      const unsubTaskProgress = await listen<ServiceTaskProgress>('service-task-progress', (event) => {
+        setTaskProgress((prev) => ({ ...prev, [event.payload.serviceId]: event.payload }));
+      });
+      unsubscribers.push(unsubTaskProgress);
+
       // Completion event
       const unsubComplete = await listen<ServiceReport>('service-completed', (event) => {
         setReport(event.payload);
@@ -207,6 +230,7 @@ export function ServiceRunProvider({ children }: ServiceRunProviderProps) {
           // Clear logs when a new run starts (report changes)
           if (state.currentReport && state.currentReport.results?.length === 0) {
             setLogs([]);
+            setTaskProgress({});
           }
         } else if (state.currentReport) {
           const status = state.currentReport.status;
@@ -232,12 +256,24 @@ export function ServiceRunProvider({ children }: ServiceRunProviderProps) {
     invoke<ServicePreset[]>('get_service_presets').then(setPresets).catch(() => {});
   }, []);
 
+  const resumeInterruptedRun = useCallback(async () => {
+    setInterruptedRun(null);
+    // Resolves when the run finishes; progress arrives through the state events
+    await invoke('resume_interrupted_run');
+  }, []);
+
+  const discardInterruptedRun = useCallback(async () => {
+    await invoke('discard_interrupted_run', { savePartial: true });
+    setInterruptedRun(null);
+  }, []);
+
   const value: ServiceRunContextValue = {
     isRunning,
     isPaused,
     phase,
     report,
     logs,
+    taskProgress,
     completedCount,
     totalCount,
     currentServiceName,
@@ -249,6 +285,9 @@ export function ServiceRunProvider({ children }: ServiceRunProviderProps) {
     listenerError,
     loadError,
     reloadPresets,
+    interruptedRun,
+    resumeInterruptedRun,
+    discardInterruptedRun,
   };
 
   return (
@@ -37,6 +37,7 @@ import type {
   ServiceDefinition,
   ServiceQueueItem,
   ServiceFinding,
+  ServiceTaskProgress,
 } from '@/types/service';
 import { getIcon, formatDuration } from './utils';
 
@@ -48,6 +49,8 @@ export interface RunnerViewProps {
   report: ServiceReport | null;
   definitions: ServiceDefinition[];
   logs: string[];
+  /** Latest progress reported by running services, keyed by service ID */
+  taskProgress?: Record<string, ServiceTaskProgress>;
   onCancel: () => void;
   onBack: () => void;
   queue: ServiceQueueItem[];
@@ -70,7 +73,7 @@ const SEVERITY_COLORS: Record<string, string> = {
 // Component
 // =============================================================================
 
-export function RunnerView({ report, definitions, logs, onCancel, onBack, queue, cancelError }: RunnerViewProps) {
+export function RunnerView({ report, definitions, logs, taskProgress = {}, onCancel, onBack, queue, cancelError }: RunnerViewProps) {
   const [showLogs, setShowLogs] = useState(true);
   const [elapsedMs, setElapsedMs] = useState(0);
   const [taskElapsedMs, setTaskElapsedMs] = useState(0);
@@ -96,6 +99,7 @@ export function RunnerView({ report, definitions, logs, onCancel, onBack, queue,
 
   const currentService = enabledServices[currentIndex];
   const currentDef = currentService ? definitionMap.get(currentService.serviceId) : null;
+  const currentTaskProgress = currentService ? taskProgress[currentService.serviceId] : undefined;
 
   // Smarter ETA: use actual completion times to compute speed factor
   const completedResults = report?.results ?? [];
@@ -352,6 +356,7 @@ export function RunnerView({ report, definitions, logs, onCancel, onBack, queue,
                   if (!def) return null;
                   const Icon = getIcon(def.icon);
                   const elapsed = parallelTaskTimers.get(index) ?? 0;
+                  const reported = taskProgress[item.serviceId];
                   return (
                     <Card key={item.serviceId} className="border-primary/30 bg-primary/5 overflow-hidden !p-0 !gap-0">
                       <div className="px-4 py-3">
@@ -369,13 +374,18 @@ export function RunnerView({ report, definitions, logs, onCancel, onBack, queue,
                                 Running
                               </span>
                             </div>
-                            <p className="text-xs text-muted-foreground truncate">{def.description}</p>
+                            <p className="text-xs text-muted-foreground truncate">
+                              {reported ? reported.phase : def.description}
+                            </p>
                           </div>
                           <div className="text-right text-xs text-muted-foreground">
                             <div className="font-medium">{formatDuration(elapsed)}</div>
                             <div>~{def.estimatedDurationSecs}s est.</div>
                           </div>
                         </div>
+                        {reported?.percent != null && (
+                          <Progress value={reported.percent} className="h-1 mt-2" />
+                        )}
                       </div>
                     </Card>
                   );
@@ -409,10 +419,19 @@ export function RunnerView({ report, definitions, logs, onCancel, onBack, queue,
                     </div>
                   </div>
 
-                  {/* Per-service estimated progress bar */}
+                  {/* Per-service progress: reported by the service when it can, estimated otherwise */}
+                  {currentTaskProgress && (
+                    <div className="mt-3 flex justify-between text-xs text-muted-foreground">
+                      <span>{currentTaskProgress.phase}</span>
+                      {currentTaskProgress.percent != null && (
+                        <span className="font-medium">{Math.round(currentTaskProgress.percent)}%</span>
+                      )}
+                    </div>
+                  )}
                   <Progress
-                    value={Math.min(100, (taskElapsedMs / (currentDef.estimatedDurationSecs * 1000)) * 100)}
-                    className="h-1 mt-3"
+                    value={currentTaskProgress?.percent
+                      ?? Math.min(100, (taskElapsedMs / (currentDef.estimatedDurationSecs * 1000)) * 100)}
+                    className={currentTaskProgress ? 'h-1 mt-1.5' : 'h-1 mt-3'}
                   />
 
                   {/* Latest log line */}
@@ -5,7 +5,8 @@
  * MCP server settings, and MCP client connections.
  */
 
-import { useState } from 'react';
+import { useEffect, useState, type ComponentProps } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 import {
   Check,
   Plus,
@@ -13,13 +14,17 @@ import {
   X,
   CheckCircle2,
   XCircle,
-  RefreshCw,
   Globe,
   AlertTriangle,
   Sparkles,
   Terminal,
   Trash2,
   Layers,
+  Download,
+  RefreshCw,
+  Play,
+  Square,
+  RotateCw,
 } from 'lucide-react';
 
 import { Button } from '@/components/ui/button';
@@ -35,8 +40,28 @@ import {
 import { Badge } from '@/components/ui/badge';
 import { Switch } from '@/components/ui/switch';
 import { Input } from '@/components/ui/input';
+import { Textarea } from '@/components/ui/textarea';
 import { useSettings } from '@/components/settings-context';
-import { AGENT_PROVIDERS, type AgentSettings, type ProviderApiKeys, type MCPServerConfig } from '@/types/agent';
+import {
+  AGENT_PROVIDERS,
+  DEFAULT_AGENT_SETTINGS,
+  type AgentSettings,
+  type ProviderApiKeys,
+  type MCPServerConfig,
+  type McpApiKey,
+  type McpFsAccess,
+  type CreateMcpApiKeyRequest,
+  type CreatedMcpApiKey,
+  type McpAuditExportFormat,
+  type McpAuditQuery,
+  type McpAuditQueryResult,
+  type McpServerLimits,
+  type McpBindAddress,
+  type McpTlsSettings,
+  type McpCertificateInfo,
+  type McpServerStatus,
+} from '@/types/agent';
+import type { PathValidationResult } from '@/types/file-attachment';
 
 // =============================================================================
 // MCP Connections Card
@@ -206,6 +231,18 @@ function MCPConnectionsCard({ agentSettings, updateSetting, isLoading }: {
                 placeholder="Bearer token for authentication"
               />
             </div>
+            <div className="flex items-center justify-between gap-3">
+              <div>
+                <Label className="text-xs">Re-export tools</Label>
+                <p className="text-[10px] text-muted-foreground">
+                  Offer this server's tools to clients of the RustService MCP server
+                </p>
+              </div>
+              <Switch
+                checked={editingServer?.reexport ?? false}
+                onCheckedChange={(checked) => setEditingServer(prev => prev ? { ...prev, reexport: checked } : null)}
+              />
+            </div>
             <div className="flex gap-2 pt-1">
               <Button size="sm" onClick={handleSaveEdit} disabled={!editingServer?.name || !editingServer?.url}>
                 <Check className="h-3.5 w-3.5 mr-1" />
@@ -250,6 +287,704 @@ function MCPConnectionsCard({ agentSettings, updateSetting, isLoading }: {
   );
 }
 
+// =============================================================================
+// MCP API Keys Section
+// =============================================================================
+
+const FS_ACCESS_LABELS: Record<McpFsAccess, string> = {
+  none: 'No file access',
+  readOnly: 'Read only',
+  readWrite: 'Read & write',
+};
+
+function formatKeyDate(value?: string): string {
+  return value ? new Date(value).toLocaleString() : 'Never';
+}
+
+/**
+ * MCP API Keys - Create, list and revoke keys for the built-in MCP server
+ */
+function MCPApiKeysSection() {
+  const [keys, setKeys] = useState<McpApiKey[]>([]);
+  const [toolNames, setToolNames] = useState<string[]>([]);
+  const [isCreating, setIsCreating] = useState(false);
+  const [createdSecret, setCreatedSecret] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  // Create form state
+  const [name, setName] = useState('');
+  const [allTools, setAllTools] = useState(true);
+  const [selectedTools, setSelectedTools] = useState<string[]>([]);
+  const [fsAccess, setFsAccess] = useState<McpFsAccess>('none');
+  const [fsPaths, setFsPaths] = useState('');
+  const [expiresOn, setExpiresOn] = useState('');
+
+  const loadKeys = async () => {
+    try {
+      setKeys(await invoke<McpApiKey[]>('list_mcp_api_keys'));
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  useEffect(() => {
+    loadKeys();
+    invoke<string[]>('list_mcp_tool_names').then(setToolNames).catch(() => {});
+  }, []);
+
+  const resetForm = () => {
+    setName('');
+    setAllTools(true);
+    setSelectedTools([]);
+    setFsAccess('none');
+    setFsPaths('');
+    setExpiresOn('');
+    setIsCreating(false);
+  };
+
+  const toggleTool = (tool: string) => {
+    setSelectedTools(prev => prev.includes(tool) ? prev.filter(t => t !== tool) : [...prev, tool]);
+  };
+
+  const handleCreate = async () => {
+    setError(null);
+    const request: CreateMcpApiKeyRequest = {
+      name,
+      allowedTools: allTools ? null : selectedTools,
+      fsAccess,
+      fsPaths: fsPaths.split('\n').map(p => p.trim()).filter(Boolean),
+      // End of the chosen day, local time
+      expiresAt: expiresOn ? new Date(`${expiresOn}T23:59:59`).toISOString() : undefined,
+    };
+    try {
+      const created = await invoke<CreatedMcpApiKey>('create_mcp_api_key', { request });
+      setCreatedSecret(created.secret);
+      resetForm();
+      await loadKeys();
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const handleRevoke = async (keyId: string) => {
+    setError(null);
+    try {
+      await invoke('revoke_mcp_api_key', { keyId });
+      await loadKeys();
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const isExpired = (key: McpApiKey) => !!key.expiresAt && new Date(key.expiresAt) <= new Date();
+
+  return (
+    <div className="space-y-3">
+      <Label>API Keys</Label>
+
+      {createdSecret && (
+        <div className="space-y-2 p-3 rounded-lg border border-emerald-500/30 bg-emerald-500/10">
+          <p className="text-xs font-medium">Copy this key now - it won't be shown again.</p>
+          <div className="flex gap-2">
+            <Input type="text" value={createdSecret} readOnly className="font-mono text-sm" />
+            <Button
+              variant="outline"
+              size="icon"
+              onClick={() => navigator.clipboard.writeText(createdSecret)}
+              title="Copy to clipboard"
+            >
+              <Check className="h-4 w-4" />
+            </Button>
+            <Button variant="ghost" size="icon" onClick={() => setCreatedSecret(null)} title="Dismiss">
+              <X className="h-4 w-4" />
+            </Button>
+          </div>
+        </div>
+      )}
+
+      {keys.length > 0 && (
+        <div className="space-y-2">
+          {keys.map(key => {
+            const inactive = !!key.revokedAt || isExpired(key);
+            return (
+              <div
+                key={key.id}
+                className={`flex items-center gap-3 p-3 rounded-lg bg-muted/40 border border-border/50 ${inactive ? 'opacity-60' : ''}`}
+              >
+                <div className="flex-1 min-w-0 space-y-1">
+                  <div className="flex items-center gap-2 flex-wrap">
+                    <span className="text-sm font-medium truncate">{key.name}</span>
+                    <code className="text-[10px] text-muted-foreground">{key.keyPrefix}…</code>
+                    {key.revokedAt && <Badge variant="destructive" className="text-[10px] h-5">Revoked</Badge>}
+                    {!key.revokedAt && isExpired(key) && (
+                      <Badge variant="secondary" className="text-[10px] h-5">Expired</Badge>
+                    )}
+                    <Badge variant="outline" className="text-[10px] h-5">
+                      {key.allowedTools ? `${key.allowedTools.length} tools` : 'All tools'}
+                    </Badge>
+                    <Badge variant="outline" className="text-[10px] h-5">{FS_ACCESS_LABELS[key.fsAccess]}</Badge>
+                  </div>
+                  {key.fsPaths.length > 0 && (
+                    <p className="text-xs text-muted-foreground truncate">Paths: {key.fsPaths.join(', ')}</p>
+                  )}
+                  <p className="text-xs text-muted-foreground">
+                    Last used: {formatKeyDate(key.lastUsedAt)} · Expires: {formatKeyDate(key.expiresAt)}
+                  </p>
+                </div>
+                {!key.revokedAt && (
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    className="text-red-500 hover:text-red-600 shrink-0"
+                    onClick={() => handleRevoke(key.id)}
+                  >
+                    Revoke
+                  </Button>
+                )}
+              </div>
+            );
+          })}
+        </div>
+      )}
+
+      {isCreating ? (
+        <div className="space-y-3 p-4 rounded-lg border border-primary/20 bg-primary/5">
+          <div className="grid grid-cols-2 gap-3">
+            <div className="space-y-1.5">
+              <Label className="text-xs">Name</Label>
+              <Input value={name} onChange={(e) => setName(e.target.value)} placeholder="Claude Desktop" />
+            </div>
+            <div className="space-y-1.5">
+              <Label className="text-xs">Expires (optional)</Label>
+              <Input type="date" value={expiresOn} onChange={(e) => setExpiresOn(e.target.value)} />
+            </div>
+          </div>
+
+          <div className="space-y-1.5">
+            <div className="flex items-center justify-between">
+              <Label className="text-xs">Allow all tools</Label>
+              <Switch checked={allTools} onCheckedChange={setAllTools} />
+            </div>
+            {!allTools && (
+              <div className="flex flex-wrap gap-1.5">
+                {toolNames.map(tool => (
+                  <Badge
+                    key={tool}
+                    variant={selectedTools.includes(tool) ? 'default' : 'outline'}
+                    className="cursor-pointer font-mono text-[10px]"
+                    onClick={() => toggleTool(tool)}
+                  >
+                    {tool}
+                  </Badge>
+                ))}
+              </div>
+            )}
+          </div>
+
+          <div className="space-y-1.5">
+            <Label className="text-xs">File Access</Label>
+            <Select value={fsAccess} onValueChange={(value) => setFsAccess(value as McpFsAccess)}>
+              <SelectTrigger>
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {(Object.keys(FS_ACCESS_LABELS) as McpFsAccess[]).map(access => (
+                  <SelectItem key={access} value={access}>{FS_ACCESS_LABELS[access]}</SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </div>
+
+          {fsAccess !== 'none' && (
+            <div className="space-y-1.5">
+              <Label className="text-xs">Allowed Folders (one per line, empty for any)</Label>
+              <Textarea
+                value={fsPaths}
+                onChange={(e) => setFsPaths(e.target.value)}
+                placeholder={'C:\\Users\\Public\nD:\\Logs'}
+                className="font-mono text-xs min-h-[60px]"
+              />
+            </div>
+          )}
+
+          <div className="flex gap-2 pt-1">
+            <Button size="sm" onClick={handleCreate} disabled={!name.trim() || (!allTools && selectedTools.length === 0)}>
+              <Check className="h-3.5 w-3.5 mr-1" />
+              Create Key
+            </Button>
+            <Button size="sm" variant="outline" onClick={resetForm}>
+              Cancel
+            </Button>
+          </div>
+        </div>
+      ) : (
+        <Button variant="outline" size="sm" className="w-full" onClick={() => setIsCreating(true)}>
+          <Plus className="h-4 w-4 mr-2" />
+          Create API Key
+        </Button>
+      )}
+
+      {error && <p className="text-xs text-red-500">{error}</p>}
+
+      <p className="text-xs text-muted-foreground">
+        Clients send the key in the Authorization header: Bearer {'<API_KEY>'}. Only a hash is stored.
+      </p>
+    </div>
+  );
+}
+
+// =============================================================================
+// MCP Network Settings
+// =============================================================================
+
+/**
+ * MCP Network - Bind address and TLS for the built-in MCP server
+ */
+function MCPNetworkSection({ agentSettings, updateSetting }: {
+  agentSettings: AgentSettings;
+  updateSetting: (key: 'agent', value: AgentSettings) => Promise<void>;
+}) {
+  const bind = agentSettings.mcpBindAddress ?? { mode: 'all' };
+  const tls = agentSettings.mcpTls ?? { enabled: false };
+  const [certificate, setCertificate] = useState<McpCertificateInfo | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const setBind = (value: McpBindAddress) => updateSetting('agent', { ...agentSettings, mcpBindAddress: value });
+  const setTls = (value: McpTlsSettings) => updateSetting('agent', { ...agentSettings, mcpTls: value });
+
+  const loadCertificate = async () => {
+    try {
+      setCertificate(await invoke<McpCertificateInfo>('get_mcp_tls_certificate'));
+      setError(null);
+    } catch (e) {
+      setCertificate(null);
+      setError(String(e));
+    }
+  };
+
+  useEffect(() => {
+    if (tls.enabled) {
+      loadCertificate();
+    }
+  }, [tls.enabled, tls.certPath, tls.keyPath]);
+
+  return (
+    <div className="space-y-3">
+      <div className="space-y-2">
+        <Label>Listen On</Label>
+        <div className="flex gap-2">
+          <Select
+            value={bind.mode}
+            onValueChange={(mode) => setBind(
+              mode === 'interface' ? { mode, address: '' } : { mode: mode as 'loopback' | 'all' }
+            )}
+          >
+            <SelectTrigger className="w-48">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value="loopback">This machine only</SelectItem>
+              <SelectItem value="all">All interfaces</SelectItem>
+              <SelectItem value="interface">Specific interface</SelectItem>
+            </SelectContent>
+          </Select>
+          {bind.mode === 'interface' && (
+            <Input
+              value={bind.address}
+              onChange={(e) => setBind({ mode: 'interface', address: e.target.value })}
+              placeholder="192.168.1.50"
+              className="font-mono"
+            />
+          )}
+        </div>
+      </div>
+
+      <div className="space-y-2">
+        <div className="flex items-center justify-between">
+          <div>
+            <Label htmlFor="mcp-tls">HTTPS</Label>
+            <p className="text-xs text-muted-foreground">
+              Encrypt traffic, including API keys. Leave the paths empty to use a generated self-signed certificate.
+            </p>
+          </div>
+          <Switch id="mcp-tls" checked={tls.enabled} onCheckedChange={(enabled) => setTls({ ...tls, enabled })} />
+        </div>
+        {tls.enabled && (
+          <>
+            <div className="grid grid-cols-2 gap-3">
+              <Input
+                value={tls.certPath ?? ''}
+                onChange={(e) => setTls({ ...tls, certPath: e.target.value || undefined })}
+                placeholder="Certificate (PEM)"
+                className="font-mono text-xs"
+              />
+              <Input
+                value={tls.keyPath ?? ''}
+                onChange={(e) => setTls({ ...tls, keyPath: e.target.value || undefined })}
+                placeholder="Private key (PEM)"
+                className="font-mono text-xs"
+              />
+            </div>
+            {certificate && (
+              <div className="space-y-1 p-3 rounded-lg bg-muted/40 border border-border/50">
+                <div className="flex items-center gap-2">
+                  <span className="text-xs font-medium">SHA-256 fingerprint</span>
+                  {certificate.selfSigned && <Badge variant="outline" className="text-[10px] h-5">Self-signed</Badge>}
+                  <Button
+                    variant="ghost"
+                    size="icon"
+                    className="h-6 w-6 ml-auto"
+                    onClick={() => navigator.clipboard.writeText(certificate.sha256Fingerprint)}
+                    title="Copy fingerprint"
+                  >
+                    <Check className="h-3.5 w-3.5" />
+                  </Button>
+                </div>
+                <code className="block text-[10px] break-all text-muted-foreground">{certificate.sha256Fingerprint}</code>
+              </div>
+            )}
+            {error && <p className="text-xs text-red-500">{error}</p>}
+          </>
+        )}
+      </div>
+    </div>
+  );
+}
+
+/** Select value standing for "no redaction" in the MCP report audience picker */
+const FULL_REPORT_AUDIENCE = '__full__';
+
+// =============================================================================
+// MCP Server Status
+// =============================================================================
+
+/** How often the status section refreshes */
+const STATUS_POLL_MS = 5000;
+
+function formatUptime(secs: number): string {
+  const h = Math.floor(secs / 3600);
+  const m = Math.floor((secs % 3600) / 60);
+  if (h > 0) return `${h}h ${m}m`;
+  if (m > 0) return `${m}m ${secs % 60}s`;
+  return `${secs}s`;
+}
+
+/**
+ * MCP Server Status - Live state of the server with start/stop/restart
+ */
+function MCPServerStatusSection() {
+  const [status, setStatus] = useState<McpServerStatus | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadStatus = async () => {
+    try {
+      setStatus(await invoke<McpServerStatus>('get_mcp_server_status'));
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  useEffect(() => {
+    loadStatus();
+    const timer = setInterval(loadStatus, STATUS_POLL_MS);
+    return () => clearInterval(timer);
+  }, []);
+
+  const control = async (command: 'start_mcp_server' | 'stop_mcp_server' | 'restart_mcp_server') => {
+    setBusy(true);
+    setError(null);
+    try {
+      setStatus(await invoke<McpServerStatus>(command));
+    } catch (e) {
+      setError(String(e));
+      await loadStatus();
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  return (
+    <div className="space-y-3 p-3 rounded-lg bg-muted/40 border border-border/50">
+      <div className="flex items-center justify-between">
+        <div className="flex items-center gap-2">
+          <Label>Status</Label>
+          <Badge
+            variant="outline"
+            className={status?.running ? 'text-green-500 border-green-500/30' : 'text-muted-foreground'}
+          >
+            {status?.running ? 'Running' : 'Stopped'}
+          </Badge>
+        </div>
+        <div className="flex items-center gap-1">
+          {status?.running ? (
+            <>
+              <Button variant="outline" size="sm" onClick={() => control('restart_mcp_server')} disabled={busy}>
+                <RotateCw className="h-3.5 w-3.5 mr-1" />
+                Restart
+              </Button>
+              <Button variant="outline" size="sm" onClick={() => control('stop_mcp_server')} disabled={busy}>
+                <Square className="h-3.5 w-3.5 mr-1" />
+                Stop
+              </Button>
+            </>
+          ) : (
+            <Button variant="outline" size="sm" onClick={() => control('start_mcp_server')} disabled={busy}>
+              <Play className="h-3.5 w-3.5 mr-1" />
+              Start
+            </Button>
+          )}
+        </div>
+      </div>
+
+      {status?.running && (
+        <div className="space-y-1 text-xs">
+          <p className="font-mono break-all">{status.listeningAddress}</p>
+          <p className="text-muted-foreground">
+            Up {formatUptime(status.uptimeSecs)} · {status.requestsTotal} requests
+            ({status.requestsRejected} rejected) · {status.activeSessions} sessions
+          </p>
+          {status.connectedClients.length > 0 && (
+            <div className="pt-1 space-y-0.5">
+              {status.connectedClients.map(client => (
+                <div key={`${client.remoteAddr}-${client.connectedAt}`} className="flex justify-between text-muted-foreground">
+                  <span className="font-mono">{client.remoteAddr}</span>
+                  <span>
+                    {client.requests} requests · since {new Date(client.connectedAt).toLocaleTimeString()}
+                  </span>
+                </div>
+              ))}
+            </div>
+          )}
+        </div>
+      )}
+
+      {status?.lastError && <p className="text-xs text-red-500">{status.lastError}</p>}
+      {error && error !== status?.lastError && <p className="text-xs text-red-500">{error}</p>}
+    </div>
+  );
+}
+
+/**
+ * One-path-per-line textarea that saves when it loses focus
+ */
+function CommitPathList({ paths, onCommit, placeholder }: {
+  paths: string[];
+  onCommit: (paths: string[]) => void;
+  placeholder?: string;
+}) {
+  const [draft, setDraft] = useState(paths.join('\n'));
+
+  useEffect(() => {
+    setDraft(paths.join('\n'));
+  }, [paths]);
+
+  const commit = () => {
+    const parsed = draft.split('\n').map(p => p.trim()).filter(Boolean);
+    if (parsed.join('\n') === paths.join('\n')) return;
+    onCommit(parsed);
+  };
+
+  return (
+    <Textarea
+      value={draft}
+      onChange={(e) => setDraft(e.target.value)}
+      onBlur={commit}
+      placeholder={placeholder}
+      className="font-mono text-xs min-h-[60px]"
+    />
+  );
+}
+
+/**
+ * Check a path against the filesystem policy and show the rule that applied
+ */
+function FilesystemPathCheck() {
+  const [path, setPath] = useState('');
+  const [result, setResult] = useState<{ allowed: boolean; text: string } | null>(null);
+
+  const check = async () => {
+    if (!path.trim()) return;
+    try {
+      const validation = await invoke<PathValidationResult>('validate_filesystem_path', { path: path.trim() });
+      const rule = validation.rule;
+      setResult(rule?.allowed
+        ? { allowed: true, text: `Allowed: inside ${rule.matched}` }
+        : { allowed: false, text: validation.error || 'Denied' });
+    } catch (e) {
+      setResult({ allowed: false, text: String(e) });
+    }
+  };
+
+  return (
+    <div className="space-y-1.5">
+      <div className="flex gap-2">
+        <Input
+          value={path}
+          onChange={(e) => setPath(e.target.value)}
+          onKeyDown={(e) => e.key === 'Enter' && check()}
+          placeholder="Check a path, e.g. C:\Users\Public\notes.txt"
+          className="font-mono text-xs"
+        />
+        <Button variant="outline" size="sm" onClick={check} disabled={!path.trim()}>
+          Check
+        </Button>
+      </div>
+      {result && (
+        <p className={`text-xs ${result.allowed ? 'text-green-500' : 'text-red-500'}`}>{result.text}</p>
+      )}
+    </div>
+  );
+}
+
+/**
+ * Number input that saves when it loses focus or Enter is pressed, so a
+ * running server isn't restarted on every keystroke
+ */
+function CommitNumberInput({ value, onCommit, ...props }: {
+  value: number;
+  onCommit: (value: number) => void;
+} & Omit<ComponentProps<typeof Input>, 'value' | 'onChange' | 'type'>) {
+  const [draft, setDraft] = useState(String(value));
+
+  useEffect(() => {
+    setDraft(String(value));
+  }, [value]);
+
+  const commit = () => {
+    const parsed = parseInt(draft);
+    if (Number.isNaN(parsed) || parsed === value) {
+      setDraft(String(value));
+      return;
+    }
+    onCommit(parsed);
+  };
+
+  return (
+    <Input
+      {...props}
+      type="number"
+      value={draft}
+      onChange={(e) => setDraft(e.target.value)}
+      onBlur={commit}
+      onKeyDown={(e) => {
+        if (e.key === 'Enter') commit();
+      }}
+    />
+  );
+}
+
+// =============================================================================
+// MCP Server Limits
+// =============================================================================
+
+const MCP_LIMIT_FIELDS: { key: keyof McpServerLimits; label: string }[] = [
+  { key: 'maxBodyBytes', label: 'Max request size (bytes)' },
+  { key: 'maxConnections', label: 'Max connections' },
+  { key: 'maxConcurrentToolCalls', label: 'Max concurrent tool calls' },
+  { key: 'requestsPerMinute', label: 'Requests per minute per key' },
+  { key: 'maxOutputBytes', label: 'Max tool output (bytes)' },
+];
+
+// =============================================================================
+// MCP Audit Log
+// =============================================================================
+
+/** Entries shown in the settings panel; exports include everything */
+const AUDIT_PAGE_SIZE = 20;
+
+/**
+ * MCP Audit Log - Recent requests to the built-in MCP server, with export
+ */
+function MCPAuditLogSection() {
+  const [result, setResult] = useState<McpAuditQueryResult | null>(null);
+  const [failuresOnly, setFailuresOnly] = useState(false);
+  const [exportedPath, setExportedPath] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const query: McpAuditQuery = failuresOnly ? { success: false } : {};
+
+  const loadEntries = async () => {
+    try {
+      setResult(await invoke<McpAuditQueryResult>('query_mcp_audit_log', {
+        query: { ...query, limit: AUDIT_PAGE_SIZE },
+      }));
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  useEffect(() => {
+    loadEntries();
+  }, [failuresOnly]);
+
+  const handleExport = async (format: McpAuditExportFormat) => {
+    setError(null);
+    try {
+      setExportedPath(await invoke<string>('export_mcp_audit_log', { query, format, outputPath: null }));
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center justify-between">
+        <Label>Audit Log</Label>
+        <div className="flex items-center gap-2">
+          <Label htmlFor="mcp-audit-failures" className="text-xs text-muted-foreground">Failures only</Label>
+          <Switch id="mcp-audit-failures" checked={failuresOnly} onCheckedChange={setFailuresOnly} />
+          <Button variant="ghost" size="icon" className="h-7 w-7" onClick={loadEntries} title="Refresh">
+            <RefreshCw className="h-3.5 w-3.5" />
+          </Button>
+        </div>
+      </div>
+
+      {result && result.entries.length > 0 ? (
+        <div className="max-h-64 overflow-y-auto rounded-lg border border-border/50 divide-y divide-border/50">
+          {result.entries.map(entry => (
+            <div key={entry.id} className="px-3 py-2 text-xs space-y-0.5">
+              <div className="flex items-center gap-2">
+                {entry.success
+                  ? <CheckCircle2 className="h-3.5 w-3.5 text-green-500 shrink-0" />
+                  : <XCircle className="h-3.5 w-3.5 text-red-500 shrink-0" />}
+                <span className="font-mono truncate">{entry.tool ?? entry.method ?? 'rejected'}</span>
+                <span className="text-muted-foreground truncate">
+                  {entry.keyName ?? entry.transport}{entry.remoteAddr ? ` · ${entry.remoteAddr}` : ''}
+                </span>
+                <span className="ml-auto text-muted-foreground shrink-0">
+                  {new Date(entry.timestamp).toLocaleString()} · {entry.durationMs}ms
+                </span>
+              </div>
+              {entry.error && <p className="text-red-500 truncate pl-5">{entry.error}</p>}
+            </div>
+          ))}
+        </div>
+      ) : (
+        <p className="text-xs text-muted-foreground">No requests recorded yet.</p>
+      )}
+
+      <div className="flex items-center gap-2">
+        <Button variant="outline" size="sm" onClick={() => handleExport('csv')}>
+          <Download className="h-3.5 w-3.5 mr-1" />
+          Export CSV
+        </Button>
+        <Button variant="outline" size="sm" onClick={() => handleExport('json')}>
+          <Download className="h-3.5 w-3.5 mr-1" />
+          Export JSON
+        </Button>
+        {result && (
+          <span className="text-xs text-muted-foreground ml-auto">{result.total} requests logged</span>
+        )}
+      </div>
+
+      {exportedPath && <p className="text-xs text-muted-foreground break-all">Exported to {exportedPath}</p>}
+      {error && <p className="text-xs text-red-500">{error}</p>}
+    </div>
+  );
+}
+
 // =============================================================================
 // Agent Panel
 // =============================================================================
@@ -341,6 +1076,8 @@ export function AgentPanel() {
     await updateSetting('agent', newSettings);
   };
 
+  const filesystemPolicy = agentSettings?.filesystemPolicy ?? DEFAULT_AGENT_SETTINGS.filesystemPolicy!;
+
   // Count configured providers (those with API keys)
   const configuredProviderCount = Object.values(agentSettings?.apiKeys || {}).filter(Boolean).length;
 
@@ -515,6 +1252,90 @@ export function AgentPanel() {
               </div>
             </div>
           )}
+
+          <div className="space-y-2">
+            <Label>Limits</Label>
+            <div className="grid grid-cols-2 gap-3">
+              <div className="space-y-1.5">
+                <Label className="text-xs">Timeout (seconds)</Label>
+                <CommitNumberInput
+                  min={0}
+                  value={agentSettings?.commandTimeoutSecs ?? DEFAULT_AGENT_SETTINGS.commandTimeoutSecs!}
+                  onCommit={async (value) => {
+                    const newSettings = { ...agentSettings, commandTimeoutSecs: Math.max(0, value) };
+                    await updateSetting('agent', newSettings);
+                  }}
+                />
+              </div>
+              <div className="space-y-1.5">
+                <Label className="text-xs">Max output per stream (bytes)</Label>
+                <CommitNumberInput
+                  min={0}
+                  value={agentSettings?.commandMaxOutputBytes ?? DEFAULT_AGENT_SETTINGS.commandMaxOutputBytes!}
+                  onCommit={async (value) => {
+                    const newSettings = { ...agentSettings, commandMaxOutputBytes: Math.max(0, value) };
+                    await updateSetting('agent', newSettings);
+                  }}
+                />
+              </div>
+            </div>
+            <p className="text-xs text-muted-foreground">
+              0 disables a limit. Commands that run too long are stopped along with anything they
+              started; long output keeps its start and end.
+            </p>
+          </div>
+
+          <div className="flex items-center justify-between gap-3">
+            <div>
+              <Label>Persistent Shell</Label>
+              <p className="text-xs text-muted-foreground">
+                Keep one shell running per conversation, so the working directory, variables and
+                imported modules carry over between commands
+              </p>
+            </div>
+            <Switch
+              checked={agentSettings?.persistentShell ?? false}
+              onCheckedChange={async (checked) => {
+                const newSettings = { ...agentSettings, persistentShell: checked };
+                await updateSetting('agent', newSettings);
+              }}
+            />
+          </div>
+
+          <div className="space-y-2">
+            <Label>Filesystem Access</Label>
+            <p className="text-xs text-muted-foreground">
+              File tools, in the app and over MCP, may use the data folder, your home folder and the
+              allowed folders below. Denied folders win over allowed ones. SSH keys,
+              registry hives, browser and Windows credential stores and RustService's own settings are
+              always denied.
+            </p>
+            <div className="grid grid-cols-2 gap-3">
+              <div className="space-y-1.5">
+                <Label className="text-xs">Allowed folders (one per line)</Label>
+                <CommitPathList
+                  paths={filesystemPolicy.allowedRoots}
+                  placeholder={'C:\\Users\\Public\nD:\\Logs'}
+                  onCommit={async (allowedRoots) => {
+                    const newSettings = { ...agentSettings, filesystemPolicy: { ...filesystemPolicy, allowedRoots } };
+                    await updateSetting('agent', newSettings);
+                  }}
+                />
+              </div>
+              <div className="space-y-1.5">
+                <Label className="text-xs">Denied folders (one per line)</Label>
+                <CommitPathList
+                  paths={filesystemPolicy.deniedRoots}
+                  placeholder={'C:\\Users\\Me\\Documents\\Private'}
+                  onCommit={async (deniedRoots) => {
+                    const newSettings = { ...agentSettings, filesystemPolicy: { ...filesystemPolicy, deniedRoots } };
+                    await updateSetting('agent', newSettings);
+                  }}
+                />
+              </div>
+            </div>
+            <FilesystemPathCheck />
+          </div>
         </CardContent>
       </Card>
 
@@ -598,12 +1419,7 @@ export function AgentPanel() {
               id="mcp-enabled"
               checked={agentSettings?.mcpServerEnabled || false}
               onCheckedChange={async (checked) => {
-                // Auto-generate API key if enabling and none exists
-                let newSettings = { ...agentSettings, mcpServerEnabled: checked };
-                if (checked && !agentSettings?.mcpApiKey) {
-                  const apiKey = crypto.randomUUID().replace(/-/g, '');
-                  newSettings = { ...newSettings, mcpApiKey: apiKey };
-                }
+                const newSettings = { ...agentSettings, mcpServerEnabled: checked };
                 await updateSetting('agent', newSettings);
               }}
               disabled={isLoading}
@@ -612,50 +1428,15 @@ export function AgentPanel() {
 
           {agentSettings?.mcpServerEnabled && (
             <>
-              <div className="space-y-2">
-                <Label>API Key</Label>
-                <div className="flex gap-2">
-                  <Input
-                    type="text"
-                    value={agentSettings?.mcpApiKey || ''}
-                    readOnly
-                    className="font-mono text-sm"
-                  />
-                  <Button
-                    variant="outline"
-                    size="icon"
-                    onClick={() => {
-                      navigator.clipboard.writeText(agentSettings?.mcpApiKey || '');
-                    }}
-                    title="Copy to clipboard"
-                  >
-                    <Check className="h-4 w-4" />
-                  </Button>
-                  <Button
-                    variant="outline"
-                    size="icon"
-                    onClick={async () => {
-                      const newKey = crypto.randomUUID().replace(/-/g, '');
-                      const newSettings = { ...agentSettings, mcpApiKey: newKey };
-                      await updateSetting('agent', newSettings);
-                    }}
-                    title="Generate new key"
-                  >
-                    <RefreshCw className="h-4 w-4" />
-                  </Button>
-                </div>
-                <p className="text-xs text-muted-foreground">
-                  Use this key in the Authorization header: Bearer {'<API_KEY>'}
-                </p>
-              </div>
+              <MCPServerStatusSection />
+
+              <MCPApiKeysSection />
 
               <div className="space-y-2">
                 <Label>Port</Label>
-                <Input
-                  type="number"
+                <CommitNumberInput
                   value={agentSettings?.mcpPort || 8377}
-                  onChange={async (e) => {
-                    const port = parseInt(e.target.value) || 8377;
+                  onCommit={async (port) => {
                     const newSettings = { ...agentSettings, mcpPort: port };
                     await updateSetting('agent', newSettings);
                   }}
@@ -664,7 +1445,65 @@ export function AgentPanel() {
                   className="w-32"
                 />
                 <p className="text-xs text-muted-foreground">
-                  Server URL: http://localhost:{agentSettings?.mcpPort || 8377}/mcp
+                  Server URL: {agentSettings?.mcpTls?.enabled ? 'https' : 'http'}://localhost:{agentSettings?.mcpPort || 8377}/mcp
+                </p>
+              </div>
+
+              <MCPNetworkSection agentSettings={agentSettings} updateSetting={updateSetting} />
+
+              <div className="space-y-2">
+                <Label>Report Redaction</Label>
+                <Select
+                  value={agentSettings?.mcpReportAudience === undefined
+                    ? 'customer'
+                    : agentSettings.mcpReportAudience ?? FULL_REPORT_AUDIENCE}
+                  onValueChange={async (value) => {
+                    const newSettings = {
+                      ...agentSettings,
+                      mcpReportAudience: value === FULL_REPORT_AUDIENCE ? null : value,
+                    };
+                    await updateSetting('agent', newSettings);
+                  }}
+                >
+                  <SelectTrigger className="w-48">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    {(settings.reports?.redactionProfiles ?? []).map((profile) => (
+                      <SelectItem key={profile.name} value={profile.name}>
+                        {profile.name}
+                      </SelectItem>
+                    ))}
+                    <SelectItem value={FULL_REPORT_AUDIENCE}>Full report</SelectItem>
+                  </SelectContent>
+                </Select>
+                <p className="text-xs text-muted-foreground">
+                  Profile applied to reports MCP clients read, unless a client asks for another.
+                </p>
+              </div>
+
+              <div className="space-y-2">
+                <Label>Limits</Label>
+                <div className="grid grid-cols-2 gap-3">
+                  {MCP_LIMIT_FIELDS.map(({ key, label }) => {
+                    const limits = agentSettings?.mcpLimits ?? DEFAULT_AGENT_SETTINGS.mcpLimits!;
+                    return (
+                      <div key={key} className="space-y-1.5">
+                        <Label className="text-xs">{label}</Label>
+                        <CommitNumberInput
+                          min={0}
+                          value={limits[key]}
+                          onCommit={async (value) => {
+                            const newSettings = { ...agentSettings, mcpLimits: { ...limits, [key]: Math.max(0, value) } };
+                            await updateSetting('agent', newSettings);
+                          }}
+                        />
+                      </div>
+                    );
+                  })}
+                </div>
+                <p className="text-xs text-muted-foreground">
+                  0 disables a limit. Requests over a limit get a JSON-RPC error; long tool output is truncated.
                 </p>
               </div>
 
@@ -676,12 +1515,15 @@ export function AgentPanel() {
                       Security Note
                     </p>
                     <p className="text-xs text-muted-foreground">
-                      Requires app restart to apply changes. For remote access, use HTTPS via a reverse proxy.
+                      Changing the port, network settings or limits restarts the server, dropping open connections; key changes apply immediately.
+                      Turn on HTTPS before using the server over the network.
                       Commands execute based on your approval mode settings.
                     </p>
                   </div>
                 </div>
               </div>
+
+              <MCPAuditLogSection />
             </>
           )}
         </CardContent>
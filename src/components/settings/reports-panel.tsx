@@ -19,10 +19,12 @@ import { Label } from '@/components/ui/label';
 import { Switch } from '@/components/ui/switch';
 import { Input } from '@/components/ui/input';
 import { useSettings } from '@/components/settings-context';
+import type { ReportPruneSummary } from '@/types/service';
 
 export function ReportsPanel() {
   const { settings, updateSetting, isLoading } = useSettings();
   const [isClearing, setIsClearing] = useState(false);
+  const [isPruning, setIsPruning] = useState(false);
 
   const handleAutoSaveChange = async (checked: boolean) => {
     await updateSetting('reports.autoSaveReports', checked);
@@ -37,6 +39,26 @@ export function ReportsPanel() {
     await updateSetting('reports.includeLogsInReport', checked);
   };
 
+  const handleArchiveExpiredChange = async (checked: boolean) => {
+    await updateSetting('reports.archiveExpiredReports', checked);
+  };
+
+  const handlePruneReports = async () => {
+    setIsPruning(true);
+    try {
+      const summary = await invoke<ReportPruneSummary>('prune_reports');
+      const count = summary.removed.length;
+      const action = summary.archivePath ? 'Archived' : 'Removed';
+      const pinned = summary.keptPinned > 0 ? ` (${summary.keptPinned} pinned kept)` : '';
+      alert(`${action} ${count} expired report${count !== 1 ? 's' : ''}${pinned}.`);
+    } catch (error) {
+      console.error('Failed to prune reports:', error);
+      alert('Failed to prune reports.');
+    } finally {
+      setIsPruning(false);
+    }
+  };
+
   const handleClearAllReports = async () => {
     if (!confirm('Are you sure you want to delete all saved reports? This cannot be undone.')) {
       return;
@@ -138,6 +160,27 @@ export function ReportsPanel() {
                 className="w-20 text-center"
               />
             </div>
+            <div className="flex items-center justify-between p-3 rounded-lg bg-muted/50">
+              <div>
+                <Label htmlFor="archive-expired" className="text-sm font-medium">Archive expired reports</Label>
+                <p className="text-xs text-muted-foreground">Zip old reports instead of deleting them</p>
+              </div>
+              <Switch
+                id="archive-expired"
+                checked={settings.reports?.archiveExpiredReports ?? false}
+                onCheckedChange={handleArchiveExpiredChange}
+                disabled={isLoading}
+              />
+            </div>
+            <Button
+              variant="outline"
+              onClick={handlePruneReports}
+              disabled={isPruning || !settings.reports?.reportRetentionDays}
+              className="w-full"
+            >
+              <Trash2 className="mr-2 h-4 w-4" />
+              {isPruning ? 'Pruning...' : 'Prune Expired Reports Now'}
+            </Button>
             <Button variant="outline" onClick={handleOpenReportsFolder} className="w-full">
               <ExternalLink className="mr-2 h-4 w-4" />
               Open Reports Folder
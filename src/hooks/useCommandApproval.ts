@@ -9,14 +9,19 @@
 
 import { useRef, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import type { CoreMessage, ToolCallPart } from 'ai';
 import { isHITLTool } from '@/lib/agent-tools';
 import { validateToolCall } from '@/lib/agent-activity-utils';
 import type { AgentActivity, ActivityStatus } from '@/types/agent-activity';
+import type { CommandOutputEvent, PendingCommand } from '@/types/agent';
 import type { FileAttachment } from '@/types/file-attachment';
 import type { ServiceReport, ServiceRunState as ServiceRunStateType } from '@/types/service';
 import type { ActiveServiceRun } from '@/hooks/useServiceSupervision';
 
+/** Characters of live command output kept on the activity while it runs */
+const LIVE_OUTPUT_CHARS = 16_000;
+
 type UpdateActivityFn = (
   msgId: string | null,
   activityId: string,
@@ -32,6 +37,8 @@ interface UseCommandApprovalParams {
   updateActivityInParts: UpdateActivityFn;
   findMessageIdForActivity: FindMessageIdFn;
   setActiveServiceRun: SetActiveServiceRunFn;
+  /** Current conversation; file changes are journaled and shell sessions kept under it */
+  conversationId: string | null;
 }
 
 /**
@@ -46,6 +53,7 @@ async function executeHITLToolCall(
   updateActivity: UpdateActivityFn,
   findMessageId: FindMessageIdFn,
   setActiveServiceRun: SetActiveServiceRunFn,
+  conversationId: string | null,
 ): Promise<CoreMessage> {
   updateActivity(null, toolCallId, { status: 'running' as ActivityStatus });
 
@@ -61,18 +69,36 @@ async function executeHITLToolCall(
       switch (toolName) {
         case 'execute_command': {
           const command = String(args.command || '');
-          const res = await invoke<{ output?: string; error?: string }>(
-            'execute_agent_command',
-            { command, reason: String(args.reason || reason || 'Agent approved') },
-          );
-          result = res.output || res.error || 'Command executed successfully.';
-          isError = !!res.error;
+          // Show output as it arrives, keeping the latest part
+          let live = '';
+          const unlisten = await listen<CommandOutputEvent>('command-output', event => {
+            if (event.payload.id !== toolCallId) return;
+            live = (live + event.payload.text).slice(-LIVE_OUTPUT_CHARS);
+            updateActivity(null, toolCallId, { output: live });
+          });
+          try {
+            const res = await invoke<PendingCommand>('execute_agent_command', {
+              command,
+              reason: String(args.reason || reason || 'Agent approved'),
+              outputId: toolCallId,
+              conversationId,
+            });
+            result = res.output || res.error || 'Command executed successfully.';
+            if (res.timedOut && res.output) {
+              result += `\n\n${res.error}`;
+            }
+            isError = !!res.error;
+          } finally {
+            unlisten();
+          }
           break;
         }
         case 'write_file': {
           await invoke('agent_write_file', {
             path: String(args.path || ''),
             content: String(args.content || ''),
+            conversationId,
+            force: Boolean(args.force),
           });
           result = `Successfully wrote to ${args.path}`;
           break;
@@ -87,6 +113,8 @@ async function executeHITLToolCall(
             old_string: String(args.oldString || ''),
             new_string: String(args.newString || ''),
             all: Boolean(args.all),
+            conversation_id: conversationId,
+            force: Boolean(args.force),
           });
           result =
             res.message || `Edited ${args.path} (${res.replacements} replacements)`;
@@ -119,6 +147,8 @@ async function executeHITLToolCall(
           await invoke('agent_move_file', {
             src: String(args.src || ''),
             dest: String(args.dest || ''),
+            conversationId,
+            force: Boolean(args.force),
           });
           result = `Moved ${args.src} to ${args.dest}`;
           break;
@@ -127,6 +157,8 @@ async function executeHITLToolCall(
           await invoke('agent_copy_file', {
             src: String(args.src || ''),
             dest: String(args.dest || ''),
+            conversationId,
+            force: Boolean(args.force),
           });
           result = `Copied ${args.src} to ${args.dest}`;
           break;
@@ -239,6 +271,7 @@ export function useCommandApproval({
   updateActivityInParts,
   findMessageIdForActivity,
   setActiveServiceRun,
+  conversationId,
 }: UseCommandApprovalParams) {
   const approvalInProgressRef = useRef(false);
 
@@ -262,8 +295,9 @@ export function useCommandApproval({
         updateActivityInParts,
         findMessageIdForActivity,
         setActiveServiceRun,
+        conversationId,
       ),
-    [updateActivityInParts, findMessageIdForActivity, setActiveServiceRun],
+    [updateActivityInParts, findMessageIdForActivity, setActiveServiceRun, conversationId],
   );
 
   /**
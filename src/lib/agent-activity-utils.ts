@@ -111,6 +111,7 @@ export function mapToolToActivityType(toolName: string): ActivityType {
     case 'get_service_status': return 'service_query';
     case 'get_service_report': return 'service_report';
     case 'get_report_statistics': return 'service_report';
+    case 'compare_reports': return 'service_report';
     case 'edit_finding': return 'service_edit';
     case 'add_finding': return 'service_edit';
     case 'remove_finding': return 'service_edit';
@@ -225,6 +226,11 @@ export function extractActivityDetails(
         reportAction: 'Get statistics',
         reportId: typeof args.report_id === 'string' ? args.report_id : undefined,
       };
+    case 'compare_reports':
+      return {
+        reportAction: 'Compare reports',
+        reportId: typeof args.after_report_id === 'string' ? args.after_report_id : undefined,
+      };
     case 'edit_finding':
       return { editAction: 'Edit finding', detail: typeof args.title === 'string' ? args.title : undefined };
     case 'add_finding':
@@ -283,6 +283,8 @@ After a service run completes:
 7. Generate the final report with \`generate_report_pdf\`
 8. Present the PDF path to the user with key findings summarized
 
+If the same machine was serviced before (e.g. a diagnostic run followed by a clean-up run), use \`compare_reports\` with the earlier report as "before" to describe what improved.
+
 ### Symptom-to-Service Mapping
 When users describe problems, map their symptoms to appropriate services:
 - **Slow/laggy**: disk-space, bleachbit, sfc, dism, drivecleanup
@@ -17,6 +17,7 @@ import type {
   ServicePreset,
   ServiceReport,
   ReportStatistics,
+  ReportDiff,
   ServiceRunState,
 } from '@/types/service';
 
@@ -79,6 +80,7 @@ const statusMessageSchema = z.object({
 const srcDestSchema = z.object({
   src: z.string().describe('Source path'),
   dest: z.string().describe('Destination path'),
+  force: z.boolean().optional().describe('Allow replacing a very large (>10 MB) file at the destination. Only set when the user asked for it'),
 });
 
 const reportIdSchema = z.object({
@@ -115,6 +117,7 @@ export const writeFileTool = tool({
   inputSchema: z.object({
     path: z.string().describe('Full absolute path to the file'),
     content: z.string().describe('Content to write to the file'),
+    force: z.boolean().optional().describe('Allow replacing a binary or very large (>10 MB) file. Only set when the user asked for it'),
   }),
   outputSchema: fileResultSchema,
 });
@@ -139,6 +142,7 @@ Use this for targeted edits instead of rewriting entire files. Always read the f
     oldString: z.string().describe('The exact string to replace (must be unique in file unless all=true)'),
     newString: z.string().describe('The replacement string'),
     all: z.boolean().optional().describe('Replace all occurrences (default: false)'),
+    force: z.boolean().optional().describe('Allow replacing a binary or very large (>10 MB) file. Only set when the user asked for it'),
   }),
   outputSchema: z.object({
     status: z.enum(['success', 'error']),
@@ -433,6 +437,20 @@ export const getReportStatisticsTool = createServerTool({
   },
 });
 
+export const compareReportsTool = createServerTool({
+  description: 'Compare two service reports ("before vs after"). Returns services added/removed, pass/fail changes, findings that appeared, were resolved or changed severity, and measurement deltas (free disk space, startup items, space reclaimed, benchmark speeds).',
+  parameters: z.object({
+    before_report_id: z.string().describe('ID of the earlier report'),
+    after_report_id: z.string().describe('ID of the later report'),
+  }),
+  execute: async ({ before_report_id, after_report_id }) => {
+    const diff = await invoke<ReportDiff>('compare_reports', {
+      beforeReportId: before_report_id, afterReportId: after_report_id, audience: null,
+    });
+    return { status: 'success' as const, diff };
+  },
+});
+
 // =============================================================================
 // Server-Side Tools — Service Report Editing (Auto-Execute)
 // =============================================================================
@@ -649,6 +667,7 @@ export const agentTools = {
   get_service_status: getServiceStatusTool,
   get_service_report: getServiceReportTool,
   get_report_statistics: getReportStatisticsTool,
+  compare_reports: compareReportsTool,
   edit_finding: editFindingTool,
   add_finding: addFindingTool,
   remove_finding: removeFindingTool,
@@ -701,6 +720,7 @@ export function getEnabledTools(settings: { searchProvider: string }): ToolSet {
     get_service_status: getServiceStatusTool,
     get_service_report: getServiceReportTool,
     get_report_statistics: getReportStatisticsTool,
+    compare_reports: compareReportsTool,
     edit_finding: editFindingTool,
     add_finding: addFindingTool,
     remove_finding: removeFindingTool,
@@ -1,13 +1,15 @@
 /**
  * MCP Client Manager
  *
- * Manages connections to external MCP servers, providing tool discovery
- * and lifecycle management. Uses @ai-sdk/mcp for protocol support.
+ * Exposes the tools of external MCP servers to the agent. The connections
+ * live in the Rust backend, which connects to the enabled servers in the
+ * saved settings, health-checks them and reconnects when they drop; this
+ * module mirrors their status and wraps their tools for the AI SDK.
  */
 
-import { createMCPClient, type MCPClient } from '@ai-sdk/mcp';
-import type { ToolSet } from 'ai';
-import type { MCPServerConfig } from '@/types/agent';
+import { invoke } from '@tauri-apps/api/core';
+import { jsonSchema, tool, type ToolSet } from 'ai';
+import type { MCPServerConfig, McpClientServerStatus, McpClientTool } from '@/types/agent';
 
 // =============================================================================
 // Types
@@ -15,7 +17,7 @@ import type { MCPServerConfig } from '@/types/agent';
 
 interface ConnectedServer {
   config: MCPServerConfig;
-  client: MCPClient;
+  /** Tools keyed by their name on the server */
   tools: ToolSet;
   error?: string;
 }
@@ -31,28 +33,89 @@ export interface MCPManagerState {
   errors: Array<{ serverId: string; serverName: string; error: string }>;
 }
 
+// =============================================================================
+// Constants
+// =============================================================================
+
+/** How long to wait for servers to finish connecting */
+const CONNECT_WAIT_MS = 15_000;
+const POLL_INTERVAL_MS = 500;
+
+/** How often the tool list is refreshed from the backend */
+const REFRESH_INTERVAL_MS = 30_000;
+
 // =============================================================================
 // MCP Manager
 // =============================================================================
 
 /**
- * Active MCP client connections - module-level singleton
+ * Snapshot of the backend's connections - module-level singleton
  */
 let activeConnections: ConnectedServer[] = [];
 let connectionErrors: Array<{ serverId: string; serverName: string; error: string }> = [];
+let mergedTools: ToolSet = {};
+let knownConfigs: MCPServerConfig[] = [];
+let refreshTimer: ReturnType<typeof setInterval> | null = null;
+
+/**
+ * Wrap an external tool so calls are proxied through the backend
+ */
+function wrapTool(def: McpClientTool) {
+  return tool({
+    description: def.description || `${def.toolName} (${def.serverName})`,
+    inputSchema: jsonSchema(def.inputSchema as Parameters<typeof jsonSchema>[0]),
+    execute: async (args) =>
+      invoke<unknown>('call_mcp_client_tool', { name: def.name, arguments: args }),
+  });
+}
+
+/**
+ * Read status and tools from the backend into the snapshot
+ */
+async function refresh(): Promise<void> {
+  const [statuses, tools] = await Promise.all([
+    invoke<McpClientServerStatus[]>('list_mcp_client_servers'),
+    invoke<McpClientTool[]>('list_mcp_client_tools'),
+  ]);
+
+  const connections: ConnectedServer[] = [];
+  const errors: Array<{ serverId: string; serverName: string; error: string }> = [];
+  const merged: ToolSet = {};
+
+  for (const status of statuses) {
+    const config = knownConfigs.find(c => c.id === status.id);
+    if (!config) continue;
+
+    if (status.state === 'connected') {
+      const serverTools: ToolSet = {};
+      for (const def of tools.filter(t => t.serverId === status.id)) {
+        const wrapped = wrapTool(def);
+        serverTools[def.toolName] = wrapped;
+        merged[def.name] = wrapped;
+      }
+      connections.push({ config, tools: serverTools });
+    } else if (status.lastError) {
+      errors.push({ serverId: status.id, serverName: status.name, error: status.lastError });
+    }
+  }
+
+  activeConnections = connections;
+  connectionErrors = errors;
+  mergedTools = merged;
+}
 
 /**
- * Connect to all enabled MCP servers and retrieve their tools.
- * Disconnects any previously connected servers first.
+ * Wait for the enabled MCP servers to connect and retrieve their tools.
+ * The backend connects on its own when settings are saved; this waits until
+ * every enabled server has connected or failed, up to a timeout.
  */
 export async function connectMCPServers(
   configs: MCPServerConfig[]
 ): Promise<{ tools: ToolSet; state: MCPManagerState }> {
-  // Disconnect existing connections first
   await disconnectAll();
 
-  const enabledConfigs = configs.filter(c => c.enabled && c.url);
-  if (enabledConfigs.length === 0) {
+  knownConfigs = configs.filter(c => c.enabled && c.url);
+  if (knownConfigs.length === 0) {
     return {
       tools: {},
       state: {
@@ -64,83 +127,44 @@ export async function connectMCPServers(
     };
   }
 
-  const connections: ConnectedServer[] = [];
-  const errors: Array<{ serverId: string; serverName: string; error: string }> = [];
-  let mergedTools: ToolSet = {};
-
-  // Connect to each server individually (don't let one failure break all)
-  for (const config of enabledConfigs) {
-    try {
-      console.log(`[MCP] Connecting to "${config.name}" at ${config.url}...`);
-
-      // Build headers
-      const headers: Record<string, string> = {};
-      if (config.apiKey) {
-        headers['Authorization'] = `Bearer ${config.apiKey}`;
-      }
-      if (config.headers) {
-        Object.assign(headers, config.headers);
-      }
-
-      const client = await createMCPClient({
-        transport: {
-          type: config.transportType,
-          url: config.url,
-          ...(Object.keys(headers).length > 0 ? { headers } : {}),
-        },
-      });
-
-      const tools = await client.tools();
-      const toolCount = Object.keys(tools).length;
-      console.log(`[MCP] Connected to "${config.name}" - ${toolCount} tools available`);
-
-      connections.push({ config, client, tools: tools as unknown as ToolSet });
-
-      // Prefix tool names with server ID to avoid collisions
-      for (const [toolName, toolDef] of Object.entries(tools)) {
-        const prefixedName = `mcp_${config.id}_${toolName}`;
-        mergedTools[prefixedName] = toolDef as any;
-      }
-    } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
-      console.error(`[MCP] Failed to connect to "${config.name}":`, errorMsg);
-      errors.push({
-        serverId: config.id,
-        serverName: config.name,
-        error: errorMsg,
-      });
-    }
+  const deadline = Date.now() + CONNECT_WAIT_MS;
+  for (;;) {
+    const statuses = await invoke<McpClientServerStatus[]>('list_mcp_client_servers');
+    const settled = knownConfigs.every(config => {
+      const status = statuses.find(s => s.id === config.id);
+      return status && (status.state === 'connected' || status.lastError);
+    });
+    if (settled || Date.now() >= deadline) break;
+    await new Promise(resolve => setTimeout(resolve, POLL_INTERVAL_MS));
   }
 
-  activeConnections = connections;
-  connectionErrors = errors;
+  await refresh();
+  console.log(
+    `[MCP] ${activeConnections.length} of ${knownConfigs.length} servers connected - ` +
+      `${Object.keys(mergedTools).length} tools available`
+  );
 
-  const state: MCPManagerState = {
-    servers: connections,
-    toolCount: Object.keys(mergedTools).length,
-    isConnecting: false,
-    errors,
-  };
+  // Pick up reconnects and tool list changes
+  refreshTimer = setInterval(() => {
+    refresh().catch(err => console.warn('[MCP] Failed to refresh MCP tools:', err));
+  }, REFRESH_INTERVAL_MS);
 
-  return { tools: mergedTools, state };
+  return { tools: mergedTools, state: getMCPState() };
 }
 
 /**
- * Disconnect all active MCP server connections
+ * Stop tracking MCP servers. The backend keeps its connections, which
+ * follow the saved settings.
  */
 export async function disconnectAll(): Promise<void> {
-  const closePromises = activeConnections.map(async (conn) => {
-    try {
-      await conn.client.close();
-      console.log(`[MCP] Disconnected from "${conn.config.name}"`);
-    } catch (error) {
-      console.warn(`[MCP] Error disconnecting from "${conn.config.name}":`, error);
-    }
-  });
-
-  await Promise.all(closePromises);
+  if (refreshTimer) {
+    clearInterval(refreshTimer);
+    refreshTimer = null;
+  }
   activeConnections = [];
   connectionErrors = [];
+  mergedTools = {};
+  knownConfigs = [];
 }
 
 /**
@@ -149,7 +173,7 @@ export async function disconnectAll(): Promise<void> {
 export function getMCPState(): MCPManagerState {
   return {
     servers: activeConnections,
-    toolCount: activeConnections.reduce((sum, c) => sum + Object.keys(c.tools).length, 0),
+    toolCount: Object.keys(mergedTools).length,
     isConnecting: false,
     errors: connectionErrors,
   };
@@ -159,14 +183,7 @@ export function getMCPState(): MCPManagerState {
  * Get tools from all currently connected MCP servers
  */
 export function getMCPTools(): ToolSet {
-  const mergedTools: ToolSet = {};
-  for (const conn of activeConnections) {
-    for (const [toolName, toolDef] of Object.entries(conn.tools)) {
-      const prefixedName = `mcp_${conn.config.id}_${toolName}`;
-      mergedTools[prefixedName] = toolDef;
-    }
-  }
-  return mergedTools;
+  return { ...mergedTools };
 }
 
 /**
@@ -203,6 +203,7 @@ export function AgentPage() {
     updateActivityInParts,
     findMessageIdForActivity,
     setActiveServiceRun,
+    conversationId: currentConversationId,
   });
 
   // --- Tool summary for sidebar ---
@@ -570,6 +571,11 @@ export function AgentPage() {
     [_handleReject],
   );
 
+  const handleResetShell = useCallback(async () => {
+    if (!currentConversationId) return;
+    await invoke('reset_agent_shell_session', { conversationId: currentConversationId });
+  }, [currentConversationId]);
+
   const executeMessage = useCallback(
     async (text: string, attachments?: FileAttachment[]) => {
       if ((!text.trim() && !attachments?.length) || isLoading || !agentSettings) return;
@@ -776,6 +782,7 @@ export function AgentPage() {
             activeServiceRun={activeServiceRun}
             onActivityApprove={handleActivityApprove}
             onActivityReject={handleActivityReject}
+            onResetShell={currentConversationId ? handleResetShell : undefined}
             onSuggestionClick={setInput}
             onCancelServiceRun={() => setActiveServiceRun(null)}
           />
@@ -805,6 +812,7 @@ export function AgentPage() {
                 onRunInstrument={handleRunInstrument}
                 mcpState={mcpState}
                 toolSummary={toolSummary}
+                conversationId={currentConversationId}
               />
             </motion.div>
           )}
@@ -6,7 +6,7 @@
  * Uses the shared ServiceReportView component for consistent styling.
  */
 
-import { useState, useEffect, useMemo } from 'react';
+import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import {
   FileText,
@@ -22,6 +22,8 @@ import {
   Eye,
   Filter,
   User,
+  ChevronLeft,
+  ChevronRight,
 } from 'lucide-react';
 
 import { Button } from '@/components/ui/button';
@@ -48,7 +50,15 @@ import {
 } from '@/components/ui/alert-dialog';
 import { Tabs, TabsList, TabsTrigger } from '@/components/ui/tabs';
 
-import type { ServiceReport, ServiceRunStatus, ServiceDefinition } from '@/types/service';
+import type {
+  ServiceReport,
+  ServiceRunStatus,
+  ServiceDefinition,
+  ReportIndexEntry,
+  ReportQuery,
+  ReportQueryResult,
+  ReportSort,
+} from '@/types/service';
 import { ServiceReportView } from '@/components/service-report-view';
 import { useSettings } from '@/components/settings-context';
 
@@ -56,10 +66,15 @@ import { useSettings } from '@/components/settings-context';
 // Types
 // =============================================================================
 
-type ReportSortOption = 'newest' | 'oldest' | 'duration-desc' | 'duration-asc' | 'technician';
 type ReportFilterStatus = 'all' | 'completed' | 'failed' | 'cancelled';
 
-const SORT_OPTIONS: { value: ReportSortOption; label: string }[] = [
+/** Reports shown per page */
+const PAGE_SIZE = 50;
+
+/** Delay before a change to the search box queries the index */
+const SEARCH_DEBOUNCE_MS = 300;
+
+const SORT_OPTIONS: { value: ReportSort; label: string }[] = [
   { value: 'newest', label: 'Newest First' },
   { value: 'oldest', label: 'Oldest First' },
   { value: 'duration-desc', label: 'Longest Duration' },
@@ -135,50 +150,21 @@ function getStatusInfo(status: ServiceRunStatus): {
   }
 }
 
-function sortReports(reports: ServiceReport[], sortBy: ReportSortOption): ServiceReport[] {
-  const sorted = [...reports];
-  
-  switch (sortBy) {
-    case 'newest':
-      return sorted.sort((a, b) => 
-        new Date(b.startedAt).getTime() - new Date(a.startedAt).getTime()
-      );
-    case 'oldest':
-      return sorted.sort((a, b) => 
-        new Date(a.startedAt).getTime() - new Date(b.startedAt).getTime()
-      );
-    case 'duration-desc':
-      return sorted.sort((a, b) => 
-        (b.totalDurationMs || 0) - (a.totalDurationMs || 0)
-      );
-    case 'duration-asc':
-      return sorted.sort((a, b) => 
-        (a.totalDurationMs || 0) - (b.totalDurationMs || 0)
-      );
-    case 'technician':
-      return sorted.sort((a, b) => 
-        (a.technicianName || '').localeCompare(b.technicianName || '')
-      );
-    default:
-      return sorted;
-  }
-}
-
 // =============================================================================
 // Report Card Component
 // =============================================================================
 
 interface ReportCardProps {
-  report: ServiceReport;
-  onView: (report: ServiceReport) => void;
+  report: ReportIndexEntry;
+  onView: (report: ReportIndexEntry) => void;
   onDelete: (reportId: string) => void;
 }
 
 function ReportCard({ report, onView, onDelete }: ReportCardProps) {
   const statusInfo = getStatusInfo(report.status);
   const StatusIcon = statusInfo.icon;
-  const successCount = report.results.filter(r => r.success).length;
-  const totalCount = report.results.length;
+  const totalCount = report.serviceIds.length;
+  const successCount = totalCount - report.failedCount;
 
   return (
     <Card className="group hover:shadow-md transition-all duration-200 hover:border-primary/50">
@@ -276,68 +262,80 @@ function EmptyState() {
 
 export function ReportsPage() {
   const { settings } = useSettings();
-  const [reports, setReports] = useState<ServiceReport[]>([]);
+  const [reports, setReports] = useState<ReportIndexEntry[]>([]);
+  const [totalReports, setTotalReports] = useState(0);
+  const [page, setPage] = useState(0);
+  const [reloadKey, setReloadKey] = useState(0);
   const [definitions, setDefinitions] = useState<ServiceDefinition[]>([]);
   const [isLoading, setIsLoading] = useState(true);
   const [searchQuery, setSearchQuery] = useState('');
-  const [sortBy, setSortBy] = useState<ReportSortOption>('newest');
+  const [debouncedSearch, setDebouncedSearch] = useState('');
+  const [sortBy, setSortBy] = useState<ReportSort>('newest');
   const [filterStatus, setFilterStatus] = useState<ReportFilterStatus>('all');
   const [filterTechnician, setFilterTechnician] = useState<string>('all');
   const [selectedReport, setSelectedReport] = useState<ServiceReport | null>(null);
   const [reportToDelete, setReportToDelete] = useState<string | null>(null);
   const [deleteDialogOpen, setDeleteDialogOpen] = useState(false);
 
-  // Load reports and definitions
+  const hasFilters = debouncedSearch !== '' || filterStatus !== 'all' || filterTechnician !== 'all';
+  const pageCount = Math.max(1, Math.ceil(totalReports / PAGE_SIZE));
+
+  // Definitions are loaded once; full reports are loaded when viewed
   useEffect(() => {
-    async function loadData() {
-      try {
-        const [reportsList, defsList] = await Promise.all([
-          invoke<ServiceReport[]>('list_service_reports'),
-          invoke<ServiceDefinition[]>('get_service_definitions'),
-        ]);
-        setReports(reportsList);
-        setDefinitions(defsList);
-      } catch (e) {
-        console.error('Failed to load data:', e);
-      } finally {
-        setIsLoading(false);
-      }
-    }
-    loadData();
+    invoke<ServiceDefinition[]>('get_service_definitions')
+      .then(setDefinitions)
+      .catch(e => console.error('Failed to load service definitions:', e));
   }, []);
 
-  // Filter and sort reports
-  const filteredReports = useMemo(() => {
-    let result = reports;
-
-    // Filter by status
-    if (filterStatus !== 'all') {
-      result = result.filter(r => r.status === filterStatus);
-    }
-
-    // Filter by technician
-    if (filterTechnician !== 'all') {
-      result = result.filter(r => r.technicianName === filterTechnician);
-    }
-
-    // Filter by search query
-    if (searchQuery) {
-      const query = searchQuery.toLowerCase();
-      result = result.filter(r => 
-        r.id.toLowerCase().includes(query) ||
-        formatDate(r.startedAt).toLowerCase().includes(query) ||
-        r.status.toLowerCase().includes(query) ||
-        (r.technicianName || '').toLowerCase().includes(query) ||
-        (r.customerName || '').toLowerCase().includes(query)
-      );
-    }
-
-    return sortReports(result, sortBy);
-  }, [reports, searchQuery, sortBy, filterStatus, filterTechnician]);
+  // Wait for typing to settle before searching
+  useEffect(() => {
+    const timer = setTimeout(() => {
+      setDebouncedSearch(searchQuery.trim());
+      setPage(0);
+    }, SEARCH_DEBOUNCE_MS);
+    return () => clearTimeout(timer);
+  }, [searchQuery]);
+
+  // Filtering, sorting and paging happen in the report index
+  useEffect(() => {
+    let stale = false;
+    const query: ReportQuery = {
+      search: debouncedSearch || undefined,
+      statuses: filterStatus === 'all' ? undefined : [filterStatus],
+      technicianName: filterTechnician === 'all' ? undefined : filterTechnician,
+      sort: sortBy,
+      offset: page * PAGE_SIZE,
+      limit: PAGE_SIZE,
+    };
+
+    invoke<ReportQueryResult>('search_reports', { query })
+      .then(result => {
+        if (stale) return;
+        // The last page emptied out (e.g. after a delete)
+        if (result.reports.length === 0 && page > 0) {
+          setPage(Math.max(0, Math.ceil(result.total / PAGE_SIZE) - 1));
+          return;
+        }
+        setReports(result.reports);
+        setTotalReports(result.total);
+      })
+      .catch(e => console.error('Failed to search reports:', e))
+      .finally(() => {
+        if (!stale) setIsLoading(false);
+      });
+
+    return () => {
+      stale = true;
+    };
+  }, [debouncedSearch, filterStatus, filterTechnician, sortBy, page, reloadKey]);
 
   // Handlers
-  const handleViewReport = (report: ServiceReport) => {
-    setSelectedReport(report);
+  const handleViewReport = async (entry: ReportIndexEntry) => {
+    try {
+      setSelectedReport(await invoke<ServiceReport>('get_service_report', { reportId: entry.id }));
+    } catch (e) {
+      console.error('Failed to load report:', e);
+    }
   };
 
   const handleDeleteClick = (reportId: string) => {
@@ -350,7 +348,7 @@ export function ReportsPage() {
 
     try {
       await invoke('delete_report', { reportId: reportToDelete });
-      setReports(prev => prev.filter(r => r.id !== reportToDelete));
+      setReloadKey(key => key + 1);
       if (selectedReport?.id === reportToDelete) {
         setSelectedReport(null);
       }
@@ -421,7 +419,7 @@ export function ReportsPage() {
           <FileText className="h-5 w-5 text-primary" />
           <h2 className="font-semibold">Reports</h2>
           <Badge variant="secondary" className="ml-1">
-            {reports.length}
+            {totalReports}
           </Badge>
         </div>
 
@@ -440,7 +438,13 @@ export function ReportsPage() {
         <div className="flex items-center gap-2 flex-shrink-0">
           {/* Technician Filter */}
           {settings.business?.enabled && (settings.business.technicians?.length ?? 0) > 0 && (
-            <Select value={filterTechnician} onValueChange={setFilterTechnician}>
+            <Select
+              value={filterTechnician}
+              onValueChange={(v) => {
+                setFilterTechnician(v);
+                setPage(0);
+              }}
+            >
               <SelectTrigger className="w-40">
                 <User className="h-4 w-4 mr-2" />
                 <SelectValue placeholder="All Techs" />
@@ -457,7 +461,13 @@ export function ReportsPage() {
           )}
 
           {/* Sort Dropdown */}
-          <Select value={sortBy} onValueChange={(v) => setSortBy(v as ReportSortOption)}>
+          <Select
+            value={sortBy}
+            onValueChange={(v) => {
+              setSortBy(v as ReportSort);
+              setPage(0);
+            }}
+          >
             <SelectTrigger className="w-44">
               <ArrowUpDown className="h-4 w-4 mr-2" />
               <SelectValue />
@@ -475,7 +485,13 @@ export function ReportsPage() {
 
       {/* Filter Tabs */}
       <div className="px-4 py-2 border-b">
-        <Tabs value={filterStatus} onValueChange={(v) => setFilterStatus(v as ReportFilterStatus)}>
+        <Tabs
+          value={filterStatus}
+          onValueChange={(v) => {
+            setFilterStatus(v as ReportFilterStatus);
+            setPage(0);
+          }}
+        >
           <TabsList>
             <TabsTrigger value="all" className="gap-1">
               <Filter className="h-3.5 w-3.5" />
@@ -499,16 +515,16 @@ export function ReportsPage() {
 
       {/* Content */}
       <ScrollArea className="flex-1 min-h-0">
-        {reports.length === 0 ? (
+        {reports.length === 0 && !hasFilters ? (
           <EmptyState />
-        ) : filteredReports.length === 0 ? (
+        ) : reports.length === 0 ? (
           <div className="flex flex-col items-center justify-center h-64 text-muted-foreground">
             <Search className="h-8 w-8 mb-2" />
             <p>No reports match your search</p>
           </div>
         ) : (
           <div className="p-4 flex flex-col gap-3">
-            {filteredReports.map(report => (
+            {reports.map(report => (
               <ReportCard
                 key={report.id}
                 report={report}
@@ -520,6 +536,40 @@ export function ReportsPage() {
         )}
       </ScrollArea>
 
+      {/* Pagination */}
+      {totalReports > PAGE_SIZE && (
+        <div className="px-4 py-2 border-t flex items-center justify-between flex-shrink-0 text-sm text-muted-foreground">
+          <span>
+            {page * PAGE_SIZE + 1}–{Math.min((page + 1) * PAGE_SIZE, totalReports)} of {totalReports}
+          </span>
+          <div className="flex items-center gap-2">
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={() => setPage(p => p - 1)}
+              disabled={page === 0}
+              className="gap-1"
+            >
+              <ChevronLeft className="h-4 w-4" />
+              Previous
+            </Button>
+            <span>
+              Page {page + 1} of {pageCount}
+            </span>
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={() => setPage(p => p + 1)}
+              disabled={page + 1 >= pageCount}
+              className="gap-1"
+            >
+              Next
+              <ChevronRight className="h-4 w-4" />
+            </Button>
+          </div>
+        </div>
+      )}
+
       {/* Delete Confirmation Dialog */}
       <AlertDialog open={deleteDialogOpen} onOpenChange={setDeleteDialogOpen}>
         <AlertDialogContent>
@@ -10,7 +10,7 @@
 
 import { useState, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { Loader2, AlertCircle } from 'lucide-react';
+import { Loader2, AlertCircle, RotateCcw } from 'lucide-react';
 
 import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
@@ -54,6 +54,7 @@ export function ServicePage() {
   const {
     report,
     logs,
+    taskProgress,
     isRunning,
     phase: runPhase,
     presets,
@@ -62,6 +63,9 @@ export function ServicePage() {
     loadError,
     listenerError,
     reloadPresets,
+    interruptedRun,
+    resumeInterruptedRun,
+    discardInterruptedRun,
   } = useServiceRun();
 
   const [phase, setPhase] = useState<ServicePhase>('presets');
@@ -74,6 +78,7 @@ export function ServicePage() {
   // Error states
   const [runError, setRunError] = useState<string | null>(null);
   const [cancelError, setCancelError] = useState<string | null>(null);
+  const [resumeError, setResumeError] = useState<string | null>(null);
 
   // Business mode dialog state
   const [showStartDialog, setShowStartDialog] = useState(false);
@@ -241,6 +246,27 @@ export function ServicePage() {
     }
   };
 
+  const handleResumeInterrupted = async () => {
+    try {
+      setResumeError(null);
+      // Phase follows the run state events once the resumed run starts
+      await resumeInterruptedRun();
+    } catch (error) {
+      console.error('Failed to resume service run:', error);
+      setResumeError(`Failed to resume service run: ${error instanceof Error ? error.message : String(error)}`);
+    }
+  };
+
+  const handleDiscardInterrupted = async () => {
+    try {
+      setResumeError(null);
+      await discardInterruptedRun();
+    } catch (error) {
+      console.error('Failed to discard interrupted run:', error);
+      setResumeError(`Failed to discard interrupted run: ${error instanceof Error ? error.message : String(error)}`);
+    }
+  };
+
   const handleNewService = () => {
     setQueue([]);
     setSelectedPresetName(undefined);
@@ -265,6 +291,31 @@ export function ServicePage() {
           <AlertDescription>{listenerError || loadError}</AlertDescription>
         </Alert>
       )}
+      {resumeError && (
+        <Alert variant="destructive" className="mx-4 mt-4 shrink-0">
+          <AlertCircle className="h-4 w-4" />
+          <AlertDescription>{resumeError}</AlertDescription>
+        </Alert>
+      )}
+      {interruptedRun && !isRunning && (
+        <Alert className="mx-4 mt-4 shrink-0">
+          <RotateCcw className="h-4 w-4" />
+          <AlertDescription className="flex items-center justify-between gap-4">
+            <span>
+              A service run was interrupted ({interruptedRun.completedIndices.length} of{' '}
+              {interruptedRun.queue.length} services completed). Resume from the next service?
+            </span>
+            <div className="flex gap-2 shrink-0">
+              <Button size="sm" variant="outline" onClick={handleDiscardInterrupted}>
+                Discard
+              </Button>
+              <Button size="sm" onClick={handleResumeInterrupted}>
+                Resume
+              </Button>
+            </div>
+          </AlertDescription>
+        </Alert>
+      )}
 
       {phase === 'presets' && (
         <PresetsView presets={presets} definitions={definitions} onSelectPreset={handleSelectPreset} />
@@ -286,6 +337,7 @@ export function ServicePage() {
           report={report}
           definitions={definitions}
           logs={logs}
+          taskProgress={taskProgress}
           onCancel={handleCancel}
           onBack={handleBack}
           queue={queue}
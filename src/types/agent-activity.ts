@@ -261,7 +261,7 @@ export interface ServiceQueryActivity extends BaseActivity {
 }
 
 /**
- * Service report activity (get_service_report, get_report_statistics)
+ * Service report activity (get_service_report, get_report_statistics, compare_reports)
  */
 export interface ServiceReportActivity extends BaseActivity {
   type: 'service_report';
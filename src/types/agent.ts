@@ -146,6 +146,82 @@ export const AGENT_PROVIDERS: ProviderConfig[] = [
 // MCP Client Types
 // =============================================================================
 
+/**
+ * Limits enforced by the MCP HTTP server. Zero disables a limit.
+ */
+export interface McpServerLimits {
+  /** Largest request body accepted, in bytes */
+  maxBodyBytes: number;
+  /** Client connections served at once */
+  maxConnections: number;
+  /** Tool calls running at once, across all keys */
+  maxConcurrentToolCalls: number;
+  /** JSON-RPC requests each API key may send per minute */
+  requestsPerMinute: number;
+  /** Output returned by read_file and command tools, in bytes */
+  maxOutputBytes: number;
+}
+
+/**
+ * Interfaces the MCP HTTP server listens on
+ */
+export type McpBindAddress =
+  | { mode: 'loopback' }
+  | { mode: 'all' }
+  | { mode: 'interface'; address: string };
+
+/**
+ * TLS for the MCP HTTP server. Without a certificate path, a self-signed
+ * certificate is generated and kept in data/mcp_tls.
+ */
+export interface McpTlsSettings {
+  enabled: boolean;
+  /** PEM certificate chain, leaf first */
+  certPath?: string;
+  /** PEM private key for the certificate */
+  keyPath?: string;
+}
+
+/**
+ * The certificate the MCP server presents, for clients to pin
+ */
+export interface McpCertificateInfo {
+  /** SHA-256 of the leaf certificate, colon-separated uppercase hex */
+  sha256Fingerprint: string;
+  certPath: string;
+  selfSigned: boolean;
+}
+
+/**
+ * Runtime state of the MCP HTTP server
+ */
+export interface McpServerStatus {
+  running: boolean;
+  /** Endpoint URL, e.g. `https://0.0.0.0:8377/mcp` */
+  listeningAddress?: string;
+  startedAt?: string;
+  uptimeSecs: number;
+  /** Requests to `/mcp` since the server started */
+  requestsTotal: number;
+  /** Of those, how many were refused or failed (HTTP 4xx/5xx) */
+  requestsRejected: number;
+  /** Sessions opened by `initialize` and not yet closed */
+  activeSessions: number;
+  connectedClients: McpConnectedClient[];
+  /** Why the server last failed to start, if it did */
+  lastError?: string;
+}
+
+/**
+ * An open connection to the MCP HTTP server
+ */
+export interface McpConnectedClient {
+  remoteAddr: string;
+  connectedAt: string;
+  /** Requests sent on this connection */
+  requests: number;
+}
+
 /**
  * Transport type for MCP server connections
  */
@@ -169,8 +245,155 @@ export interface MCPServerConfig {
   apiKey?: string;
   /** Optional custom headers */
   headers?: Record<string, string>;
+  /** Offer this server's tools to clients of our own MCP server */
+  reexport?: boolean;
+}
+
+// =============================================================================
+// MCP Server Key Types
+// =============================================================================
+
+/**
+ * Filesystem access granted to an MCP API key
+ */
+export type McpFsAccess = 'none' | 'readOnly' | 'readWrite';
+
+/**
+ * An API key for the built-in MCP server (the secret is never returned)
+ */
+export interface McpApiKey {
+  id: string;
+  /** Display name, e.g. who or what the key was issued to */
+  name: string;
+  /** First characters of the secret, to tell keys apart */
+  keyPrefix: string;
+  /** Tools this key may call; null allows every tool */
+  allowedTools?: string[] | null;
+  fsAccess: McpFsAccess;
+  /** Path prefixes the file tools are limited to; empty allows any path */
+  fsPaths: string[];
+  createdAt: string;
+  expiresAt?: string;
+  lastUsedAt?: string;
+  revokedAt?: string;
+}
+
+/**
+ * Request to create an MCP API key
+ */
+export interface CreateMcpApiKeyRequest {
+  name: string;
+  allowedTools?: string[] | null;
+  fsAccess: McpFsAccess;
+  fsPaths: string[];
+  /** RFC 3339 expiry time; omit for a key that never expires */
+  expiresAt?: string;
+}
+
+/**
+ * A newly created key. The secret is only shown once.
+ */
+export interface CreatedMcpApiKey {
+  key: McpApiKey;
+  secret: string;
+}
+
+// =============================================================================
+// MCP Client Types
+// =============================================================================
+
+/**
+ * Connection state of an external MCP server
+ */
+export type McpClientState = 'connecting' | 'connected' | 'disconnected';
+
+/**
+ * Status of one configured external MCP server
+ */
+export interface McpClientServerStatus {
+  id: string;
+  name: string;
+  url: string;
+  state: McpClientState;
+  /** Name and version the server reported in `initialize` */
+  serverInfo?: string;
+  /** Original (un-namespaced) names of the server's tools */
+  tools: string[];
+  lastError?: string;
+  connectedAt?: string;
+  /** Last successful health check */
+  lastCheckedAt?: string;
+  /** Failed connection attempts since the last success */
+  reconnectAttempts: number;
 }
 
+/**
+ * A tool offered by an external MCP server
+ */
+export interface McpClientTool {
+  /** Namespaced name, `mcp_<serverId>_<toolName>` */
+  name: string;
+  serverId: string;
+  serverName: string;
+  /** Name on the external server */
+  toolName: string;
+  description?: string;
+  /** JSON Schema for the tool's arguments */
+  inputSchema: Record<string, unknown>;
+  /** Whether our MCP server offers this tool to its clients */
+  reexported: boolean;
+}
+
+// =============================================================================
+// MCP Audit Types
+// =============================================================================
+
+/**
+ * One MCP request as recorded in the audit log
+ */
+export interface McpAuditEntry {
+  id: number;
+  timestamp: string;
+  transport: 'http' | 'stdio';
+  remoteAddr?: string | null;
+  /** Key the request authenticated with; null for stdio and rejected requests */
+  keyId?: string | null;
+  keyName?: string | null;
+  method?: string | null;
+  tool?: string | null;
+  /** Request arguments as JSON, with secrets redacted */
+  arguments?: string | null;
+  durationMs: number;
+  success: boolean;
+  error?: string | null;
+  outputBytes: number;
+}
+
+/**
+ * Filters for the MCP audit log (all optional, combined with AND)
+ */
+export interface McpAuditQuery {
+  keyId?: string;
+  method?: string;
+  tool?: string;
+  success?: boolean;
+  /** RFC 3339 or YYYY-MM-DD */
+  after?: string;
+  /** RFC 3339 or YYYY-MM-DD, exclusive */
+  before?: string;
+  /** Free-text search over arguments and errors */
+  text?: string;
+  offset?: number;
+  limit?: number;
+}
+
+export interface McpAuditQueryResult {
+  total: number;
+  entries: McpAuditEntry[];
+}
+
+export type McpAuditExportFormat = 'csv' | 'json';
+
 // =============================================================================
 // Command Approval Types
 // =============================================================================
@@ -180,6 +403,44 @@ export interface MCPServerConfig {
  */
 export type ApprovalMode = "always" | "whitelist" | "yolo";
 
+/**
+ * Risk class of a shell command, from least to most severe
+ */
+export type CommandRiskClass =
+  | "readOnly"
+  | "networkEgress"
+  | "modifiesSystem"
+  | "credentialAccess"
+  | "destructive";
+
+/**
+ * One finding behind a command's risk class
+ */
+export interface CommandRiskReason {
+  class: CommandRiskClass;
+  /** The command (cmdlet, program or construct) the finding is about */
+  command: string;
+  detail: string;
+}
+
+/**
+ * Risk analysis of a shell command
+ */
+export interface CommandRisk {
+  /** Most severe class among the reasons; read-only when there are none */
+  class: CommandRiskClass;
+  reasons: CommandRiskReason[];
+}
+
+/**
+ * Whether a command can run without the technician's approval, and why
+ */
+export interface ApprovalDecision {
+  needsApproval: boolean;
+  explanation: string;
+  risk: CommandRisk;
+}
+
 /**
  * Status of a pending command
  */
@@ -201,6 +462,54 @@ export interface PendingCommand {
   status: CommandStatus;
   output?: string;
   error?: string;
+  /** Who asked for the command when it wasn't the in-app agent (e.g. "MCP (key name)") */
+  source?: string;
+  /** What the command was judged to do */
+  risk: CommandRisk;
+  /** How long the command ran, once it has run */
+  elapsedMs?: number;
+  /** Whether the command was killed for running past the timeout */
+  timedOut?: boolean;
+  /** Whether output was cut down to the output limit */
+  outputTruncated?: boolean;
+}
+
+/**
+ * Output of a running command, emitted as `command-output`
+ */
+export interface CommandOutputEvent {
+  /** The command's ID, or the `outputId` passed to `execute_agent_command` */
+  id: string;
+  stream: 'stdout' | 'stderr';
+  text: string;
+}
+
+// =============================================================================
+// File Journal Types
+// =============================================================================
+
+/**
+ * A file operation the agent performs
+ */
+export type FileOperationKind = 'write' | 'edit' | 'move' | 'copy';
+
+/**
+ * A journaled file operation, with what is needed to undo it
+ */
+export interface FileJournalEntry {
+  id: string;
+  conversationId?: string;
+  operation: FileOperationKind;
+  /** The file written or edited, or the destination of a move or copy */
+  path: string;
+  /** Where a moved or copied file came from */
+  source?: string;
+  /** Size of the file the operation replaced; absent when it created `path` */
+  replacedBytes?: number;
+  /** SHA-256 of `path` after the operation */
+  resultChecksum?: string;
+  createdAt: string;
+  undoneAt?: string;
 }
 
 // =============================================================================
@@ -264,6 +573,18 @@ export interface ConversationWithMessages extends Conversation {
 // Agent Settings
 // =============================================================================
 
+/**
+ * Folders the file tools may use. The data folder and the home folder are
+ * always allowed; denied roots win over allowed ones,
+ * and sensitive locations such as .ssh are denied regardless.
+ */
+export interface FilesystemPolicy {
+  /** Extra folders the file tools may use */
+  allowedRoots: string[];
+  /** Folders the file tools may never use, even inside an allowed one */
+  deniedRoots: string[];
+}
+
 /**
  * Agent configuration settings
  */
@@ -277,6 +598,14 @@ export interface AgentSettings {
   // Execution control
   approvalMode: ApprovalMode;
   whitelistedCommands: string[];
+  /** Seconds a shell command may run before it is killed (0 = no limit) */
+  commandTimeoutSecs?: number;
+  /** Bytes of stdout and of stderr kept per command (0 = no limit) */
+  commandMaxOutputBytes?: number;
+  /** Keep one shell running per conversation so state carries over between commands */
+  persistentShell?: boolean;
+  /** Where the agent's and MCP's file tools may read and write */
+  filesystemPolicy?: FilesystemPolicy;
 
   // Search configuration
   searchProvider: SearchProvider;
@@ -289,10 +618,16 @@ export interface AgentSettings {
   // MCP Server Settings (serving)
   /** Whether the MCP HTTP server is enabled */
   mcpServerEnabled: boolean;
-  /** API key for MCP server authentication (auto-generated) */
-  mcpApiKey?: string;
   /** Port for the MCP HTTP server */
   mcpPort: number;
+  /** Request, concurrency and output limits for the MCP HTTP server */
+  mcpLimits?: McpServerLimits;
+  /** Which network interfaces the MCP HTTP server listens on */
+  mcpBindAddress?: McpBindAddress;
+  /** HTTPS for the MCP server */
+  mcpTls?: McpTlsSettings;
+  /** Redaction profile applied to reports read over MCP unless the caller names another; null serves the full report */
+  mcpReportAudience?: string | null;
 
   // MCP Client Settings (connecting to external servers)
   /** External MCP servers the agent can connect to for additional tools */
@@ -316,14 +651,27 @@ export const DEFAULT_AGENT_SETTINGS: AgentSettings = {
     '^hostname$',
     '^whoami$',
   ],
+  commandTimeoutSecs: 300,
+  commandMaxOutputBytes: 128 * 1024,
+  persistentShell: false,
+  filesystemPolicy: { allowedRoots: [], deniedRoots: [] },
   searchProvider: 'none',
   tavilyApiKey: undefined,
   searxngUrl: undefined,
   systemPrompt: undefined,
   // MCP Server Settings
   mcpServerEnabled: false,
-  mcpApiKey: undefined,
   mcpPort: 8377,
+  mcpLimits: {
+    maxBodyBytes: 1024 * 1024,
+    maxConnections: 32,
+    maxConcurrentToolCalls: 4,
+    requestsPerMinute: 120,
+    maxOutputBytes: 256 * 1024,
+  },
+  mcpBindAddress: { mode: 'all' },
+  mcpTls: { enabled: false },
+  mcpReportAudience: 'customer',
   // MCP Client Settings
   mcpServers: [],
 };
@@ -175,6 +175,23 @@ export interface PathValidationResult {
   sanitizedPath?: string;
   error?: string;
   withinSandbox: boolean;
+  /** The filesystem policy rule that decided access, once the path resolved */
+  rule?: PathPolicyRule;
+}
+
+/**
+ * Kinds of filesystem policy rule
+ */
+export type PathRuleKind = 'protected' | 'deniedRoot' | 'allowedRoot' | 'outsideAllowedRoots';
+
+/**
+ * The rule that decided whether a path may be used
+ */
+export interface PathPolicyRule {
+  kind: PathRuleKind;
+  /** The root that matched, or the name of the protected location */
+  matched?: string;
+  allowed: boolean;
 }
 
 /**
@@ -154,6 +154,8 @@ export interface ServiceResult {
   logs: string[];
   /** Agent-generated analysis for this service result */
   agentAnalysis?: string;
+  /** The service was stopped by the watchdog for exceeding its timeout */
+  timedOut?: boolean;
 }
 
 /** Status of a service run */
@@ -191,12 +193,28 @@ export interface ServiceReport {
   agentSummary?: string;
   /** Agent-computed health score (0-100) */
   healthScore?: number;
+  /** Pinned reports are never removed by retention pruning */
+  pinned?: boolean;
+  /** Machine the run was performed on (absent in reports from older versions) */
+  machine?: MachineIdentity;
 }
 
 // =============================================================================
 // Service Run State
 // =============================================================================
 
+/** Progress reported by a running service (`service-task-progress` event) */
+export interface ServiceTaskProgress {
+  /** Service reporting progress */
+  serviceId: ServiceId;
+  /** Percent complete (0-100), absent when the phase has no measurable progress */
+  percent?: number | null;
+  /** Current phase of the service (e.g. "Sequential write") */
+  phase: string;
+  /** When the update was emitted (ISO string) */
+  timestamp: string;
+}
+
 /** Global service run state */
 export interface ServiceRunState {
   /** Whether a service run is currently active */
@@ -207,6 +225,23 @@ export interface ServiceRunState {
   currentReport?: ServiceReport;
 }
 
+/**
+ * Snapshot of an in-progress run, written to disk after every service so
+ * the run can be resumed after a crash or reboot
+ */
+export interface RunCheckpoint {
+  /** Report with the results gathered so far */
+  report: ServiceReport;
+  /** Enabled queue items in execution order */
+  queue: ServiceQueueItem[];
+  /** Indices into `queue` of services that have finished */
+  completedIndices: number[];
+  /** Run time before the checkpoint in milliseconds */
+  elapsedMs: number;
+  /** When the checkpoint was written (ISO string) */
+  checkpointedAt: string;
+}
+
 // =============================================================================
 // UI State
 // =============================================================================
@@ -285,3 +320,224 @@ export interface ReportStatistics {
   /** Computed health score 0-100 */
   healthScore: number;
 }
+
+// =============================================================================
+// Report Retention
+// =============================================================================
+
+/** Summary of a retention pass over the reports directory */
+export interface ReportPruneSummary {
+  /** IDs of reports that were removed */
+  removed: string[];
+  /** Zip archive the removed reports were moved into (if archiving is enabled) */
+  archivePath?: string;
+  /** Number of expired reports kept because they are pinned */
+  keptPinned: number;
+  /** Total size of the removed files in bytes */
+  bytesFreed: number;
+  /** Reports that could not be removed, with the reason */
+  errors: string[];
+}
+
+// =============================================================================
+// Report Index
+// =============================================================================
+
+/** Lightweight report listing row served from the report index */
+export interface ReportIndexEntry {
+  id: ReportId;
+  startedAt: string;
+  completedAt?: string;
+  status: ServiceRunStatus;
+  technicianName?: string;
+  customerName?: string;
+  totalDurationMs?: number;
+  healthScore?: number;
+  /** Services that were run, in result order */
+  serviceIds: ServiceId[];
+  /** Number of services that failed */
+  failedCount: number;
+  /** Number of findings across all services */
+  findingCount: number;
+  /** Most severe finding in the report */
+  maxSeverity?: FindingSeverity;
+  pinned: boolean;
+  /** Key of the machine the report was produced on */
+  machineKey?: string;
+  hostname?: string;
+}
+
+/** Order of report search results */
+export type ReportSort = 'newest' | 'oldest' | 'duration-desc' | 'duration-asc' | 'technician';
+
+/** Filters for searching the report index (all optional, combined with AND) */
+export interface ReportQuery {
+  /** Case-insensitive substring match on report ID, customer or technician name */
+  search?: string;
+  /** Case-insensitive substring match on customer name */
+  customerName?: string;
+  /** Case-insensitive substring match on technician name */
+  technicianName?: string;
+  /** Only reports started at or after this time (ISO string or YYYY-MM-DD) */
+  startedAfter?: string;
+  /** Only reports started before this time (ISO string or YYYY-MM-DD, exclusive) */
+  startedBefore?: string;
+  /** Only reports with one of these statuses */
+  statuses?: ServiceRunStatus[];
+  /** Only reports that ran at least one of these services */
+  serviceIds?: ServiceId[];
+  /** Only reports with a finding at least this severe */
+  minSeverity?: FindingSeverity;
+  /** Free-text search over finding titles */
+  text?: string;
+  /** Only reports produced on this machine */
+  machineKey?: string;
+  /** Result order; newest first by default */
+  sort?: ReportSort;
+  /** Number of matching reports to skip */
+  offset?: number;
+  /** Maximum number of reports to return */
+  limit?: number;
+}
+
+/** A page of report search results */
+export interface ReportQueryResult {
+  /** Total number of reports matching the filters (ignoring pagination) */
+  total: number;
+  reports: ReportIndexEntry[];
+}
+
+// =============================================================================
+// Machine History
+// =============================================================================
+
+/** Hardware identity of the machine a report was produced on */
+export interface MachineIdentity {
+  /** Stable key derived from the most specific hardware identifier available */
+  machineKey: string;
+  hostname: string;
+  /** SMBIOS system UUID */
+  systemUuid?: string;
+  /** System (chassis) serial number */
+  systemSerial?: string;
+  /** Motherboard serial number */
+  boardSerial?: string;
+  vendor?: string;
+  model?: string;
+}
+
+/** A machine that appears in saved reports */
+export interface MachineSummary {
+  machineKey: string;
+  /** Hostname at the most recent visit */
+  hostname?: string;
+  vendor?: string;
+  model?: string;
+  /** Customer name at the most recent visit */
+  customerName?: string;
+  reportCount: number;
+  /** Start time of the first report for this machine */
+  firstSeen: string;
+  /** Start time of the most recent report for this machine */
+  lastSeen: string;
+}
+
+/** A single value of a metric, taken from one report */
+export interface TrendPoint {
+  reportId: ReportId;
+  /** When the report was started (ISO string) */
+  timestamp: string;
+  value: number;
+}
+
+/** A metric tracked across every visit of a machine, oldest first */
+export interface MetricTrend {
+  /** Metric identifier (e.g. "smart_wear", "disk_free") */
+  metric: string;
+  /** Human-readable metric name */
+  label: string;
+  /** Unit of `value` (e.g. "%", "bytes", "Mbps") */
+  unit: string;
+  /** What the metric refers to when there can be several (drive, mount point) */
+  subject?: string;
+  points: TrendPoint[];
+  /** Last value minus first value (absent with fewer than two points) */
+  change?: number;
+}
+
+/** Metric trends for one machine */
+export interface MachineTrends {
+  machineKey: string;
+  /** Number of reports the trends were computed from */
+  reportCount: number;
+  trends: MetricTrend[];
+}
+
+// =============================================================================
+// Report Diff
+// =============================================================================
+
+/** A service whose pass/fail result differs between two reports */
+export interface ServiceStatusChange {
+  serviceId: ServiceId;
+  beforeSuccess: boolean;
+  afterSuccess: boolean;
+}
+
+/** A finding present in only one of two reports, or whose severity changed */
+export interface FindingChange {
+  serviceId: ServiceId;
+  /** Title in the later report (earlier report if the finding disappeared) */
+  title: string;
+  beforeSeverity?: FindingSeverity;
+  afterSeverity?: FindingSeverity;
+}
+
+/** Change of a numeric metric between two reports */
+export interface MetricDelta {
+  /** Metric identifier (e.g. "disk_free", "bleachbit_reclaimed") */
+  metric: string;
+  /** Human-readable metric name */
+  label: string;
+  /** Unit of the values (e.g. "bytes", "items", "MB/s") */
+  unit: string;
+  /** What the metric refers to when there can be several (drive, mount point) */
+  subject?: string;
+  before?: number;
+  after?: number;
+  /** `after - before` when both values exist */
+  delta?: number;
+}
+
+/** Structured "before vs after" comparison of two reports */
+export interface ReportDiff {
+  beforeReportId: ReportId;
+  afterReportId: ReportId;
+  beforeStartedAt: string;
+  afterStartedAt: string;
+  beforeHealthScore?: number;
+  afterHealthScore?: number;
+  /** Services run only in the later report */
+  servicesAdded: ServiceId[];
+  /** Services run only in the earlier report */
+  servicesRemoved: ServiceId[];
+  /** Services run in both reports whose pass/fail result changed */
+  statusChanges: ServiceStatusChange[];
+  /** Findings only in the later report (services run in both reports) */
+  findingsAppeared: FindingChange[];
+  /** Findings only in the earlier report (services run in both reports) */
+  findingsResolved: FindingChange[];
+  /** Findings in both reports with a different severity */
+  severityChanges: FindingChange[];
+  metricDeltas: MetricDelta[];
+}
+
+// =============================================================================
+// Report Export
+// =============================================================================
+
+/** Text-based output format for report export */
+export type ReportExportFormat = 'html' | 'markdown';
+
+/** How much detail an exported report includes */
+export type ReportDetailLevel = 'customer' | 'technician';
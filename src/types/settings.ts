@@ -238,6 +238,38 @@ export interface ReportsSettings {
   reportRetentionDays: number;
   /** Whether to include detailed logs in saved reports */
   includeLogsInReport: boolean;
+  /** Move expired reports into a zip archive instead of deleting them */
+  archiveExpiredReports: boolean;
+  /** Named redaction profiles applied when exporting for an audience */
+  redactionProfiles: ReportRedactionProfile[];
+}
+
+/**
+ * Named profile deciding which parts of a report an audience sees
+ */
+export interface ReportRedactionProfile {
+  /** Profile name used to select it (e.g., "customer") */
+  name: string;
+  /** Finding severities to leave out */
+  hiddenSeverities: FindingSeverity[];
+  /** Service IDs whose results are left out entirely */
+  hiddenServices: string[];
+  /** Whether service logs are kept */
+  includeLogs: boolean;
+  /** Whether raw finding data blobs are kept */
+  includeFindingData: boolean;
+  /** Whether per-service agent analysis is kept */
+  includeAgentAnalysis: boolean;
+  /** Whether the agent executive summary is kept */
+  includeAgentSummary: boolean;
+  /** Whether the technician's name is kept */
+  includeTechnicianName: boolean;
+  /** Whether the machine's hostname and hardware serials are kept */
+  includeMachineIdentity?: boolean;
+  /** Replace file paths in logs, finding text and data, and agent text with a placeholder */
+  redactPaths: boolean;
+  /** Replace user names (profile folders, current user) with a placeholder */
+  redactUsernames: boolean;
 }
 
 /**
@@ -326,7 +358,7 @@ export interface TechnicianTabsSettings {
   useFavicons: boolean;
 }
 
-import type { ServicePreset } from './service';
+import type { FindingSeverity, ServicePreset } from './service';
 import type { AgentSettings } from './agent';
 import { DEFAULT_AGENT_SETTINGS } from './agent';
 
@@ -338,6 +370,20 @@ export interface PresetsSettings {
   customPresets: ServicePreset[];
 }
 
+/**
+ * Service runner settings (watchdog timeouts)
+ */
+export interface ServicesSettings {
+  /** Whether the watchdog stops services that run far past their expected duration */
+  timeoutsEnabled: boolean;
+  /** Timeout as a multiple of the expected duration */
+  timeoutMultiplier: number;
+  /** Lower bound for computed timeouts, in seconds */
+  minTimeoutSecs: number;
+  /** Fixed timeouts in seconds keyed by service ID (0 = never time out) */
+  timeoutOverrides: Record<string, number>;
+}
+
 // =============================================================================
 // Main Settings Interface
 // =============================================================================
@@ -369,6 +415,8 @@ export interface AppSettings {
   technicianTabs: TechnicianTabsSettings;
   /** Custom service presets */
   presets: PresetsSettings;
+  /** Service runner settings (timeouts) */
+  services: ServicesSettings;
   /** Agent AI settings */
   agent: AgentSettings;
 }
@@ -400,6 +448,35 @@ export const DEFAULT_REPORTS: ReportsSettings = {
   autoSaveReports: true,
   reportRetentionDays: 0,
   includeLogsInReport: true,
+  archiveExpiredReports: false,
+  redactionProfiles: [
+    {
+      name: 'customer',
+      hiddenSeverities: ['info'],
+      hiddenServices: [],
+      includeLogs: false,
+      includeFindingData: false,
+      includeAgentAnalysis: true,
+      includeAgentSummary: true,
+      includeTechnicianName: true,
+      includeMachineIdentity: false,
+      redactPaths: true,
+      redactUsernames: true,
+    },
+    {
+      name: 'technician',
+      hiddenSeverities: [],
+      hiddenServices: [],
+      includeLogs: true,
+      includeFindingData: true,
+      includeAgentAnalysis: true,
+      includeAgentSummary: true,
+      includeTechnicianName: true,
+      includeMachineIdentity: true,
+      redactPaths: false,
+      redactUsernames: false,
+    },
+  ],
 };
 
 /**
@@ -440,6 +517,16 @@ export const DEFAULT_PRESETS: PresetsSettings = {
   customPresets: [],
 };
 
+/**
+ * Default service runner settings
+ */
+export const DEFAULT_SERVICES: ServicesSettings = {
+  timeoutsEnabled: true,
+  timeoutMultiplier: 3,
+  minTimeoutSecs: 600,
+  timeoutOverrides: {},
+};
+
 /**
  * Default application settings
  */
@@ -452,6 +539,7 @@ export const DEFAULT_SETTINGS: AppSettings = {
   programs: DEFAULT_PROGRAMS,
   technicianTabs: DEFAULT_TECHNICIAN_TABS,
   presets: DEFAULT_PRESETS,
+  services: DEFAULT_SERVICES,
   agent: DEFAULT_AGENT_SETTINGS,
 };
 
@@ -470,6 +558,8 @@ export type SettingKey =
   | 'reports.autoSaveReports'
   | 'reports.reportRetentionDays'
   | 'reports.includeLogsInReport'
+  | 'reports.archiveExpiredReports'
+  | 'reports.redactionProfiles'
   | 'business.enabled'
   | 'business.name'
   | 'business.logoPath'
@@ -483,6 +573,10 @@ export type SettingKey =
   | 'technicianTabs.tabs'
   | 'technicianTabs.useFavicons'
   | 'presets.customPresets'
+  | 'services.timeoutsEnabled'
+  | 'services.timeoutMultiplier'
+  | 'services.minTimeoutSecs'
+  | 'services.timeoutOverrides'
   | 'agent';
 
 /**
@@ -496,6 +590,8 @@ export type SettingValueType<K extends SettingKey> =
   K extends 'reports.autoSaveReports' ? boolean :
   K extends 'reports.reportRetentionDays' ? number :
   K extends 'reports.includeLogsInReport' ? boolean :
+  K extends 'reports.archiveExpiredReports' ? boolean :
+  K extends 'reports.redactionProfiles' ? ReportRedactionProfile[] :
   K extends 'business.enabled' ? boolean :
   K extends 'business.name' ? string :
   K extends 'business.logoPath' ? string | undefined :
@@ -509,6 +605,10 @@ export type SettingValueType<K extends SettingKey> =
   K extends 'technicianTabs.tabs' ? TechnicianTab[] :
   K extends 'technicianTabs.useFavicons' ? boolean :
   K extends 'presets.customPresets' ? ServicePreset[] :
+  K extends 'services.timeoutsEnabled' ? boolean :
+  K extends 'services.timeoutMultiplier' ? number :
+  K extends 'services.minTimeoutSecs' ? number :
+  K extends 'services.timeoutOverrides' ? Record<string, number> :
   K extends 'agent' ? AgentSettings :
   never;
 